@@ -0,0 +1,7 @@
+-- 通用操作日志：每条记录携带逆操作与撤销前置条件，覆盖移动/复制/回收站/恢复等文件操作
+
+ALTER TABLE operation_logs ADD COLUMN inverse TEXT;      -- JSON，撤销时执行的逆操作
+ALTER TABLE operation_logs ADD COLUMN precondition TEXT; -- JSON，撤销前需满足的文件系统状态
+ALTER TABLE operation_logs ADD COLUMN undone_at DATETIME;
+
+CREATE INDEX IF NOT EXISTS idx_operation_logs_undone_at ON operation_logs(undone_at);
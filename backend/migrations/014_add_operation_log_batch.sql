@@ -0,0 +1,3 @@
+-- 操作日志关联批次/任务，支持整批撤销
+ALTER TABLE operation_logs ADD COLUMN batch_id TEXT;
+CREATE INDEX IF NOT EXISTS idx_operation_logs_batch_id ON operation_logs(batch_id);
@@ -0,0 +1,2 @@
+-- 多集文件（S01E01-E02）：记录末集编号，单集文件为空
+ALTER TABLE media_files ADD COLUMN detected_episode_end INTEGER;
@@ -0,0 +1,2 @@
+-- 绝对集数（番剧常用），与季/集编号并存
+ALTER TABLE media_files ADD COLUMN detected_absolute_episode INTEGER;
@@ -0,0 +1,2 @@
+-- 媒体库（监控目录）启用的元数据源，JSON 数组；NULL 表示使用全部已注册数据源
+ALTER TABLE watch_folders ADD COLUMN providers TEXT;
@@ -0,0 +1,14 @@
+-- 元数据源响应缓存：按 数据源 + 接口 + 参数 缓存搜索与详情结果
+CREATE TABLE IF NOT EXISTS provider_cache (
+    cache_key TEXT PRIMARY KEY,
+    provider TEXT NOT NULL,
+    endpoint TEXT NOT NULL,
+    params TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    fetched_at DATETIME NOT NULL,
+    expires_at DATETIME NOT NULL,
+    hit_count INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_provider_cache_provider ON provider_cache(provider, endpoint);
+CREATE INDEX IF NOT EXISTS idx_provider_cache_expires ON provider_cache(expires_at);
@@ -0,0 +1,2 @@
+-- 结构化发布名解析结果（JSON）：分辨率、来源、编码、HDR、音轨、发布组、版本等
+ALTER TABLE media_files ADD COLUMN release_info TEXT;
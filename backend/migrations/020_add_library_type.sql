@@ -0,0 +1,2 @@
+-- 媒体库类型：movie / tv / anime；NULL 表示按文件名自动判断
+ALTER TABLE watch_folders ADD COLUMN library_type TEXT;
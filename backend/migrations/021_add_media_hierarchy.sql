@@ -0,0 +1,155 @@
+-- 规范化的影视目录：电影 / 剧集 / 季 / 单集，media_files 通过外键关联
+-- 主键由数据源与外部 ID 拼接（`tmdb:1396`、`tmdb:1396:s1`、`tmdb:1396:s1e3`），重复识别时幂等
+CREATE TABLE IF NOT EXISTS movies (
+    id TEXT PRIMARY KEY,
+    provider TEXT NOT NULL,
+    external_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    original_title TEXT,
+    year INTEGER,
+    overview TEXT,
+    poster_url TEXT,
+    backdrop_url TEXT,
+    rating REAL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (provider, external_id)
+);
+
+CREATE TABLE IF NOT EXISTS shows (
+    id TEXT PRIMARY KEY,
+    provider TEXT NOT NULL,
+    external_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    original_title TEXT,
+    year INTEGER,
+    overview TEXT,
+    poster_url TEXT,
+    backdrop_url TEXT,
+    rating REAL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (provider, external_id)
+);
+
+CREATE TABLE IF NOT EXISTS seasons (
+    id TEXT PRIMARY KEY,
+    show_id TEXT NOT NULL REFERENCES shows(id) ON DELETE CASCADE,
+    season_number INTEGER NOT NULL,
+    name TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (show_id, season_number)
+);
+
+CREATE TABLE IF NOT EXISTS episodes (
+    id TEXT PRIMARY KEY,
+    show_id TEXT NOT NULL REFERENCES shows(id) ON DELETE CASCADE,
+    season_id TEXT NOT NULL REFERENCES seasons(id) ON DELETE CASCADE,
+    season_number INTEGER NOT NULL,
+    episode_number INTEGER NOT NULL,
+    absolute_number INTEGER,
+    title TEXT,
+    overview TEXT,
+    air_date TEXT,
+    runtime INTEGER,
+    rating REAL,
+    still_url TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (show_id, season_number, episode_number)
+);
+
+ALTER TABLE media_files ADD COLUMN movie_id TEXT REFERENCES movies(id) ON DELETE SET NULL;
+ALTER TABLE media_files ADD COLUMN show_id TEXT REFERENCES shows(id) ON DELETE SET NULL;
+ALTER TABLE media_files ADD COLUMN season_id TEXT REFERENCES seasons(id) ON DELETE SET NULL;
+ALTER TABLE media_files ADD COLUMN episode_id TEXT REFERENCES episodes(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_media_files_movie_id ON media_files(movie_id);
+CREATE INDEX IF NOT EXISTS idx_media_files_show_id ON media_files(show_id);
+CREATE INDEX IF NOT EXISTS idx_media_files_season_id ON media_files(season_id);
+CREATE INDEX IF NOT EXISTS idx_media_files_episode_id ON media_files(episode_id);
+CREATE INDEX IF NOT EXISTS idx_episodes_season_id ON episodes(season_id);
+
+-- 回填：从已识别文件的 metadata JSON 生成目录记录
+-- 旧版刮削结果没有 match_provider，按 tmdb_id 视为 TMDb；media_type 缺失时有季号即视为剧集
+CREATE TEMP TABLE identified_files AS
+SELECT
+    id AS file_id,
+    COALESCE(match_provider, CASE WHEN tmdb_id IS NOT NULL THEN 'tmdb' END) AS provider,
+    COALESCE(match_external_id, CAST(tmdb_id AS TEXT)) AS external_id,
+    COALESCE(
+        json_extract(metadata, '$.media_type'),
+        CASE WHEN json_extract(metadata, '$.season_number') IS NOT NULL THEN 'tv' ELSE 'movie' END
+    ) AS media_type,
+    COALESCE(json_extract(metadata, '$.title'), json_extract(metadata, '$.name'), detected_title) AS title,
+    COALESCE(json_extract(metadata, '$.original_title'), json_extract(metadata, '$.original_name')) AS original_title,
+    COALESCE(json_extract(metadata, '$.year'), detected_year) AS year,
+    json_extract(metadata, '$.overview') AS overview,
+    json_extract(metadata, '$.poster_url') AS poster_url,
+    json_extract(metadata, '$.backdrop_url') AS backdrop_url,
+    json_extract(metadata, '$.rating') AS rating,
+    COALESCE(json_extract(metadata, '$.season_number'), detected_season) AS season_number,
+    COALESCE(json_extract(metadata, '$.episode_number'), detected_episode) AS episode_number,
+    COALESCE(json_extract(metadata, '$.absolute_number'), detected_absolute_episode) AS absolute_number,
+    json_extract(metadata, '$.season_name') AS season_name,
+    json_extract(metadata, '$.episode_title') AS episode_title,
+    json_extract(metadata, '$.episode_overview') AS episode_overview,
+    json_extract(metadata, '$.episode_air_date') AS episode_air_date,
+    json_extract(metadata, '$.episode_runtime') AS episode_runtime,
+    json_extract(metadata, '$.episode_rating') AS episode_rating,
+    json_extract(metadata, '$.episode_still_url') AS episode_still_url
+FROM media_files
+WHERE metadata IS NOT NULL
+  AND json_valid(metadata)
+  AND COALESCE(match_external_id, CAST(tmdb_id AS TEXT)) IS NOT NULL
+  AND COALESCE(match_provider, CASE WHEN tmdb_id IS NOT NULL THEN 'tmdb' END) IS NOT NULL;
+
+INSERT OR IGNORE INTO movies (id, provider, external_id, title, original_title, year, overview, poster_url, backdrop_url, rating)
+SELECT provider || ':' || external_id, provider, external_id, COALESCE(title, ''), original_title, year, overview, poster_url, backdrop_url, rating
+FROM identified_files
+WHERE media_type = 'movie';
+
+INSERT OR IGNORE INTO shows (id, provider, external_id, title, original_title, year, overview, poster_url, backdrop_url, rating)
+SELECT provider || ':' || external_id, provider, external_id, COALESCE(title, ''), original_title, year, overview, poster_url, backdrop_url, rating
+FROM identified_files
+WHERE media_type = 'tv';
+
+INSERT OR IGNORE INTO seasons (id, show_id, season_number, name)
+SELECT provider || ':' || external_id || ':s' || season_number, provider || ':' || external_id, season_number, season_name
+FROM identified_files
+WHERE media_type = 'tv' AND season_number IS NOT NULL;
+
+INSERT OR IGNORE INTO episodes (id, show_id, season_id, season_number, episode_number, absolute_number, title, overview, air_date, runtime, rating, still_url)
+SELECT
+    provider || ':' || external_id || ':s' || season_number || 'e' || episode_number,
+    provider || ':' || external_id,
+    provider || ':' || external_id || ':s' || season_number,
+    season_number, episode_number, absolute_number,
+    episode_title, episode_overview, episode_air_date, episode_runtime, episode_rating, episode_still_url
+FROM identified_files
+WHERE media_type = 'tv' AND season_number IS NOT NULL AND episode_number IS NOT NULL;
+
+UPDATE media_files
+SET movie_id = (
+    SELECT provider || ':' || external_id FROM identified_files
+    WHERE identified_files.file_id = media_files.id AND media_type = 'movie'
+)
+WHERE id IN (SELECT file_id FROM identified_files WHERE media_type = 'movie');
+
+UPDATE media_files
+SET show_id = (
+        SELECT provider || ':' || external_id FROM identified_files
+        WHERE identified_files.file_id = media_files.id
+    ),
+    season_id = (
+        SELECT provider || ':' || external_id || ':s' || season_number FROM identified_files
+        WHERE identified_files.file_id = media_files.id AND season_number IS NOT NULL
+    ),
+    episode_id = (
+        SELECT provider || ':' || external_id || ':s' || season_number || 'e' || episode_number FROM identified_files
+        WHERE identified_files.file_id = media_files.id AND season_number IS NOT NULL AND episode_number IS NOT NULL
+    )
+WHERE id IN (SELECT file_id FROM identified_files WHERE media_type = 'tv');
+
+DROP TABLE identified_files;
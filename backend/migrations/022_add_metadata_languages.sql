@@ -0,0 +1,8 @@
+-- 媒体库元数据语言偏好：JSON 数组，按顺序回退（如 ["zh-CN","zh-TW","ja","en"]），为空时使用全局 tmdb_language
+ALTER TABLE watch_folders ADD COLUMN languages TEXT;
+
+-- 目录保存各语言标题与别名，供检索与匹配
+ALTER TABLE movies ADD COLUMN localized_titles TEXT;
+ALTER TABLE movies ADD COLUMN aliases TEXT;
+ALTER TABLE shows ADD COLUMN localized_titles TEXT;
+ALTER TABLE shows ADD COLUMN aliases TEXT;
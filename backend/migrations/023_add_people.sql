@@ -0,0 +1,31 @@
+-- 演职人员：主键由数据源与人员 ID 拼接（`tmdb:287`），同一人出现在多部作品时共享记录
+-- profile_path 为头像在本地人员图片缓存中的路径，未下载时为空
+CREATE TABLE IF NOT EXISTS people (
+    id TEXT PRIMARY KEY,
+    provider TEXT NOT NULL,
+    external_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    original_name TEXT,
+    profile_url TEXT,
+    profile_path TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (provider, external_id)
+);
+
+-- 作品的演职员表：role 为 actor / director / writer，character 为演员饰演的角色，job 为原始职务名
+CREATE TABLE IF NOT EXISTS credits (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    person_id TEXT NOT NULL REFERENCES people(id) ON DELETE CASCADE,
+    movie_id TEXT REFERENCES movies(id) ON DELETE CASCADE,
+    show_id TEXT REFERENCES shows(id) ON DELETE CASCADE,
+    role TEXT NOT NULL,
+    character TEXT,
+    job TEXT,
+    sort_order INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_credits_person_id ON credits(person_id);
+CREATE INDEX IF NOT EXISTS idx_credits_movie_id ON credits(movie_id);
+CREATE INDEX IF NOT EXISTS idx_credits_show_id ON credits(show_id);
+CREATE INDEX IF NOT EXISTS idx_people_name ON people(name);
@@ -0,0 +1,35 @@
+-- 电影系列（合集）：主键由数据源与系列 ID 拼接（`tmdb:10`）
+-- poster_path / backdrop_path 为图片在本地系列图片缓存中的路径，未下载时为空
+CREATE TABLE IF NOT EXISTS collections (
+    id TEXT PRIMARY KEY,
+    provider TEXT NOT NULL,
+    external_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    overview TEXT,
+    poster_url TEXT,
+    backdrop_url TEXT,
+    poster_path TEXT,
+    backdrop_path TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (provider, external_id)
+);
+
+-- 系列包含的全部作品（含未入库的），movie_id 与 movies.id 同格式，用于比对已拥有与缺失的部分
+CREATE TABLE IF NOT EXISTS collection_parts (
+    collection_id TEXT NOT NULL REFERENCES collections(id) ON DELETE CASCADE,
+    movie_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    original_title TEXT,
+    year INTEGER,
+    release_date TEXT,
+    overview TEXT,
+    poster_url TEXT,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (collection_id, movie_id)
+);
+
+ALTER TABLE movies ADD COLUMN collection_id TEXT REFERENCES collections(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_movies_collection_id ON movies(collection_id);
+CREATE INDEX IF NOT EXISTS idx_collection_parts_movie_id ON collection_parts(movie_id);
@@ -0,0 +1,15 @@
+-- 自动识别的复核队列：得分低于自动应用阈值的文件连同解析结果与候选列表入队，等待人工确认
+-- status: pending（待复核）/ accepted（已应用）/ rejected（已拒绝）
+CREATE TABLE IF NOT EXISTS identify_review_queue (
+    file_id TEXT PRIMARY KEY REFERENCES media_files(id) ON DELETE CASCADE,
+    status TEXT NOT NULL DEFAULT 'pending',
+    reason TEXT NOT NULL,
+    parse TEXT NOT NULL,
+    candidates TEXT NOT NULL,
+    recommended TEXT,
+    score REAL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_identify_review_queue_status ON identify_review_queue(status, created_at);
@@ -0,0 +1,6 @@
+-- AI 解析后端：cloudflare（Workers AI）或 openai（任意 OpenAI 兼容端点，如本地 Ollama / llama.cpp）
+INSERT OR IGNORE INTO settings (id, category, key, value, description) VALUES
+('ai-backend', 'ai', 'ai_backend', 'cloudflare', 'AI parsing backend: cloudflare, openai'),
+('ai-openai-base-url', 'ai', 'openai_base_url', 'http://127.0.0.1:11434/v1', 'Base URL of an OpenAI-compatible endpoint (without /chat/completions)'),
+('ai-openai-api-key', 'ai', 'openai_api_key', '', 'Optional API key for the OpenAI-compatible endpoint'),
+('ai-openai-model', 'ai', 'openai_model', 'qwen2.5:7b-instruct', 'Model used by the OpenAI-compatible endpoint for filename parsing');
@@ -0,0 +1,21 @@
+-- 标题别名：人工确认的匹配记为「归一化解析标题（+ 可选路径前缀）→ 数据源 ID」，
+-- 同名的兄弟剧集与后续下载直接命中别名，不再模糊搜索
+-- path_prefix 为空字符串表示全局生效，查找时取最长的匹配前缀
+-- source: manual（人工应用识别结果时学习）/ api（通过别名接口维护）
+CREATE TABLE IF NOT EXISTS title_aliases (
+    id TEXT PRIMARY KEY,
+    normalized_title TEXT NOT NULL,
+    path_prefix TEXT NOT NULL DEFAULT '',
+    title TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    external_id TEXT NOT NULL,
+    media_type TEXT NOT NULL,
+    source TEXT NOT NULL DEFAULT 'manual',
+    hits INTEGER NOT NULL DEFAULT 0,
+    last_used_at DATETIME,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (normalized_title, path_prefix)
+);
+
+CREATE INDEX IF NOT EXISTS idx_title_aliases_provider ON title_aliases(provider, external_id);
@@ -0,0 +1,22 @@
+-- 识别时的规则解析快照（ParsedTitle JSON），解析规则升级后据此判断解析结果是否变化
+ALTER TABLE media_files ADD COLUMN parse_snapshot TEXT;
+
+-- 解析版本升级后的重新识别差异：匹配或季集发生变化的文件先记录差异，批准后才覆盖元数据
+-- status: pending（待批准）/ applied（已批准并应用）/ rejected（保留原匹配）
+CREATE TABLE IF NOT EXISTS reparse_diffs (
+    file_id TEXT PRIMARY KEY REFERENCES media_files(id) ON DELETE CASCADE,
+    status TEXT NOT NULL DEFAULT 'pending',
+    old_parse_version TEXT,
+    new_parse_version TEXT NOT NULL,
+    old_parse TEXT NOT NULL,
+    new_parse TEXT NOT NULL,
+    old_provider TEXT,
+    old_external_id TEXT,
+    candidate TEXT NOT NULL,
+    changes TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_reparse_diffs_status ON reparse_diffs(status);
+CREATE INDEX IF NOT EXISTS idx_media_files_parse_version ON media_files(parse_version);
@@ -0,0 +1,26 @@
+-- 外部 ID 交叉引用：每部作品（数据源 + 外部 ID + 媒体类型）已知的全部 ID
+-- id_type: tmdb / imdb / tvdb / bgm / wikidata 等，作品自身 ID 也记一行（id_type = provider）
+-- source: provider（数据源详情给出）/ manual（接口维护，不被自动来源覆盖）
+CREATE TABLE IF NOT EXISTS external_ids (
+    provider TEXT NOT NULL,
+    external_id TEXT NOT NULL,
+    media_type TEXT NOT NULL,
+    id_type TEXT NOT NULL,
+    value TEXT NOT NULL,
+    source TEXT NOT NULL DEFAULT 'provider',
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (provider, external_id, media_type, id_type)
+);
+
+CREATE INDEX IF NOT EXISTS idx_external_ids_value ON external_ids(id_type, value);
+
+-- 已有匹配补记自身 ID
+INSERT OR IGNORE INTO external_ids (provider, external_id, media_type, id_type, value, source)
+SELECT DISTINCT match_provider, match_external_id, json_extract(metadata, '$.media_type'),
+       match_provider, match_external_id, 'provider'
+FROM media_files
+WHERE match_provider IS NOT NULL
+  AND match_external_id IS NOT NULL
+  AND json_valid(metadata)
+  AND json_extract(metadata, '$.media_type') IS NOT NULL;
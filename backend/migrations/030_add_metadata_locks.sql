@@ -0,0 +1,17 @@
+-- 元数据字段锁：JSON 数组（如 ["title","overview"]），重新刮削时这些字段保留原值
+ALTER TABLE media_files ADD COLUMN locked_fields TEXT;
+
+-- 元数据历史：每次人工修改、重新刮削或回滚前保存旧版本，可回滚到任一版本
+-- source: edit（人工修改）/ scrape（重新刮削）/ revert（回滚）
+-- changes: 本次变化的字段 [{field, old, new, locked}]，locked 表示因字段锁未被覆盖
+CREATE TABLE IF NOT EXISTS metadata_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    file_id TEXT NOT NULL REFERENCES media_files(id) ON DELETE CASCADE,
+    source TEXT NOT NULL,
+    metadata TEXT NOT NULL,
+    locked_fields TEXT,
+    changes TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_metadata_history_file_id ON metadata_history(file_id, id);
@@ -0,0 +1,30 @@
+-- 标题别名按「归一化标题 + 路径前缀 + 媒体类型 + 年份」区分：
+-- 同名的电影与剧集、翻拍与原作可以各自记录，年份为空表示不限年份
+CREATE TABLE IF NOT EXISTS title_aliases_new (
+    id TEXT PRIMARY KEY,
+    normalized_title TEXT NOT NULL,
+    path_prefix TEXT NOT NULL DEFAULT '',
+    title TEXT NOT NULL,
+    year INTEGER,
+    provider TEXT NOT NULL,
+    external_id TEXT NOT NULL,
+    media_type TEXT NOT NULL,
+    source TEXT NOT NULL DEFAULT 'manual',
+    hits INTEGER NOT NULL DEFAULT 0,
+    last_used_at DATETIME,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO title_aliases_new (id, normalized_title, path_prefix, title, provider, external_id,
+    media_type, source, hits, last_used_at, created_at, updated_at)
+SELECT id, normalized_title, path_prefix, title, provider, external_id,
+    media_type, source, hits, last_used_at, created_at, updated_at
+FROM title_aliases;
+
+DROP TABLE title_aliases;
+ALTER TABLE title_aliases_new RENAME TO title_aliases;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_title_aliases_key
+    ON title_aliases(normalized_title, path_prefix, media_type, IFNULL(year, 0));
+CREATE INDEX IF NOT EXISTS idx_title_aliases_provider ON title_aliases(provider, external_id);
@@ -0,0 +1,2 @@
+-- 重新识别没有推荐候选时记下尝试时的解析版本，同一版本不再重复查询数据源
+ALTER TABLE media_files ADD COLUMN reparse_attempt_version TEXT;
@@ -15,10 +15,10 @@ use crate::services::progress_hub::ProgressHub;
 use crate::services::smart_cache::{SmartCacheConfig, SmartCacheManager, WarmupStrategy};
 use crate::services::task_executors::{
     BatchHashExecutor, HashExecutor, RenameExecutor, ScanExecutor, ScrapeExecutor,
-    SimilarScanExecutor,
+    SimilarScanExecutor, UndoBatchExecutor,
 };
 use crate::services::task_queue::{TaskQueue, TaskQueueConfig, TaskType};
-use crate::services::{scheduler, watcher};
+use crate::services::{scheduler, title_aliases, watcher};
 
 use crate::routes::build_app_router;
 
@@ -57,6 +57,11 @@ impl BootstrapContext {
             .await
             .map_err(|e| anyhow::anyhow!("Migration failed: {}", e))?;
 
+        // 标题归一化规则随版本变化时，已存别名按新规则重算
+        if let Err(e) = title_aliases::renormalize(&db).await {
+            tracing::warn!("Failed to renormalize title aliases: {}", e);
+        }
+
         let cache_config = SmartCacheConfig {
             max_size: 10000,
             ttl: std::time::Duration::from_secs(3600),
@@ -126,6 +131,7 @@ impl BootstrapContext {
                 db: db.clone(),
                 http_client: reqwest::Client::new(),
                 config: config.clone(),
+                plugin_manager: plugin_manager.clone(),
             }),
         );
         task_queue.register_executor(
@@ -140,6 +146,10 @@ impl BootstrapContext {
             TaskType::Custom("similar_scan".to_string()),
             Arc::new(SimilarScanExecutor { db: db.clone() }),
         );
+        task_queue.register_executor(
+            TaskType::Custom("undo_batch".to_string()),
+            Arc::new(UndoBatchExecutor { db: db.clone() }),
+        );
 
         Ok(Self {
             config,
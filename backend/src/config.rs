@@ -16,6 +16,10 @@ pub struct AppConfig {
     pub chunk_size: usize,  // 流式处理的块大小（字节）
     pub hash_cache_dir: PathBuf,
     pub trash_dir: PathBuf,
+    /// 演职人员头像缓存目录
+    pub people_cache_dir: PathBuf,
+    /// 电影系列图片缓存目录
+    pub collection_cache_dir: PathBuf,
     pub media_directories: Vec<PathBuf>,
     pub log_level: String,  // 日志级别
     pub log_format: String, // 日志格式: "pretty" 或 "json"
@@ -53,6 +57,8 @@ impl AppConfig {
         // 确保必要目录存在 (使用默认值检查，后续由 config crate 覆盖)
         let default_hash_cache = PathBuf::from("./data/hash_cache");
         let default_trash = PathBuf::from("./data/trash");
+        let default_people_cache = PathBuf::from("./data/people");
+        let default_collection_cache = PathBuf::from("./data/collections");
 
         let builder = config::Config::builder()
             // 1. 设置默认值
@@ -62,6 +68,11 @@ impl AppConfig {
             .set_default("chunk_size", 64 * 1024 * 1024)? // 64MB
             .set_default("hash_cache_dir", default_hash_cache.to_str().unwrap())?
             .set_default("trash_dir", default_trash.to_str().unwrap())?
+            .set_default("people_cache_dir", default_people_cache.to_str().unwrap())?
+            .set_default(
+                "collection_cache_dir",
+                default_collection_cache.to_str().unwrap(),
+            )?
             .set_default("log_level", "cine=info,axum=info")?
             .set_default("log_format", "pretty")?
             .set_default("enable_plugins", true)?
@@ -90,6 +101,8 @@ impl AppConfig {
             chunk_size: config.chunk_size,
             hash_cache_dir: PathBuf::from(config.hash_cache_dir),
             trash_dir: PathBuf::from(config.trash_dir),
+            people_cache_dir: PathBuf::from(config.people_cache_dir),
+            collection_cache_dir: PathBuf::from(config.collection_cache_dir),
             media_directories: config
                 .media_directories
                 .unwrap_or_default()
@@ -112,6 +125,8 @@ impl AppConfig {
         // 确保目录存在
         std::fs::create_dir_all(&app_config.hash_cache_dir)?;
         std::fs::create_dir_all(&app_config.trash_dir)?;
+        std::fs::create_dir_all(&app_config.people_cache_dir)?;
+        std::fs::create_dir_all(&app_config.collection_cache_dir)?;
 
         Ok(app_config)
     }
@@ -127,6 +142,8 @@ struct Dictionary {
     chunk_size: usize,
     hash_cache_dir: String,
     trash_dir: String,
+    people_cache_dir: String,
+    collection_cache_dir: String,
     media_directories: Option<Vec<String>>,
     log_level: String,
     log_format: String,
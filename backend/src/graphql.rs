@@ -4,15 +4,16 @@
 //! 支持复杂的数据关联查询和条件过滤
 
 use async_graphql::{
-    Context, EmptyMutation, EmptySubscription, InputObject, Object, Result as GraphQLResult,
-    Schema, SimpleObject, ID,
+    ComplexObject, Context, EmptyMutation, EmptySubscription, InputObject, Object,
+    Result as GraphQLResult, Schema, SimpleObject, ID,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use crate::{
     handlers::AppState,
-    models::MediaFile,
+    models::{Episode, MediaFile, Movie, Season, Show},
+    services::catalog::{self, Level},
     services::queries::{get_file_stats_optimized, get_files_paginated_optimized, QueryOptions},
 };
 
@@ -40,6 +41,258 @@ pub struct MediaFileGQL {
     pub last_modified: chrono::DateTime<chrono::Utc>,
     pub video_info: Option<String>,
     pub metadata: Option<String>,
+    pub movie_id: Option<String>,
+    pub show_id: Option<String>,
+    pub season_id: Option<String>,
+    pub episode_id: Option<String>,
+}
+
+impl From<MediaFile> for MediaFileGQL {
+    fn from(file: MediaFile) -> Self {
+        MediaFileGQL {
+            id: ID(file.id),
+            path: file.path,
+            name: file.name,
+            size: file.size,
+            file_type: file.file_type,
+            hash_xxhash: file.hash_xxhash,
+            hash_md5: file.hash_md5,
+            tmdb_id: file.tmdb_id.map(|id| id as i64),
+            quality_score: file.quality_score.map(|s| s as f64),
+            created_at: file.created_at,
+            updated_at: file.updated_at,
+            last_modified: file.last_modified,
+            video_info: file.video_info,
+            metadata: file.metadata,
+            movie_id: file.movie_id,
+            show_id: file.show_id,
+            season_id: file.season_id,
+            episode_id: file.episode_id,
+        }
+    }
+}
+
+/// 电影 GraphQL 对象
+#[derive(SimpleObject)]
+#[graphql(name = "Movie", complex)]
+pub struct MovieGQL {
+    pub id: ID,
+    pub provider: String,
+    pub external_id: String,
+    pub title: String,
+    pub original_title: Option<String>,
+    /// 各语言标题（JSON 对象，键为语言标签）
+    pub localized_titles: Option<String>,
+    pub aliases: Vec<String>,
+    pub year: Option<i32>,
+    pub overview: Option<String>,
+    pub poster_url: Option<String>,
+    pub backdrop_url: Option<String>,
+    pub rating: Option<f64>,
+    /// 所属电影系列 ID
+    pub collection_id: Option<String>,
+    pub file_count: i64,
+}
+
+impl From<Movie> for MovieGQL {
+    fn from(movie: Movie) -> Self {
+        MovieGQL {
+            id: ID(movie.id),
+            provider: movie.provider,
+            external_id: movie.external_id,
+            title: movie.title,
+            original_title: movie.original_title,
+            localized_titles: movie.localized_titles,
+            aliases: parse_aliases(movie.aliases.as_deref()),
+            year: movie.year,
+            overview: movie.overview,
+            poster_url: movie.poster_url,
+            backdrop_url: movie.backdrop_url,
+            rating: movie.rating,
+            collection_id: movie.collection_id,
+            file_count: movie.file_count,
+        }
+    }
+}
+
+#[ComplexObject]
+impl MovieGQL {
+    /// 关联的媒体文件
+    async fn files(&self, ctx: &Context<'_>) -> GraphQLResult<Vec<MediaFileGQL>> {
+        catalog_files(ctx, Level::Movie, &self.id).await
+    }
+}
+
+/// 剧集 GraphQL 对象
+#[derive(SimpleObject)]
+#[graphql(name = "Show", complex)]
+pub struct ShowGQL {
+    pub id: ID,
+    pub provider: String,
+    pub external_id: String,
+    pub title: String,
+    pub original_title: Option<String>,
+    /// 各语言标题（JSON 对象，键为语言标签）
+    pub localized_titles: Option<String>,
+    pub aliases: Vec<String>,
+    pub year: Option<i32>,
+    pub overview: Option<String>,
+    pub poster_url: Option<String>,
+    pub backdrop_url: Option<String>,
+    pub rating: Option<f64>,
+    pub season_count: i64,
+    pub episode_count: i64,
+    pub file_count: i64,
+}
+
+impl From<Show> for ShowGQL {
+    fn from(show: Show) -> Self {
+        ShowGQL {
+            id: ID(show.id),
+            provider: show.provider,
+            external_id: show.external_id,
+            title: show.title,
+            original_title: show.original_title,
+            localized_titles: show.localized_titles,
+            aliases: parse_aliases(show.aliases.as_deref()),
+            year: show.year,
+            overview: show.overview,
+            poster_url: show.poster_url,
+            backdrop_url: show.backdrop_url,
+            rating: show.rating,
+            season_count: show.season_count,
+            episode_count: show.episode_count,
+            file_count: show.file_count,
+        }
+    }
+}
+
+#[ComplexObject]
+impl ShowGQL {
+    /// 各季（按季号排序）
+    async fn seasons(&self, ctx: &Context<'_>) -> GraphQLResult<Vec<SeasonGQL>> {
+        let app_state = ctx.data::<Arc<AppState>>()?;
+        let seasons = catalog::list_seasons(&app_state.db, &self.id)
+            .await
+            .map_err(|e| async_graphql::Error::new(format!("Seasons query failed: {}", e)))?;
+        Ok(seasons.into_iter().map(SeasonGQL::from).collect())
+    }
+
+    /// 按季号获取单季
+    async fn season(&self, ctx: &Context<'_>, number: i32) -> GraphQLResult<Option<SeasonGQL>> {
+        let app_state = ctx.data::<Arc<AppState>>()?;
+        let season = catalog::get_season(&app_state.db, &self.id, number)
+            .await
+            .map_err(|e| async_graphql::Error::new(format!("Season query failed: {}", e)))?;
+        Ok(season.map(SeasonGQL::from))
+    }
+}
+
+/// 季 GraphQL 对象
+#[derive(SimpleObject)]
+#[graphql(name = "Season", complex)]
+pub struct SeasonGQL {
+    pub id: ID,
+    pub show_id: String,
+    pub season_number: i32,
+    pub name: Option<String>,
+    pub episode_count: i64,
+    pub file_count: i64,
+}
+
+impl From<Season> for SeasonGQL {
+    fn from(season: Season) -> Self {
+        SeasonGQL {
+            id: ID(season.id),
+            show_id: season.show_id,
+            season_number: season.season_number,
+            name: season.name,
+            episode_count: season.episode_count,
+            file_count: season.file_count,
+        }
+    }
+}
+
+#[ComplexObject]
+impl SeasonGQL {
+    /// 单集（按集号排序）
+    async fn episodes(&self, ctx: &Context<'_>) -> GraphQLResult<Vec<EpisodeGQL>> {
+        let app_state = ctx.data::<Arc<AppState>>()?;
+        let episodes = catalog::list_episodes(&app_state.db, &self.id)
+            .await
+            .map_err(|e| async_graphql::Error::new(format!("Episodes query failed: {}", e)))?;
+        Ok(episodes.into_iter().map(EpisodeGQL::from).collect())
+    }
+
+    /// 关联到该季的全部媒体文件
+    async fn files(&self, ctx: &Context<'_>) -> GraphQLResult<Vec<MediaFileGQL>> {
+        catalog_files(ctx, Level::Season, &self.id).await
+    }
+}
+
+/// 单集 GraphQL 对象
+#[derive(SimpleObject)]
+#[graphql(name = "Episode", complex)]
+pub struct EpisodeGQL {
+    pub id: ID,
+    pub show_id: String,
+    pub season_id: String,
+    pub season_number: i32,
+    pub episode_number: i32,
+    pub absolute_number: Option<i32>,
+    pub title: Option<String>,
+    pub overview: Option<String>,
+    pub air_date: Option<String>,
+    pub runtime: Option<i32>,
+    pub rating: Option<f64>,
+    pub still_url: Option<String>,
+    pub file_count: i64,
+}
+
+impl From<Episode> for EpisodeGQL {
+    fn from(episode: Episode) -> Self {
+        EpisodeGQL {
+            id: ID(episode.id),
+            show_id: episode.show_id,
+            season_id: episode.season_id,
+            season_number: episode.season_number,
+            episode_number: episode.episode_number,
+            absolute_number: episode.absolute_number,
+            title: episode.title,
+            overview: episode.overview,
+            air_date: episode.air_date,
+            runtime: episode.runtime,
+            rating: episode.rating,
+            still_url: episode.still_url,
+            file_count: episode.file_count,
+        }
+    }
+}
+
+#[ComplexObject]
+impl EpisodeGQL {
+    /// 该集的媒体文件（可能有多个版本）
+    async fn files(&self, ctx: &Context<'_>) -> GraphQLResult<Vec<MediaFileGQL>> {
+        catalog_files(ctx, Level::Episode, &self.id).await
+    }
+}
+
+/// 别名列以 JSON 数组保存
+fn parse_aliases(raw: Option<&str>) -> Vec<String> {
+    raw.and_then(|raw| serde_json::from_str(raw).ok())
+        .unwrap_or_default()
+}
+
+async fn catalog_files(
+    ctx: &Context<'_>,
+    level: Level,
+    id: &str,
+) -> GraphQLResult<Vec<MediaFileGQL>> {
+    let app_state = ctx.data::<Arc<AppState>>()?;
+    let files = catalog::files_for(&app_state.db, level, id)
+        .await
+        .map_err(|e| async_graphql::Error::new(format!("Files query failed: {}", e)))?;
+    Ok(files.into_iter().map(MediaFileGQL::from).collect())
 }
 
 /// 文件查询过滤器
@@ -131,25 +384,7 @@ impl QueryRoot {
         let has_more = params.limit.is_some_and(|limit| total_count > limit);
 
         // 转换为GraphQL对象
-        let gql_files = files
-            .into_iter()
-            .map(|file| MediaFileGQL {
-                id: ID(file.id),
-                path: file.path,
-                name: file.name,
-                size: file.size,
-                file_type: file.file_type,
-                hash_xxhash: file.hash_xxhash,
-                hash_md5: file.hash_md5,
-                tmdb_id: file.tmdb_id.map(|id| id as i64),
-                quality_score: file.quality_score.map(|s| s as f64),
-                created_at: file.created_at,
-                updated_at: file.updated_at,
-                last_modified: file.last_modified,
-                video_info: file.video_info,
-                metadata: file.metadata,
-            })
-            .collect();
+        let gql_files = files.into_iter().map(MediaFileGQL::from).collect();
 
         Ok(FileListResponse {
             files: gql_files,
@@ -169,22 +404,7 @@ impl QueryRoot {
             .await
             .map_err(|e| async_graphql::Error::new(format!("Database query failed: {}", e)))?;
 
-        let gql_file = file.map(|file| MediaFileGQL {
-            id: ID(file.id),
-            path: file.path,
-            name: file.name,
-            size: file.size,
-            file_type: file.file_type,
-            hash_xxhash: file.hash_xxhash,
-            hash_md5: file.hash_md5,
-            tmdb_id: file.tmdb_id.map(|id| id as i64),
-            quality_score: file.quality_score.map(|s| s as f64),
-            created_at: file.created_at,
-            updated_at: file.updated_at,
-            last_modified: file.last_modified,
-            video_info: file.video_info,
-            metadata: file.metadata,
-        });
+        let gql_file = file.map(MediaFileGQL::from);
 
         Ok(gql_file)
     }
@@ -273,25 +493,7 @@ impl QueryRoot {
                     async_graphql::Error::new(format!("Large files query failed: {}", e))
                 })?;
 
-        let gql_files = files
-            .into_iter()
-            .map(|file| MediaFileGQL {
-                id: ID(file.id),
-                path: file.path,
-                name: file.name,
-                size: file.size,
-                file_type: file.file_type,
-                hash_xxhash: file.hash_xxhash,
-                hash_md5: file.hash_md5,
-                tmdb_id: file.tmdb_id.map(|id| id as i64),
-                quality_score: file.quality_score.map(|s| s as f64),
-                created_at: file.created_at,
-                updated_at: file.updated_at,
-                last_modified: file.last_modified,
-                video_info: file.video_info,
-                metadata: file.metadata,
-            })
-            .collect();
+        let gql_files = files.into_iter().map(MediaFileGQL::from).collect();
 
         Ok(gql_files)
     }
@@ -328,28 +530,73 @@ impl QueryRoot {
         // 按照请求的ID顺序返回结果
         let results = string_ids
             .into_iter()
-            .map(|id| {
-                file_map.get(&id).map(|file| MediaFileGQL {
-                    id: ID(file.id.clone()),
-                    path: file.path.clone(),
-                    name: file.name.clone(),
-                    size: file.size,
-                    file_type: file.file_type.clone(),
-                    hash_xxhash: file.hash_xxhash.clone(),
-                    hash_md5: file.hash_md5.clone(),
-                    tmdb_id: file.tmdb_id.map(|id| id as i64),
-                    quality_score: file.quality_score.map(|s| s as f64),
-                    created_at: file.created_at,
-                    updated_at: file.updated_at,
-                    last_modified: file.last_modified,
-                    video_info: file.video_info.clone(),
-                    metadata: file.metadata.clone(),
-                })
-            })
+            .map(|id| file_map.get(&id).cloned().map(MediaFileGQL::from))
             .collect();
 
         Ok(results)
     }
+
+    /// 浏览目录中的电影
+    #[graphql(name = "movies")]
+    async fn movies(
+        &self,
+        ctx: &Context<'_>,
+        search: Option<String>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> GraphQLResult<Vec<MovieGQL>> {
+        let app_state = ctx.data::<Arc<AppState>>()?;
+        let movies = catalog::list_movies(
+            &app_state.db,
+            search.as_deref(),
+            limit.unwrap_or(50),
+            offset.unwrap_or(0),
+        )
+        .await
+        .map_err(|e| async_graphql::Error::new(format!("Movies query failed: {}", e)))?;
+        Ok(movies.into_iter().map(MovieGQL::from).collect())
+    }
+
+    /// 根据ID查询单部电影
+    #[graphql(name = "movie")]
+    async fn movie(&self, ctx: &Context<'_>, id: ID) -> GraphQLResult<Option<MovieGQL>> {
+        let app_state = ctx.data::<Arc<AppState>>()?;
+        let movie = catalog::get_movie(&app_state.db, &id)
+            .await
+            .map_err(|e| async_graphql::Error::new(format!("Movie query failed: {}", e)))?;
+        Ok(movie.map(MovieGQL::from))
+    }
+
+    /// 浏览目录中的剧集
+    #[graphql(name = "shows")]
+    async fn shows(
+        &self,
+        ctx: &Context<'_>,
+        search: Option<String>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> GraphQLResult<Vec<ShowGQL>> {
+        let app_state = ctx.data::<Arc<AppState>>()?;
+        let shows = catalog::list_shows(
+            &app_state.db,
+            search.as_deref(),
+            limit.unwrap_or(50),
+            offset.unwrap_or(0),
+        )
+        .await
+        .map_err(|e| async_graphql::Error::new(format!("Shows query failed: {}", e)))?;
+        Ok(shows.into_iter().map(ShowGQL::from).collect())
+    }
+
+    /// 根据ID查询单部剧集
+    #[graphql(name = "show")]
+    async fn show(&self, ctx: &Context<'_>, id: ID) -> GraphQLResult<Option<ShowGQL>> {
+        let app_state = ctx.data::<Arc<AppState>>()?;
+        let show = catalog::get_show(&app_state.db, &id)
+            .await
+            .map_err(|e| async_graphql::Error::new(format!("Show query failed: {}", e)))?;
+        Ok(show.map(ShowGQL::from))
+    }
 }
 
 /// 创建GraphQL schema
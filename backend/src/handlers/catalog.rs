@@ -0,0 +1,228 @@
+use crate::handlers::AppState;
+use crate::models::{Episode, MediaFile, Movie, Season, Show, TitleCredit};
+use crate::services::catalog::{self, Level};
+use crate::services::people;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Deserialize, IntoParams)]
+pub struct CatalogQuery {
+    /// 按标题（含原名）模糊搜索
+    pub search: Option<String>,
+    /// 返回条目数，默认 100，最大 1000
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MovieDetail {
+    pub movie: Movie,
+    pub files: Vec<MediaFile>,
+    /// 演职员表：演员在前，其后为导演、编剧
+    pub credits: Vec<TitleCredit>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ShowDetail {
+    pub show: Show,
+    pub seasons: Vec<Season>,
+    /// 演职员表：演员在前，其后为导演、编剧
+    pub credits: Vec<TitleCredit>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct EpisodeDetail {
+    pub episode: Episode,
+    pub files: Vec<MediaFile>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SeasonDetail {
+    pub season: Season,
+    pub episodes: Vec<EpisodeDetail>,
+    /// 已关联到该季但未能对应到具体单集的文件
+    pub unmatched_files: Vec<MediaFile>,
+}
+
+fn internal_error(error: anyhow::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+}
+
+fn not_found(what: &str) -> (StatusCode, String) {
+    (StatusCode::NOT_FOUND, format!("{what} not found"))
+}
+
+fn page(query: &CatalogQuery) -> (Option<&str>, i64, i64) {
+    (
+        query
+            .search
+            .as_deref()
+            .filter(|search| !search.trim().is_empty()),
+        query.limit.unwrap_or(100).clamp(1, 1000),
+        query.offset.unwrap_or(0).max(0),
+    )
+}
+
+/// 列出目录中的电影
+#[utoipa::path(
+    get,
+    path = "/api/movies",
+    tag = "catalog",
+    params(CatalogQuery),
+    responses(
+        (status = 200, description = "获取电影列表成功", body = Vec<Movie>),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn list_movies(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<CatalogQuery>,
+) -> Result<Json<Vec<Movie>>, (StatusCode, String)> {
+    let (search, limit, offset) = page(&query);
+    catalog::list_movies(&state.db, search, limit, offset)
+        .await
+        .map(Json)
+        .map_err(internal_error)
+}
+
+/// 获取电影及其关联文件
+#[utoipa::path(
+    get,
+    path = "/api/movies/{id}",
+    tag = "catalog",
+    params(("id" = String, Path, description = "电影 ID，如 tmdb:603")),
+    responses(
+        (status = 200, description = "获取电影成功", body = MovieDetail),
+        (status = 404, description = "电影不存在"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_movie(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<MovieDetail>, (StatusCode, String)> {
+    let movie = catalog::get_movie(&state.db, &id)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| not_found("Movie"))?;
+    let files = catalog::files_for(&state.db, Level::Movie, &id)
+        .await
+        .map_err(internal_error)?;
+    let credits = people::credits_for(&state.db, Level::Movie, &id)
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(MovieDetail {
+        movie,
+        files,
+        credits,
+    }))
+}
+
+/// 列出目录中的剧集
+#[utoipa::path(
+    get,
+    path = "/api/shows",
+    tag = "catalog",
+    params(CatalogQuery),
+    responses(
+        (status = 200, description = "获取剧集列表成功", body = Vec<Show>),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn list_shows(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<CatalogQuery>,
+) -> Result<Json<Vec<Show>>, (StatusCode, String)> {
+    let (search, limit, offset) = page(&query);
+    catalog::list_shows(&state.db, search, limit, offset)
+        .await
+        .map(Json)
+        .map_err(internal_error)
+}
+
+/// 获取剧集及其各季
+#[utoipa::path(
+    get,
+    path = "/api/shows/{id}",
+    tag = "catalog",
+    params(("id" = String, Path, description = "剧集 ID，如 tmdb:1396")),
+    responses(
+        (status = 200, description = "获取剧集成功", body = ShowDetail),
+        (status = 404, description = "剧集不存在"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_show(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<ShowDetail>, (StatusCode, String)> {
+    let show = catalog::get_show(&state.db, &id)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| not_found("Show"))?;
+    let seasons = catalog::list_seasons(&state.db, &id)
+        .await
+        .map_err(internal_error)?;
+    let credits = people::credits_for(&state.db, Level::Show, &id)
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(ShowDetail {
+        show,
+        seasons,
+        credits,
+    }))
+}
+
+/// 获取某一季的单集及其文件
+#[utoipa::path(
+    get,
+    path = "/api/shows/{id}/seasons/{season}",
+    tag = "catalog",
+    params(
+        ("id" = String, Path, description = "剧集 ID"),
+        ("season" = i32, Path, description = "季号，特别篇为 0")
+    ),
+    responses(
+        (status = 200, description = "获取季成功", body = SeasonDetail),
+        (status = 404, description = "季不存在"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_season(
+    State(state): State<Arc<AppState>>,
+    Path((id, season_number)): Path<(String, i32)>,
+) -> Result<Json<SeasonDetail>, (StatusCode, String)> {
+    let season = catalog::get_season(&state.db, &id, season_number)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| not_found("Season"))?;
+
+    let mut episodes = Vec::new();
+    for episode in catalog::list_episodes(&state.db, &season.id)
+        .await
+        .map_err(internal_error)?
+    {
+        let files = catalog::files_for(&state.db, Level::Episode, &episode.id)
+            .await
+            .map_err(internal_error)?;
+        episodes.push(EpisodeDetail { episode, files });
+    }
+    let unmatched_files = catalog::files_for(&state.db, Level::Season, &season.id)
+        .await
+        .map_err(internal_error)?
+        .into_iter()
+        .filter(|file| file.episode_id.is_none())
+        .collect();
+
+    Ok(Json(SeasonDetail {
+        season,
+        episodes,
+        unmatched_files,
+    }))
+}
@@ -0,0 +1,92 @@
+use crate::handlers::AppState;
+use crate::models::{Collection, CollectionPart};
+use crate::services::collections;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Deserialize, IntoParams)]
+pub struct CollectionQuery {
+    /// 按系列名称模糊搜索
+    pub search: Option<String>,
+    /// 返回条目数，默认 100，最大 1000
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CollectionDetail {
+    pub collection: Collection,
+    /// 已入库的作品
+    pub owned: Vec<CollectionPart>,
+    /// 尚未入库的作品
+    pub missing: Vec<CollectionPart>,
+}
+
+fn internal_error(error: anyhow::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+}
+
+/// 列出电影系列
+#[utoipa::path(
+    get,
+    path = "/api/collections",
+    tag = "catalog",
+    params(CollectionQuery),
+    responses(
+        (status = 200, description = "获取系列列表成功", body = Vec<Collection>),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn list_collections(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<CollectionQuery>,
+) -> Result<Json<Vec<Collection>>, (StatusCode, String)> {
+    let search = query
+        .search
+        .as_deref()
+        .filter(|search| !search.trim().is_empty());
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+    let offset = query.offset.unwrap_or(0).max(0);
+    collections::list_collections(&state.db, search, limit, offset)
+        .await
+        .map(Json)
+        .map_err(internal_error)
+}
+
+/// 获取系列及其已拥有与缺失的作品
+#[utoipa::path(
+    get,
+    path = "/api/collections/{id}",
+    tag = "catalog",
+    params(("id" = String, Path, description = "系列 ID，如 tmdb:10")),
+    responses(
+        (status = 200, description = "获取系列成功", body = CollectionDetail),
+        (status = 404, description = "系列不存在"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_collection(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<CollectionDetail>, (StatusCode, String)> {
+    let collection = collections::get_collection(&state.db, &id)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Collection not found".to_string()))?;
+    let (owned, missing) = collections::collection_parts(&state.db, &id)
+        .await
+        .map_err(internal_error)?
+        .into_iter()
+        .partition(|part| part.file_count > 0);
+    Ok(Json(CollectionDetail {
+        collection,
+        owned,
+        missing,
+    }))
+}
@@ -10,7 +10,7 @@ use utoipa::{IntoParams, ToSchema};
 use crate::handlers::tasks::TaskActionResponse;
 use crate::handlers::AppState;
 use crate::services::library_service::LibraryService;
-use crate::services::{dedupe, empty_dirs};
+use crate::services::{dedupe, empty_dirs, log};
 
 #[derive(Serialize, ToSchema)]
 pub struct DuplicateResponse {
@@ -119,6 +119,8 @@ pub struct DeleteEmptyDirsRequest {
 pub struct DeleteEmptyDirsResponse {
     pub deleted: Vec<String>,
     pub message: String,
+    /// 批次 ID，可用于整批撤销
+    pub batch_id: String,
 }
 
 /// 删除空目录
@@ -133,16 +135,26 @@ pub struct DeleteEmptyDirsResponse {
     )
 )]
 pub async fn delete_empty_dirs(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
     Json(req): Json<DeleteEmptyDirsRequest>,
 ) -> Result<Json<DeleteEmptyDirsResponse>, (axum::http::StatusCode, String)> {
     let deleted = empty_dirs::delete_empty_directories(&req.dirs)
         .await
         .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
+    // 记录日志，便于撤销（重新创建目录）
+    let batch_id = uuid::Uuid::new_v4().to_string();
+    for dir in &deleted {
+        let entry = log::JournalEntry::dir_removal(dir).in_batch(Some(&batch_id));
+        if let Err(e) = log::record_entry(&state.db, entry).await {
+            tracing::warn!("Failed to record directory removal {}: {}", dir, e);
+        }
+    }
+
     Ok(Json(DeleteEmptyDirsResponse {
         deleted: deleted.clone(),
         message: format!("Deleted {} empty directories", deleted.len()),
+        batch_id,
     }))
 }
 
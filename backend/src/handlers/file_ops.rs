@@ -41,6 +41,8 @@ pub struct BatchFileOperationResponse {
     pub total: usize,
     pub success: usize,
     pub failed: usize,
+    /// 批次 ID，可用于整批撤销
+    pub batch_id: String,
 }
 
 /// 移动文件
@@ -102,9 +104,11 @@ pub async fn batch_move_files(
     State(state): State<Arc<AppState>>,
     Json(req): Json<BatchMoveRequest>,
 ) -> Result<Json<BatchFileOperationResponse>, (axum::http::StatusCode, String)> {
-    let results = file_ops::move_files_batch(&state.db, &req.file_ids, &req.target_dir)
-        .await
-        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let batch_id = uuid::Uuid::new_v4().to_string();
+    let results =
+        file_ops::move_files_batch(&state.db, &req.file_ids, &req.target_dir, Some(&batch_id))
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
     let success = results.iter().filter(|r| r.success).count();
     let failed = results.len() - success;
@@ -114,6 +118,7 @@ pub async fn batch_move_files(
         success,
         failed,
         results,
+        batch_id,
     }))
 }
 
@@ -132,9 +137,11 @@ pub async fn batch_copy_files(
     State(state): State<Arc<AppState>>,
     Json(req): Json<BatchCopyRequest>,
 ) -> Result<Json<BatchFileOperationResponse>, (axum::http::StatusCode, String)> {
-    let results = file_ops::copy_files_batch(&state.db, &req.file_ids, &req.target_dir)
-        .await
-        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let batch_id = uuid::Uuid::new_v4().to_string();
+    let results =
+        file_ops::copy_files_batch(&state.db, &req.file_ids, &req.target_dir, Some(&batch_id))
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
     let success = results.iter().filter(|r| r.success).count();
     let failed = results.len() - success;
@@ -144,5 +151,6 @@ pub async fn batch_copy_files(
         success,
         failed,
         results,
+        batch_id,
     }))
 }
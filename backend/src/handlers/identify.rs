@@ -1,11 +1,21 @@
 use std::sync::Arc;
 
-use axum::{extract::State, response::Json};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::handlers::AppState;
+use crate::services::external_ids::{self, ExternalId, TitleRef};
 use crate::services::identify::{self, ApplySelection, IdentifyPreview};
+use crate::services::metadata_provider::ProviderSession;
+use crate::services::reparse::{self, ReparseDiff};
+use crate::services::review_queue::{self, AutoIdentifyOptions, ReviewItem};
+use crate::services::sibling_apply::{self, SiblingReport};
+use crate::services::title_aliases::{self, AliasInput, TitleAlias};
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct IdentifyPreviewRequest {
@@ -76,6 +86,7 @@ pub async fn preview_identify(
         &state.db,
         &state.http_client,
         &state.config,
+        &ProviderSession::new(state.plugin_manager.clone()),
         &file_ids,
         allow_ai,
     )
@@ -112,13 +123,18 @@ pub async fn apply_identify(
             generate_nfo: item.generate_nfo.unwrap_or(false),
         })
         .collect::<Vec<_>>();
-    let applied =
-        identify::apply_selections(&state.db, &state.http_client, &state.config, &selections)
-            .await
-            .map_err(internal_error)?
-            .into_iter()
-            .map(|(file_id, metadata)| IdentifyApplyResult { file_id, metadata })
-            .collect();
+    let applied = identify::apply_selections(
+        &state.db,
+        &state.http_client,
+        &state.config,
+        &ProviderSession::new(state.plugin_manager.clone()),
+        &selections,
+    )
+    .await
+    .map_err(internal_error)?
+    .into_iter()
+    .map(|(file_id, metadata)| IdentifyApplyResult { file_id, metadata })
+    .collect();
 
     Ok(Json(IdentifyApplyResponse { applied }))
 }
@@ -198,6 +214,758 @@ pub async fn apply_identify_batch(
     }))
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct IdentifyApplySiblingsRequest {
+    pub file_id: String,
+    pub provider: String,
+    pub external_id: String,
+    pub media_type: String,
+    /// folder（同目录，默认）/ series（季目录上溯到剧集目录）
+    pub scope: Option<String>,
+    pub lock_match: Option<bool>,
+    pub download_images: Option<bool>,
+    pub generate_nfo: Option<bool>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/identify/apply/siblings",
+    tag = "identify",
+    request_body = IdentifyApplySiblingsRequest,
+    responses(
+        (status = 200, description = "已应用到同目录 / 同剧集文件", body = SiblingReport),
+        (status = 400, description = "请求参数错误"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn apply_identify_siblings(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<IdentifyApplySiblingsRequest>,
+) -> Result<Json<SiblingReport>, (StatusCode, String)> {
+    let scope = req.scope.as_deref().unwrap_or(sibling_apply::SCOPE_FOLDER);
+    if scope != sibling_apply::SCOPE_FOLDER && scope != sibling_apply::SCOPE_SERIES {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("unsupported scope: {scope}"),
+        ));
+    }
+    let selection = ApplySelection {
+        file_id: req.file_id,
+        provider: req.provider,
+        external_id: req.external_id,
+        media_type: req.media_type,
+        lock_match: req.lock_match.unwrap_or(false),
+        download_images: req.download_images.unwrap_or(false),
+        generate_nfo: req.generate_nfo.unwrap_or(false),
+    };
+    sibling_apply::apply_with_siblings(
+        &state.db,
+        &state.http_client,
+        &state.config,
+        &ProviderSession::new(state.plugin_manager.clone()),
+        &selection,
+        scope,
+    )
+    .await
+    .map(Json)
+    .map_err(internal_error)
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct IdentifyAutoRequest {
+    /// 为空时处理全部尚未识别的视频文件
+    pub file_ids: Option<Vec<String>>,
+    pub allow_ai: Option<bool>,
+    pub download_images: Option<bool>,
+    pub generate_nfo: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReviewQueueQuery {
+    /// pending / accepted / rejected / all，默认 pending
+    pub status: Option<String>,
+    /// 返回条目数，默认 100，最大 1000
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct ReviewAcceptRequest {
+    pub download_images: Option<bool>,
+    pub generate_nfo: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReviewChooseRequest {
+    pub provider: String,
+    pub external_id: String,
+    pub media_type: String,
+    pub lock_match: Option<bool>,
+    pub download_images: Option<bool>,
+    pub generate_nfo: Option<bool>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReviewRejectResponse {
+    pub file_id: String,
+    pub status: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/identify/auto",
+    tag = "identify",
+    request_body = IdentifyAutoRequest,
+    responses(
+        (status = 200, description = "自动识别任务已提交", body = IdentifyTaskResponse),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn auto_identify(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<IdentifyAutoRequest>,
+) -> Result<Json<IdentifyTaskResponse>, (StatusCode, String)> {
+    let description = match &req.file_ids {
+        Some(file_ids) => format!("自动识别 {} 个文件", file_ids.len()),
+        None => "自动识别未识别的文件".to_string(),
+    };
+    let task_id = state
+        .task_queue
+        .submit(
+            crate::services::task_queue::TaskType::Scrape,
+            Some(description),
+            serde_json::json!({
+                "operation": "identify_auto",
+                "file_ids": req.file_ids,
+                "allow_ai": req.allow_ai.unwrap_or(true),
+                "download_images": req.download_images.unwrap_or(true),
+                "generate_nfo": req.generate_nfo.unwrap_or(true)
+            }),
+        )
+        .await
+        .map_err(internal_error)?;
+
+    Ok(Json(IdentifyTaskResponse {
+        task_id,
+        status: "submitted".to_string(),
+        message: "Identify auto task created".to_string(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/identify/review",
+    tag = "identify",
+    params(ReviewQueueQuery),
+    responses(
+        (status = 200, description = "获取复核队列成功", body = Vec<ReviewItem>),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn list_review_queue(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ReviewQueueQuery>,
+) -> Result<Json<Vec<ReviewItem>>, (StatusCode, String)> {
+    let status = match query.status.as_deref() {
+        Some("all") => None,
+        Some(status) => Some(status),
+        None => Some(review_queue::STATUS_PENDING),
+    };
+    review_queue::list(
+        &state.db,
+        status,
+        query.limit.unwrap_or(100).clamp(1, 1000),
+        query.offset.unwrap_or(0).max(0),
+    )
+    .await
+    .map(Json)
+    .map_err(internal_error)
+}
+
+async fn require_review_item(
+    state: &AppState,
+    file_id: &str,
+) -> Result<ReviewItem, (StatusCode, String)> {
+    review_queue::get(&state.db, file_id)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Review item not found".to_string()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/identify/review/{file_id}/accept",
+    tag = "identify",
+    params(("file_id" = String, Path, description = "文件 ID")),
+    request_body = ReviewAcceptRequest,
+    responses(
+        (status = 200, description = "已应用推荐候选", body = IdentifyApplyResult),
+        (status = 404, description = "复核项不存在"),
+        (status = 409, description = "复核项没有推荐候选"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn accept_review(
+    State(state): State<Arc<AppState>>,
+    Path(file_id): Path<String>,
+    req: Option<Json<ReviewAcceptRequest>>,
+) -> Result<Json<IdentifyApplyResult>, (StatusCode, String)> {
+    let item = require_review_item(&state, &file_id).await?;
+    if item.recommended.is_none() {
+        return Err((
+            StatusCode::CONFLICT,
+            "Review item has no recommended candidate".to_string(),
+        ));
+    }
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    let defaults = AutoIdentifyOptions::default();
+    let options = AutoIdentifyOptions {
+        allow_ai: false,
+        download_images: req.download_images.unwrap_or(defaults.download_images),
+        generate_nfo: req.generate_nfo.unwrap_or(defaults.generate_nfo),
+    };
+    let metadata = review_queue::accept(
+        &state.db,
+        &state.http_client,
+        &state.config,
+        &ProviderSession::new(state.plugin_manager.clone()),
+        &file_id,
+        options,
+    )
+    .await
+    .map_err(internal_error)?;
+    Ok(Json(IdentifyApplyResult { file_id, metadata }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/identify/review/{file_id}/choose",
+    tag = "identify",
+    params(("file_id" = String, Path, description = "文件 ID")),
+    request_body = ReviewChooseRequest,
+    responses(
+        (status = 200, description = "已应用所选候选", body = IdentifyApplyResult),
+        (status = 404, description = "复核项不存在"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn choose_review_candidate(
+    State(state): State<Arc<AppState>>,
+    Path(file_id): Path<String>,
+    Json(req): Json<ReviewChooseRequest>,
+) -> Result<Json<IdentifyApplyResult>, (StatusCode, String)> {
+    require_review_item(&state, &file_id).await?;
+    let selection = ApplySelection {
+        file_id: file_id.clone(),
+        provider: req.provider,
+        external_id: req.external_id,
+        media_type: req.media_type,
+        lock_match: req.lock_match.unwrap_or(false),
+        download_images: req.download_images.unwrap_or(true),
+        generate_nfo: req.generate_nfo.unwrap_or(true),
+    };
+    let metadata = review_queue::choose(
+        &state.db,
+        &state.http_client,
+        &state.config,
+        &ProviderSession::new(state.plugin_manager.clone()),
+        &selection,
+    )
+    .await
+    .map_err(internal_error)?;
+    Ok(Json(IdentifyApplyResult { file_id, metadata }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/identify/review/{file_id}/reject",
+    tag = "identify",
+    params(("file_id" = String, Path, description = "文件 ID")),
+    responses(
+        (status = 200, description = "已拒绝", body = ReviewRejectResponse),
+        (status = 404, description = "复核项不存在"),
+        (status = 409, description = "复核项已处理"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn reject_review(
+    State(state): State<Arc<AppState>>,
+    Path(file_id): Path<String>,
+) -> Result<Json<ReviewRejectResponse>, (StatusCode, String)> {
+    require_review_item(&state, &file_id).await?;
+    let rejected = review_queue::reject(&state.db, &file_id)
+        .await
+        .map_err(internal_error)?;
+    if !rejected {
+        return Err((
+            StatusCode::CONFLICT,
+            "Review item is not pending".to_string(),
+        ));
+    }
+    Ok(Json(ReviewRejectResponse {
+        file_id,
+        status: review_queue::STATUS_REJECTED.to_string(),
+    }))
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct IdentifyReparseRequest {
+    /// 不指定时处理全部解析版本落后的文件
+    pub file_ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReparseDiffQuery {
+    /// pending / applied / rejected / all，默认 pending
+    pub status: Option<String>,
+    /// 返回条目数，默认 100，最大 1000
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/identify/reparse",
+    tag = "identify",
+    request_body = IdentifyReparseRequest,
+    responses(
+        (status = 200, description = "重新解析任务已提交", body = IdentifyTaskResponse),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn reparse_identify(
+    State(state): State<Arc<AppState>>,
+    req: Option<Json<IdentifyReparseRequest>>,
+) -> Result<Json<IdentifyTaskResponse>, (StatusCode, String)> {
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    let description = match &req.file_ids {
+        Some(file_ids) => format!("重新解析 {} 个文件", file_ids.len()),
+        None => format!("重新解析早于 {} 的文件", identify::PARSE_VERSION),
+    };
+    let task_id = state
+        .task_queue
+        .submit(
+            crate::services::task_queue::TaskType::Scrape,
+            Some(description),
+            serde_json::json!({
+                "operation": "identify_reparse",
+                "file_ids": req.file_ids
+            }),
+        )
+        .await
+        .map_err(internal_error)?;
+
+    Ok(Json(IdentifyTaskResponse {
+        task_id,
+        status: "submitted".to_string(),
+        message: "Identify reparse task created".to_string(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/identify/reparse/diffs",
+    tag = "identify",
+    params(ReparseDiffQuery),
+    responses(
+        (status = 200, description = "获取重新解析差异成功", body = Vec<ReparseDiff>),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn list_reparse_diffs(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ReparseDiffQuery>,
+) -> Result<Json<Vec<ReparseDiff>>, (StatusCode, String)> {
+    let status = match query.status.as_deref() {
+        Some("all") => None,
+        Some(status) => Some(status),
+        None => Some(reparse::STATUS_PENDING),
+    };
+    reparse::list(
+        &state.db,
+        status,
+        query.limit.unwrap_or(100).clamp(1, 1000),
+        query.offset.unwrap_or(0).max(0),
+    )
+    .await
+    .map(Json)
+    .map_err(internal_error)
+}
+
+async fn require_pending_diff(
+    state: &AppState,
+    file_id: &str,
+) -> Result<ReparseDiff, (StatusCode, String)> {
+    let diff = reparse::get(&state.db, file_id)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Reparse diff not found".to_string()))?;
+    if diff.status != reparse::STATUS_PENDING {
+        return Err((
+            StatusCode::CONFLICT,
+            "Reparse diff is not pending".to_string(),
+        ));
+    }
+    Ok(diff)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/identify/reparse/diffs/{file_id}/approve",
+    tag = "identify",
+    params(("file_id" = String, Path, description = "文件 ID")),
+    request_body = ReviewAcceptRequest,
+    responses(
+        (status = 200, description = "已按新解析结果重新应用", body = IdentifyApplyResult),
+        (status = 404, description = "差异不存在"),
+        (status = 409, description = "差异已处理"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn approve_reparse_diff(
+    State(state): State<Arc<AppState>>,
+    Path(file_id): Path<String>,
+    req: Option<Json<ReviewAcceptRequest>>,
+) -> Result<Json<IdentifyApplyResult>, (StatusCode, String)> {
+    require_pending_diff(&state, &file_id).await?;
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    let defaults = AutoIdentifyOptions::default();
+    let options = AutoIdentifyOptions {
+        allow_ai: false,
+        download_images: req.download_images.unwrap_or(defaults.download_images),
+        generate_nfo: req.generate_nfo.unwrap_or(defaults.generate_nfo),
+    };
+    let metadata = reparse::approve(
+        &state.db,
+        &state.http_client,
+        &state.config,
+        &ProviderSession::new(state.plugin_manager.clone()),
+        &file_id,
+        options,
+    )
+    .await
+    .map_err(internal_error)?;
+    Ok(Json(IdentifyApplyResult { file_id, metadata }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/identify/reparse/diffs/{file_id}/reject",
+    tag = "identify",
+    params(("file_id" = String, Path, description = "文件 ID")),
+    responses(
+        (status = 200, description = "已拒绝，保留原匹配", body = ReviewRejectResponse),
+        (status = 404, description = "差异不存在"),
+        (status = 409, description = "差异已处理"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn reject_reparse_diff(
+    State(state): State<Arc<AppState>>,
+    Path(file_id): Path<String>,
+) -> Result<Json<ReviewRejectResponse>, (StatusCode, String)> {
+    require_pending_diff(&state, &file_id).await?;
+    reparse::reject(&state.db, &file_id)
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(ReviewRejectResponse {
+        file_id,
+        status: reparse::STATUS_REJECTED.to_string(),
+    }))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AliasQuery {
+    /// 按标题或数据源 ID 过滤
+    pub q: Option<String>,
+    /// 返回条目数，默认 100，最大 1000
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/identify/aliases",
+    tag = "identify",
+    params(AliasQuery),
+    responses(
+        (status = 200, description = "获取标题别名成功", body = Vec<TitleAlias>),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn list_aliases(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AliasQuery>,
+) -> Result<Json<Vec<TitleAlias>>, (StatusCode, String)> {
+    title_aliases::list(
+        &state.db,
+        query.q.as_deref().filter(|q| !q.trim().is_empty()),
+        query.limit.unwrap_or(100).clamp(1, 1000),
+        query.offset.unwrap_or(0).max(0),
+    )
+    .await
+    .map(Json)
+    .map_err(internal_error)
+}
+
+fn validate_alias(input: &AliasInput) -> Result<(), (StatusCode, String)> {
+    if identify::normalize_title(&input.title).is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "title is required".to_string()));
+    }
+    if input.provider.trim().is_empty() || input.external_id.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "provider and external_id are required".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/identify/aliases",
+    tag = "identify",
+    request_body = AliasInput,
+    responses(
+        (status = 200, description = "已保存标题别名", body = TitleAlias),
+        (status = 400, description = "请求参数错误"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn create_alias(
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<AliasInput>,
+) -> Result<Json<TitleAlias>, (StatusCode, String)> {
+    validate_alias(&input)?;
+    title_aliases::upsert(&state.db, &input, title_aliases::SOURCE_API)
+        .await
+        .map(Json)
+        .map_err(internal_error)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/identify/aliases/{id}",
+    tag = "identify",
+    params(("id" = String, Path, description = "别名 ID")),
+    request_body = AliasInput,
+    responses(
+        (status = 200, description = "已修改标题别名", body = TitleAlias),
+        (status = 400, description = "请求参数错误"),
+        (status = 404, description = "别名不存在"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn update_alias(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(input): Json<AliasInput>,
+) -> Result<Json<TitleAlias>, (StatusCode, String)> {
+    validate_alias(&input)?;
+    title_aliases::update(&state.db, &id, &input)
+        .await
+        .map_err(internal_error)?
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Alias not found".to_string()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/identify/aliases/{id}",
+    tag = "identify",
+    params(("id" = String, Path, description = "别名 ID")),
+    responses(
+        (status = 204, description = "已删除标题别名"),
+        (status = 404, description = "别名不存在"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn delete_alias(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    if title_aliases::delete(&state.db, &id)
+        .await
+        .map_err(internal_error)?
+    {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err((StatusCode::NOT_FOUND, "Alias not found".to_string()))
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ExternalIdQuery {
+    pub provider: String,
+    pub external_id: String,
+    /// movie / tv
+    pub media_type: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ExternalIdLookupQuery {
+    /// tmdb / imdb / tvdb / bgm / wikidata …
+    pub id_type: String,
+    pub value: String,
+    /// movie / tv，不指定时不限类型
+    pub media_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ExternalIdUnlinkQuery {
+    pub provider: String,
+    pub external_id: String,
+    pub media_type: String,
+    pub id_type: String,
+}
+
+/// 为作品登记一个外部 ID（如 Bangumi 条目对应的 TMDb 剧集）
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ExternalIdLinkRequest {
+    pub provider: String,
+    pub external_id: String,
+    /// movie / tv
+    pub media_type: String,
+    pub id_type: String,
+    pub value: String,
+}
+
+fn title_ref(
+    provider: &str,
+    external_id: &str,
+    media_type: &str,
+) -> Result<TitleRef, (StatusCode, String)> {
+    if provider.trim().is_empty() || external_id.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "provider and external_id are required".to_string(),
+        ));
+    }
+    if !matches!(media_type, "movie" | "tv") {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "media_type must be movie or tv".to_string(),
+        ));
+    }
+    Ok(TitleRef::new(
+        provider.trim(),
+        external_id.trim(),
+        media_type,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/identify/external-ids",
+    tag = "identify",
+    params(ExternalIdQuery),
+    responses(
+        (status = 200, description = "获取作品的外部 ID 成功", body = Vec<ExternalId>),
+        (status = 400, description = "请求参数错误"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn list_external_ids(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ExternalIdQuery>,
+) -> Result<Json<Vec<ExternalId>>, (StatusCode, String)> {
+    let title = title_ref(&query.provider, &query.external_id, &query.media_type)?;
+    external_ids::for_title(&state.db, &title)
+        .await
+        .map(Json)
+        .map_err(internal_error)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/identify/external-ids/lookup",
+    tag = "identify",
+    params(ExternalIdLookupQuery),
+    responses(
+        (status = 200, description = "按外部 ID 查找作品成功", body = Vec<TitleRef>),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn lookup_external_id(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ExternalIdLookupQuery>,
+) -> Result<Json<Vec<TitleRef>>, (StatusCode, String)> {
+    external_ids::resolve(
+        &state.db,
+        &query.id_type,
+        &query.value,
+        query.media_type.as_deref(),
+    )
+    .await
+    .map(Json)
+    .map_err(internal_error)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/identify/external-ids",
+    tag = "identify",
+    request_body = ExternalIdLinkRequest,
+    responses(
+        (status = 200, description = "已登记外部 ID", body = Vec<ExternalId>),
+        (status = 400, description = "请求参数错误"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn link_external_id(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ExternalIdLinkRequest>,
+) -> Result<Json<Vec<ExternalId>>, (StatusCode, String)> {
+    let title = title_ref(&req.provider, &req.external_id, &req.media_type)?;
+    let id_type = req.id_type.trim().to_ascii_lowercase();
+    if id_type.is_empty() || req.value.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "id_type and value are required".to_string(),
+        ));
+    }
+    if id_type == title.provider {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "id_type must differ from provider".to_string(),
+        ));
+    }
+    external_ids::link(&state.db, &title, &id_type, &req.value)
+        .await
+        .map_err(internal_error)?;
+    external_ids::for_title(&state.db, &title)
+        .await
+        .map(Json)
+        .map_err(internal_error)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/identify/external-ids",
+    tag = "identify",
+    params(ExternalIdUnlinkQuery),
+    responses(
+        (status = 204, description = "已删除外部 ID"),
+        (status = 400, description = "请求参数错误"),
+        (status = 404, description = "外部 ID 不存在"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn unlink_external_id(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ExternalIdUnlinkQuery>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let title = title_ref(&query.provider, &query.external_id, &query.media_type)?;
+    if external_ids::unlink(&state.db, &title, &query.id_type)
+        .await
+        .map_err(internal_error)?
+    {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err((StatusCode::NOT_FOUND, "External id not found".to_string()))
+    }
+}
+
 fn internal_error<E: std::fmt::Display>(err: E) -> (axum::http::StatusCode, String) {
     (
         axum::http::StatusCode::INTERNAL_SERVER_ERROR,
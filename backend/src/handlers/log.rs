@@ -1,10 +1,12 @@
 use crate::handlers::AppState;
-use crate::services::{log, renamer};
+use crate::services::log;
 use axum::{
     extract::{Path, State},
     response::Json,
 };
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use utoipa::ToSchema;
 
 /// 获取操作记录（最多 200 条，防止大库时响应过大）
 pub async fn list_operation_logs(
@@ -21,10 +23,68 @@ pub async fn undo_operation(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
 ) -> Result<Json<String>, (axum::http::StatusCode, String)> {
-    // 目前重命名支持撤销
-    renamer::undo_rename_by_log(&state.db, &id)
+    // 执行日志中记录的逆操作（重命名、移动、复制、回收站、恢复等）
+    log::undo_operation(&state.db, &id)
         .await
         .map_err(|e| (axum::http::StatusCode::BAD_REQUEST, e.to_string()))?;
 
     Ok(Json("Operation successfully undone".to_string()))
 }
+
+#[derive(Default, Deserialize, ToSchema)]
+pub struct UndoBatchRequest {
+    /// 仅预演，不实际撤销（默认 true）
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum UndoBatchResponse {
+    Preview(log::BatchUndoReport),
+    Task(crate::handlers::tasks::TaskActionResponse),
+}
+
+/// 整批撤销：按相反顺序撤销同一批次/任务产生的所有操作
+#[utoipa::path(
+    post,
+    path = "/api/logs/batches/{batch_id}/undo",
+    tag = "log",
+    params(("batch_id" = String, Path, description = "批次 ID（任务 ID 或批量请求返回的 batch_id）")),
+    request_body = UndoBatchRequest,
+    responses(
+        (status = 200, description = "预演报告或已提交的撤销任务", body = UndoBatchResponse),
+        (status = 404, description = "批次不存在")
+    )
+)]
+pub async fn undo_batch(
+    State(state): State<Arc<AppState>>,
+    Path(batch_id): Path<String>,
+    req: Option<Json<UndoBatchRequest>>,
+) -> Result<Json<UndoBatchResponse>, (axum::http::StatusCode, String)> {
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    let report = log::preview_batch_undo(&state.db, &batch_id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::NOT_FOUND, e.to_string()))?;
+
+    if req.dry_run.unwrap_or(true) {
+        return Ok(Json(UndoBatchResponse::Preview(report)));
+    }
+
+    let task_id = state
+        .task_queue
+        .submit(
+            crate::services::task_queue::TaskType::Custom("undo_batch".to_string()),
+            Some(format!("撤销批次 {} 的 {} 项操作", batch_id, report.total)),
+            serde_json::json!({ "batch_id": batch_id }),
+        )
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(UndoBatchResponse::Task(
+        crate::handlers::tasks::TaskActionResponse {
+            task_id,
+            status: "submitted".to_string(),
+            message: format!("Undo task submitted for {} operations", report.total),
+        },
+    )))
+}
@@ -0,0 +1,203 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::handlers::AppState;
+use crate::services::metadata_edit::{self, HistoryEntry, MetadataState};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MetadataEditRequest {
+    /// 要修改的字段，如 `{"title": "...", "year": 2020}`；值为 null 表示清空
+    #[schema(value_type = Object)]
+    pub fields: serde_json::Map<String, serde_json::Value>,
+    /// 同时锁定修改的字段，默认 true
+    pub lock: Option<bool>,
+    /// 修改后重新生成 NFO
+    pub generate_nfo: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MetadataLocksRequest {
+    #[serde(default)]
+    pub lock: Vec<String>,
+    #[serde(default)]
+    pub unlock: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct MetadataHistoryQuery {
+    /// 返回条目数，默认 50，最大 500
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+fn internal_error(error: anyhow::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+}
+
+fn file_not_found() -> (StatusCode, String) {
+    (StatusCode::NOT_FOUND, "File not found".to_string())
+}
+
+/// 获取文件元数据与字段锁
+#[utoipa::path(
+    get,
+    path = "/api/files/{id}/metadata",
+    tag = "metadata",
+    params(("id" = String, Path, description = "文件 ID")),
+    responses(
+        (status = 200, description = "获取元数据成功", body = MetadataState),
+        (status = 404, description = "文件不存在"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_metadata(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<MetadataState>, (StatusCode, String)> {
+    metadata_edit::get(&state.db, &id)
+        .await
+        .map_err(internal_error)?
+        .map(Json)
+        .ok_or_else(file_not_found)
+}
+
+/// 人工修改元数据字段
+///
+/// 修改前的版本记入历史；默认同时锁定修改的字段，重新刮削时保留人工值。
+#[utoipa::path(
+    put,
+    path = "/api/files/{id}/metadata",
+    tag = "metadata",
+    params(("id" = String, Path, description = "文件 ID")),
+    request_body = MetadataEditRequest,
+    responses(
+        (status = 200, description = "修改成功", body = MetadataState),
+        (status = 400, description = "字段不可编辑或取值无效"),
+        (status = 404, description = "文件不存在"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn edit_metadata(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(payload): Json<MetadataEditRequest>,
+) -> Result<Json<MetadataState>, (StatusCode, String)> {
+    metadata_edit::validate_fields(&payload.fields)
+        .map_err(|message| (StatusCode::BAD_REQUEST, message))?;
+    let edited = metadata_edit::edit(
+        &state.db,
+        &id,
+        &payload.fields,
+        payload.lock.unwrap_or(true),
+    )
+    .await
+    .map_err(internal_error)?
+    .ok_or_else(file_not_found)?;
+
+    if payload.generate_nfo.unwrap_or(false) {
+        let path: String = sqlx::query_scalar("SELECT path FROM media_files WHERE id = ?")
+            .bind(&id)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|error| internal_error(error.into()))?;
+        let media_type = match edited.metadata.get("media_type").and_then(|v| v.as_str()) {
+            Some("tv") => "tvshow",
+            _ => "movie",
+        };
+        crate::services::nfo::generate_nfo_file(&path, &edited.metadata, media_type)
+            .await
+            .map_err(internal_error)?;
+    }
+    Ok(Json(edited))
+}
+
+/// 加锁 / 解锁元数据字段
+#[utoipa::path(
+    put,
+    path = "/api/files/{id}/metadata/locks",
+    tag = "metadata",
+    params(("id" = String, Path, description = "文件 ID")),
+    request_body = MetadataLocksRequest,
+    responses(
+        (status = 200, description = "更新字段锁成功", body = MetadataState),
+        (status = 400, description = "字段不可锁定"),
+        (status = 404, description = "文件不存在"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn update_metadata_locks(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(payload): Json<MetadataLocksRequest>,
+) -> Result<Json<MetadataState>, (StatusCode, String)> {
+    metadata_edit::validate_lock_names(&payload.lock)
+        .and_then(|_| metadata_edit::validate_lock_names(&payload.unlock))
+        .map_err(|message| (StatusCode::BAD_REQUEST, message))?;
+    metadata_edit::set_locks(&state.db, &id, &payload.lock, &payload.unlock)
+        .await
+        .map_err(internal_error)?
+        .map(Json)
+        .ok_or_else(file_not_found)
+}
+
+/// 元数据历史版本（新的在前），含每次人工修改 / 重新刮削 / 回滚的字段差异
+#[utoipa::path(
+    get,
+    path = "/api/files/{id}/metadata/history",
+    tag = "metadata",
+    params(("id" = String, Path, description = "文件 ID"), MetadataHistoryQuery),
+    responses(
+        (status = 200, description = "获取历史成功", body = Vec<HistoryEntry>),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn list_metadata_history(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<MetadataHistoryQuery>,
+) -> Result<Json<Vec<HistoryEntry>>, (StatusCode, String)> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    let offset = query.offset.unwrap_or(0).max(0);
+    metadata_edit::history(&state.db, &id, limit, offset)
+        .await
+        .map(Json)
+        .map_err(internal_error)
+}
+
+/// 回滚到历史版本（元数据与字段锁一并恢复）
+#[utoipa::path(
+    post,
+    path = "/api/files/{id}/metadata/history/{history_id}/revert",
+    tag = "metadata",
+    params(
+        ("id" = String, Path, description = "文件 ID"),
+        ("history_id" = i64, Path, description = "历史版本 ID")
+    ),
+    responses(
+        (status = 200, description = "回滚成功", body = MetadataState),
+        (status = 404, description = "文件或历史版本不存在"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn revert_metadata(
+    State(state): State<Arc<AppState>>,
+    Path((id, history_id)): Path<(String, i64)>,
+) -> Result<Json<MetadataState>, (StatusCode, String)> {
+    metadata_edit::revert(&state.db, &id, history_id)
+        .await
+        .map_err(internal_error)?
+        .map(Json)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                "Metadata history entry not found".to_string(),
+            )
+        })
+}
@@ -7,11 +7,15 @@ use crate::services::plugin::PluginManager;
 use crate::services::progress_hub::ProgressHub;
 use crate::services::task_queue::TaskQueue;
 
+pub mod catalog;
+pub mod collections;
 pub mod dedupe;
 pub mod hash;
 pub mod identify;
+pub mod metadata;
 pub mod metrics;
 pub mod nfo;
+pub mod people;
 pub mod performance_monitor;
 pub mod queue_stats;
 pub mod rename;
@@ -0,0 +1,84 @@
+use crate::handlers::AppState;
+use crate::models::{Person, PersonCredit};
+use crate::services::people;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Deserialize, IntoParams)]
+pub struct PeopleQuery {
+    /// 按姓名（含原名）模糊搜索
+    pub search: Option<String>,
+    /// 返回条目数，默认 100，最大 1000
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PersonDetail {
+    pub person: Person,
+    /// 参与的全部作品
+    pub titles: Vec<PersonCredit>,
+}
+
+fn internal_error(error: anyhow::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+}
+
+/// 列出演职人员
+#[utoipa::path(
+    get,
+    path = "/api/people",
+    tag = "catalog",
+    params(PeopleQuery),
+    responses(
+        (status = 200, description = "获取人员列表成功", body = Vec<Person>),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn list_people(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<PeopleQuery>,
+) -> Result<Json<Vec<Person>>, (StatusCode, String)> {
+    let search = query
+        .search
+        .as_deref()
+        .filter(|search| !search.trim().is_empty());
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+    let offset = query.offset.unwrap_or(0).max(0);
+    people::list_people(&state.db, search, limit, offset)
+        .await
+        .map(Json)
+        .map_err(internal_error)
+}
+
+/// 获取人员及其参与的全部作品
+#[utoipa::path(
+    get,
+    path = "/api/people/{id}",
+    tag = "catalog",
+    params(("id" = String, Path, description = "人员 ID，如 tmdb:287")),
+    responses(
+        (status = 200, description = "获取人员成功", body = PersonDetail),
+        (status = 404, description = "人员不存在"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_person(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<PersonDetail>, (StatusCode, String)> {
+    let person = people::get_person(&state.db, &id)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Person not found".to_string()))?;
+    let titles = people::titles_for_person(&state.db, &id)
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(PersonDetail { person, titles }))
+}
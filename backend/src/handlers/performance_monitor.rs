@@ -8,6 +8,7 @@ use std::sync::Arc;
 
 use crate::handlers::AppState;
 use crate::services::metrics::{PerformanceAnomaly, PerformanceTrend, ResourceStats};
+use crate::services::provider_guard::{self, CircuitState, ProviderStatus};
 
 /// 获取性能趋势分析
 #[utoipa::path(
@@ -175,6 +176,17 @@ pub async fn get_system_health(
         health_score *= 0.9;
     }
 
+    // 元数据源熔断影响健康分数
+    let providers = provider_guard::statuses();
+    let open_providers: Vec<&str> = providers
+        .iter()
+        .filter(|p| p.circuit != CircuitState::Closed)
+        .map(|p| p.provider.as_str())
+        .collect();
+    if !open_providers.is_empty() {
+        health_score *= 0.9;
+    }
+
     // 失败率影响健康分数
     let total_tasks = queue_stats.completed_tasks + queue_stats.failed_tasks;
     if total_tasks > 0 {
@@ -265,6 +277,19 @@ pub async fn get_system_health(
             },
             message: format!("High-retry tasks: {}", high_retry_tasks),
         },
+        HealthCheck {
+            name: "Metadata Providers".to_string(),
+            status: if open_providers.is_empty() {
+                HealthStatus::Healthy
+            } else {
+                HealthStatus::Warning
+            },
+            message: if open_providers.is_empty() {
+                format!("Providers tracked: {}", providers.len())
+            } else {
+                format!("Circuit open: {}", open_providers.join(", "))
+            },
+        },
     ];
 
     let health = SystemHealth {
@@ -274,6 +299,7 @@ pub async fn get_system_health(
         resource_stats,
         queue_stats: Some(queue_stats),
         checks,
+        providers,
     };
 
     Ok(Json(health))
@@ -287,6 +313,8 @@ pub struct SystemHealth {
     pub resource_stats: Option<ResourceStats>,
     pub queue_stats: Option<crate::services::task_queue::QueueStats>,
     pub checks: Vec<HealthCheck>,
+    /// 各元数据源的限流与熔断状态
+    pub providers: Vec<ProviderStatus>,
 }
 
 #[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
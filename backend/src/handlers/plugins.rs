@@ -1,7 +1,15 @@
 use crate::handlers::AppState;
+use crate::services::identify;
+use crate::services::metadata_provider::{ProviderInfo, ProviderRegistry};
 use crate::services::plugin::PluginInfo;
-use axum::{extract::State, response::Json};
+use crate::services::provider_cache::{self, CacheEntrySummary, CacheStats};
+use axum::{
+    extract::{Query, State},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
 
 /// 列出所有加载的插件
 #[utoipa::path(
@@ -26,3 +34,115 @@ pub async fn list_plugins(
     let plugins = state.plugin_manager.list_plugins().await;
     Ok(Json(plugins))
 }
+
+/// 列出可用的元数据源（内置 + 已加载插件）
+#[utoipa::path(
+    get,
+    path = "/api/providers",
+    tag = "system",
+    responses(
+        (status = 200, description = "获取数据源列表成功", body = Vec<ProviderInfo>),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn list_providers(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<ProviderInfo>>, (axum::http::StatusCode, String)> {
+    let settings = identify::load_runtime_settings(&state.db, &state.config)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let registry =
+        ProviderRegistry::from_settings(&state.http_client, &settings, &state.plugin_manager).await;
+    Ok(Json(registry.list()))
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ProviderCacheQuery {
+    pub provider: Option<String>,
+    /// 返回条目数，默认 100，最大 1000
+    pub limit: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ProviderCacheResponse {
+    pub offline: bool,
+    pub stats: Vec<CacheStats>,
+    pub entries: Vec<CacheEntrySummary>,
+}
+
+/// 查看元数据源响应缓存
+#[utoipa::path(
+    get,
+    path = "/api/providers/cache",
+    tag = "system",
+    params(ProviderCacheQuery),
+    responses(
+        (status = 200, description = "获取缓存信息成功", body = ProviderCacheResponse),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_provider_cache(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ProviderCacheQuery>,
+) -> Result<Json<ProviderCacheResponse>, (axum::http::StatusCode, String)> {
+    let internal =
+        |e: anyhow::Error| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+    let settings = identify::load_runtime_settings(&state.db, &state.config)
+        .await
+        .map_err(internal)?;
+    let stats = provider_cache::stats(&state.db).await.map_err(internal)?;
+    let entries = provider_cache::list_entries(
+        &state.db,
+        query.provider.as_deref(),
+        query.limit.unwrap_or(100).clamp(1, 1000),
+    )
+    .await
+    .map_err(internal)?;
+
+    Ok(Json(ProviderCacheResponse {
+        offline: settings.provider_offline,
+        stats,
+        entries,
+    }))
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct PurgeProviderCacheQuery {
+    pub provider: Option<String>,
+    /// search / details / episodes / artwork
+    pub endpoint: Option<String>,
+    /// 只删除已过期条目
+    pub expired_only: Option<bool>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PurgeProviderCacheResponse {
+    pub removed: u64,
+}
+
+/// 清理元数据源响应缓存
+#[utoipa::path(
+    delete,
+    path = "/api/providers/cache",
+    tag = "system",
+    params(PurgeProviderCacheQuery),
+    responses(
+        (status = 200, description = "清理缓存成功", body = PurgeProviderCacheResponse),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn purge_provider_cache(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<PurgeProviderCacheQuery>,
+) -> Result<Json<PurgeProviderCacheResponse>, (axum::http::StatusCode, String)> {
+    let removed = provider_cache::purge(
+        &state.db,
+        query.provider.as_deref(),
+        query.endpoint.as_deref(),
+        query.expired_only.unwrap_or(false),
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(PurgeProviderCacheResponse { removed }))
+}
@@ -11,6 +11,8 @@ pub struct RenameRequest {
     pub file_ids: Vec<String>,
     pub template: String, // 例如: "{title}.S{season:02d}E{episode:02d}.{ext}"
     pub preview: Option<bool>,
+    /// 冲突解决策略：suffix（默认）、skip、fail
+    pub conflict_policy: Option<renamer::ConflictPolicy>,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -18,6 +20,22 @@ pub struct RenamePreview {
     pub file_id: String,
     pub old_name: String,
     pub new_name: String,
+    pub new_path: String,
+    pub action: renamer::PlanAction,
+    pub issues: Vec<renamer::RenameIssue>,
+}
+
+impl From<renamer::RenamePlanItem> for RenamePreview {
+    fn from(item: renamer::RenamePlanItem) -> Self {
+        Self {
+            file_id: item.file_id,
+            old_name: item.old_name,
+            new_name: item.new_name,
+            new_path: item.new_path,
+            action: item.action,
+            issues: item.issues,
+        }
+    }
 }
 
 #[derive(Serialize, ToSchema)]
@@ -25,6 +43,11 @@ pub struct RenamePreview {
 pub enum RenameActionResponse {
     Preview {
         preview: Vec<RenamePreview>,
+        conflict_policy: renamer::ConflictPolicy,
+        rename_count: usize,
+        skipped_count: usize,
+        blocked_count: usize,
+        executable: bool,
         message: String,
     },
     Task(crate::handlers::tasks::TaskActionResponse),
@@ -38,6 +61,7 @@ pub enum RenameActionResponse {
     request_body = RenameRequest,
     responses(
         (status = 200, description = "重命名请求成功（预览或任务提交）", body = RenameActionResponse),
+        (status = 409, description = "fail 策略下计划存在冲突，未执行"),
         (status = 500, description = "服务器内部错误")
     )
 )]
@@ -64,31 +88,51 @@ pub async fn batch_rename(
         .await
         .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    // 生成重命名预览
-    let preview_list: Vec<RenamePreview> = files
+    // 生成重命名计划（检测批内重复、已存在目标、大小写变化、超长与非法字符）
+    // 按请求顺序生成目标名，计划与执行顺序保持一致
+    let proposals: Vec<(String, String)> = req
+        .file_ids
         .iter()
+        .filter_map(|file_id| files.iter().find(|file| &file.id == file_id))
         .filter_map(|file| {
-            renamer::generate_new_name(file, &req.template).map(|new_name| RenamePreview {
-                file_id: file.id.clone(),
-                old_name: file.name.clone(),
-                new_name,
-            })
+            renamer::generate_new_name(file, &req.template).map(|name| (file.id.clone(), name))
         })
         .collect();
+    let plan = renamer::plan_renames(&files, &proposals, req.conflict_policy.unwrap_or_default());
 
     // 如果只是预览，直接返回
     if preview {
+        let message = if plan.executable {
+            "Preview generated".to_string()
+        } else {
+            format!(
+                "Preview generated with {} blocked files",
+                plan.blocked_count
+            )
+        };
         return Ok(Json(RenameActionResponse::Preview {
-            preview: preview_list,
-            message: "Preview generated".to_string(),
+            conflict_policy: plan.policy,
+            rename_count: plan.rename_count,
+            skipped_count: plan.skipped_count,
+            blocked_count: plan.blocked_count,
+            executable: plan.executable,
+            preview: plan.items.into_iter().map(RenamePreview::from).collect(),
+            message,
         }));
     }
 
-    // 执行重命名任务
-    let rename_items: Vec<(String, String)> = preview_list
-        .iter()
-        .map(|p| (p.file_id.clone(), p.new_name.clone()))
-        .collect();
+    if !plan.executable {
+        return Err((
+            axum::http::StatusCode::CONFLICT,
+            format!(
+                "Rename plan has {} conflicting files; preview and resolve them first",
+                plan.blocked_count
+            ),
+        ));
+    }
+
+    // 执行重命名任务（按计划顺序，保证链式改名正确）
+    let rename_items = plan.rename_items();
     let count = rename_items.len();
 
     let task_id = state
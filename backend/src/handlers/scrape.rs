@@ -4,7 +4,8 @@ use std::sync::Arc;
 use utoipa::ToSchema;
 
 use crate::handlers::AppState;
-use crate::services::{identify, quality, video};
+use crate::services::metadata_provider::ProviderSession;
+use crate::services::{identify, quality, release_parser, video};
 use chrono::Utc;
 
 #[derive(Deserialize, ToSchema)]
@@ -57,14 +58,21 @@ pub async fn scrape_metadata(
     };
 
     let auto_match = req.auto_match.unwrap_or(true);
+    let providers = ProviderSession::new(state.plugin_manager.clone());
     let download_images = req.download_images.unwrap_or(false);
     let generate_nfo = req.generate_nfo.unwrap_or(false);
 
     if !auto_match {
-        let preview =
-            identify::preview_file(&state.db, &state.http_client, &state.config, &file, true)
-                .await
-                .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        let preview = identify::preview_file(
+            &state.db,
+            &state.http_client,
+            &state.config,
+            &providers,
+            &file,
+            true,
+        )
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
         let metadata = preview
             .candidates
@@ -95,84 +103,81 @@ pub async fn scrape_metadata(
         }));
     }
 
-    let metadata = if let Some(tmdb_id) = req.tmdb_id {
-        identify::apply_selection(
+    let selection = if let Some(tmdb_id) = req.tmdb_id {
+        Ok(identify::ApplySelection {
+            file_id: file.id.clone(),
+            provider: "tmdb".to_string(),
+            external_id: tmdb_id.to_string(),
+            media_type: if file.name.contains('S') || file.name.contains('E') {
+                "tv".to_string()
+            } else {
+                "movie".to_string()
+            },
+            lock_match: false,
+            download_images,
+            generate_nfo,
+        })
+    } else {
+        let preview = identify::preview_file(
             &state.db,
             &state.http_client,
             &state.config,
-            &identify::ApplySelection {
-                file_id: file.id.clone(),
-                provider: "tmdb".to_string(),
-                external_id: tmdb_id.to_string(),
-                media_type: if file.name.contains('S') || file.name.contains('E') {
-                    "tv".to_string()
-                } else {
-                    "movie".to_string()
-                },
-                lock_match: false,
-                download_images,
-                generate_nfo,
-            },
+            &providers,
+            &file,
+            true,
         )
-        .await
-    } else {
-        let preview =
-            identify::preview_file(&state.db, &state.http_client, &state.config, &file, true).await;
+        .await;
         match preview {
-            Ok(preview) => {
-                if let Some(recommended) = preview.recommended {
-                    identify::apply_selection(
-                        &state.db,
-                        &state.http_client,
-                        &state.config,
-                        &identify::ApplySelection {
-                            file_id: file.id.clone(),
-                            provider: recommended.provider,
-                            external_id: recommended.external_id,
-                            media_type: recommended.media_type,
-                            lock_match: false,
-                            download_images,
-                            generate_nfo,
-                        },
-                    )
-                    .await
-                } else {
-                    Err(anyhow::anyhow!("No matching candidate found"))
-                }
-            }
+            Ok(preview) => preview
+                .recommended
+                .map(|recommended| identify::ApplySelection {
+                    file_id: file.id.clone(),
+                    provider: recommended.provider,
+                    external_id: recommended.external_id,
+                    media_type: recommended.media_type,
+                    lock_match: false,
+                    download_images,
+                    generate_nfo,
+                })
+                .ok_or_else(|| anyhow::anyhow!("No matching candidate found")),
             Err(err) => Err(err),
         }
     };
+    let metadata = match selection {
+        Ok(selection) => {
+            identify::apply_selection(
+                &state.db,
+                &state.http_client,
+                &state.config,
+                &providers,
+                &selection,
+            )
+            .await
+        }
+        Err(err) => Err(err),
+    };
 
     match metadata {
         Ok(metadata) => {
-            // 解析 TMDB ID
-            let tmdb_id = metadata
-                .get("tmdb_id")
-                .and_then(|v| v.as_u64())
-                .map(|v| v as u32);
-
-            // 执行视频质量分析
+            // 执行视频质量分析（元数据已由 apply_selection 写入）
             let video_info = video::extract_video_info(&file.path).await.ok();
-            let quality_score = video_info.as_ref().map(quality::calculate_quality_score);
-
-            // 保存到数据库
-            let metadata_json = serde_json::to_string(&metadata).unwrap_or_default();
+            let release = release_parser::parse(&file.name);
+            let quality_score =
+                quality::calculate_file_quality_score(video_info.as_ref(), &release);
             let video_info_json = video_info
                 .as_ref()
                 .map(|info| serde_json::to_string(info).unwrap_or_default());
 
-            let _ = sqlx::query(
-                "UPDATE media_files SET metadata = ?, video_info = ?, tmdb_id = ?, quality_score = ?, updated_at = ? WHERE id = ?"
+            sqlx::query(
+                "UPDATE media_files SET video_info = ?, quality_score = ?, updated_at = ? WHERE id = ?",
             )
-            .bind(metadata_json)
             .bind(video_info_json)
-            .bind(tmdb_id)
             .bind(quality_score)
             .bind(Utc::now())
             .bind(&file.id)
             .execute(&state.db)
-            .await;
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
             Ok(Json(ScrapeResponse {
                 metadata: Some(metadata),
@@ -1,4 +1,5 @@
 use crate::handlers::AppState;
+use crate::services::ai_parser;
 use axum::{
     extract::{Query, State},
     response::Json,
@@ -130,9 +131,14 @@ fn get_default_settings() -> Vec<(&'static str, &'static str)> {
         ("cloudflare_api_token", ""),
         ("cloudflare_ai_model", "@cf/meta/llama-3.1-8b-instruct"),
         ("cloudflare_ai_base_url", ""),
+        ("ai_backend", ai_parser::BACKEND_CLOUDFLARE),
+        ("openai_base_url", ai_parser::DEFAULT_OPENAI_BASE_URL),
+        ("openai_api_key", ""),
+        ("openai_model", ai_parser::DEFAULT_OPENAI_MODEL),
         ("ai_mode", "assist"),
         ("ai_budget_mode", "strict_free"),
         ("ai_daily_budget", "100"),
+        ("provider_offline_mode", "false"),
     ]
 }
 
@@ -216,6 +222,9 @@ pub async fn health_check_settings(
         "tmdb" => check_tmdb(&state.http_client, &settings).await,
         "bangumi" => check_bangumi(&state.http_client, &settings).await,
         "cloudflare" | "cloudflare_ai" => check_cloudflare_ai(&state.http_client, &settings).await,
+        "openai" | "openai_compatible" => {
+            check_openai_compatible(&state.http_client, &settings).await
+        }
         _ => {
             return Err((
                 axum::http::StatusCode::BAD_REQUEST,
@@ -230,7 +239,11 @@ pub async fn health_check_settings(
 fn is_sensitive_key(key: &str) -> bool {
     matches!(
         key,
-        "tmdb_api_key" | "bgm_api_key" | "cloudflare_api_token" | "cloudflare_account_id"
+        "tmdb_api_key"
+            | "bgm_api_key"
+            | "cloudflare_api_token"
+            | "cloudflare_account_id"
+            | "openai_api_key"
     )
 }
 
@@ -407,6 +420,56 @@ async fn check_cloudflare_ai(
     })
 }
 
+async fn check_openai_compatible(
+    client: &reqwest::Client,
+    settings: &HashMap<String, String>,
+) -> Result<SettingsHealthCheckResponse, (axum::http::StatusCode, String)> {
+    let base_url = string_setting(settings, "openai_base_url")
+        .unwrap_or_else(|| ai_parser::DEFAULT_OPENAI_BASE_URL.to_string());
+    let model = string_setting(settings, "openai_model")
+        .unwrap_or_else(|| ai_parser::DEFAULT_OPENAI_MODEL.to_string());
+    let url = format!("{}/chat/completions", base_url.trim_end_matches('/'));
+    let payload = json!({
+        "model": model,
+        "messages": [
+            { "role": "system", "content": "Reply with OK only." },
+            { "role": "user", "content": "health check" }
+        ],
+        "max_tokens": 8
+    });
+
+    let mut request = client.post(&url).json(&payload);
+    if let Some(api_key) = string_setting(settings, "openai_api_key") {
+        request = request.bearer_auth(api_key);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(http_error("OpenAI-compatible request failed"))?;
+    let payload: Value = parse_json_response(response, "OpenAI-compatible endpoint").await?;
+    let content = payload
+        .get("choices")
+        .and_then(Value::as_array)
+        .and_then(|choices| choices.first())
+        .and_then(|choice| choice.get("message"))
+        .and_then(|message| message.get("content"))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+
+    let mut details = HashMap::new();
+    details.insert("base_url".to_string(), base_url);
+    details.insert("model".to_string(), model);
+    details.insert("reply".to_string(), content);
+
+    Ok(SettingsHealthCheckResponse {
+        provider: "openai_compatible".to_string(),
+        ok: true,
+        message: "OpenAI 兼容端点连接正常".to_string(),
+        details,
+    })
+}
+
 fn http_error(
     prefix: &'static str,
 ) -> impl Fn(reqwest::Error) -> (axum::http::StatusCode, String) + Send + Sync + 'static {
@@ -19,6 +19,8 @@ pub struct TrashListResponse {
 #[derive(Deserialize, ToSchema)]
 pub struct RestoreRequest {
     pub target_path: Option<String>,
+    /// 将恢复操作关联到已有批次，便于整批撤销
+    pub batch_id: Option<String>,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -35,6 +37,8 @@ pub struct BatchTrashRequest {
 #[derive(Serialize, ToSchema)]
 pub struct BatchTrashResponse {
     pub results: Vec<TrashResult>,
+    /// 批次 ID，可用于整批撤销
+    pub batch_id: Option<String>,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -90,9 +94,14 @@ pub async fn restore_from_trash(
     Path(file_id): Path<String>,
     Json(req): Json<RestoreRequest>,
 ) -> Result<Json<RestoreResponse>, (axum::http::StatusCode, String)> {
-    let restored_path = trash::restore_from_trash(&state.db, &file_id, req.target_path.as_deref())
-        .await
-        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let restored_path = trash::restore_from_trash_in_batch(
+        &state.db,
+        &file_id,
+        req.target_path.as_deref(),
+        req.batch_id.as_deref(),
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
     Ok(Json(RestoreResponse {
         restored_path: restored_path.clone(),
@@ -131,6 +140,7 @@ pub async fn batch_move_to_trash(
     if req.file_ids.is_empty() {
         return Ok(Json(BatchTrashResponse {
             results: Vec::new(),
+            batch_id: None,
         }));
     }
 
@@ -142,6 +152,7 @@ pub async fn batch_move_to_trash(
         .join("trash");
 
     let trash_config = trash::TrashConfig::new(trash_dir);
+    let batch_id = uuid::Uuid::new_v4().to_string();
 
     // 并行处理批量删除
     let max_concurrent = 4;
@@ -152,11 +163,14 @@ pub async fn batch_move_to_trash(
             let semaphore = semaphore.clone();
             let db = state.db.clone();
             let trash_config = &trash_config;
+            let batch_id = batch_id.as_str();
 
             async move {
                 let _permit = semaphore.acquire().await.unwrap();
 
-                match trash::move_to_trash(&db, &file_id, trash_config).await {
+                match trash::move_to_trash_in_batch(&db, &file_id, trash_config, Some(batch_id))
+                    .await
+                {
                     Ok(_) => TrashResult {
                         file_id,
                         success: true,
@@ -174,7 +188,10 @@ pub async fn batch_move_to_trash(
         .collect()
         .await;
 
-    Ok(Json(BatchTrashResponse { results }))
+    Ok(Json(BatchTrashResponse {
+        results,
+        batch_id: Some(batch_id),
+    }))
 }
 
 pub async fn cleanup_trash(
@@ -1,5 +1,6 @@
 use crate::handlers::AppState;
 use crate::models::WatchFolder;
+use crate::services::metadata_provider;
 use axum::{
     extract::{Path, State},
     response::IntoResponse,
@@ -33,18 +34,28 @@ pub async fn add_watch_folder(
         .and_then(|p| p.as_bool())
         .unwrap_or(false);
 
+    let providers = providers_column(payload.get("providers"));
+    let library_type = match library_type_column(payload.get("library_type")) {
+        Ok(library_type) => library_type,
+        Err(message) => return (axum::http::StatusCode::BAD_REQUEST, message).into_response(),
+    };
+    let languages = languages_column(payload.get("languages"));
+
     if path.is_empty() {
         return (axum::http::StatusCode::BAD_REQUEST, "Path is required").into_response();
     }
 
     let id = Uuid::new_v4().to_string();
     let result = sqlx::query(
-        "INSERT INTO watch_folders (id, path, auto_scrape, auto_rename) VALUES (?, ?, ?, ?)",
+        "INSERT INTO watch_folders (id, path, auto_scrape, auto_rename, providers, library_type, languages) VALUES (?, ?, ?, ?, ?, ?, ?)",
     )
     .bind(&id)
     .bind(path)
     .bind(auto_scrape)
     .bind(auto_rename)
+    .bind(providers)
+    .bind(library_type)
+    .bind(languages)
     .execute(&state.db)
     .await;
 
@@ -68,3 +79,104 @@ pub async fn delete_watch_folder(
         Err(e) => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
     }
 }
+
+/// 设置媒体库使用的元数据源（`{"providers": ["bgm", "tmdb"]}`，null 恢复为全部）
+pub async fn update_watch_folder_providers(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(payload): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    let providers = providers_column(payload.get("providers"));
+    let result = sqlx::query("UPDATE watch_folders SET providers = ? WHERE id = ?")
+        .bind(providers)
+        .bind(id)
+        .execute(&state.db)
+        .await;
+
+    match result {
+        Ok(done) if done.rows_affected() == 0 => {
+            (axum::http::StatusCode::NOT_FOUND, "Watch folder not found").into_response()
+        }
+        Ok(_) => (axum::http::StatusCode::OK, "Updated").into_response(),
+        Err(e) => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+/// 设置媒体库类型（`{"library_type": "anime"}`，null 恢复为自动判断）
+pub async fn update_watch_folder_library_type(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(payload): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    let library_type = match library_type_column(payload.get("library_type")) {
+        Ok(library_type) => library_type,
+        Err(message) => return (axum::http::StatusCode::BAD_REQUEST, message).into_response(),
+    };
+    let result = sqlx::query("UPDATE watch_folders SET library_type = ? WHERE id = ?")
+        .bind(library_type)
+        .bind(id)
+        .execute(&state.db)
+        .await;
+
+    match result {
+        Ok(done) if done.rows_affected() == 0 => {
+            (axum::http::StatusCode::NOT_FOUND, "Watch folder not found").into_response()
+        }
+        Ok(_) => (axum::http::StatusCode::OK, "Updated").into_response(),
+        Err(e) => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+/// 设置媒体库元数据语言回退链（`{"languages": ["zh-CN", "ja", "en"]}`，null 恢复为全局设置）
+pub async fn update_watch_folder_languages(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(payload): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    let languages = languages_column(payload.get("languages"));
+    let result = sqlx::query("UPDATE watch_folders SET languages = ? WHERE id = ?")
+        .bind(languages)
+        .bind(id)
+        .execute(&state.db)
+        .await;
+
+    match result {
+        Ok(done) if done.rows_affected() == 0 => {
+            (axum::http::StatusCode::NOT_FOUND, "Watch folder not found").into_response()
+        }
+        Ok(_) => (axum::http::StatusCode::OK, "Updated").into_response(),
+        Err(e) => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+/// 媒体库类型只接受 `movie` / `tv` / `anime`，null 或缺省视为自动判断
+fn library_type_column(value: Option<&serde_json::Value>) -> Result<Option<String>, &'static str> {
+    match value {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(value) => match value.as_str() {
+            Some(kind @ ("movie" | "tv" | "anime")) => Ok(Some(kind.to_string())),
+            _ => Err("library_type must be movie, tv or anime"),
+        },
+    }
+}
+
+/// 数据源数组序列化为列值，非数组或空数组视为未配置
+fn providers_column(value: Option<&serde_json::Value>) -> Option<String> {
+    let ids: Vec<&str> = value?
+        .as_array()?
+        .iter()
+        .filter_map(|id| id.as_str())
+        .collect();
+    (!ids.is_empty()).then(|| serde_json::to_string(&ids).unwrap_or_default())
+}
+
+/// 语言列表（数组或 `zh-CN,ja,en` 字符串）规范化为 JSON 数组列值，空值视为未配置
+fn languages_column(value: Option<&serde_json::Value>) -> Option<String> {
+    let raw = match value? {
+        serde_json::Value::String(raw) => raw.clone(),
+        value @ serde_json::Value::Array(_) => value.to_string(),
+        _ => return None,
+    };
+    metadata_provider::parse_language_list(Some(&raw))
+        .map(|languages| serde_json::to_string(&languages).unwrap_or_default())
+}
@@ -20,6 +20,9 @@ pub struct MediaFile {
     pub detected_year: Option<i32>,
     pub detected_season: Option<i32>,
     pub detected_episode: Option<i32>,
+    pub detected_episode_end: Option<i32>, // 多集文件的末集
+    pub detected_absolute_episode: Option<i32>, // 绝对集数
+    pub release_info: Option<String>,      // JSON，结构化发布名解析结果
     pub parser_provider: Option<String>,
     pub parse_version: Option<String>,
     pub confidence_score: Option<f64>,
@@ -29,12 +32,16 @@ pub struct MediaFile {
     pub locked_match_provider: Option<String>,
     pub locked_match_external_id: Option<String>,
     pub ai_disabled_reason: Option<String>,
+    pub movie_id: Option<String>,
+    pub show_id: Option<String>,
+    pub season_id: Option<String>,
+    pub episode_id: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub last_modified: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
 pub struct VideoInfo {
     pub duration: Option<f64>, // 秒
     pub width: Option<u32>,
@@ -162,6 +169,10 @@ pub struct OperationLog {
     pub file_id: Option<String>,
     pub old_path: String,
     pub new_path: Option<String>,
+    pub inverse: Option<String>,      // JSON
+    pub precondition: Option<String>, // JSON
+    pub undone_at: Option<DateTime<Utc>>,
+    pub batch_id: Option<String>, // 所属批次（任务 ID 或批量请求 ID）
     pub created_at: DateTime<Utc>,
 }
 
@@ -183,6 +194,12 @@ pub struct WatchFolder {
     pub recursive: bool,
     pub enabled: bool,
     pub created_at: DateTime<Utc>,
+    /// 启用的元数据源（JSON 数组，如 `["bgm","tmdb"]`），为空表示全部
+    pub providers: Option<String>,
+    /// 媒体库类型（`movie` / `tv` / `anime`），为空时按文件名自动判断
+    pub library_type: Option<String>,
+    /// 元数据语言回退链（JSON 数组，如 `["zh-CN","ja","en"]`），为空时使用全局设置
+    pub languages: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
@@ -194,3 +211,170 @@ pub struct Setting {
     pub description: Option<String>,
     pub updated_at: DateTime<Utc>,
 }
+
+/// 目录中的电影，ID 形如 `tmdb:603`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Movie {
+    pub id: String,
+    pub provider: String,
+    pub external_id: String,
+    pub title: String,
+    pub original_title: Option<String>,
+    /// 各语言标题（JSON 对象，键为 `zh-CN` 形式的语言标签）
+    pub localized_titles: Option<String>,
+    /// 别名（JSON 数组）
+    pub aliases: Option<String>,
+    pub year: Option<i32>,
+    pub overview: Option<String>,
+    pub poster_url: Option<String>,
+    pub backdrop_url: Option<String>,
+    pub rating: Option<f64>,
+    /// 所属电影系列，如 `tmdb:10`
+    pub collection_id: Option<String>,
+    /// 关联的媒体文件数
+    pub file_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 目录中的剧集，ID 形如 `tmdb:1396`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Show {
+    pub id: String,
+    pub provider: String,
+    pub external_id: String,
+    pub title: String,
+    pub original_title: Option<String>,
+    /// 各语言标题（JSON 对象，键为 `zh-CN` 形式的语言标签）
+    pub localized_titles: Option<String>,
+    /// 别名（JSON 数组）
+    pub aliases: Option<String>,
+    pub year: Option<i32>,
+    pub overview: Option<String>,
+    pub poster_url: Option<String>,
+    pub backdrop_url: Option<String>,
+    pub rating: Option<f64>,
+    pub season_count: i64,
+    pub episode_count: i64,
+    pub file_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 剧集的一季，ID 形如 `tmdb:1396:s1`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Season {
+    pub id: String,
+    pub show_id: String,
+    pub season_number: i32,
+    pub name: Option<String>,
+    pub episode_count: i64,
+    pub file_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 剧集的一集，ID 形如 `tmdb:1396:s1e3`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Episode {
+    pub id: String,
+    pub show_id: String,
+    pub season_id: String,
+    pub season_number: i32,
+    pub episode_number: i32,
+    pub absolute_number: Option<i32>,
+    pub title: Option<String>,
+    pub overview: Option<String>,
+    pub air_date: Option<String>,
+    pub runtime: Option<i32>,
+    pub rating: Option<f64>,
+    pub still_url: Option<String>,
+    pub file_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 演职人员，ID 形如 `tmdb:287`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Person {
+    pub id: String,
+    pub provider: String,
+    pub external_id: String,
+    pub name: String,
+    pub original_name: Option<String>,
+    /// 数据源头像地址
+    pub profile_url: Option<String>,
+    /// 本地缓存的头像路径
+    pub profile_path: Option<String>,
+    /// 参与的作品数
+    pub title_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 作品演职员表中的一项
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TitleCredit {
+    pub person_id: String,
+    pub name: String,
+    pub original_name: Option<String>,
+    pub profile_url: Option<String>,
+    pub profile_path: Option<String>,
+    /// actor / director / writer
+    pub role: String,
+    pub character: Option<String>,
+    pub job: Option<String>,
+    pub sort_order: i64,
+}
+
+/// 人员参与的作品
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct PersonCredit {
+    /// movie 或 tv
+    pub media_type: String,
+    pub title_id: String,
+    pub title: String,
+    pub year: Option<i32>,
+    pub poster_url: Option<String>,
+    pub role: String,
+    pub character: Option<String>,
+    pub job: Option<String>,
+}
+
+/// 电影系列（合集），ID 形如 `tmdb:10`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Collection {
+    pub id: String,
+    pub provider: String,
+    pub external_id: String,
+    pub name: String,
+    pub overview: Option<String>,
+    pub poster_url: Option<String>,
+    pub backdrop_url: Option<String>,
+    /// 本地缓存的海报路径
+    pub poster_path: Option<String>,
+    /// 本地缓存的背景图路径
+    pub backdrop_path: Option<String>,
+    /// 系列包含的作品数
+    pub part_count: i64,
+    /// 已入库（有关联文件）的作品数
+    pub owned_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 系列中的一部作品
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct CollectionPart {
+    /// 与 movies.id 同格式，如 `tmdb:603`
+    pub movie_id: String,
+    pub title: String,
+    pub original_title: Option<String>,
+    pub year: Option<i32>,
+    pub release_date: Option<String>,
+    pub overview: Option<String>,
+    pub poster_url: Option<String>,
+    pub sort_order: i64,
+    /// 关联的媒体文件数，为 0 表示缺失
+    pub file_count: i64,
+}
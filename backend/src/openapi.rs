@@ -34,6 +34,15 @@ use utoipa::OpenApi;
         crate::models::ScanHistory,
         crate::models::WatchFolder,
         crate::models::Setting,
+        crate::models::Movie,
+        crate::models::Show,
+        crate::models::Season,
+        crate::models::Episode,
+        crate::models::Collection,
+        crate::models::CollectionPart,
+        crate::models::Person,
+        crate::models::TitleCredit,
+        crate::models::PersonCredit,
         crate::services::task_queue::TaskInfo,
         crate::services::task_queue::TaskStatus,
         crate::services::task_queue::TaskType,
@@ -56,9 +65,32 @@ use utoipa::OpenApi;
         crate::handlers::identify::IdentifyApplyResult,
         crate::handlers::identify::IdentifyApplyResponse,
         crate::handlers::identify::IdentifyTaskResponse,
+        crate::handlers::identify::IdentifyApplySiblingsRequest,
+        crate::handlers::identify::IdentifyAutoRequest,
+        crate::handlers::identify::ReviewAcceptRequest,
+        crate::handlers::identify::ReviewChooseRequest,
+        crate::handlers::identify::ReviewRejectResponse,
+        crate::handlers::identify::IdentifyReparseRequest,
+        crate::handlers::identify::ExternalIdLinkRequest,
+        crate::services::title_aliases::TitleAlias,
+        crate::services::sibling_apply::SiblingReport,
+        crate::services::sibling_apply::SiblingApplied,
+        crate::services::sibling_apply::SiblingConflict,
+        crate::services::title_aliases::AliasInput,
+        crate::services::review_queue::ReviewItem,
+        crate::services::reparse::ReparseDiff,
+        crate::services::reparse::ReparseSummary,
+        crate::services::external_ids::ExternalId,
+        crate::services::external_ids::TitleRef,
+        crate::handlers::metadata::MetadataEditRequest,
+        crate::handlers::metadata::MetadataLocksRequest,
+        crate::services::metadata_edit::MetadataState,
+        crate::services::metadata_edit::HistoryEntry,
+        crate::services::metadata_edit::FieldChange,
         crate::handlers::settings::SettingsHealthCheckRequest,
         crate::handlers::settings::SettingsHealthCheckResponse,
         crate::services::identify::ParsedTitle,
+        crate::services::release_parser::ReleaseInfo,
         crate::services::identify::IdentifyCandidate,
         crate::services::identify::IdentifyPreview,
         crate::services::empty_dirs::EmptyDirInfo,
@@ -69,6 +101,16 @@ use utoipa::OpenApi;
         crate::handlers::rename::RenameRequest,
         crate::handlers::rename::RenamePreview,
         crate::handlers::rename::RenameActionResponse,
+        crate::services::renamer::ConflictPolicy,
+        crate::services::renamer::RenameIssue,
+        crate::services::renamer::PlanAction,
+        crate::services::log::InverseOp,
+        crate::services::log::Precondition,
+        crate::services::log::BatchUndoStatus,
+        crate::services::log::BatchUndoItem,
+        crate::services::log::BatchUndoReport,
+        crate::handlers::log::UndoBatchRequest,
+        crate::handlers::log::UndoBatchResponse,
         crate::services::file_ops::FileOperationResult,
         crate::handlers::file_ops::MoveFileRequest,
         crate::handlers::file_ops::CopyFileRequest,
@@ -78,6 +120,19 @@ use utoipa::OpenApi;
         crate::handlers::file_ops::FileOperationResponse,
         crate::handlers::file_ops::BatchFileOperationResponse,
         crate::services::plugin::PluginInfo,
+        crate::services::metadata_provider::ProviderInfo,
+        crate::services::provider_cache::CacheStats,
+        crate::services::provider_cache::CacheEntrySummary,
+        crate::services::provider_guard::ProviderStatus,
+        crate::services::provider_guard::CircuitState,
+        crate::handlers::plugins::ProviderCacheResponse,
+        crate::handlers::plugins::PurgeProviderCacheResponse,
+        crate::handlers::catalog::MovieDetail,
+        crate::handlers::catalog::ShowDetail,
+        crate::handlers::catalog::SeasonDetail,
+        crate::handlers::catalog::EpisodeDetail,
+        crate::handlers::collections::CollectionDetail,
+        crate::handlers::people::PersonDetail,
     )),
     paths(
         crate::handlers::tasks::list_tasks,
@@ -94,6 +149,29 @@ use utoipa::OpenApi;
         crate::handlers::identify::preview_identify_batch,
         crate::handlers::identify::apply_identify,
         crate::handlers::identify::apply_identify_batch,
+        crate::handlers::identify::apply_identify_siblings,
+        crate::handlers::identify::auto_identify,
+        crate::handlers::identify::list_review_queue,
+        crate::handlers::identify::accept_review,
+        crate::handlers::identify::choose_review_candidate,
+        crate::handlers::identify::reject_review,
+        crate::handlers::identify::reparse_identify,
+        crate::handlers::identify::list_reparse_diffs,
+        crate::handlers::identify::approve_reparse_diff,
+        crate::handlers::identify::reject_reparse_diff,
+        crate::handlers::identify::list_external_ids,
+        crate::handlers::identify::lookup_external_id,
+        crate::handlers::identify::link_external_id,
+        crate::handlers::identify::unlink_external_id,
+        crate::handlers::identify::list_aliases,
+        crate::handlers::identify::create_alias,
+        crate::handlers::identify::update_alias,
+        crate::handlers::identify::delete_alias,
+        crate::handlers::metadata::get_metadata,
+        crate::handlers::metadata::edit_metadata,
+        crate::handlers::metadata::update_metadata_locks,
+        crate::handlers::metadata::list_metadata_history,
+        crate::handlers::metadata::revert_metadata,
         crate::handlers::settings::health_check_settings,
         crate::handlers::dedupe::find_duplicates,
         crate::handlers::dedupe::find_empty_dirs,
@@ -102,12 +180,25 @@ use utoipa::OpenApi;
         crate::handlers::dedupe::find_duplicate_movies,
         crate::handlers::dedupe::find_duplicate_movies,
         crate::handlers::rename::batch_rename,
+        crate::handlers::log::undo_batch,
         crate::handlers::file_ops::move_file,
         crate::handlers::file_ops::copy_file,
         crate::handlers::file_ops::batch_move_files,
         crate::handlers::file_ops::batch_move_files,
         crate::handlers::file_ops::batch_copy_files,
         crate::handlers::plugins::list_plugins,
+        crate::handlers::plugins::list_providers,
+        crate::handlers::plugins::get_provider_cache,
+        crate::handlers::plugins::purge_provider_cache,
+        crate::handlers::catalog::list_movies,
+        crate::handlers::catalog::get_movie,
+        crate::handlers::catalog::list_shows,
+        crate::handlers::catalog::get_show,
+        crate::handlers::catalog::get_season,
+        crate::handlers::collections::list_collections,
+        crate::handlers::collections::get_collection,
+        crate::handlers::people::list_people,
+        crate::handlers::people::get_person,
     ),
     tags(
         (name = "scan", description = "文件扫描 - 扫描目录并索引媒体文件"),
@@ -115,7 +206,9 @@ use utoipa::OpenApi;
         (name = "video", description = "视频信息 - 提取视频元数据"),
         (name = "subtitle", description = "字幕管理 - 查找和匹配字幕文件"),
         (name = "scrape", description = "元数据刮削 - 兼容旧接口的自动应用入口"),
+        (name = "catalog", description = "影视目录 - 按电影 / 剧集 / 季 / 单集浏览已识别的媒体"),
         (name = "identify", description = "识别与审核 - 规则解析优先，TMDb + Bangumi 检索，Cloudflare AI 仅兜底"),
+        (name = "metadata", description = "元数据编辑 - 人工修改字段、字段锁与历史回滚"),
         (name = "rename", description = "批量重命名 - 按模板重命名文件"),
         (name = "dedupe", description = "文件去重 - 查找重复文件"),
         (name = "file_ops", description = "文件操作 - 移动/复制文件"),
@@ -6,7 +6,7 @@ use axum::{
     extract::{State, WebSocketUpgrade},
     http::{Request, StatusCode, Uri},
     response::IntoResponse,
-    routing::{delete, get, post},
+    routing::{delete, get, post, put},
     Extension, Router,
 };
 use tower::util::ServiceExt;
@@ -164,6 +164,64 @@ pub fn build_app_router(
             "/api/identify/apply/batch",
             post(handlers::identify::apply_identify_batch),
         )
+        .route(
+            "/api/identify/apply/siblings",
+            post(handlers::identify::apply_identify_siblings),
+        )
+        .route(
+            "/api/identify/auto",
+            post(handlers::identify::auto_identify),
+        )
+        .route(
+            "/api/identify/review",
+            get(handlers::identify::list_review_queue),
+        )
+        .route(
+            "/api/identify/review/:file_id/accept",
+            post(handlers::identify::accept_review),
+        )
+        .route(
+            "/api/identify/review/:file_id/choose",
+            post(handlers::identify::choose_review_candidate),
+        )
+        .route(
+            "/api/identify/review/:file_id/reject",
+            post(handlers::identify::reject_review),
+        )
+        .route(
+            "/api/identify/reparse",
+            post(handlers::identify::reparse_identify),
+        )
+        .route(
+            "/api/identify/reparse/diffs",
+            get(handlers::identify::list_reparse_diffs),
+        )
+        .route(
+            "/api/identify/reparse/diffs/:file_id/approve",
+            post(handlers::identify::approve_reparse_diff),
+        )
+        .route(
+            "/api/identify/reparse/diffs/:file_id/reject",
+            post(handlers::identify::reject_reparse_diff),
+        )
+        .route(
+            "/api/identify/external-ids",
+            get(handlers::identify::list_external_ids)
+                .post(handlers::identify::link_external_id)
+                .delete(handlers::identify::unlink_external_id),
+        )
+        .route(
+            "/api/identify/external-ids/lookup",
+            get(handlers::identify::lookup_external_id),
+        )
+        .route(
+            "/api/identify/aliases",
+            get(handlers::identify::list_aliases).post(handlers::identify::create_alias),
+        )
+        .route(
+            "/api/identify/aliases/:id",
+            put(handlers::identify::update_alias).delete(handlers::identify::delete_alias),
+        )
         .route("/api/rename", post(handlers::rename::batch_rename))
         .route("/api/dedupe", post(handlers::dedupe::find_duplicates))
         .route(
@@ -207,6 +265,10 @@ pub fn build_app_router(
         .route("/api/trash/cleanup", post(handlers::trash::cleanup_trash))
         .route("/api/logs", get(handlers::log::list_operation_logs))
         .route("/api/logs/:id/undo", post(handlers::log::undo_operation))
+        .route(
+            "/api/logs/batches/:batch_id/undo",
+            post(handlers::log::undo_batch),
+        )
         .route("/api/history", get(handlers::history::list_scan_history))
         .route(
             "/api/watch-folders",
@@ -216,10 +278,38 @@ pub fn build_app_router(
             "/api/watch-folders/:id",
             delete(handlers::watcher::delete_watch_folder),
         )
+        .route(
+            "/api/watch-folders/:id/providers",
+            put(handlers::watcher::update_watch_folder_providers),
+        )
+        .route(
+            "/api/watch-folders/:id/library-type",
+            put(handlers::watcher::update_watch_folder_library_type),
+        )
+        .route(
+            "/api/watch-folders/:id/languages",
+            put(handlers::watcher::update_watch_folder_languages),
+        )
         .route(
             "/api/files/:id/nfo",
             get(handlers::nfo::get_nfo).put(handlers::nfo::update_nfo),
         )
+        .route(
+            "/api/files/:id/metadata",
+            get(handlers::metadata::get_metadata).put(handlers::metadata::edit_metadata),
+        )
+        .route(
+            "/api/files/:id/metadata/locks",
+            put(handlers::metadata::update_metadata_locks),
+        )
+        .route(
+            "/api/files/:id/metadata/history",
+            get(handlers::metadata::list_metadata_history),
+        )
+        .route(
+            "/api/files/:id/metadata/history/:history_id/revert",
+            post(handlers::metadata::revert_metadata),
+        )
         .route(
             "/api/settings",
             get(handlers::settings::get_settings).post(handlers::settings::update_settings),
@@ -228,7 +318,31 @@ pub fn build_app_router(
             "/api/settings/health-check",
             post(handlers::settings::health_check_settings),
         )
+        .route("/api/movies", get(handlers::catalog::list_movies))
+        .route("/api/movies/:id", get(handlers::catalog::get_movie))
+        .route("/api/shows", get(handlers::catalog::list_shows))
+        .route("/api/shows/:id", get(handlers::catalog::get_show))
+        .route(
+            "/api/shows/:id/seasons/:season",
+            get(handlers::catalog::get_season),
+        )
+        .route(
+            "/api/collections",
+            get(handlers::collections::list_collections),
+        )
+        .route(
+            "/api/collections/:id",
+            get(handlers::collections::get_collection),
+        )
+        .route("/api/people", get(handlers::people::list_people))
+        .route("/api/people/:id", get(handlers::people::get_person))
         .route("/api/plugins", get(handlers::plugins::list_plugins))
+        .route("/api/providers", get(handlers::plugins::list_providers))
+        .route(
+            "/api/providers/cache",
+            get(handlers::plugins::get_provider_cache)
+                .delete(handlers::plugins::purge_provider_cache),
+        )
         .route(
             "/api/queue/stats",
             get(handlers::queue_stats::get_queue_stats),
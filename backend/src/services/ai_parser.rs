@@ -0,0 +1,274 @@
+//! AI 辅助文件名解析
+//!
+//! 规则解析置信度不足时，把文件名交给大模型提取标题 / 年份 / 季集。
+//! 后端由 `ai_backend` 设置决定：
+//! - `cloudflare`：Cloudflare Workers AI（默认）
+//! - `openai`：任意 OpenAI 兼容的 `/v1/chat/completions` 端点（Ollama、llama.cpp、vLLM 等本地服务）
+//!
+//! 模型输出先按 [`PARSE_SCHEMA`] 做 JSON Schema 校验，不合格的结果直接丢弃，回退到规则解析。
+
+use std::collections::BTreeMap;
+
+use anyhow::Context;
+use jsonschema::JSONSchema;
+use once_cell::sync::Lazy;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+use crate::services::identify::{ParsedTitle, RuntimeSettings};
+
+pub const BACKEND_CLOUDFLARE: &str = "cloudflare";
+pub const BACKEND_OPENAI: &str = "openai";
+
+pub const DEFAULT_CLOUDFLARE_MODEL: &str = "@cf/meta/llama-3.1-8b-instruct";
+pub const DEFAULT_OPENAI_BASE_URL: &str = "http://127.0.0.1:11434/v1";
+pub const DEFAULT_OPENAI_MODEL: &str = "qwen2.5:7b-instruct";
+
+/// 模型未给出置信度时使用的默认值
+const DEFAULT_CONFIDENCE: f64 = 0.72;
+
+/// 模型输出必须满足的结构
+pub static PARSE_SCHEMA: Lazy<Value> = Lazy::new(|| {
+    let count = json!({ "type": ["integer", "null"], "minimum": 0, "maximum": 9999 });
+    json!({
+        "type": "object",
+        "required": ["title"],
+        "properties": {
+            "title": { "type": "string", "minLength": 1, "maxLength": 300 },
+            "year": { "type": ["integer", "null"], "minimum": 1870, "maximum": 2100 },
+            "season": count,
+            "episode": count,
+            "episode_end": count,
+            "absolute_episode": count,
+            "is_special": { "type": ["boolean", "null"] },
+            "special_type": { "type": ["string", "null"] },
+            "confidence": { "type": ["number", "null"], "minimum": 0, "maximum": 1 }
+        }
+    })
+});
+
+static COMPILED_SCHEMA: Lazy<JSONSchema> =
+    Lazy::new(|| JSONSchema::compile(&PARSE_SCHEMA).expect("AI parse schema is valid"));
+
+/// 系统提示词：针对中日韩发布组命名习惯
+pub const SYSTEM_PROMPT: &str = "You extract structured metadata from a single video release filename. \
+Reply with one JSON object and nothing else, using the keys: title, year, season, episode, \
+episode_end (last episode of a multi-episode file, else null), absolute_episode (episode number \
+counted across all seasons when no season is given), is_special, special_type (special/ova/oad/pv/cm or null), \
+confidence (0-1). Use only information present in the filename; use null for anything missing.\n\
+Rules for CJK releases:\n\
+- Text in leading square brackets such as [Nekomoe kissaten], 【喵萌奶茶屋】 or [字幕组] is a release group, never the title.\n\
+- Tags like 简体/繁體/简日双语/CHS/CHT/GB/BIG5/内嵌/外挂/1080P/WEB-DL/HEVC are not part of the title.\n\
+- 第二季 / 第2期 / 2nd Season / S2 mean season 2; 第05话 / 第05集 / 第5話 / EP05 / [05] mean episode 5.\n\
+- Chinese numerals count: 一=1 二=2 三=3 四=4 五=5 六=6 七=7 八=8 九=9 十=10 十二=12 二十=20.\n\
+- When the name has both a Chinese/Japanese title and a romanized or English title, return the CJK title as written.\n\
+- 剧场版 / 劇場版 / 电影版 is a movie: keep it in the title, no season or episode; SP / 特别篇 / 特別編 / OVA / OAD mean is_special=true.\n\
+- A bare number after ' - ' in an anime release with no season marker is absolute_episode.";
+
+/// 少样本示例：(文件名, 期望输出)
+const FEW_SHOT: &[(&str, &str)] = &[
+    (
+        "[喵萌奶茶屋&LoliHouse] 葬送的芙莉莲 / Sousou no Frieren - 05 [WebRip 1080p HEVC-10bit AAC][简繁日内封字幕].mkv",
+        r#"{"title":"葬送的芙莉莲","year":null,"season":null,"episode":null,"episode_end":null,"absolute_episode":5,"is_special":false,"special_type":null,"confidence":0.9}"#,
+    ),
+    (
+        "【幻樱字幕组】【4月新番】【间谍过家家 第二季 SPY×FAMILY S2】【03】【GB_MP4】【1920X1080】.mp4",
+        r#"{"title":"间谍过家家","year":null,"season":2,"episode":3,"episode_end":null,"absolute_episode":null,"is_special":false,"special_type":null,"confidence":0.9}"#,
+    ),
+    (
+        "甄嬛传.Empresses.in.the.Palace.2011.第七十六集.1080p.WEB-DL.mp4",
+        r#"{"title":"甄嬛传","year":2011,"season":1,"episode":76,"episode_end":null,"absolute_episode":null,"is_special":false,"special_type":null,"confidence":0.85}"#,
+    ),
+    (
+        "劇場版 呪術廻戦 0 (2021) [BDRip 1080p].mkv",
+        r#"{"title":"劇場版 呪術廻戦 0","year":2021,"season":null,"episode":null,"episode_end":null,"absolute_episode":null,"is_special":false,"special_type":null,"confidence":0.85}"#,
+    ),
+];
+
+/// 一个可调用的 chat completions 端点
+#[derive(Debug, Clone)]
+pub struct AiEndpoint {
+    /// `cloudflare` / `openai`
+    pub backend: String,
+    /// 不含 `/chat/completions` 的基础地址
+    pub base_url: String,
+    /// 本地服务通常不需要密钥
+    pub api_key: Option<String>,
+    pub model: String,
+}
+
+impl AiEndpoint {
+    /// 按 `ai_backend` 选择端点，缺少必填配置时返回 None
+    pub fn from_settings(settings: &RuntimeSettings) -> Option<Self> {
+        if settings.ai_backend == BACKEND_OPENAI {
+            return Some(Self {
+                backend: BACKEND_OPENAI.to_string(),
+                base_url: settings
+                    .openai_base_url
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_OPENAI_BASE_URL.to_string()),
+                api_key: settings.openai_api_key.clone(),
+                model: settings.openai_model.clone(),
+            });
+        }
+
+        let account_id = settings.cloudflare_account_id.as_deref()?;
+        let token = settings.cloudflare_api_token.clone()?;
+        Some(Self {
+            backend: BACKEND_CLOUDFLARE.to_string(),
+            base_url: settings.cloudflare_ai_base_url.clone().unwrap_or_else(|| {
+                format!("https://api.cloudflare.com/client/v4/accounts/{account_id}/ai/v1")
+            }),
+            api_key: Some(token),
+            model: settings.cloudflare_ai_model.clone(),
+        })
+    }
+
+    /// 只有部署在本机或内网的 OpenAI 兼容服务（如 Ollama）视为免费；
+    /// 其余端点（api.openai.com、OpenRouter 等托管服务）都受 `strict_free` 每日额度限制
+    pub fn is_paid(&self) -> bool {
+        self.backend != BACKEND_OPENAI || !is_local_url(&self.base_url)
+    }
+
+    pub fn completions_url(&self) -> String {
+        format!("{}/chat/completions", self.base_url.trim_end_matches('/'))
+    }
+
+    /// 写入 ParsedTitle.parser_provider 的名称
+    pub fn parser_provider(&self) -> &'static str {
+        if self.backend == BACKEND_OPENAI {
+            "openai_compatible"
+        } else {
+            "cloudflare_ai"
+        }
+    }
+}
+
+/// 地址是否指向本机或内网（loopback、私有网段、链路本地）
+fn is_local_url(base_url: &str) -> bool {
+    let Ok(url) = url::Url::parse(base_url) else {
+        return false;
+    };
+    match url.host() {
+        Some(url::Host::Domain(host)) => host.eq_ignore_ascii_case("localhost"),
+        Some(url::Host::Ipv4(ip)) => ip.is_loopback() || ip.is_private() || ip.is_link_local(),
+        // fc00::/7 为唯一本地地址
+        Some(url::Host::Ipv6(ip)) => ip.is_loopback() || (ip.segments()[0] & 0xfe00) == 0xfc00,
+        None => false,
+    }
+}
+
+/// 构造请求消息：系统提示词 + 少样本示例 + 待解析文件名
+pub fn build_messages(filename: &str) -> Vec<Value> {
+    let mut messages = vec![json!({ "role": "system", "content": SYSTEM_PROMPT })];
+    for (example, answer) in FEW_SHOT {
+        messages.push(json!({ "role": "user", "content": example }));
+        messages.push(json!({ "role": "assistant", "content": answer }));
+    }
+    messages.push(json!({ "role": "user", "content": filename }));
+    messages
+}
+
+/// 调用模型解析文件名；响应不合 schema 时返回 None
+pub async fn parse_filename(
+    client: &Client,
+    endpoint: &AiEndpoint,
+    filename: &str,
+) -> anyhow::Result<Option<ParsedTitle>> {
+    let payload = json!({
+        "model": endpoint.model,
+        "response_format": { "type": "json_object" },
+        "temperature": 0,
+        "messages": build_messages(filename),
+    });
+    let mut request = client.post(endpoint.completions_url()).json(&payload);
+    if let Some(api_key) = endpoint.api_key.as_deref().filter(|key| !key.is_empty()) {
+        request = request.bearer_auth(api_key);
+    }
+    let response: Value = request
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+        .with_context(|| format!("failed to parse {} AI response", endpoint.backend))?;
+
+    let Some(content) = response
+        .get("choices")
+        .and_then(Value::as_array)
+        .and_then(|choices| choices.first())
+        .and_then(|choice| choice.get("message"))
+        .and_then(|message| message.get("content"))
+        .and_then(Value::as_str)
+    else {
+        return Ok(None);
+    };
+
+    match validate_content(content) {
+        Ok(data) => Ok(Some(parsed_from_value(&data, endpoint.parser_provider()))),
+        Err(err) => {
+            tracing::warn!(backend = %endpoint.backend, filename, "discarding AI parse: {err}");
+            Ok(None)
+        }
+    }
+}
+
+/// 取出模型回复中的 JSON 对象并校验；兼容 ```json 代码块包裹
+pub fn validate_content(content: &str) -> anyhow::Result<Value> {
+    let trimmed = content.trim();
+    let body = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.trim_end().strip_suffix("```"))
+        .unwrap_or(trimmed)
+        .trim();
+    let data: Value = serde_json::from_str(body).context("AI reply is not JSON")?;
+    if let Err(errors) = COMPILED_SCHEMA.validate(&data) {
+        let messages: Vec<String> = errors
+            .map(|err| format!("{}: {}", err.instance_path, err))
+            .collect();
+        anyhow::bail!("AI reply does not match schema: {}", messages.join("; "));
+    }
+    if data["title"]
+        .as_str()
+        .is_some_and(|title| title.trim().is_empty())
+    {
+        anyhow::bail!("AI reply has an empty title");
+    }
+    Ok(data)
+}
+
+fn parsed_from_value(data: &Value, parser_provider: &str) -> ParsedTitle {
+    let number = |key: &str| {
+        data.get(key)
+            .and_then(Value::as_u64)
+            .map(|value| value as u32)
+    };
+    ParsedTitle {
+        title: data["title"]
+            .as_str()
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+        year: number("year"),
+        season: number("season"),
+        episode: number("episode"),
+        episode_end: number("episode_end"),
+        absolute_episode: number("absolute_episode"),
+        is_special: data
+            .get("is_special")
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+        special_type: data
+            .get("special_type")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        confidence: data
+            .get("confidence")
+            .and_then(Value::as_f64)
+            .unwrap_or(DEFAULT_CONFIDENCE),
+        parser_provider: parser_provider.to_string(),
+        ai_disabled_reason: None,
+        field_sources: BTreeMap::new(),
+    }
+}
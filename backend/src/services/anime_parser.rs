@@ -0,0 +1,489 @@
+//! 番剧字幕组命名解析
+//!
+//! 处理 `[Group] Title - 01v2 [1080p][CHS]`、`【Group】[标题][05][1080P][简日双语]`、
+//! `[Group][Title][01-12][BDRip]` 这类字幕组命名：开头方括号为字幕组，` - 01` / `[01]` 为集数，
+//! 支持 `v2` 版本号、`[01-12]` 合集、OVA/SP 特别篇与字幕语言标记。技术标记仍交给
+//! [`release_parser`] 解析，这里只负责标题、集数与字幕相关字段。
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::services::release_parser::{self, ReleaseInfo};
+use crate::services::scraper;
+
+/// 标题后的集数：` - 05`、` - 05v2`、` - 05 END`、` - SP01`、` - 01-12`
+static DASH_EPISODE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\s[-–—]\s*(?:(?P<special>SP|OVA|OAD|Special)\s*)?(?P<ep>\d{1,4})(?:\s*[-~]\s*(?P<end>\d{1,4}))?(?:v(?P<ver>\d+))?(?:\s*(?:END|Fin))?(?:\s|$)")
+        .unwrap()
+});
+
+/// 标题后不带集数的特别篇：` - OVA`、` - SP`
+static DASH_SPECIAL_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\s[-–—]\s*(?P<special>SP|OVA|OAD|Special)(?:\s|$)").unwrap());
+
+/// 标题后直接跟集数：`Title 05`、`Title 第05话`
+static TRAILING_EPISODE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\s(?:第\s*)?(?P<ep>\d{2,4})(?:v(?P<ver>\d+))?\s*(?:[话話集])?$").unwrap()
+});
+
+/// 方括号内的集数：`[05]`、`[05v2]`、`[第05话]`、`[05 END]`、`[OVA01]`、`[SP]`
+static BRACKET_EPISODE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(?:(?P<special>SP|OVA|OAD|Special)\s*)?(?:第\s*)?(?P<ep>\d{1,4})?(?:v(?P<ver>\d+))?\s*(?:[话話集])?(?:\s*(?:END|Fin|完))?$")
+        .unwrap()
+});
+
+/// 方括号内的合集范围：`[01-12]`、`[01~26 Fin]`、`[01-12 精校合集]`
+static BRACKET_BATCH_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(?:第\s*)?(?P<ep>\d{1,4})\s*[-~]\s*(?P<end>\d{1,4})\s*(?:[话話集])?(?:\s*(?:END|Fin|完|TV|BD|合集|全集|精校合集|Batch))*$")
+        .unwrap()
+});
+
+/// 标题末尾的季标记：`Title S2`、`Title 2nd Season`、`Title Season 2`、`标题 第二季`
+static TITLE_SEASON_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\s*(?:\bS(?P<s>\d{1,2})|\bSeason\s*(?P<season>\d{1,2})|\b(?P<nth>\d{1,2})(?:st|nd|rd|th)\s+Season|第\s*(?P<cn>[0-9一二三四五六七八九十两]{1,3})\s*[季期])$")
+        .unwrap()
+});
+
+/// 宣传装饰：`★04月新番★`、`[10月新番]`
+static DECORATION_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"[★☆][^★☆]*[★☆]").unwrap());
+
+/// 文件校验码 `[ABCD1234]`
+static CRC_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[0-9A-Fa-f]{8}$").unwrap());
+
+/// 播出/发布平台等非标题标记
+const PLATFORM_TAGS: &[&str] = &[
+    "baha", "cr", "abema", "nf", "netflix", "b-global", "bilibili", "amzn", "dsnp", "mp4", "mkv",
+    "avc", "gb", "big5", "raw", "raws", "tv", "bd", "dvd", "web",
+];
+
+/// 字幕语言缩写
+const SUBTITLE_TOKENS: &[(&str, &str)] = &[
+    ("CHS", "zh-hans"),
+    ("SC", "zh-hans"),
+    ("GB", "zh-hans"),
+    ("JPSC", "zh-hans"),
+    ("CHT", "zh-hant"),
+    ("TC", "zh-hant"),
+    ("BIG5", "zh-hant"),
+    ("JPTC", "zh-hant"),
+    ("JPN", "ja"),
+    ("JP", "ja"),
+    ("ENG", "en"),
+    ("EN", "en"),
+];
+
+/// 含这些字样的中文标记才按字幕语言解析（避免把标题里的「日」「中」当成语言）
+const SUBTITLE_MARKERS: &[&str] = &[
+    "简", "繁", "字幕", "内封", "内嵌", "外挂", "双语", "中字", "中文",
+];
+
+/// 字幕组命名特征：以方括号开头，且带有更多方括号或 ` - 01` 式集数
+pub fn looks_like_fansub(name: &str) -> bool {
+    let name = scraper::strip_extension(name).trim_start();
+    if !name.starts_with('[') && !name.starts_with('【') {
+        return false;
+    }
+    let segments = split_segments(name);
+    segments.iter().filter(|s| s.bracketed).count() >= 2
+        || scraper::absolute_episode_in(name).is_some()
+}
+
+/// 解析文件名（去掉扩展名）
+pub fn parse(filename: &str) -> ReleaseInfo {
+    parse_anime_name(scraper::strip_extension(filename))
+}
+
+/// 解析不含扩展名的字幕组发布名或目录名
+pub fn parse_anime_name(name: &str) -> ReleaseInfo {
+    let mut segments = split_segments(name);
+    let mut info = ReleaseInfo::default();
+    let mut confidence = std::collections::BTreeMap::new();
+
+    // 开头方括号为字幕组（整个名称只有一个方括号时视为标题）
+    if segments.len() > 1 && segments[0].bracketed {
+        let group = segments.remove(0).text.trim().to_string();
+        if !group.is_empty() {
+            info.release_group = Some(group);
+            confidence.insert("release_group".to_string(), 0.9);
+        }
+    }
+
+    // 标题、集数、季、特别篇
+    let mut title: Option<String> = None;
+    let mut title_confidence = 0.85;
+    let mut tech_text: Vec<String> = Vec::new();
+    let mut episode_found = false;
+
+    let free_text: String = segments
+        .iter()
+        .filter(|s| !s.bracketed)
+        .map(|s| s.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let free_text = DECORATION_RE.replace_all(&free_text, " ");
+    let free_text = free_text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if is_title_like(&free_text) {
+        let padded = format!(" {free_text} ");
+        if let Some(caps) = scraper::SEASON_EPISODE_RE.captures(&free_text) {
+            let start = caps.get(0).map_or(0, |m| m.start());
+            info.season = caps[1].parse().ok();
+            info.episode = caps[2].parse().ok();
+            info.episode_end = scraper::episode_range_in(&free_text)
+                .filter(|(first, _)| Some(*first) == info.episode)
+                .map(|(_, end)| end);
+            confidence.insert("season".to_string(), 0.95);
+            confidence.insert("episode".to_string(), 0.95);
+            episode_found = true;
+            title = Some(free_text[..start].to_string());
+            tech_text.push(free_text[start..].to_string());
+        } else if let Some(caps) = last_match(&DASH_EPISODE_RE, &padded) {
+            let whole = caps.get(0).unwrap();
+            apply_episode(&mut info, &mut confidence, &caps, 0.9);
+            episode_found = true;
+            title = Some(padded[..whole.start()].to_string());
+            tech_text.push(padded[whole.end()..].to_string());
+        } else if let Some(caps) = last_match(&DASH_SPECIAL_RE, &padded) {
+            let whole = caps.get(0).unwrap();
+            apply_special(&mut info, &mut confidence, &caps["special"]);
+            title = Some(padded[..whole.start()].to_string());
+            tech_text.push(padded[whole.end()..].to_string());
+        } else {
+            // 没有集数标记时按普通发布名截取标题（`[Group] Movie.2019.1080p.BluRay`）
+            let scene = release_parser::parse_scene_name(&free_text);
+            info.year = scene.year;
+            title = Some(scene.title);
+            tech_text.push(free_text.clone());
+        }
+    } else if !free_text.is_empty() {
+        tech_text.push(free_text.clone());
+    }
+
+    for segment in segments.iter().filter(|s| s.bracketed) {
+        let text = segment.text.trim();
+        if !episode_found {
+            if let Some(caps) = BRACKET_BATCH_RE.captures(text) {
+                let (first, last) = (caps["ep"].parse().ok(), caps["end"].parse().ok());
+                if first.is_some() && last > first {
+                    info.absolute_episode = first;
+                    info.episode_end = last;
+                    info.batch = true;
+                    confidence.insert("episode".to_string(), 0.9);
+                    episode_found = true;
+                    continue;
+                }
+            }
+            if let Some(caps) = BRACKET_EPISODE_RE.captures(text).filter(|caps| {
+                caps.name("ep").is_some_and(|ep| !is_year(ep.as_str()))
+                    || caps.name("special").is_some()
+            }) {
+                apply_episode(&mut info, &mut confidence, &caps, 0.85);
+                episode_found = caps.name("ep").is_some();
+                continue;
+            }
+        }
+        if info.season.is_none() {
+            if let Some(season) = TITLE_SEASON_RE
+                .captures(&format!(" {text}"))
+                .filter(|caps| caps.get(0).is_some_and(|m| m.start() == 0))
+                .and_then(|caps| season_from(&caps))
+            {
+                info.season = Some(season);
+                confidence.insert("season".to_string(), 0.85);
+                continue;
+            }
+        }
+        if title.is_none() && is_title_like(text) && !is_tag(text) {
+            title = Some(text.to_string());
+            title_confidence = 0.75;
+            continue;
+        }
+        tech_text.push(text.to_string());
+    }
+
+    // 方括号里也没有集数时，才把标题末尾的数字当作集数（`Title 05 [1080p]`，而非 `Mob Psycho 100 [01]`）
+    if !episode_found {
+        if let Some(caps) = title.as_deref().and_then(|t| {
+            TRAILING_EPISODE_RE
+                .captures(t)
+                .filter(|caps| !is_year(&caps["ep"]))
+        }) {
+            let start = caps.get(0).map_or(0, |m| m.start());
+            apply_episode(&mut info, &mut confidence, &caps, 0.6);
+            title = title.map(|mut t| {
+                t.truncate(start);
+                t
+            });
+        }
+    }
+
+    // 标题：拆分 `中文名 / Romaji`，剥离季标记与年份
+    if let Some(raw) = title {
+        let mut titles: Vec<String> = raw
+            .split(['/', '|'])
+            .map(|t| t.split_whitespace().collect::<Vec<_>>().join(" "))
+            .map(|t| {
+                t.trim_matches(|c: char| matches!(c, '-' | '_' | '.' | ' '))
+                    .to_string()
+            })
+            .filter(|t| !t.is_empty())
+            .collect();
+        for title in titles.iter_mut() {
+            if let Some(caps) = TITLE_SEASON_RE.captures(title) {
+                if let Some(season) = season_from(&caps) {
+                    if info.season.is_none() {
+                        info.season = Some(season);
+                        confidence.insert("season".to_string(), 0.85);
+                    }
+                    let start = caps.get(0).map_or(title.len(), |m| m.start());
+                    title.truncate(start);
+                    *title = title.trim().to_string();
+                }
+            }
+        }
+        titles.retain(|t| !t.is_empty());
+        if !titles.is_empty() {
+            info.title = titles.remove(0);
+            info.alternative_titles = titles;
+            confidence.insert("title".to_string(), title_confidence);
+        }
+    }
+
+    // 季号出现时，番剧集数是季内集号
+    if info.season.is_some() && info.episode.is_none() && !info.batch {
+        info.episode = info.absolute_episode.take();
+    }
+
+    // 年份：只认括号年份，避免与集数混淆
+    if info.year.is_none() {
+        if let Some(year) = release_parser::PAREN_YEAR_RE
+            .captures(name)
+            .and_then(|caps| caps[1].parse().ok())
+        {
+            info.year = Some(year);
+            confidence.insert("year".to_string(), 0.9);
+        }
+    } else {
+        confidence.insert("year".to_string(), 0.8);
+    }
+
+    // 技术标记与字幕语言
+    let tech = release_parser::parse_scene_name(&tech_text.join(" "));
+    for field in [
+        "resolution",
+        "source",
+        "video_codec",
+        "hdr",
+        "audio",
+        "audio_channels",
+        "edition",
+        "repack",
+        "proper",
+        "languages",
+    ] {
+        if let Some(value) = tech.confidence.get(field) {
+            confidence.insert(field.to_string(), *value);
+        }
+    }
+    info.resolution = tech.resolution;
+    info.source = tech.source;
+    info.video_codec = tech.video_codec;
+    info.hdr = tech.hdr;
+    info.audio = tech.audio;
+    info.audio_channels = tech.audio_channels;
+    info.edition = tech.edition;
+    info.repack = tech.repack;
+    info.proper = tech.proper;
+    info.languages = tech.languages;
+
+    for text in &tech_text {
+        for code in subtitle_languages(text) {
+            if !info.subtitles.iter().any(|s| s == code) {
+                info.subtitles.push(code.to_string());
+            }
+        }
+    }
+    if !info.subtitles.is_empty() {
+        confidence.insert("subtitles".to_string(), 0.85);
+    }
+
+    info.confidence = confidence;
+    info
+}
+
+/// 名称片段：方括号（`[]`、`【】`、`()`）内的标记或括号外的文本
+struct Segment {
+    text: String,
+    bracketed: bool,
+}
+
+fn split_segments(name: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut closing = None;
+    let flush = |current: &mut String, bracketed: bool, segments: &mut Vec<Segment>| {
+        if !current.trim().is_empty() {
+            segments.push(Segment {
+                text: current.trim().to_string(),
+                bracketed,
+            });
+        }
+        current.clear();
+    };
+    for c in name.chars() {
+        match (closing, c) {
+            (None, '[' | '【' | '(' | '（') => {
+                flush(&mut current, false, &mut segments);
+                closing = Some(match c {
+                    '[' => ']',
+                    '【' => '】',
+                    '(' => ')',
+                    _ => '）',
+                });
+            }
+            (Some(close), _) if c == close => {
+                flush(&mut current, true, &mut segments);
+                closing = None;
+            }
+            _ => current.push(c),
+        }
+    }
+    flush(&mut current, closing.is_some(), &mut segments);
+    segments
+}
+
+/// 取最后一个匹配（标题本身可能含 ` - `）
+fn last_match<'a>(re: &Regex, text: &'a str) -> Option<regex::Captures<'a>> {
+    let mut last = None;
+    let mut start = 0;
+    while let Some(caps) = re.captures_at(text, start) {
+        let whole = caps.get(0).unwrap();
+        // 匹配吃掉了结尾空格，下一次从空格处继续以便相邻的 ` - `
+        start = whole.start() + 1;
+        last = Some(caps);
+    }
+    last
+}
+
+fn apply_episode(
+    info: &mut ReleaseInfo,
+    confidence: &mut std::collections::BTreeMap<String, f64>,
+    caps: &regex::Captures,
+    episode_confidence: f64,
+) {
+    let episode = caps.name("ep").and_then(|m| m.as_str().parse::<u32>().ok());
+    let end = caps
+        .name("end")
+        .and_then(|m| m.as_str().parse::<u32>().ok())
+        .filter(|end| episode.is_some_and(|ep| *end > ep));
+    if let Some(special) = caps.name("special") {
+        apply_special(info, confidence, special.as_str());
+        info.episode = episode;
+    } else {
+        info.absolute_episode = episode;
+    }
+    if episode.is_some() {
+        confidence.insert("episode".to_string(), episode_confidence);
+    }
+    if end.is_some() {
+        info.episode_end = end;
+        info.batch = true;
+    }
+    if let Some(version) = caps.name("ver").and_then(|m| m.as_str().parse().ok()) {
+        info.version = Some(version);
+        confidence.insert("version".to_string(), 0.95);
+    }
+}
+
+/// OVA/OAD 与 SP 归入第 0 季
+fn apply_special(
+    info: &mut ReleaseInfo,
+    confidence: &mut std::collections::BTreeMap<String, f64>,
+    marker: &str,
+) {
+    let special = match marker.to_ascii_lowercase().as_str() {
+        "ova" | "oad" => "ova",
+        _ => "special",
+    };
+    info.special_type = Some(special.to_string());
+    info.season = Some(0);
+    confidence.insert("special_type".to_string(), 0.85);
+}
+
+fn season_from(caps: &regex::Captures) -> Option<u32> {
+    if let Some(m) = caps
+        .name("s")
+        .or_else(|| caps.name("season"))
+        .or_else(|| caps.name("nth"))
+    {
+        return m.as_str().parse().ok();
+    }
+    let cn = caps.name("cn")?.as_str();
+    cn.parse().ok().or_else(|| scraper::chinese_number(cn))
+}
+
+fn is_year(text: &str) -> bool {
+    text.parse::<u32>().is_ok_and(|n| (1900..2100).contains(&n))
+}
+
+/// 至少含一个字母或汉字
+fn is_title_like(text: &str) -> bool {
+    text.chars().any(char::is_alphabetic)
+}
+
+/// 技术、平台、字幕语言、宣传或校验码标记，不能作为标题
+fn is_tag(text: &str) -> bool {
+    let lower = text.to_ascii_lowercase();
+    if PLATFORM_TAGS.contains(&lower.as_str())
+        || CRC_RE.is_match(text)
+        || text.contains("新番")
+        || text.contains("招募")
+        || !subtitle_languages(text).is_empty()
+        || lower.contains("subtitle")
+        || lower.contains("subs")
+    {
+        return true;
+    }
+    let parsed = release_parser::parse_scene_name(text);
+    parsed
+        .confidence
+        .keys()
+        .any(|k| k != "title" && k != "year")
+}
+
+/// 标记中的字幕语言
+fn subtitle_languages(text: &str) -> Vec<&'static str> {
+    let mut codes = Vec::new();
+    let mut push = |code: &'static str| {
+        if !codes.contains(&code) {
+            codes.push(code);
+        }
+    };
+    for token in text.split(|c: char| c.is_whitespace() || "&_+/,.-".contains(c)) {
+        let upper = token.to_ascii_uppercase();
+        if let Some((_, code)) = SUBTITLE_TOKENS.iter().find(|(t, _)| *t == upper) {
+            push(code);
+        }
+    }
+    let lower = text.to_ascii_lowercase();
+    if lower.contains("multi") && (lower.contains("sub") || lower.contains("subtitle")) {
+        push("multi");
+    }
+    if SUBTITLE_MARKERS.iter().any(|m| text.contains(m)) {
+        if text.contains('简') {
+            push("zh-hans");
+        }
+        if text.contains('繁') {
+            push("zh-hant");
+        }
+        if !text.contains('简') && !text.contains('繁') && text.contains('中') {
+            push("zh");
+        }
+        if text.contains('日') {
+            push("ja");
+        }
+        if text.contains('英') {
+            push("en");
+        }
+    }
+    codes
+}
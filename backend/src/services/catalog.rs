@@ -0,0 +1,404 @@
+//! 影视目录：把识别结果规范化为 电影 / 剧集 / 季 / 单集 四级结构
+//!
+//! 目录记录以「数据源:外部 ID」为主键，多个文件识别到同一部作品时共享同一条记录，
+//! media_files 通过 movie_id / show_id / season_id / episode_id 关联。
+
+use crate::models::{Episode, MediaFile, Movie, Season, Show};
+use crate::services::{collections, people};
+use chrono::Utc;
+use serde_json::Value;
+use sqlx::SqlitePool;
+
+const MOVIE_FIELDS: &str =
+    "m.id, m.provider, m.external_id, m.title, m.original_title, m.localized_titles, m.aliases, m.year, m.overview, \
+    m.poster_url, m.backdrop_url, m.rating, m.collection_id, \
+    (SELECT COUNT(*) FROM media_files f WHERE f.movie_id = m.id) AS file_count, \
+    m.created_at, m.updated_at";
+const SHOW_FIELDS: &str =
+    "s.id, s.provider, s.external_id, s.title, s.original_title, s.localized_titles, s.aliases, s.year, s.overview, \
+    s.poster_url, s.backdrop_url, s.rating, \
+    (SELECT COUNT(*) FROM seasons se WHERE se.show_id = s.id) AS season_count, \
+    (SELECT COUNT(*) FROM episodes e WHERE e.show_id = s.id) AS episode_count, \
+    (SELECT COUNT(*) FROM media_files f WHERE f.show_id = s.id) AS file_count, \
+    s.created_at, s.updated_at";
+const SEASON_FIELDS: &str = "se.id, se.show_id, se.season_number, se.name, \
+    (SELECT COUNT(*) FROM episodes e WHERE e.season_id = se.id) AS episode_count, \
+    (SELECT COUNT(*) FROM media_files f WHERE f.season_id = se.id) AS file_count, \
+    se.created_at, se.updated_at";
+const EPISODE_FIELDS: &str =
+    "e.id, e.show_id, e.season_id, e.season_number, e.episode_number, e.absolute_number, \
+    e.title, e.overview, e.air_date, e.runtime, e.rating, e.still_url, \
+    (SELECT COUNT(*) FROM media_files f WHERE f.episode_id = e.id) AS file_count, \
+    e.created_at, e.updated_at";
+
+/// 作品 ID：`<provider>:<external_id>`
+pub fn title_id(provider: &str, external_id: &str) -> String {
+    format!("{provider}:{external_id}")
+}
+
+/// 季 ID：`<show_id>:s<season>`
+pub fn season_id(show_id: &str, season: i64) -> String {
+    format!("{show_id}:s{season}")
+}
+
+/// 单集 ID：`<show_id>:s<season>e<episode>`
+pub fn episode_id(show_id: &str, season: i64, episode: i64) -> String {
+    format!("{show_id}:s{season}e{episode}")
+}
+
+fn str_field<'a>(details: &'a Value, keys: &[&str]) -> Option<&'a str> {
+    keys.iter()
+        .find_map(|key| details.get(*key).and_then(Value::as_str))
+        .filter(|value| !value.is_empty())
+}
+
+/// 非空的对象或数组字段序列化为 JSON 列值
+fn json_field(details: &Value, key: &str) -> Option<String> {
+    details
+        .get(key)
+        .filter(|value| match value {
+            Value::Object(map) => !map.is_empty(),
+            Value::Array(items) => !items.is_empty(),
+            _ => false,
+        })
+        .map(Value::to_string)
+}
+
+fn int_field(details: &Value, key: &str) -> Option<i64> {
+    details.get(key).and_then(Value::as_i64)
+}
+
+/// 将一次识别结果写入目录并关联到文件
+///
+/// 电影写入 movies；剧集写入 shows，有季号时写入 seasons，有集号时写入 episodes
+/// （多集文件按 `episodes` 数组逐集写入，文件关联首集）。结果中的演职员写入 people / credits，
+/// 电影所属系列写入 collections。重复调用是幂等的。
+pub async fn link_file(
+    db: &SqlitePool,
+    file_id: &str,
+    provider: &str,
+    external_id: &str,
+    media_type: &str,
+    details: &Value,
+) -> anyhow::Result<()> {
+    let id = title_id(provider, external_id);
+    let now = Utc::now();
+    let table = if media_type == "tv" {
+        "shows"
+    } else {
+        "movies"
+    };
+    sqlx::query(&format!(
+        "INSERT INTO {table} (id, provider, external_id, title, original_title, localized_titles, aliases, year, overview, poster_url, backdrop_url, rating, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(id) DO UPDATE SET
+             title = excluded.title,
+             original_title = COALESCE(excluded.original_title, {table}.original_title),
+             localized_titles = COALESCE(excluded.localized_titles, {table}.localized_titles),
+             aliases = COALESCE(excluded.aliases, {table}.aliases),
+             year = COALESCE(excluded.year, {table}.year),
+             overview = COALESCE(excluded.overview, {table}.overview),
+             poster_url = COALESCE(excluded.poster_url, {table}.poster_url),
+             backdrop_url = COALESCE(excluded.backdrop_url, {table}.backdrop_url),
+             rating = COALESCE(excluded.rating, {table}.rating),
+             updated_at = excluded.updated_at"
+    ))
+    .bind(&id)
+    .bind(provider)
+    .bind(external_id)
+    .bind(str_field(details, &["title", "name"]).unwrap_or_default())
+    .bind(str_field(details, &["original_title", "original_name"]))
+    .bind(json_field(details, "localized_titles"))
+    .bind(json_field(details, "aliases"))
+    .bind(int_field(details, "year"))
+    .bind(str_field(details, &["overview"]))
+    .bind(str_field(details, &["poster_url"]))
+    .bind(str_field(details, &["backdrop_url"]))
+    .bind(details.get("rating").and_then(Value::as_f64))
+    .bind(now)
+    .bind(now)
+    .execute(db)
+    .await?;
+    let level = if media_type == "tv" {
+        Level::Show
+    } else {
+        Level::Movie
+    };
+    people::store_credits(db, provider, level, &id, details).await?;
+
+    if media_type != "tv" {
+        collections::link_movie(db, provider, &id, details).await?;
+        sqlx::query(
+            "UPDATE media_files SET movie_id = ?, show_id = NULL, season_id = NULL, episode_id = NULL WHERE id = ?",
+        )
+        .bind(&id)
+        .bind(file_id)
+        .execute(db)
+        .await?;
+        return Ok(());
+    }
+
+    let season = int_field(details, "season_number");
+    let mut linked_season = None;
+    let mut linked_episode = None;
+    if let Some(season) = season {
+        let season_key = season_id(&id, season);
+        sqlx::query(
+            "INSERT INTO seasons (id, show_id, season_number, name, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET
+                 name = COALESCE(excluded.name, seasons.name),
+                 updated_at = excluded.updated_at",
+        )
+        .bind(&season_key)
+        .bind(&id)
+        .bind(season)
+        .bind(str_field(details, &["season_name"]))
+        .bind(now)
+        .bind(now)
+        .execute(db)
+        .await?;
+
+        for episode in episode_rows(details) {
+            let episode_key = episode_id(&id, season, episode.number);
+            sqlx::query(
+                "INSERT INTO episodes (id, show_id, season_id, season_number, episode_number, absolute_number, title, overview, air_date, runtime, rating, still_url, created_at, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                 ON CONFLICT(id) DO UPDATE SET
+                     absolute_number = COALESCE(excluded.absolute_number, episodes.absolute_number),
+                     title = COALESCE(excluded.title, episodes.title),
+                     overview = COALESCE(excluded.overview, episodes.overview),
+                     air_date = COALESCE(excluded.air_date, episodes.air_date),
+                     runtime = COALESCE(excluded.runtime, episodes.runtime),
+                     rating = COALESCE(excluded.rating, episodes.rating),
+                     still_url = COALESCE(excluded.still_url, episodes.still_url),
+                     updated_at = excluded.updated_at",
+            )
+            .bind(&episode_key)
+            .bind(&id)
+            .bind(&season_key)
+            .bind(season)
+            .bind(episode.number)
+            .bind(episode.absolute_number)
+            .bind(episode.title)
+            .bind(episode.overview)
+            .bind(episode.air_date)
+            .bind(episode.runtime)
+            .bind(episode.rating)
+            .bind(episode.still_url)
+            .bind(now)
+            .bind(now)
+            .execute(db)
+            .await?;
+            linked_episode.get_or_insert(episode_key);
+        }
+        linked_season = Some(season_key);
+    }
+
+    sqlx::query(
+        "UPDATE media_files SET movie_id = NULL, show_id = ?, season_id = ?, episode_id = ? WHERE id = ?",
+    )
+    .bind(&id)
+    .bind(linked_season)
+    .bind(linked_episode)
+    .bind(file_id)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+struct EpisodeRow<'a> {
+    number: i64,
+    absolute_number: Option<i64>,
+    title: Option<&'a str>,
+    overview: Option<&'a str>,
+    air_date: Option<&'a str>,
+    runtime: Option<i64>,
+    rating: Option<f64>,
+    still_url: Option<&'a str>,
+}
+
+/// 从识别结果中取出单集记录：优先使用数据源返回的 `episodes` 数组，否则退回 `episode_*` 字段
+fn episode_rows(details: &Value) -> Vec<EpisodeRow<'_>> {
+    let Some(first) = int_field(details, "episode_number") else {
+        return Vec::new();
+    };
+    let absolute = int_field(details, "absolute_number");
+
+    let from_provider: Vec<EpisodeRow<'_>> = details
+        .get("episodes")
+        .and_then(Value::as_array)
+        .map(|episodes| {
+            episodes
+                .iter()
+                .filter_map(|episode| {
+                    let number = int_field(episode, "episode_number")?;
+                    Some(EpisodeRow {
+                        number,
+                        absolute_number: int_field(episode, "absolute_number")
+                            .or_else(|| absolute.map(|a| a + number - first)),
+                        title: str_field(episode, &["name"]),
+                        overview: str_field(episode, &["overview"]),
+                        air_date: str_field(episode, &["air_date"]),
+                        runtime: int_field(episode, "runtime"),
+                        rating: episode.get("rating").and_then(Value::as_f64),
+                        still_url: str_field(episode, &["still_url"]),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if !from_provider.is_empty() {
+        return from_provider;
+    }
+
+    let last = int_field(details, "episode_number_end")
+        .filter(|last| *last > first)
+        .unwrap_or(first);
+    (first..=last)
+        .map(|number| {
+            let is_first = number == first;
+            EpisodeRow {
+                number,
+                absolute_number: absolute.map(|a| a + number - first),
+                title: str_field(details, &["episode_title"]).filter(|_| is_first),
+                overview: str_field(details, &["episode_overview"]).filter(|_| is_first),
+                air_date: str_field(details, &["episode_air_date"]).filter(|_| is_first),
+                runtime: int_field(details, "episode_runtime").filter(|_| is_first),
+                rating: details
+                    .get("episode_rating")
+                    .and_then(Value::as_f64)
+                    .filter(|_| is_first),
+                still_url: str_field(details, &["episode_still_url"]).filter(|_| is_first),
+            }
+        })
+        .collect()
+}
+
+/// 列出电影，可按标题、原名、各语言标题与别名模糊搜索
+pub async fn list_movies(
+    db: &SqlitePool,
+    search: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> anyhow::Result<Vec<Movie>> {
+    let movies = sqlx::query_as::<_, Movie>(&format!(
+        "SELECT {MOVIE_FIELDS} FROM movies m
+         WHERE ?1 IS NULL OR m.title LIKE '%' || ?1 || '%' OR m.original_title LIKE '%' || ?1 || '%'
+            OR m.localized_titles LIKE '%' || ?1 || '%' OR m.aliases LIKE '%' || ?1 || '%'
+         ORDER BY m.title COLLATE NOCASE, m.year LIMIT ?2 OFFSET ?3"
+    ))
+    .bind(search)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(db)
+    .await?;
+    Ok(movies)
+}
+
+pub async fn get_movie(db: &SqlitePool, id: &str) -> anyhow::Result<Option<Movie>> {
+    let movie = sqlx::query_as::<_, Movie>(&format!(
+        "SELECT {MOVIE_FIELDS} FROM movies m WHERE m.id = ?"
+    ))
+    .bind(id)
+    .fetch_optional(db)
+    .await?;
+    Ok(movie)
+}
+
+/// 列出剧集，可按标题、原名、各语言标题与别名模糊搜索
+pub async fn list_shows(
+    db: &SqlitePool,
+    search: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> anyhow::Result<Vec<Show>> {
+    let shows = sqlx::query_as::<_, Show>(&format!(
+        "SELECT {SHOW_FIELDS} FROM shows s
+         WHERE ?1 IS NULL OR s.title LIKE '%' || ?1 || '%' OR s.original_title LIKE '%' || ?1 || '%'
+            OR s.localized_titles LIKE '%' || ?1 || '%' OR s.aliases LIKE '%' || ?1 || '%'
+         ORDER BY s.title COLLATE NOCASE, s.year LIMIT ?2 OFFSET ?3"
+    ))
+    .bind(search)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(db)
+    .await?;
+    Ok(shows)
+}
+
+pub async fn get_show(db: &SqlitePool, id: &str) -> anyhow::Result<Option<Show>> {
+    let show =
+        sqlx::query_as::<_, Show>(&format!("SELECT {SHOW_FIELDS} FROM shows s WHERE s.id = ?"))
+            .bind(id)
+            .fetch_optional(db)
+            .await?;
+    Ok(show)
+}
+
+pub async fn list_seasons(db: &SqlitePool, show_id: &str) -> anyhow::Result<Vec<Season>> {
+    let seasons = sqlx::query_as::<_, Season>(&format!(
+        "SELECT {SEASON_FIELDS} FROM seasons se WHERE se.show_id = ? ORDER BY se.season_number"
+    ))
+    .bind(show_id)
+    .fetch_all(db)
+    .await?;
+    Ok(seasons)
+}
+
+pub async fn get_season(
+    db: &SqlitePool,
+    show_id: &str,
+    season_number: i32,
+) -> anyhow::Result<Option<Season>> {
+    let season = sqlx::query_as::<_, Season>(&format!(
+        "SELECT {SEASON_FIELDS} FROM seasons se WHERE se.show_id = ? AND se.season_number = ?"
+    ))
+    .bind(show_id)
+    .bind(season_number)
+    .fetch_optional(db)
+    .await?;
+    Ok(season)
+}
+
+pub async fn list_episodes(db: &SqlitePool, season_id: &str) -> anyhow::Result<Vec<Episode>> {
+    let episodes = sqlx::query_as::<_, Episode>(&format!(
+        "SELECT {EPISODE_FIELDS} FROM episodes e WHERE e.season_id = ? ORDER BY e.episode_number"
+    ))
+    .bind(season_id)
+    .fetch_all(db)
+    .await?;
+    Ok(episodes)
+}
+
+/// 目录层级，用于查询关联文件
+#[derive(Debug, Clone, Copy)]
+pub enum Level {
+    Movie,
+    Show,
+    Season,
+    Episode,
+}
+
+impl Level {
+    fn column(self) -> &'static str {
+        match self {
+            Level::Movie => "movie_id",
+            Level::Show => "show_id",
+            Level::Season => "season_id",
+            Level::Episode => "episode_id",
+        }
+    }
+}
+
+/// 列出关联到某个目录条目的媒体文件
+pub async fn files_for(db: &SqlitePool, level: Level, id: &str) -> anyhow::Result<Vec<MediaFile>> {
+    let files = sqlx::query_as::<_, MediaFile>(&format!(
+        "SELECT * FROM media_files WHERE {} = ? ORDER BY detected_season, detected_episode, name",
+        level.column()
+    ))
+    .bind(id)
+    .fetch_all(db)
+    .await?;
+    Ok(files)
+}
@@ -0,0 +1,261 @@
+//! 中日文标题归一化
+//!
+//! 标题匹配前把同一标题的不同写法折叠为同一形式：全角转半角、繁体转简体、
+//! 季 / 期等标记中的中文数字转阿拉伯数字、假名转平文式罗马字，并合并罗马字长音的不同写法。
+//! 长音等罗马字写法只在确定是日文的词（由假名转来或带长音符号）上合并，
+//! 避免 `Good` / `God`、`House` / `Hose` 这类英文词被折叠成同一写法。结果只用于比较，不用于显示。
+
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+
+use crate::services::scraper;
+
+/// 常见繁体字 → 简体字（以影视标题用字为主，不追求完整）
+const TRADITIONAL_SIMPLIFIED: &str = "
+萬万 與与 專专 業业 東东 絲丝 兩两 嚴严 個个 豐丰 臨临 為为 麗丽 舉举 麼么 義义 烏乌 樂乐
+喬乔 習习 鄉乡 書书 買买 亂乱 爭争 於于 虧亏 雲云 亞亚 產产 親亲 億亿 僅仅 從从 倉仓 儀仪
+們们 價价 眾众 優优 會会 傘伞 偉伟 傳传 傷伤 倫伦 偽伪 體体 餘余 俠侠 侶侣 偵侦 側侧 僑侨
+係系 倆俩 儉俭 債债 傾倾 償偿 儲储 兒儿 黨党 蘭兰 關关 興兴 養养 獸兽 岡冈 冊册 寫写 軍军
+農农 馮冯 衝冲 決决 況况 凍冻 淨净 涼凉 減减 幾几 鳳凤 憑凭 凱凯 擊击 劃划 劉刘 則则 剛刚
+創创 刪删 別别 劑剂 劍剑 剝剥 劇剧 勸劝 辦办 務务 動动 勵励 勁劲 勞劳 勢势 勳勋 匯汇 區区
+醫医 華华 協协 單单 賣卖 盧卢 衛卫 卻却 廠厂 廳厅 曆历 厲厉 壓压 厭厌 縣县 參参 雙双 發发
+變变 敘叙 疊叠 葉叶 號号 嘆叹 嚇吓 嗎吗 啟启 吳吴 員员 響响 問问 喚唤 喪丧 嘩哗 團团 園园
+圍围 圖图 圓圆 聖圣 場场 壞坏 塊块 堅坚 壇坛 墳坟 墜坠 壘垒 執执 塗涂 壺壶 壽寿 夠够 夢梦
+頭头 誇夸 夾夹 奪夺 奮奋 獎奖 妝妆 婦妇 媽妈 孫孙 學学 寧宁 寶宝 實实 寵宠 審审 憲宪 宮宫
+寬宽 賓宾 對对 尋寻 導导 將将 爾尔 塵尘 嘗尝 堯尧 屍尸 盡尽 層层 屆届 屬属 歲岁 豈岂 島岛
+峽峡 崗岗 嶺岭 巖岩 帥帅 師师 帳帐 帶带 幫帮 幣币 幹干 並并 廣广 莊庄 慶庆 庫库 應应 廟庙
+龐庞 廢废 開开 異异 棄弃 張张 彌弥 彎弯 強强 歸归 當当 錄录 徹彻 徑径 後后 復复 憶忆 懷怀
+態态 憐怜 總总 戀恋 惡恶 惱恼 悅悦 懸悬 驚惊 慘惨 慣惯 願愿 戰战 戲戏 戶户 擁拥 擔担 擇择
+掛挂 撲扑 擴扩 掃扫 揚扬 擾扰 撫抚 搶抢 護护 報报 擋挡 擠挤 揮挥 損损 換换 據据 攜携 擺摆
+攝摄 敵敌 數数 齋斋 鬥斗 斬斩 斷断 無无 舊旧 時时 曉晓 暫暂 曬晒 殺杀 雜杂 權权 條条 來来
+楊杨 極极 構构 槍枪 楓枫 櫃柜 標标 欄栏 樹树 樣样 橋桥 機机 檢检 樓楼 櫻樱 歐欧 歡欢 歷历
+殘残 殼壳 毀毁 畢毕 氣气 漢汉 湯汤 溝沟 沒没 淚泪 澤泽 潔洁 灑洒 濃浓 濤涛 潤润 淵渊 漸渐
+漁渔 溫温 灣湾 濕湿 滅灭 燈灯 靈灵 災灾 爐炉 點点 煉炼 爛烂 燒烧 熱热 煙烟 營营 愛爱 爺爷
+牆墙 牽牵 犧牺 狀状 獨独 獄狱 貓猫 獵猎 獻献 獲获 環环 現现 瑪玛 電电 畫画 暢畅 療疗 瘋疯
+盞盏 監监 盤盘 瞞瞒 確确 礦矿 碼码 禮礼 禍祸 離离 種种 稱称 積积 穩稳 窮穷 竊窃 競竞 筆笔
+築筑 簡简 範范 節节 糧粮 緊紧 紅红 約约 級级 紀纪 紋纹 納纳 純纯 紙纸 紗纱 線线 練练 組组
+細细 終终 絕绝 絆绊 給给 統统 結结 經经 綠绿 維维 網网 緣缘 編编 縮缩 織织 繪绘 繫系 羅罗
+罰罚 聞闻 聯联 聰聪 聲声 職职 聽听 肅肃 脅胁 腦脑 膠胶 臉脸 臟脏 艦舰 艷艳 藝艺 蘇苏 蘋苹
+莖茎 薦荐 藥药 蓋盖 蟲虫 蝕蚀 蝦虾 術术 補补 裝装 裡里 裏里 襲袭 製制 見见 規规 視视 覺觉
+覽览 觀观 觸触 計计 訂订 認认 討讨 讓让 訓训 議议 記记 講讲 許许 論论 設设 訪访 證证 評评
+識识 詩诗 試试 話话 誠诚 誕诞 語语 說说 誰谁 課课 調调 談谈 請请 諸诸 讀读 謎谜 謝谢 譯译
+讚赞 豬猪 貝贝 負负 財财 責责 賢贤 敗败 貨货 質质 販贩 貧贫 購购 貴贵 費费 資资 賊贼 賞赏
+賜赐 贈赠 贊赞 趕赶 趙赵 躍跃 蹤踪 車车 軌轨 軟软 輕轻 載载 輪轮 輸输 輝辉 轉转 轟轰 辭辞
+邊边 遼辽 達达 遷迁 過过 運运 還还 這这 進进 遠远 違违 連连 遲迟 適适 選选 遺遗 遙遥 邏逻
+郵邮 鄭郑 鄰邻 醜丑 釋释 針针 釣钓 鈴铃 鉛铅 銀银 銅铜 銳锐 鋒锋 鋼钢 錢钱 錯错 錶表 鍵键
+鍋锅 鍾钟 鎖锁 鎧铠 鏈链 鏡镜 鐘钟 諜谍 鐵铁 鐮镰 長长 門门 閃闪 閉闭 間间 閱阅 闆板 闊阔 陽阳
+陰阴 陣阵 陸陆 隊队 際际 隨随 險险 隱隐 隻只 難难 雞鸡 霧雾 靜静 韓韩 韻韵 頁页 頂顶 項项
+順顺 須须 預预 領领 頻频 題题 顏颜 額额 類类 顧顾 風风 颳刮 飛飞 飯饭 飲饮 館馆 饑饥 馬马
+駕驾 騎骑 驗验 驅驱 髮发 鬆松 鬍胡 魚鱼 鮮鲜 鯨鲸 鳥鸟 鳴鸣 鶴鹤 鷹鹰 麥麦 麵面 黃黄 齊齐
+齒齿 龍龙 龜龟 臺台 檯台 週周 準准 捨舍 穀谷 乾干 傑杰 僕仆 彈弹 燭烛 嶽岳 盜盗 擬拟 蠟蜡
+";
+
+static SIMPLIFIED: Lazy<HashMap<char, char>> = Lazy::new(|| {
+    TRADITIONAL_SIMPLIFIED
+        .split_whitespace()
+        .filter_map(|pair| {
+            let mut chars = pair.chars();
+            Some((chars.next()?, chars.next()?))
+        })
+        .collect()
+});
+
+/// 平假名 U+3041 ~ U+3096 的平文式罗马字
+#[rustfmt::skip]
+const HIRAGANA_ROMAJI: [&str; 86] = [
+    "a", "a", "i", "i", "u", "u", "e", "e", "o", "o",                  // ぁ ~ お
+    "ka", "ga", "ki", "gi", "ku", "gu", "ke", "ge", "ko", "go",        // か ~ ご
+    "sa", "za", "shi", "ji", "su", "zu", "se", "ze", "so", "zo",      // さ ~ ぞ
+    "ta", "da", "chi", "ji", "", "tsu", "zu", "te", "de", "to", "do", // た ~ ど（っ 单独处理）
+    "na", "ni", "nu", "ne", "no",                                      // な ~ の
+    "ha", "ba", "pa", "hi", "bi", "pi", "fu", "bu", "pu",              // は ~ ぷ
+    "he", "be", "pe", "ho", "bo", "po",                                // へ ~ ぽ
+    "ma", "mi", "mu", "me", "mo",                                      // ま ~ も
+    "ya", "ya", "yu", "yu", "yo", "yo",                                // ゃ ~ よ
+    "ra", "ri", "ru", "re", "ro",                                      // ら ~ ろ
+    "wa", "wa", "i", "e", "wo", "n", "vu", "ka", "ke",                 // ゎ ~ ゖ
+];
+
+/// 季 / 期 等标记前的中文数字转为阿拉伯数字
+const NUMBER_SUFFIXES: &str = "季期部篇章集话話回";
+const CHINESE_DIGITS: &str = "零〇一二两三四五六七八九十";
+
+/// 匹配用的中日文折叠：全角转半角、繁转简、标记中的中文数字、假名转罗马字，最后转小写；
+/// 含假名或长音符号的词再合并罗马字长音
+pub fn fold(value: &str) -> String {
+    let text: String = value
+        .chars()
+        .map(to_half_width)
+        .map(to_simplified)
+        .collect();
+    let text = convert_chinese_numerals(&text);
+    let mut result = String::with_capacity(text.len());
+    for (index, word) in text.split(' ').enumerate() {
+        if index > 0 {
+            result.push(' ');
+        }
+        let romaji = kana_to_romaji(word).to_lowercase();
+        if has_japanese_hint(word) {
+            result.push_str(&fold_romaji(&romaji));
+        } else {
+            result.push_str(&romaji);
+        }
+    }
+    result
+}
+
+/// 文本是否明确是日文：含假名、长音符，或带长音符号的罗马字（`ō`、`â` 等）
+pub fn has_japanese_hint(value: &str) -> bool {
+    value.chars().any(|ch| {
+        matches!(katakana_to_hiragana(ch), '\u{3041}'..='\u{3096}' | 'ー')
+            || "āīūēōâîûêôĀĪŪĒŌÂÎÛÊÔ".contains(ch)
+    })
+}
+
+/// 全角 ASCII 与全角空格转半角，常见中日文标点转为空格
+pub fn to_half_width(ch: char) -> char {
+    match ch {
+        '\u{3000}' => ' ',
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(ch as u32 - 0xFEE0).unwrap_or(ch),
+        '、' | '。' | '・' | '「' | '」' | '『' | '』' | '【' | '】' | '《' | '》' | '〈'
+        | '〉' | '〔' | '〕' => ' ',
+        _ => ch,
+    }
+}
+
+/// 繁体字转简体字；不在对照表中的字原样返回
+pub fn to_simplified(ch: char) -> char {
+    SIMPLIFIED.get(&ch).copied().unwrap_or(ch)
+}
+
+/// `第二季` → `第2季`、`二期` → `2期`：只转换「第」之后或季 / 期等标记之前的中文数字，
+/// 标题中其他位置的数字字（如「一拳超人」）保持不变
+pub fn convert_chinese_numerals(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    let mut result = String::with_capacity(value.len());
+    let mut index = 0;
+    while index < chars.len() {
+        if !CHINESE_DIGITS.contains(chars[index]) {
+            result.push(chars[index]);
+            index += 1;
+            continue;
+        }
+        let end = (index..chars.len())
+            .find(|i| !CHINESE_DIGITS.contains(chars[*i]))
+            .unwrap_or(chars.len());
+        let run: String = chars[index..end].iter().collect();
+        let marked = (index > 0 && chars[index - 1] == '第')
+            || chars
+                .get(end)
+                .is_some_and(|next| NUMBER_SUFFIXES.contains(*next));
+        match scraper::chinese_number(&run).filter(|_| marked) {
+            Some(number) => result.push_str(&number.to_string()),
+            None => result.push_str(&run),
+        }
+        index = end;
+    }
+    result
+}
+
+/// 平假名 / 片假名转平文式罗马字（拗音、促音、外来语小写元音与长音符都会处理）
+pub fn kana_to_romaji(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut after_kana = false;
+    let mut sokuon = false;
+    for ch in value.chars() {
+        let ch = katakana_to_hiragana(ch);
+        let Some(syllable) = hiragana_romaji(ch) else {
+            // 长音符：罗马字长音随后统一合并，直接略去
+            if ch == 'ー' && after_kana {
+                continue;
+            }
+            result.push(ch);
+            after_kana = false;
+            sokuon = false;
+            continue;
+        };
+        match ch {
+            'っ' => sokuon = true,
+            // きゃ → kya、しゃ → sha、じゃ → ja
+            'ゃ' | 'ゅ' | 'ょ' if after_kana && result.ends_with('i') => {
+                result.pop();
+                if !(result.ends_with("sh") || result.ends_with("ch") || result.ends_with('j')) {
+                    result.push('y');
+                }
+                result.push_str(&syllable[1..]);
+            }
+            // ファ → fa、ティ → ti
+            'ぁ' | 'ぃ' | 'ぅ' | 'ぇ' | 'ぉ' if after_kana => {
+                result.pop();
+                result.push_str(syllable);
+            }
+            _ => {
+                if std::mem::take(&mut sokuon) {
+                    match syllable.chars().next() {
+                        Some('c') => result.push('t'),
+                        Some(first) if !"aiueon".contains(first) => result.push(first),
+                        _ => {}
+                    }
+                }
+                result.push_str(syllable);
+            }
+        }
+        after_kana = true;
+    }
+    result
+}
+
+/// 合并罗马字长音的不同写法：`ō` / `ou` / `oo` → `o`、`ū` / `uu` → `u` 等，
+/// 以及 `shimbun` / `shinbun` 这类 b、p 前的 m / n、`botchi` / `bocchi` 这类促音写法
+pub fn fold_romaji(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut prev: Option<char> = None;
+    for ch in value.chars() {
+        let ch = match ch {
+            'ā' | 'â' => 'a',
+            'ī' | 'î' => 'i',
+            'ū' | 'û' => 'u',
+            'ē' | 'ê' => 'e',
+            'ō' | 'ô' => 'o',
+            _ => ch,
+        };
+        let long_vowel = matches!(
+            (prev, ch),
+            (Some('a'), 'a')
+                | (Some('i'), 'i')
+                | (Some('u'), 'u')
+                | (Some('e'), 'e')
+                | (Some('o'), 'o')
+                | (Some('o'), 'u')
+        );
+        if long_vowel {
+            continue;
+        }
+        if matches!(ch, 'b' | 'p') && result.ends_with('m') {
+            result.pop();
+            result.push('n');
+        }
+        if ch == 'h' && result.ends_with("tc") {
+            result.truncate(result.len() - 2);
+            result.push_str("cc");
+        }
+        result.push(ch);
+        prev = Some(ch);
+    }
+    result
+}
+
+fn katakana_to_hiragana(ch: char) -> char {
+    match ch {
+        '\u{30A1}'..='\u{30F6}' => char::from_u32(ch as u32 - 0x60).unwrap_or(ch),
+        _ => ch,
+    }
+}
+
+fn hiragana_romaji(ch: char) -> Option<&'static str> {
+    match ch {
+        '\u{3041}'..='\u{3096}' => Some(HIRAGANA_ROMAJI[ch as usize - 0x3041]),
+        _ => None,
+    }
+}
@@ -0,0 +1,356 @@
+//! 电影系列（合集）：记录电影所属的系列，缓存系列图片，并比对系列中已拥有与缺失的作品
+//!
+//! 识别结果中的 `collection` 为数据源无关的统一格式：`id`、`name`、`poster_url`、`backdrop_url`，
+//! 系列详情另含 `overview` 与 `parts`（每项含 `id`、`title`、`original_title`、`year`、
+//! `release_date`、`overview`、`poster_url`）。系列以「数据源:系列 ID」为主键。
+
+use std::path::Path;
+
+use chrono::Utc;
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+
+use crate::models::{Collection, CollectionPart};
+use crate::services::catalog;
+use crate::services::metadata_provider::MetadataProvider;
+use crate::services::poster;
+
+const TMDB_POSTER_BASE: &str = "https://image.tmdb.org/t/p/w500";
+const TMDB_BACKDROP_BASE: &str = "https://image.tmdb.org/t/p/w1280";
+
+const COLLECTION_FIELDS: &str =
+    "c.id, c.provider, c.external_id, c.name, c.overview, c.poster_url, c.backdrop_url, c.poster_path, c.backdrop_path, \
+    (SELECT COUNT(*) FROM collection_parts p WHERE p.collection_id = c.id) AS part_count, \
+    (SELECT COUNT(DISTINCT m.id) FROM movies m WHERE m.collection_id = c.id \
+        AND EXISTS (SELECT 1 FROM media_files f WHERE f.movie_id = m.id)) AS owned_count, \
+    c.created_at, c.updated_at";
+
+fn text(value: Option<&Value>) -> Option<String> {
+    value
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn id_text(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::Number(number) => Some(number.to_string()),
+        value => text(Some(value)),
+    }
+}
+
+fn tmdb_image(item: &Value, key: &str, base: &str) -> Option<String> {
+    text(item.get(key)).map(|path| format!("{base}{path}"))
+}
+
+/// TMDb 电影详情中的 `belongs_to_collection` 转为统一的系列引用
+pub fn collection_ref_from_tmdb(value: &Value) -> Option<Value> {
+    Some(json!({
+        "id": id_text(value.get("id"))?,
+        "name": text(value.get("name"))?,
+        "poster_url": tmdb_image(value, "poster_path", TMDB_POSTER_BASE),
+        "backdrop_url": tmdb_image(value, "backdrop_path", TMDB_BACKDROP_BASE),
+    }))
+}
+
+/// 解析 TMDb `/collection/{id}`，作品按上映日期排序
+pub fn collection_from_tmdb_payload(payload: &Value) -> Value {
+    let mut parts: Vec<Value> = payload
+        .get("parts")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|part| {
+            let release_date = text(part.get("release_date"));
+            Some(json!({
+                "id": id_text(part.get("id"))?,
+                "title": text(part.get("title"))?,
+                "original_title": text(part.get("original_title")),
+                "year": release_date
+                    .as_deref()
+                    .and_then(|date| date.split('-').next())
+                    .and_then(|year| year.parse::<u32>().ok()),
+                "release_date": release_date,
+                "overview": text(part.get("overview")),
+                "poster_url": tmdb_image(part, "poster_path", TMDB_POSTER_BASE),
+            }))
+        })
+        .collect();
+    // 未定档的作品排在最后
+    parts.sort_by_key(|part| {
+        part.get("release_date")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| "9999".to_string())
+    });
+
+    json!({
+        "id": id_text(payload.get("id")),
+        "name": text(payload.get("name")),
+        "overview": text(payload.get("overview")),
+        "poster_url": tmdb_image(payload, "poster_path", TMDB_POSTER_BASE),
+        "backdrop_url": tmdb_image(payload, "backdrop_path", TMDB_BACKDROP_BASE),
+        "parts": parts,
+    })
+}
+
+pub fn has_overview(collection: &Value) -> bool {
+    text(collection.get("overview")).is_some()
+}
+
+/// 用回退语言的结果补全系列名称与简介，以及各部作品的标题与简介
+pub fn merge_collection_translation(primary: Option<Value>, fallback: Value) -> Value {
+    let Some(mut primary) = primary else {
+        return fallback;
+    };
+    for key in ["name", "overview"] {
+        if text(primary.get(key)).is_none() {
+            if let Some(value) = text(fallback.get(key)) {
+                primary[key] = Value::String(value);
+            }
+        }
+    }
+    let fallback_parts = fallback.get("parts").and_then(Value::as_array);
+    if let Some(parts) = primary.get_mut("parts").and_then(Value::as_array_mut) {
+        for part in parts {
+            let Some(translated) = fallback_parts
+                .and_then(|list| list.iter().find(|other| other.get("id") == part.get("id")))
+            else {
+                continue;
+            };
+            if text(part.get("overview")).is_none() {
+                if let Some(overview) = text(translated.get("overview")) {
+                    part["overview"] = Value::String(overview);
+                }
+            }
+        }
+    }
+    primary
+}
+
+/// 按识别结果中的系列引用请求系列详情，并把简介补充到识别结果的 `collection` 中（用于 NFO 的 `<set>`）
+pub async fn fetch_for_details(
+    provider: &dyn MetadataProvider,
+    details: &mut Value,
+) -> anyhow::Result<Option<Value>> {
+    let Some(external_id) = details
+        .get("collection")
+        .and_then(|collection| id_text(collection.get("id")))
+    else {
+        return Ok(None);
+    };
+    let Some(collection) = provider.collection(&external_id).await? else {
+        return Ok(None);
+    };
+    if let Some(overview) = text(collection.get("overview")) {
+        details["collection"]["overview"] = Value::String(overview);
+    }
+    Ok(Some(collection))
+}
+
+/// 按识别结果设置电影所属系列
+///
+/// 结果不含 `collection` 字段（如不支持系列的数据源）时保留原关联，字段为空时清除关联。
+pub async fn link_movie(
+    db: &SqlitePool,
+    provider: &str,
+    movie_id: &str,
+    details: &Value,
+) -> anyhow::Result<()> {
+    let Some(reference) = details.get("collection") else {
+        return Ok(());
+    };
+    let collection_id = match upsert_collection(db, provider, reference).await? {
+        Some(id) => Some(id),
+        None if reference.is_null() => None,
+        None => return Ok(()),
+    };
+    sqlx::query("UPDATE movies SET collection_id = ?, updated_at = ? WHERE id = ?")
+        .bind(collection_id)
+        .bind(Utc::now())
+        .bind(movie_id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// 写入系列记录，缺失的字段保留已有值；返回系列 ID
+async fn upsert_collection(
+    db: &SqlitePool,
+    provider: &str,
+    collection: &Value,
+) -> anyhow::Result<Option<String>> {
+    let (Some(external_id), Some(name)) =
+        (id_text(collection.get("id")), text(collection.get("name")))
+    else {
+        return Ok(None);
+    };
+    let id = catalog::title_id(provider, &external_id);
+    let now = Utc::now();
+    sqlx::query(
+        "INSERT INTO collections (id, provider, external_id, name, overview, poster_url, backdrop_url, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(id) DO UPDATE SET
+             name = excluded.name,
+             overview = COALESCE(excluded.overview, collections.overview),
+             poster_path = CASE WHEN excluded.poster_url IS NULL OR excluded.poster_url = collections.poster_url THEN collections.poster_path END,
+             backdrop_path = CASE WHEN excluded.backdrop_url IS NULL OR excluded.backdrop_url = collections.backdrop_url THEN collections.backdrop_path END,
+             poster_url = COALESCE(excluded.poster_url, collections.poster_url),
+             backdrop_url = COALESCE(excluded.backdrop_url, collections.backdrop_url),
+             updated_at = excluded.updated_at",
+    )
+    .bind(&id)
+    .bind(provider)
+    .bind(&external_id)
+    .bind(&name)
+    .bind(text(collection.get("overview")))
+    .bind(text(collection.get("poster_url")))
+    .bind(text(collection.get("backdrop_url")))
+    .bind(now)
+    .bind(now)
+    .execute(db)
+    .await?;
+    Ok(Some(id))
+}
+
+/// 写入系列详情并替换其作品列表；返回系列 ID
+pub async fn store_collection(
+    db: &SqlitePool,
+    provider: &str,
+    collection: &Value,
+) -> anyhow::Result<Option<String>> {
+    let Some(id) = upsert_collection(db, provider, collection).await? else {
+        return Ok(None);
+    };
+    let Some(parts) = collection.get("parts").and_then(Value::as_array) else {
+        return Ok(Some(id));
+    };
+
+    let mut tx = db.begin().await?;
+    sqlx::query("DELETE FROM collection_parts WHERE collection_id = ?")
+        .bind(&id)
+        .execute(&mut *tx)
+        .await?;
+    for (sort_order, part) in parts.iter().enumerate() {
+        let (Some(external_id), Some(title)) = (id_text(part.get("id")), text(part.get("title")))
+        else {
+            continue;
+        };
+        sqlx::query(
+            "INSERT OR REPLACE INTO collection_parts (collection_id, movie_id, title, original_title, year, release_date, overview, poster_url, sort_order)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(catalog::title_id(provider, &external_id))
+        .bind(title)
+        .bind(text(part.get("original_title")))
+        .bind(part.get("year").and_then(Value::as_i64))
+        .bind(text(part.get("release_date")))
+        .bind(text(part.get("overview")))
+        .bind(text(part.get("poster_url")))
+        .bind(sort_order as i64)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(Some(id))
+}
+
+/// 列出系列，可按名称模糊搜索
+pub async fn list_collections(
+    db: &SqlitePool,
+    search: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> anyhow::Result<Vec<Collection>> {
+    let collections = sqlx::query_as::<_, Collection>(&format!(
+        "SELECT {COLLECTION_FIELDS} FROM collections c
+         WHERE ?1 IS NULL OR c.name LIKE '%' || ?1 || '%'
+         ORDER BY c.name COLLATE NOCASE LIMIT ?2 OFFSET ?3"
+    ))
+    .bind(search)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(db)
+    .await?;
+    Ok(collections)
+}
+
+pub async fn get_collection(db: &SqlitePool, id: &str) -> anyhow::Result<Option<Collection>> {
+    let collection = sqlx::query_as::<_, Collection>(&format!(
+        "SELECT {COLLECTION_FIELDS} FROM collections c WHERE c.id = ?"
+    ))
+    .bind(id)
+    .fetch_optional(db)
+    .await?;
+    Ok(collection)
+}
+
+/// 系列中的全部作品及各自的入库文件数
+///
+/// 系列详情尚未取回时，以目录中已关联到该系列的电影代替。
+pub async fn collection_parts(
+    db: &SqlitePool,
+    collection_id: &str,
+) -> anyhow::Result<Vec<CollectionPart>> {
+    let parts = sqlx::query_as::<_, CollectionPart>(
+        "SELECT p.movie_id, p.title, p.original_title, p.year, p.release_date, p.overview, p.poster_url, p.sort_order,
+                (SELECT COUNT(*) FROM media_files f WHERE f.movie_id = p.movie_id) AS file_count
+         FROM collection_parts p WHERE p.collection_id = ?1
+         UNION ALL
+         SELECT m.id AS movie_id, m.title, m.original_title, m.year, NULL AS release_date, m.overview, m.poster_url,
+                (SELECT COUNT(*) FROM collection_parts p WHERE p.collection_id = ?1) AS sort_order,
+                (SELECT COUNT(*) FROM media_files f WHERE f.movie_id = m.id) AS file_count
+         FROM movies m
+         WHERE m.collection_id = ?1
+           AND NOT EXISTS (SELECT 1 FROM collection_parts p WHERE p.collection_id = ?1 AND p.movie_id = m.id)
+         ORDER BY sort_order, year",
+    )
+    .bind(collection_id)
+    .fetch_all(db)
+    .await?;
+    Ok(parts)
+}
+
+/// 下载系列海报与背景图到 `cache_dir/<数据源>/<系列 ID>-poster.jpg` / `-fanart.jpg`
+pub async fn cache_artwork(
+    db: &SqlitePool,
+    cache_dir: &Path,
+    collection_id: &str,
+) -> anyhow::Result<()> {
+    let Some(collection) = get_collection(db, collection_id).await? else {
+        return Ok(());
+    };
+    let dir = cache_dir.join(&collection.provider);
+    let images = [
+        (
+            "poster_path",
+            collection.poster_url.as_deref(),
+            collection.poster_path.as_deref(),
+            "poster",
+        ),
+        (
+            "backdrop_path",
+            collection.backdrop_url.as_deref(),
+            collection.backdrop_path.as_deref(),
+            "fanart",
+        ),
+    ];
+    for (column, url, cached, suffix) in images {
+        let (Some(url), None) = (url, cached) else {
+            continue;
+        };
+        let path = dir.join(format!("{}-{suffix}.jpg", collection.external_id));
+        poster::download_image(url, &path).await?;
+        sqlx::query(&format!(
+            "UPDATE collections SET {column} = ?, updated_at = ? WHERE id = ?"
+        ))
+        .bind(path.to_string_lossy().to_string())
+        .bind(Utc::now())
+        .bind(collection_id)
+        .execute(db)
+        .await?;
+    }
+    Ok(())
+}
@@ -1,16 +1,17 @@
 use crate::models::{DuplicateGroup, MediaFile};
+use crate::services::cjk;
 use crate::services::task_queue::TaskContext;
 use sqlx::SqlitePool;
 use std::collections::{HashMap, HashSet};
 
 const DEDUPE_MEDIA_FILE_FIELDS: &str =
     "id, path, name, size, file_type, NULL AS hash_xxhash, hash_md5, tmdb_id, quality_score, video_info, NULL AS metadata, \
-    detected_title, detected_year, detected_season, detected_episode, parser_provider, parse_version, confidence_score, review_state, \
-    match_provider, match_external_id, locked_match_provider, locked_match_external_id, ai_disabled_reason, created_at, updated_at, last_modified";
+    detected_title, detected_year, detected_season, detected_episode, detected_episode_end, detected_absolute_episode, release_info, parser_provider, parse_version, confidence_score, review_state, \
+    match_provider, match_external_id, locked_match_provider, locked_match_external_id, ai_disabled_reason, movie_id, show_id, season_id, episode_id, created_at, updated_at, last_modified";
 const DEDUPE_MEDIA_FILE_FIELDS_WITH_METADATA: &str =
     "id, path, name, size, file_type, NULL AS hash_xxhash, hash_md5, tmdb_id, quality_score, video_info, metadata, \
-    detected_title, detected_year, detected_season, detected_episode, parser_provider, parse_version, confidence_score, review_state, \
-    match_provider, match_external_id, locked_match_provider, locked_match_external_id, ai_disabled_reason, created_at, updated_at, last_modified";
+    detected_title, detected_year, detected_season, detected_episode, detected_episode_end, detected_absolute_episode, release_info, parser_provider, parse_version, confidence_score, review_state, \
+    match_provider, match_external_id, locked_match_provider, locked_match_external_id, ai_disabled_reason, movie_id, show_id, season_id, episode_id, created_at, updated_at, last_modified";
 
 /// 查找重复文件（优化版本：使用数据库分组）
 pub async fn find_duplicates(db: &SqlitePool) -> anyhow::Result<Vec<DuplicateGroup>> {
@@ -158,13 +159,15 @@ fn normalize_filename(name: &str) -> String {
         .file_stem()
         .and_then(|s| s.to_str())
         .unwrap_or(name);
+    // 繁简、全半角、中文数字与假名罗马字折叠为同一写法
+    let name = cjk::fold(name);
 
     // 移除常见质量标识
     let re = regex::Regex::new(
         r"(?i)\.?(1080p|720p|480p|2160p|4k|uhd|bluray|bdrip|webrip|dvdrip|hdtv|x264|x265|hevc|h\.?264|aac|ac3|dts|5\.1|10bit|hdr|remux|proper)\b"
     ).unwrap();
 
-    let normalized = re.replace_all(name, "");
+    let normalized = re.replace_all(&name, "");
 
     // 移除额外的分隔符和空格
     let re_sep = regex::Regex::new(r"[\._\-\s]+").unwrap();
@@ -0,0 +1,155 @@
+//! 绝对集数与季/集编号的互相映射
+//!
+//! 字幕组发布常用绝对集数（`[Group] Title - 137 [1080p]`），而 TMDb 按季组织剧集，
+//! Bangumi 则按条目记录每集的全局序号（sort）与条目内序号（ep）。
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::services::metadata_provider::ProviderEpisode;
+use crate::services::{provider_guard, scraper};
+
+// 条目名中的季标记：`第二季`、`第2期`、`Season 2`、`2nd Season`、`S2`
+static SUBJECT_SEASON_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)第\s*([0-9一二三四五六七八九十两]{1,3})\s*[季期]|(?:season|series)\s*(\d{1,2})\b|\b(\d{1,2})(?:st|nd|rd|th)\s+season\b|\bS(\d{1,2})\s*$")
+        .unwrap()
+});
+
+/// 单季集数（来自 TMDb 剧集详情的 `seasons` 列表）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SeasonEpisodeCount {
+    pub season: u32,
+    pub episode_count: u32,
+}
+
+/// 绝对集数 → (季, 集)，忽略第 0 季（特别篇）
+pub fn absolute_to_season(seasons: &[SeasonEpisodeCount], absolute: u32) -> Option<(u32, u32)> {
+    if absolute == 0 {
+        return None;
+    }
+    let mut ordered: Vec<_> = seasons.iter().filter(|s| s.season > 0).collect();
+    ordered.sort_by_key(|s| s.season);
+
+    let mut remaining = absolute;
+    for season in ordered {
+        if remaining <= season.episode_count {
+            return Some((season.season, remaining));
+        }
+        remaining -= season.episode_count;
+    }
+    None
+}
+
+/// (季, 集) → 绝对集数
+pub fn season_to_absolute(
+    seasons: &[SeasonEpisodeCount],
+    season: u32,
+    episode: u32,
+) -> Option<u32> {
+    let current = seasons.iter().find(|s| s.season == season && season > 0)?;
+    if episode == 0 || episode > current.episode_count {
+        return None;
+    }
+    let before: u32 = seasons
+        .iter()
+        .filter(|s| s.season > 0 && s.season < season)
+        .map(|s| s.episode_count)
+        .sum();
+    Some(before + episode)
+}
+
+/// 在 Bangumi 条目的集列表中按绝对集数查找条目内集数
+///
+/// 集列表来自 [`episodes_from_bangumi`](crate::services::metadata_provider::episodes_from_bangumi)：`absolute_number` 为跨季的全局序号（sort），
+/// `episode_number` 为条目内序号（ep）。续作条目的 sort 通常延续前作编号；
+/// 找不到时若数字落在条目范围内则视为条目内集数。
+pub fn bangumi_absolute_to_episode(episodes: &[ProviderEpisode], absolute: u32) -> Option<u32> {
+    episodes
+        .iter()
+        .find(|e| e.absolute_number == Some(absolute))
+        .or_else(|| episodes.iter().find(|e| e.episode_number == absolute))
+        .map(|e| e.episode_number)
+}
+
+/// 从 Bangumi 条目详情的名称（标题、原名、别名）推断季号，未标明时返回 None
+///
+/// Bangumi 每季单独成条目，续作条目名通常带 `第二季`、`2nd Season` 等标记。
+pub fn bangumi_subject_season(details: &Value) -> Option<u32> {
+    let names = ["title", "original_title"]
+        .iter()
+        .filter_map(|key| details.get(*key).and_then(Value::as_str))
+        .chain(
+            details
+                .get("aliases")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str),
+        );
+    names
+        .filter_map(|name| {
+            let caps = SUBJECT_SEASON_RE.captures(name)?;
+            let number = (1..=4).find_map(|i| caps.get(i))?.as_str();
+            number
+                .parse::<u32>()
+                .ok()
+                .or_else(|| scraper::chinese_number(number))
+        })
+        .find(|season| *season > 0)
+}
+
+/// 从 TMDb 剧集详情载荷提取各季集数
+pub fn tmdb_season_counts_from_payload(payload: &Value) -> Vec<SeasonEpisodeCount> {
+    payload
+        .get("seasons")
+        .and_then(Value::as_array)
+        .map(|seasons| {
+            seasons
+                .iter()
+                .filter_map(|season| {
+                    Some(SeasonEpisodeCount {
+                        season: season.get("season_number")?.as_u64()? as u32,
+                        episode_count: season.get("episode_count")?.as_u64()? as u32,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 从数据源的集列表统计各季集数（忽略未标季号的集）
+pub fn season_counts_from_episodes(episodes: &[ProviderEpisode]) -> Vec<SeasonEpisodeCount> {
+    let mut counts: Vec<SeasonEpisodeCount> = Vec::new();
+    for season in episodes.iter().filter_map(|episode| episode.season_number) {
+        match counts.iter_mut().find(|count| count.season == season) {
+            Some(count) => count.episode_count += 1,
+            None => counts.push(SeasonEpisodeCount {
+                season,
+                episode_count: 1,
+            }),
+        }
+    }
+    counts.sort_by_key(|count| count.season);
+    counts
+}
+
+/// 获取 TMDb 剧集的各季集数
+pub async fn fetch_tmdb_season_counts(
+    client: &Client,
+    api_key: &str,
+    tmdb_id: &str,
+) -> anyhow::Result<Vec<SeasonEpisodeCount>> {
+    let url = format!(
+        "{}/tv/{tmdb_id}?api_key={api_key}",
+        scraper::tmdb_api_base_url()
+    );
+    let payload: Value = provider_guard::send("tmdb", client.get(url))
+        .await?
+        .json()
+        .await?;
+    Ok(tmdb_season_counts_from_payload(&payload))
+}
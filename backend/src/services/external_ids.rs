@@ -0,0 +1,257 @@
+//! 外部 ID 交叉引用
+//!
+//! 每部作品（数据源 + 外部 ID + 媒体类型）记录其已知的全部 ID：自身 ID、数据源详情给出的
+//! IMDb / TVDB / Wikidata 等，以及人工建立的 Bangumi ↔ TMDb 对应。
+//! 识别时 NFO / 路径中的任一 ID 都可经此表找到已启用数据源上的作品；生成 NFO 时写为多个 `<uniqueid>`。
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+
+pub const SOURCE_PROVIDER: &str = "provider";
+pub const SOURCE_MANUAL: &str = "manual";
+
+/// 可作为识别目标的数据源 ID 类型；建立对应时双向记录
+const PROVIDER_ID_TYPES: [&str; 2] = ["tmdb", "bgm"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ExternalId {
+    pub provider: String,
+    pub external_id: String,
+    pub media_type: String,
+    /// tmdb / imdb / tvdb / bgm / wikidata …
+    pub id_type: String,
+    pub value: String,
+    /// provider / manual
+    pub source: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 作品：数据源 + 外部 ID + 媒体类型
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TitleRef {
+    pub provider: String,
+    pub external_id: String,
+    pub media_type: String,
+}
+
+impl TitleRef {
+    pub fn new(provider: &str, external_id: &str, media_type: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            external_id: external_id.to_string(),
+            media_type: media_type.to_string(),
+        }
+    }
+}
+
+/// 详情中的外部 ID：`external_ids` 对象以及 `tmdb_id` / `bgm_id`
+pub fn ids_from_details(details: &Value) -> BTreeMap<String, String> {
+    let mut ids = BTreeMap::new();
+    if let Some(object) = details.get("external_ids").and_then(Value::as_object) {
+        for (id_type, value) in object {
+            if let Some(value) = id_value(value) {
+                ids.insert(id_type.to_ascii_lowercase(), value);
+            }
+        }
+    }
+    for (id_type, key) in [("tmdb", "tmdb_id"), ("bgm", "bgm_id")] {
+        if let Some(value) = details.get(key).and_then(id_value) {
+            ids.entry(id_type.to_string()).or_insert(value);
+        }
+    }
+    ids
+}
+
+/// 整理 TMDb `external_ids`（`append_to_response=external_ids`）为 `{imdb, tvdb, wikidata, tvrage}`，
+/// 社交账号类 ID 不保留；电影顶层的 `imdb_id` 作为补充
+pub fn tmdb_external_ids(payload: &Value) -> BTreeMap<String, String> {
+    let mut ids = BTreeMap::new();
+    let sources = [payload.get("external_ids"), Some(payload)];
+    for (id_type, key) in [
+        ("imdb", "imdb_id"),
+        ("tvdb", "tvdb_id"),
+        ("wikidata", "wikidata_id"),
+        ("tvrage", "tvrage_id"),
+    ] {
+        if let Some(value) = sources
+            .iter()
+            .flatten()
+            .find_map(|source| source.get(key).and_then(id_value))
+        {
+            ids.insert(id_type.to_string(), value);
+        }
+    }
+    ids
+}
+
+fn id_value(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) => Some(value.trim().to_string()),
+        Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+    .filter(|value| !value.is_empty() && value != "0")
+}
+
+/// 记录作品的外部 ID（自身 ID 总会写入）；人工维护的 ID 不被自动来源覆盖
+pub async fn store(
+    db: &SqlitePool,
+    title: &TitleRef,
+    ids: &BTreeMap<String, String>,
+    source: &str,
+) -> anyhow::Result<()> {
+    let mut ids = ids.clone();
+    ids.insert(title.provider.clone(), title.external_id.clone());
+    for (id_type, value) in &ids {
+        // 自身 ID 不随来源变化
+        let source = if *id_type == title.provider {
+            SOURCE_PROVIDER
+        } else {
+            source
+        };
+        upsert(db, title, id_type, value, source).await?;
+    }
+    Ok(())
+}
+
+async fn upsert(
+    db: &SqlitePool,
+    title: &TitleRef,
+    id_type: &str,
+    value: &str,
+    source: &str,
+) -> anyhow::Result<()> {
+    let now = Utc::now();
+    sqlx::query(
+        "INSERT INTO external_ids (provider, external_id, media_type, id_type, value, source, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(provider, external_id, media_type, id_type) DO UPDATE SET
+             value = excluded.value,
+             source = excluded.source,
+             updated_at = excluded.updated_at
+         WHERE external_ids.source != 'manual' OR excluded.source = 'manual'",
+    )
+    .bind(&title.provider)
+    .bind(&title.external_id)
+    .bind(&title.media_type)
+    .bind(id_type)
+    .bind(value)
+    .bind(source)
+    .bind(now)
+    .bind(now)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// 作品记录的全部外部 ID
+pub async fn for_title(db: &SqlitePool, title: &TitleRef) -> anyhow::Result<Vec<ExternalId>> {
+    Ok(sqlx::query_as(
+        "SELECT * FROM external_ids WHERE provider = ? AND external_id = ? AND media_type = ?
+         ORDER BY id_type",
+    )
+    .bind(&title.provider)
+    .bind(&title.external_id)
+    .bind(&title.media_type)
+    .fetch_all(db)
+    .await?)
+}
+
+/// 作品已知 ID 的 `id_type → value` 表
+pub async fn known_ids(
+    db: &SqlitePool,
+    title: &TitleRef,
+) -> anyhow::Result<BTreeMap<String, String>> {
+    Ok(for_title(db, title)
+        .await?
+        .into_iter()
+        .map(|id| (id.id_type, id.value))
+        .collect())
+}
+
+/// 按任一已知 ID 查找作品；`media_type` 为空时不限类型
+pub async fn resolve(
+    db: &SqlitePool,
+    id_type: &str,
+    value: &str,
+    media_type: Option<&str>,
+) -> anyhow::Result<Vec<TitleRef>> {
+    Ok(sqlx::query_as(
+        "SELECT DISTINCT provider, external_id, media_type FROM external_ids
+         WHERE id_type = ?1 AND value = ?2 AND (?3 IS NULL OR media_type = ?3)
+         ORDER BY provider = ?1 DESC, provider, external_id",
+    )
+    .bind(id_type.to_ascii_lowercase())
+    .bind(value.trim())
+    .bind(media_type)
+    .fetch_all(db)
+    .await?)
+}
+
+/// 人工建立对应；另一端是数据源 ID（tmdb / bgm）时双向记录
+pub async fn link(
+    db: &SqlitePool,
+    title: &TitleRef,
+    id_type: &str,
+    value: &str,
+) -> anyhow::Result<()> {
+    let id_type = id_type.trim().to_ascii_lowercase();
+    let value = value.trim();
+    store(db, title, &BTreeMap::new(), SOURCE_PROVIDER).await?;
+    upsert(db, title, &id_type, value, SOURCE_MANUAL).await?;
+    if PROVIDER_ID_TYPES.contains(&id_type.as_str()) && id_type != title.provider {
+        let other = TitleRef::new(&id_type, value, &title.media_type);
+        store(db, &other, &BTreeMap::new(), SOURCE_PROVIDER).await?;
+        upsert(
+            db,
+            &other,
+            &title.provider,
+            &title.external_id,
+            SOURCE_MANUAL,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// 删除作品的一个外部 ID（自身 ID 不可删除）；数据源之间的对应同时删除反向记录
+pub async fn unlink(db: &SqlitePool, title: &TitleRef, id_type: &str) -> anyhow::Result<bool> {
+    let id_type = id_type.trim().to_ascii_lowercase();
+    if id_type == title.provider {
+        return Ok(false);
+    }
+    let value: Option<String> = sqlx::query_scalar(
+        "DELETE FROM external_ids
+         WHERE provider = ? AND external_id = ? AND media_type = ? AND id_type = ?
+         RETURNING value",
+    )
+    .bind(&title.provider)
+    .bind(&title.external_id)
+    .bind(&title.media_type)
+    .bind(&id_type)
+    .fetch_optional(db)
+    .await?;
+    let Some(value) = value else {
+        return Ok(false);
+    };
+    if PROVIDER_ID_TYPES.contains(&id_type.as_str()) {
+        sqlx::query(
+            "DELETE FROM external_ids
+             WHERE provider = ? AND external_id = ? AND media_type = ? AND id_type = ? AND value = ?",
+        )
+        .bind(&id_type)
+        .bind(&value)
+        .bind(&title.media_type)
+        .bind(&title.provider)
+        .bind(&title.external_id)
+        .execute(db)
+        .await?;
+    }
+    Ok(true)
+}
@@ -17,6 +17,16 @@ pub async fn move_file(
     db: &SqlitePool,
     file_id: &str,
     target_dir: &str,
+) -> anyhow::Result<FileOperationResult> {
+    move_file_in_batch(db, file_id, target_dir, None).await
+}
+
+/// 移动文件，并将操作日志关联到批次
+pub async fn move_file_in_batch(
+    db: &SqlitePool,
+    file_id: &str,
+    target_dir: &str,
+    batch_id: Option<&str>,
 ) -> anyhow::Result<FileOperationResult> {
     // 获取文件信息
     let file: MediaFile = sqlx::query_as("SELECT * FROM media_files WHERE id = ?")
@@ -89,6 +99,20 @@ pub async fn move_file(
         .execute(db)
         .await?;
 
+    // 文件已移动，日志写入失败不应让调用方误判为移动失败
+    if let Err(e) = crate::services::log::record_operation(
+        db,
+        "move",
+        Some(file_id),
+        &file.path,
+        Some(&new_path_str),
+        batch_id,
+    )
+    .await
+    {
+        tracing::warn!("Failed to journal move of {}: {}", file_id, e);
+    }
+
     Ok(FileOperationResult {
         file_id: file_id.to_string(),
         success: true,
@@ -102,6 +126,16 @@ pub async fn copy_file(
     db: &SqlitePool,
     file_id: &str,
     target_dir: &str,
+) -> anyhow::Result<FileOperationResult> {
+    copy_file_in_batch(db, file_id, target_dir, None).await
+}
+
+/// 复制文件，并将操作日志关联到批次
+pub async fn copy_file_in_batch(
+    db: &SqlitePool,
+    file_id: &str,
+    target_dir: &str,
+    batch_id: Option<&str>,
 ) -> anyhow::Result<FileOperationResult> {
     // 获取文件信息
     let file: MediaFile = sqlx::query_as("SELECT * FROM media_files WHERE id = ?")
@@ -159,11 +193,25 @@ pub async fn copy_file(
     }
 
     target_file.sync_all().await?;
+    let copied_size = target_file.metadata().await?.len();
 
     let new_path_str = target_path.to_string_lossy().to_string();
 
-    // 可以选择是否在数据库中创建新记录
-    // 这里只返回结果，不创建新记录
+    // 不为副本创建数据库记录，仅记录日志以便撤销（删除副本）
+    if let Err(e) = crate::services::log::record_entry(
+        db,
+        crate::services::log::JournalEntry::copy(
+            Some(file_id),
+            &file.path,
+            &new_path_str,
+            copied_size,
+        )
+        .in_batch(batch_id),
+    )
+    .await
+    {
+        tracing::warn!("Failed to journal copy of {}: {}", file_id, e);
+    }
 
     Ok(FileOperationResult {
         file_id: file_id.to_string(),
@@ -178,11 +226,12 @@ pub async fn move_files_batch(
     db: &SqlitePool,
     file_ids: &[String],
     target_dir: &str,
+    batch_id: Option<&str>,
 ) -> anyhow::Result<Vec<FileOperationResult>> {
     let mut results = Vec::new();
 
     for file_id in file_ids {
-        match move_file(db, file_id, target_dir).await {
+        match move_file_in_batch(db, file_id, target_dir, batch_id).await {
             Ok(result) => results.push(result),
             Err(e) => results.push(FileOperationResult {
                 file_id: file_id.clone(),
@@ -201,11 +250,12 @@ pub async fn copy_files_batch(
     db: &SqlitePool,
     file_ids: &[String],
     target_dir: &str,
+    batch_id: Option<&str>,
 ) -> anyhow::Result<Vec<FileOperationResult>> {
     let mut results = Vec::new();
 
     for file_id in file_ids {
-        match copy_file(db, file_id, target_dir).await {
+        match copy_file_in_batch(db, file_id, target_dir, batch_id).await {
             Ok(result) => results.push(result),
             Err(e) => results.push(FileOperationResult {
                 file_id: file_id.clone(),
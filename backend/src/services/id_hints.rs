@@ -0,0 +1,260 @@
+//! 从现有 NFO 与路径中的 ID 标记识别（先于模糊搜索）
+//!
+//! 读取相邻 `movie.nfo` / `<文件名>.nfo` / `tvshow.nfo` 中的 `<uniqueid>`、`<tmdbid>`、`<imdbid>`，
+//! 以及目录或文件名中的 `{tmdb-12345}`、`[imdbid-tt0133093]`、`[tvdbid-81189]` 等标记。
+
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::Client;
+use serde_json::Value;
+
+use crate::services::{nfo, provider_guard, scraper};
+
+/// 路径标记：`{tmdb-123}`、`[tmdbid-123]`、`[imdbid-tt123]`、`{tvdb-123}`、`[bgm=123]`
+static PATH_TOKEN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)[\[{]\s*(tmdb|imdb|tvdb|bgm|bangumi)(?:id)?\s*[-=:]\s*((?:tt)?\d+)\s*[\]}]")
+        .unwrap()
+});
+
+/// NFO 无法按结构解析时（如重复 `<thumb>`、只含链接的 NFO）退回文本扫描
+static NFO_UNIQUE_ID: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"(?is)<uniqueid[^>]*\btype\s*=\s*["']([a-z]+)["'][^>]*>\s*([^<\s]+)\s*</uniqueid>"#,
+    )
+    .unwrap()
+});
+static NFO_ID_TAG: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)<(tmdbid|imdbid|tvdbid)>\s*([^<\s]+)\s*</").unwrap());
+static NFO_URL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(themoviedb\.org/(movie|tv)/(\d+)|imdb\.com/title/(tt\d+)|bgm\.tv/subject/(\d+)|bangumi\.tv/subject/(\d+))")
+        .unwrap()
+});
+
+/// 向上查找路径标记的目录层数（如 `Show {tmdb-1}/Season 1/01.mkv`）
+const PATH_TOKEN_DEPTH: usize = 3;
+
+/// 从 NFO 或路径中得到的外部 ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdHint {
+    /// 小写数据源：`tmdb`、`imdb`、`tvdb`、`bgm`
+    pub provider: String,
+    pub external_id: String,
+    /// NFO 类型或链接能确定时为 `movie` / `tv`
+    pub media_type: Option<String>,
+    /// `nfo` 或 `path_id`
+    pub source: String,
+}
+
+fn normalize_provider(provider: &str) -> Option<&'static str> {
+    match provider.to_ascii_lowercase().as_str() {
+        "tmdb" | "themoviedb" => Some("tmdb"),
+        "imdb" => Some("imdb"),
+        "tvdb" | "thetvdb" => Some("tvdb"),
+        "bgm" | "bangumi" => Some("bgm"),
+        _ => None,
+    }
+}
+
+fn push_hint(hints: &mut Vec<IdHint>, hint: IdHint) {
+    if !hints
+        .iter()
+        .any(|h| h.provider == hint.provider && h.external_id == hint.external_id)
+    {
+        hints.push(hint);
+    }
+}
+
+/// 提取文本中的路径 ID 标记
+pub fn path_tokens(text: &str) -> Vec<IdHint> {
+    let mut hints = Vec::new();
+    for caps in PATH_TOKEN.captures_iter(text) {
+        let Some(provider) = normalize_provider(&caps[1]) else {
+            continue;
+        };
+        let id = caps[2].to_ascii_lowercase();
+        // IMDb 必须是 tt 开头，其他数据源必须是纯数字
+        if (provider == "imdb") != id.starts_with("tt") {
+            continue;
+        }
+        push_hint(
+            &mut hints,
+            IdHint {
+                provider: provider.to_string(),
+                external_id: id,
+                media_type: None,
+                source: "path_id".to_string(),
+            },
+        );
+    }
+    hints
+}
+
+/// 去掉名称中的 ID 标记，避免干扰标题解析
+pub fn strip_path_tokens(name: &str) -> String {
+    PATH_TOKEN.replace_all(name, " ").trim().to_string()
+}
+
+/// 文件名及其上级目录中的 ID 标记，近处优先
+pub fn path_hints(file_path: &str) -> Vec<IdHint> {
+    let mut hints = Vec::new();
+    let components = Path::new(file_path)
+        .ancestors()
+        .take(PATH_TOKEN_DEPTH + 1)
+        .filter_map(|p| p.file_name().and_then(|n| n.to_str()));
+    for component in components {
+        for hint in path_tokens(component) {
+            push_hint(&mut hints, hint);
+        }
+    }
+    hints
+}
+
+/// 可能描述该文件的 NFO 路径及其媒体类型
+///
+/// 剧集不读同名 NFO（单集 NFO 的 ID 指向单集而非剧集）；所在目录及上两级的 `tvshow.nfo` 总会检查。
+fn nfo_paths(file_path: &str, is_tv: bool) -> Vec<(PathBuf, &'static str)> {
+    let path = Path::new(file_path);
+    let Some(dir) = path.parent() else {
+        return Vec::new();
+    };
+    let mut paths = Vec::new();
+    if !is_tv {
+        paths.push((path.with_extension("nfo"), "movie"));
+        paths.push((dir.join("movie.nfo"), "movie"));
+    }
+    paths.extend(
+        dir.ancestors()
+            .take(3)
+            .map(|d| (d.join("tvshow.nfo"), "tv")),
+    );
+    paths
+}
+
+/// 文本扫描 NFO 中的 ID（结构解析失败时使用）
+pub fn scan_nfo_text(content: &str) -> Vec<(String, String, Option<String>)> {
+    let mut ids = Vec::new();
+    for caps in NFO_UNIQUE_ID.captures_iter(content) {
+        ids.push((caps[1].to_ascii_lowercase(), caps[2].to_string(), None));
+    }
+    for caps in NFO_ID_TAG.captures_iter(content) {
+        let kind = caps[1]
+            .to_ascii_lowercase()
+            .trim_end_matches("id")
+            .to_string();
+        ids.push((kind, caps[2].to_string(), None));
+    }
+    for caps in NFO_URL.captures_iter(content) {
+        if let (Some(kind), Some(id)) = (caps.get(2), caps.get(3)) {
+            ids.push((
+                "tmdb".to_string(),
+                id.as_str().to_string(),
+                Some(kind.as_str().to_ascii_lowercase()),
+            ));
+        } else if let Some(id) = caps.get(4) {
+            ids.push(("imdb".to_string(), id.as_str().to_string(), None));
+        } else if let Some(id) = caps.get(5).or_else(|| caps.get(6)) {
+            ids.push(("bgm".to_string(), id.as_str().to_string(), None));
+        }
+    }
+    ids
+}
+
+/// 读取相邻 NFO 中的外部 ID
+pub async fn nfo_hints(file_path: &str, is_tv: bool) -> Vec<IdHint> {
+    let mut hints = Vec::new();
+    for (path, media_type) in nfo_paths(file_path, is_tv) {
+        let Some(path_str) = path.to_str() else {
+            continue;
+        };
+        if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            continue;
+        }
+        let ids: Vec<(String, String, Option<String>)> = match nfo::read_nfo_file(path_str).await {
+            Ok(parsed) => parsed
+                .external_ids()
+                .into_iter()
+                .map(|(kind, id)| (kind, id, None))
+                .collect(),
+            Err(error) => {
+                tracing::debug!("Structured NFO parse failed for {}: {}", path_str, error);
+                match tokio::fs::read_to_string(&path).await {
+                    Ok(content) => scan_nfo_text(&content),
+                    Err(_) => continue,
+                }
+            }
+        };
+        for (kind, id, kind_media_type) in ids {
+            let Some(provider) = normalize_provider(&kind) else {
+                continue;
+            };
+            push_hint(
+                &mut hints,
+                IdHint {
+                    provider: provider.to_string(),
+                    external_id: id,
+                    media_type: Some(kind_media_type.unwrap_or_else(|| media_type.to_string())),
+                    source: "nfo".to_string(),
+                },
+            );
+        }
+    }
+    hints
+}
+
+/// 全部 ID 线索：NFO 优先于路径标记
+pub async fn collect_hints(file_path: &str, is_tv: bool) -> Vec<IdHint> {
+    let mut hints = nfo_hints(file_path, is_tv).await;
+    for hint in path_hints(file_path) {
+        push_hint(&mut hints, hint);
+    }
+    hints
+}
+
+/// 解析 TMDb `/find` 响应，返回 (TMDb ID, 媒体类型)
+pub fn tmdb_find_result(payload: &Value, prefer_tv: bool) -> Option<(String, String)> {
+    let pick = |key: &str, media_type: &str| {
+        payload
+            .get(key)
+            .and_then(Value::as_array)
+            .and_then(|results| results.first())
+            .and_then(|item| item.get("id"))
+            .and_then(Value::as_u64)
+            .map(|id| (id.to_string(), media_type.to_string()))
+    };
+    let (movie, tv) = (pick("movie_results", "movie"), pick("tv_results", "tv"));
+    if prefer_tv {
+        tv.or(movie)
+    } else {
+        movie.or(tv)
+    }
+}
+
+/// 通过 TMDb `/find` 把 IMDb / TVDB ID 换成 TMDb ID
+pub async fn find_tmdb_id(
+    client: &Client,
+    api_key: &str,
+    provider: &str,
+    external_id: &str,
+    prefer_tv: bool,
+) -> anyhow::Result<Option<(String, String)>> {
+    let source = match provider {
+        "imdb" => "imdb_id",
+        "tvdb" => "tvdb_id",
+        _ => return Ok(None),
+    };
+    let url = format!(
+        "{}/find/{}?api_key={}&external_source={}",
+        scraper::tmdb_api_base_url(),
+        urlencoding::encode(external_id),
+        api_key,
+        source
+    );
+    let response = provider_guard::send("tmdb", client.get(&url)).await?;
+    if !response.status().is_success() {
+        anyhow::bail!("TMDb find failed: {}", response.status());
+    }
+    let payload: Value = response.json().await?;
+    Ok(tmdb_find_result(&payload, prefer_tv))
+}
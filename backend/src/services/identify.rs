@@ -1,7 +1,9 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Arc;
 
-use anyhow::Context;
 use chrono::Utc;
+use futures::future::join_all;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
@@ -12,12 +14,41 @@ use uuid::Uuid;
 
 use crate::config::AppConfig;
 use crate::models::MediaFile;
-use crate::services::scraper;
-
-const PARSE_VERSION: &str = "identify-v1";
+use crate::services::metadata_provider::{
+    MetadataProvider, ProviderEpisode, ProviderQuery, ProviderRegistry, ProviderSession,
+};
+use crate::services::{
+    ai_parser, catalog, cjk, collections, episode_map, external_ids, id_hints, metadata_edit,
+    metadata_provider, people, provider_guard, release_parser, review_queue, scraper,
+    title_aliases,
+};
+
+/// 规则解析版本：解析规则变化时递增，重新识别任务据此找出需要重新解析的文件
+pub const PARSE_VERSION: &str = "identify-v2";
 const AUTO_THRESHOLD: f64 = 0.82;
 const REVIEW_THRESHOLD: f64 = 0.55;
 
+/// 媒体库根目录常见命名，不能当作剧名/片名
+const GENERIC_FOLDERS: &[&str] = &[
+    "movies",
+    "movie",
+    "films",
+    "tv",
+    "tv shows",
+    "shows",
+    "series",
+    "anime",
+    "media",
+    "videos",
+    "downloads",
+    "library",
+    "电影",
+    "电视剧",
+    "剧集",
+    "动漫",
+    "番剧",
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RuntimeSettings {
     pub tmdb_api_key: Option<String>,
@@ -26,9 +57,20 @@ pub struct RuntimeSettings {
     pub cloudflare_api_token: Option<String>,
     pub cloudflare_ai_model: String,
     pub cloudflare_ai_base_url: Option<String>,
+    /// AI 解析后端：`cloudflare` 或 `openai`（OpenAI 兼容端点，如本地 Ollama / llama.cpp）
+    pub ai_backend: String,
+    pub openai_base_url: Option<String>,
+    pub openai_api_key: Option<String>,
+    pub openai_model: String,
     pub ai_mode: String,
     pub ai_budget_mode: String,
     pub ai_daily_budget: usize,
+    /// 离线模式：元数据只读缓存，不访问网络
+    #[serde(default)]
+    pub provider_offline: bool,
+    /// 元数据语言回退链（如 zh-CN → zh-TW → ja → en），媒体库可单独覆盖
+    #[serde(default)]
+    pub languages: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -37,11 +79,20 @@ pub struct ParsedTitle {
     pub year: Option<u32>,
     pub season: Option<u32>,
     pub episode: Option<u32>,
+    /// 多集文件的末集（S01E01-E02 中的 2）
+    #[serde(default)]
+    pub episode_end: Option<u32>,
+    /// 绝对集数（番剧 `Title - 137`），应用时映射为季/集
+    #[serde(default)]
+    pub absolute_episode: Option<u32>,
     pub is_special: bool,
     pub special_type: Option<String>,
     pub confidence: f64,
     pub parser_provider: String,
     pub ai_disabled_reason: Option<String>,
+    /// 各字段取自哪一段路径（`file_name` / `season_folder` / `show_folder`），供复核界面解释匹配
+    #[serde(default)]
+    pub field_sources: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -56,6 +107,9 @@ pub struct IdentifyCandidate {
     pub overview: Option<String>,
     pub poster_url: Option<String>,
     pub backdrop_url: Option<String>,
+    /// 其他语言标题与别名，参与标题匹配
+    #[serde(default)]
+    pub alternative_titles: Vec<String>,
     pub metadata: Value,
 }
 
@@ -69,6 +123,23 @@ pub struct IdentifyPreview {
     pub needs_review: bool,
     pub ai_used: bool,
     pub budget_state: String,
+    /// 因熔断被跳过的数据源，候选只来自其余数据源
+    #[serde(default)]
+    pub skipped_providers: Vec<String>,
+}
+
+impl IdentifyPreview {
+    /// 需要人工复核的原因；可自动应用时为 `None`
+    pub fn review_reason(&self) -> Option<&'static str> {
+        match &self.recommended {
+            None => Some("no_candidates"),
+            Some(candidate) if candidate.score < AUTO_THRESHOLD => Some("low_score"),
+            Some(_) if self.parse.confidence < REVIEW_THRESHOLD => Some("low_confidence"),
+            Some(_) if !self.skipped_providers.is_empty() => Some("providers_skipped"),
+            Some(_) if self.needs_review => Some("needs_review"),
+            Some(_) => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -86,6 +157,7 @@ pub async fn preview_files(
     db: &SqlitePool,
     client: &Client,
     config: &AppConfig,
+    providers: &ProviderSession,
     file_ids: &[String],
     allow_ai: bool,
 ) -> anyhow::Result<Vec<IdentifyPreview>> {
@@ -98,7 +170,7 @@ pub async fn preview_files(
                 .fetch_optional(db)
                 .await?
         {
-            results.push(preview_file(db, client, config, &file, allow_ai).await?);
+            results.push(preview_file(db, client, config, providers, &file, allow_ai).await?);
         }
     }
 
@@ -109,18 +181,79 @@ pub async fn apply_selections(
     db: &SqlitePool,
     client: &Client,
     config: &AppConfig,
+    providers: &ProviderSession,
     selections: &[ApplySelection],
 ) -> anyhow::Result<Vec<(String, Value)>> {
     let mut applied = Vec::new();
 
     for selection in selections {
-        let metadata = apply_selection(db, client, config, selection).await?;
+        let metadata = apply_selection(db, client, config, providers, selection).await?;
+        learn_alias_best_effort(db, selection).await;
         applied.push((selection.file_id.clone(), metadata));
     }
 
     Ok(applied)
 }
 
+/// 应用匹配后学习别名：匹配已经写入，学习失败只记日志，不影响调用方与后续文件
+pub async fn learn_alias_best_effort(db: &SqlitePool, selection: &ApplySelection) {
+    if let Err(error) = learn_alias(db, selection).await {
+        tracing::warn!(
+            "Failed to learn title alias for {}: {}",
+            selection.file_id,
+            error
+        );
+    }
+}
+
+/// 人工确认的匹配记为标题别名：文件名规则解析出的标题 → 所选数据源 ID
+///
+/// 别名限定在文件所属媒体库（不在媒体库内时为所在目录）；文件名中的年份与所选条目不一致时不记录。
+pub async fn learn_alias(db: &SqlitePool, selection: &ApplySelection) -> anyhow::Result<()> {
+    let file: MediaFile = sqlx::query_as("SELECT * FROM media_files WHERE id = ?")
+        .bind(&selection.file_id)
+        .fetch_one(db)
+        .await?;
+    let library_type = metadata_provider::library_type(db, &file.path).await?;
+    let parsed = parse_with_rules(&file, library_type.as_deref());
+    if !has_title_text(&parsed.title) {
+        return Ok(());
+    }
+    let matched_year = file
+        .metadata
+        .as_deref()
+        .and_then(|metadata| serde_json::from_str::<Value>(metadata).ok())
+        .and_then(|metadata| metadata.get("year").and_then(Value::as_u64))
+        .map(|year| year as u32);
+    if parsed
+        .year
+        .zip(matched_year)
+        .is_some_and(|(lhs, rhs)| lhs != rhs)
+    {
+        return Ok(());
+    }
+    let path_prefix = match metadata_provider::library_root(db, &file.path).await? {
+        Some(root) => Some(root),
+        None => Path::new(&file.path)
+            .parent()
+            .map(|parent| parent.to_string_lossy().to_string()),
+    };
+    title_aliases::upsert(
+        db,
+        &title_aliases::AliasInput {
+            title: parsed.title,
+            path_prefix,
+            year: parsed.year.or(matched_year).map(|year| year as i32),
+            provider: selection.provider.clone(),
+            external_id: selection.external_id.clone(),
+            media_type: selection.media_type.clone(),
+        },
+        title_aliases::SOURCE_MANUAL,
+    )
+    .await?;
+    Ok(())
+}
+
 pub async fn load_runtime_settings(
     db: &SqlitePool,
     config: &AppConfig,
@@ -140,8 +273,14 @@ pub async fn load_runtime_settings(
         cloudflare_account_id: string_setting(&map, "cloudflare_account_id"),
         cloudflare_api_token: string_setting(&map, "cloudflare_api_token"),
         cloudflare_ai_model: string_setting(&map, "cloudflare_ai_model")
-            .unwrap_or_else(|| "@cf/meta/llama-3.1-8b-instruct".to_string()),
+            .unwrap_or_else(|| ai_parser::DEFAULT_CLOUDFLARE_MODEL.to_string()),
         cloudflare_ai_base_url: string_setting(&map, "cloudflare_ai_base_url"),
+        ai_backend: string_setting(&map, "ai_backend")
+            .unwrap_or_else(|| ai_parser::BACKEND_CLOUDFLARE.to_string()),
+        openai_base_url: string_setting(&map, "openai_base_url"),
+        openai_api_key: string_setting(&map, "openai_api_key"),
+        openai_model: string_setting(&map, "openai_model")
+            .unwrap_or_else(|| ai_parser::DEFAULT_OPENAI_MODEL.to_string()),
         ai_mode: string_setting(&map, "ai_mode").unwrap_or_else(|| "assist".to_string()),
         ai_budget_mode: string_setting(&map, "ai_budget_mode")
             .unwrap_or_else(|| "strict_free".to_string()),
@@ -149,19 +288,44 @@ pub async fn load_runtime_settings(
             .get("ai_daily_budget")
             .and_then(|v| v.parse::<usize>().ok())
             .unwrap_or(100),
+        provider_offline: map
+            .get("provider_offline_mode")
+            .is_some_and(|v| v == "true" || v == "1"),
+        languages: metadata_provider::parse_language_list(
+            map.get("tmdb_language").map(String::as_str),
+        )
+        .unwrap_or_else(|| vec![metadata_provider::DEFAULT_LANGUAGE.to_string()]),
     })
 }
 
+/// 读取运行时设置，并套用文件所属媒体库的语言偏好
+pub async fn load_library_settings(
+    db: &SqlitePool,
+    config: &AppConfig,
+    file_path: &str,
+) -> anyhow::Result<RuntimeSettings> {
+    let mut settings = load_runtime_settings(db, config).await?;
+    if let Some(languages) = metadata_provider::library_languages(db, file_path).await? {
+        settings.languages = languages;
+    }
+    Ok(settings)
+}
+
 pub async fn preview_file(
     db: &SqlitePool,
     client: &Client,
     config: &AppConfig,
+    providers: &ProviderSession,
     file: &MediaFile,
     allow_ai: bool,
 ) -> anyhow::Result<IdentifyPreview> {
-    let settings = load_runtime_settings(db, config).await?;
+    let settings = load_library_settings(db, config, &file.path).await?;
+    let registry = providers.registry(db, client, &settings).await;
+    let enabled = metadata_provider::library_providers(db, &file.path).await?;
+    let providers = registry.select(enabled.as_deref());
     let budget_ok = can_use_ai(db, &settings).await?;
-    let mut parsed = parse_with_rules(file);
+    let library_type = metadata_provider::library_type(db, &file.path).await?;
+    let mut parsed = parse_with_rules(file, library_type.as_deref());
     let mut ai_used = false;
     let mut budget_state = if budget_ok {
         "available".to_string()
@@ -169,27 +333,87 @@ pub async fn preview_file(
         "strict_free_exhausted".to_string()
     };
 
-    let mut candidates = query_candidates(client, &settings, &parsed).await?;
+    // 已有 NFO 或路径 ID 时直接采用，不再模糊搜索
+    if let Some((hinted, candidate)) =
+        identify_from_hints(db, client, &settings, &registry, file, &parsed).await?
+    {
+        return Ok(IdentifyPreview {
+            file_id: file.id.clone(),
+            file_name: file.name.clone(),
+            parse: hinted,
+            candidates: vec![candidate.clone()],
+            recommended: Some(candidate),
+            needs_review: false,
+            ai_used,
+            budget_state,
+            skipped_providers: Vec::new(),
+        });
+    }
+
+    // 人工确认过的标题别名代替模糊搜索，但仍按年份与解析置信度评分、必要时进入复核
+    if let Some(candidate) = identify_from_alias(db, &registry, file, &parsed).await? {
+        let recommended = rank_candidates(&parsed, std::slice::from_ref(&candidate))
+            .into_iter()
+            .next();
+        let needs_review = recommended.as_ref().is_none_or(|candidate| {
+            candidate.score < AUTO_THRESHOLD || parsed.confidence < REVIEW_THRESHOLD
+        });
+        return Ok(IdentifyPreview {
+            file_id: file.id.clone(),
+            file_name: file.name.clone(),
+            parse: parsed,
+            candidates: recommended.iter().cloned().collect(),
+            recommended,
+            needs_review,
+            ai_used,
+            budget_state,
+            skipped_providers: Vec::new(),
+        });
+    }
+
+    let search = search_candidates(&providers, &parsed).await?;
+    let mut candidates = search.candidates;
+    let mut skipped_providers = search.skipped_providers;
     let low_confidence = parsed.confidence < AUTO_THRESHOLD || candidates.is_empty();
-    let ai_enabled = allow_ai && settings.ai_mode != "disabled" && budget_ok;
+    let ai_enabled =
+        allow_ai && settings.ai_mode != "disabled" && budget_ok && !settings.provider_offline;
 
     if low_confidence && ai_enabled {
-        if let Some(ai_parsed) = parse_with_cloudflare_ai(client, &settings, &file.name).await? {
-            ai_used = true;
-            record_ai_usage(db, &settings, &file.name).await?;
-            parsed = ai_parsed;
-            candidates = query_candidates(client, &settings, &parsed).await?;
+        if let Some(endpoint) = ai_parser::AiEndpoint::from_settings(&settings) {
+            if let Some(ai_parsed) =
+                ai_parser::parse_filename(client, &endpoint, &file.name).await?
+            {
+                ai_used = true;
+                record_ai_usage(db, &endpoint, &file.name).await?;
+                parsed = ai_parsed;
+                let search = search_candidates(&providers, &parsed).await?;
+                candidates = search.candidates;
+                skipped_providers = search.skipped_providers;
+            }
         }
     } else if low_confidence && allow_ai && !budget_ok {
         parsed.ai_disabled_reason = Some("strict_free_budget_exhausted".to_string());
         budget_state = "degraded".to_string();
     }
 
-    let recommended = rank_candidates(&parsed, &candidates).into_iter().next();
+    let mut recommended = rank_candidates(&parsed, &candidates).into_iter().next();
+    // 只为推荐的 TMDb 候选请求一次语言回退后的详情，补全标题后重新评分
+    if let Some(candidate) = recommended.as_mut().filter(|c| c.provider == "tmdb") {
+        if let Some(provider) = providers.iter().find(|p| p.id() == candidate.provider) {
+            metadata_provider::localize_candidate(provider.as_ref(), candidate).await;
+            candidate.score = rank_candidates(&parsed, std::slice::from_ref(candidate))[0].score;
+            if let Some(entry) = candidates.iter_mut().find(|entry| {
+                entry.provider == candidate.provider && entry.external_id == candidate.external_id
+            }) {
+                *entry = candidate.clone();
+            }
+        }
+    }
     let needs_review = recommended
         .as_ref()
         .map(|candidate| candidate.score < AUTO_THRESHOLD || parsed.confidence < REVIEW_THRESHOLD)
-        .unwrap_or(true);
+        .unwrap_or(true)
+        || !skipped_providers.is_empty();
 
     Ok(IdentifyPreview {
         file_id: file.id.clone(),
@@ -200,6 +424,7 @@ pub async fn preview_file(
         needs_review,
         ai_used,
         budget_state,
+        skipped_providers,
     })
 }
 
@@ -207,53 +432,89 @@ pub async fn apply_selection(
     db: &SqlitePool,
     client: &Client,
     config: &AppConfig,
+    providers: &ProviderSession,
     selection: &ApplySelection,
 ) -> anyhow::Result<Value> {
     let file: MediaFile = sqlx::query_as("SELECT * FROM media_files WHERE id = ?")
         .bind(&selection.file_id)
         .fetch_one(db)
         .await?;
-    let settings = load_runtime_settings(db, config).await?;
-    let parsed = if file.detected_title.is_some()
-        || file.detected_year.is_some()
-        || file.detected_season.is_some()
-        || file.detected_episode.is_some()
-    {
-        ParsedTitle {
-            title: file.detected_title.clone().unwrap_or_default(),
-            year: file.detected_year.map(|value| value as u32),
-            season: file.detected_season.map(|value| value as u32),
-            episode: file.detected_episode.map(|value| value as u32),
-            is_special: false,
-            special_type: None,
-            confidence: file.confidence_score.unwrap_or(0.0),
-            parser_provider: file
-                .parser_provider
-                .clone()
-                .unwrap_or_else(|| "stored".to_string()),
-            ai_disabled_reason: file.ai_disabled_reason.clone(),
+    let settings = load_library_settings(db, config, &file.path).await?;
+    let library_type = metadata_provider::library_type(db, &file.path).await?;
+    let rules_parse = parse_with_rules(&file, library_type.as_deref());
+    let parsed = stored_parse(&file).unwrap_or_else(|| rules_parse.clone());
+    let registry = providers.registry(db, client, &settings).await;
+    let provider = registry
+        .get(&selection.provider)
+        .ok_or_else(|| anyhow::anyhow!("unsupported provider: {}", selection.provider))?;
+    let mut details = provider
+        .details(&selection.external_id, &selection.media_type)
+        .await?;
+    merge_detected_context(&mut details, &parsed);
+    if selection.media_type == "tv" {
+        if let Err(error) = map_absolute_episode(&provider, selection, &parsed, &mut details).await
+        {
+            tracing::warn!(
+                "Failed to map absolute episode for {}: {}",
+                selection.file_id,
+                error
+            );
+        }
+    }
+    if selection.provider == "tmdb" && selection.media_type == "tv" && !settings.provider_offline {
+        let _ = enrich_tmdb_tv_metadata(
+            client,
+            settings.tmdb_api_key.as_deref().unwrap_or_default(),
+            &mut details,
+        )
+        .await;
+    }
+    if selection.media_type == "tv" {
+        if let Err(error) =
+            enrich_episode_metadata(&provider, &selection.external_id, &mut details).await
+        {
+            tracing::warn!(
+                "Failed to fetch episode metadata for {}: {}",
+                selection.file_id,
+                error
+            );
         }
+    }
+
+    let collection = if selection.media_type == "tv" {
+        None
     } else {
-        parse_with_rules(&file)
+        collections::fetch_for_details(provider.as_ref(), &mut details)
+            .await
+            .unwrap_or_else(|error| {
+                tracing::warn!(
+                    "Failed to fetch collection for {}: {}",
+                    selection.file_id,
+                    error
+                );
+                None
+            })
     };
-    let mut details = fetch_provider_details(
-        client,
-        &settings,
+
+    let title = external_ids::TitleRef::new(
         &selection.provider,
         &selection.external_id,
         &selection.media_type,
+    );
+    external_ids::store(
+        db,
+        &title,
+        &external_ids::ids_from_details(&details),
+        external_ids::SOURCE_PROVIDER,
     )
     .await?;
-    merge_detected_context(&mut details, &parsed);
-    if selection.provider == "tmdb" && selection.media_type == "tv" {
-        let _ = enrich_tmdb_tv_metadata(
-            client,
-            settings.tmdb_api_key.as_deref().unwrap_or_default(),
-            &selection.external_id,
-            &mut details,
-        )
-        .await;
+    // 人工建立的对应（如 Bangumi ↔ TMDb）一并写入元数据，NFO 输出全部 uniqueid
+    let known_ids = external_ids::known_ids(db, &title).await?;
+    if let Some(object) = details.as_object_mut() {
+        object.insert("external_ids".to_string(), json!(known_ids));
     }
+    // 人工锁定的字段保留原值，旧版本与差异记入元数据历史
+    metadata_edit::merge_scrape(db, &selection.file_id, &mut details).await?;
 
     let tmdb_id = if selection.provider == "tmdb" {
         selection.external_id.parse::<u32>().ok()
@@ -264,8 +525,8 @@ pub async fn apply_selection(
     let metadata_json = serde_json::to_string(&details).unwrap_or_default();
     sqlx::query(
         "UPDATE media_files
-         SET metadata = ?, tmdb_id = ?, detected_title = ?, detected_year = ?, detected_season = ?, detected_episode = ?,
-             parser_provider = COALESCE(parser_provider, ?), parse_version = ?, confidence_score = ?, review_state = ?,
+         SET metadata = ?, tmdb_id = ?, detected_title = ?, detected_year = ?, detected_season = ?, detected_episode = ?, detected_episode_end = ?, detected_absolute_episode = ?,
+             parser_provider = COALESCE(parser_provider, ?), parse_version = ?, parse_snapshot = ?, confidence_score = ?, review_state = ?,
              match_provider = ?, match_external_id = ?, locked_match_provider = ?, locked_match_external_id = ?, ai_disabled_reason = NULL, updated_at = ?
          WHERE id = ?",
     )
@@ -275,8 +536,11 @@ pub async fn apply_selection(
     .bind(details.get("year").and_then(Value::as_i64).map(|v| v as i32))
     .bind(details.get("season_number").and_then(Value::as_i64).map(|v| v as i32))
     .bind(details.get("episode_number").and_then(Value::as_i64).map(|v| v as i32))
+    .bind(details.get("episode_number_end").and_then(Value::as_i64).map(|v| v as i32))
+    .bind(details.get("absolute_number").and_then(Value::as_i64).map(|v| v as i32))
     .bind("manual_apply")
     .bind(PARSE_VERSION)
+    .bind(serde_json::to_string(&rules_parse)?)
     .bind(1.0_f64)
     .bind("applied")
     .bind(&selection.provider)
@@ -287,6 +551,23 @@ pub async fn apply_selection(
     .bind(&selection.file_id)
     .execute(db)
     .await?;
+    catalog::link_file(
+        db,
+        &selection.file_id,
+        &selection.provider,
+        &selection.external_id,
+        &selection.media_type,
+        &details,
+    )
+    .await?;
+    let collection_id = match collection {
+        Some(collection) => {
+            collections::store_collection(db, &selection.provider, &collection).await?
+        }
+        None => None,
+    };
+    // 目录与系列都写入成功后才结束复核，失败时条目仍留在复核队列
+    review_queue::mark_accepted(db, &selection.file_id).await?;
 
     if selection.download_images {
         let poster_url = details.get("poster_url").and_then(Value::as_str);
@@ -294,6 +575,31 @@ pub async fn apply_selection(
         let _ =
             crate::services::poster::download_media_images(&file.path, poster_url, backdrop_url)
                 .await;
+        if let Some(still_url) = details.get("episode_still_url").and_then(Value::as_str) {
+            let _ = crate::services::poster::download_episode_still(&file.path, still_url).await;
+        }
+        let level = if selection.media_type == "tv" {
+            catalog::Level::Show
+        } else {
+            catalog::Level::Movie
+        };
+        let title_id = catalog::title_id(&selection.provider, &selection.external_id);
+        if let Err(error) =
+            people::cache_profile_images(db, &config.people_cache_dir, level, &title_id).await
+        {
+            tracing::warn!("Failed to cache profile images for {}: {}", title_id, error);
+        }
+        if let Some(collection_id) = &collection_id {
+            if let Err(error) =
+                collections::cache_artwork(db, &config.collection_cache_dir, collection_id).await
+            {
+                tracing::warn!(
+                    "Failed to cache collection artwork for {}: {}",
+                    collection_id,
+                    error
+                );
+            }
+        }
     }
 
     if selection.generate_nfo {
@@ -308,17 +614,109 @@ pub async fn apply_selection(
     Ok(details)
 }
 
-fn parse_with_rules(file: &MediaFile) -> ParsedTitle {
-    let (title, year, season, episode) = scraper::parse_filename(&file.name);
-    let name_lower = file.name.to_ascii_lowercase();
-    let special_type = if name_lower.contains("ova") {
-        Some("ova".to_string())
-    } else if name_lower.contains("special") || name_lower.contains(".sp") {
-        Some("special".to_string())
-    } else {
-        None
+/// 上次识别写入 media_files 的解析字段；从未识别过时为 None
+pub(crate) fn stored_parse(file: &MediaFile) -> Option<ParsedTitle> {
+    if file.detected_title.is_none()
+        && file.detected_year.is_none()
+        && file.detected_season.is_none()
+        && file.detected_episode.is_none()
+    {
+        return None;
+    }
+    Some(ParsedTitle {
+        title: file.detected_title.clone().unwrap_or_default(),
+        year: file.detected_year.map(|value| value as u32),
+        season: file.detected_season.map(|value| value as u32),
+        episode: file.detected_episode.map(|value| value as u32),
+        episode_end: file.detected_episode_end.map(|value| value as u32),
+        absolute_episode: file.detected_absolute_episode.map(|value| value as u32),
+        is_special: false,
+        special_type: None,
+        confidence: file.confidence_score.unwrap_or(0.0),
+        parser_provider: file
+            .parser_provider
+            .clone()
+            .unwrap_or_else(|| "stored".to_string()),
+        ai_disabled_reason: file.ai_disabled_reason.clone(),
+        field_sources: BTreeMap::new(),
+    })
+}
+
+/// 组合文件名、季目录与剧集目录解析：文件名优先，缺失的字段由上级目录补全；
+/// 番剧库或带字幕组方括号特征的文件名按字幕组命名解析
+pub(crate) fn parse_with_rules(file: &MediaFile, library_type: Option<&str>) -> ParsedTitle {
+    let name = id_hints::strip_path_tokens(&file.name);
+    let release = release_parser::parse_for_library(&name, library_type);
+    let (mut title, mut year, mut season, mut episode) =
+        (release.title, release.year, release.season, release.episode);
+    let episode_end = release.episode_end;
+    let mut absolute_episode = release.absolute_episode;
+    let name_lower = name.to_ascii_lowercase();
+    let special_type = release.special_type.or_else(|| {
+        if name_lower.contains("ova") {
+            Some("ova".to_string())
+        } else if name_lower.contains("special") || name_lower.contains(".sp") {
+            Some("special".to_string())
+        } else {
+            None
+        }
+    });
+
+    let mut field_sources = BTreeMap::new();
+    let mut mark = |field: &str, present: bool, source: &str| {
+        if present {
+            field_sources.insert(field.to_string(), source.to_string());
+        }
     };
-    let confidence = if !title.is_empty() {
+    mark("title", has_title_text(&title), "file_name");
+    mark("year", year.is_some(), "file_name");
+    mark("season", season.is_some(), "file_name");
+    mark("episode", episode.is_some(), "file_name");
+
+    let folders = folder_context(&file.path);
+    if let Some((folder_season, _)) = &folders.season {
+        if season.is_none() {
+            season = Some(*folder_season);
+            mark("season", true, "season_folder");
+        }
+        if episode.is_none() {
+            // 季目录下的 `Show - 05.mkv` / `05.mkv` 是季内集号而非绝对集数
+            episode = absolute_episode
+                .take()
+                .or_else(|| scraper::parse_bare_episode(&name));
+            mark("episode", episode.is_some(), "file_name");
+        }
+    }
+
+    let folder_titles = [
+        folders
+            .season
+            .as_ref()
+            .map(|(_, rest)| (rest.clone(), None, "season_folder")),
+        folders
+            .show
+            .as_ref()
+            .map(|(show, show_year)| (show.clone(), *show_year, "show_folder")),
+    ];
+    for (folder_title, folder_year, source) in folder_titles.into_iter().flatten() {
+        if !has_title_text(&folder_title) {
+            continue;
+        }
+        let same_title = title_similarity(&folder_title, &title) >= 1.0;
+        if !has_title_text(&title) {
+            title = folder_title;
+            mark("title", true, source);
+        } else if !same_title {
+            continue;
+        }
+        if year.is_none() && folder_year.is_some() {
+            year = folder_year;
+            mark("year", true, source);
+        }
+        break;
+    }
+
+    let confidence = if has_title_text(&title) {
         if season.is_some() || episode.is_some() {
             0.78
         } else {
@@ -333,73 +731,345 @@ fn parse_with_rules(file: &MediaFile) -> ParsedTitle {
         year,
         season,
         episode,
+        episode_end,
+        absolute_episode,
         is_special: special_type.is_some(),
         special_type,
         confidence,
         parser_provider: "rules".to_string(),
         ai_disabled_reason: None,
+        field_sources,
     }
 }
 
-async fn query_candidates(
+/// 文件所在的季目录（季号, 去掉季标记后的剩余名称）与剧集/电影目录（标题, 年份）
+#[derive(Debug, Default)]
+struct FolderContext {
+    season: Option<(u32, String)>,
+    show: Option<(String, Option<u32>)>,
+}
+
+fn folder_context(file_path: &str) -> FolderContext {
+    // 顶层目录（`/tmp`、`/media`）是挂载点而非剧集目录，不参与解析
+    let mut dirs = Path::new(file_path)
+        .ancestors()
+        .skip(1)
+        .filter(|dir| dir.parent().and_then(Path::parent).is_some())
+        .filter_map(|dir| dir.file_name().and_then(|name| name.to_str()));
+    let mut context = FolderContext::default();
+    let Some(parent) = dirs.next() else {
+        return context;
+    };
+    let show_dir = match scraper::parse_season_folder(parent) {
+        Some(season) => {
+            context.season = Some(season);
+            dirs.next()
+        }
+        None => Some(parent),
+    };
+    context.show = show_dir
+        .filter(|dir| !GENERIC_FOLDERS.contains(&dir.to_lowercase().as_str()))
+        .map(|dir| {
+            let (title, year, _, _) = scraper::parse_folder_name(&id_hints::strip_path_tokens(dir));
+            (title, year)
+        });
+    context
+}
+
+/// 标题中至少含一个字母或汉字（`02`、`E05` 这类只剩集号的文件名不算）
+fn has_title_text(title: &str) -> bool {
+    title.chars().any(char::is_alphabetic)
+}
+
+/// 按 NFO / 路径中的外部 ID 直接取详情；命中即视为确定匹配（置信度 1.0）
+///
+/// 已启用数据源的 ID 直接取详情；其他 ID 经外部 ID 交叉引用找到已启用数据源上的作品，
+/// 仍找不到时 IMDb / TVDB 经 TMDb /find 换算
+async fn identify_from_hints(
+    db: &SqlitePool,
     client: &Client,
     settings: &RuntimeSettings,
+    registry: &ProviderRegistry,
+    file: &MediaFile,
     parsed: &ParsedTitle,
-) -> anyhow::Result<Vec<IdentifyCandidate>> {
-    if parsed.title.trim().is_empty() {
-        return Ok(Vec::new());
+) -> anyhow::Result<Option<(ParsedTitle, IdentifyCandidate)>> {
+    let is_tv =
+        parsed.season.is_some() || parsed.episode.is_some() || parsed.absolute_episode.is_some();
+
+    for hint in id_hints::collect_hints(&file.path, is_tv).await {
+        let media_type = hint
+            .media_type
+            .clone()
+            .unwrap_or_else(|| if is_tv { "tv" } else { "movie" }.to_string());
+        let mut targets = Vec::new();
+        if registry.get(&hint.provider).is_some() {
+            targets.push((
+                hint.provider.clone(),
+                hint.external_id.clone(),
+                media_type.clone(),
+            ));
+        }
+        for title in external_ids::resolve(
+            db,
+            &hint.provider,
+            &hint.external_id,
+            hint.media_type.as_deref(),
+        )
+        .await?
+        {
+            let target = (title.provider, title.external_id, title.media_type);
+            if registry.get(&target.0).is_some() && !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+        if targets.is_empty() {
+            targets.extend(find_tmdb_target(db, client, settings, &hint, &media_type).await?);
+        }
+
+        for (provider_id, external_id, media_type) in targets {
+            let Some(provider) = registry.get(&provider_id) else {
+                continue;
+            };
+
+            match provider.details(&external_id, &media_type).await {
+                Ok(details) => {
+                    let candidate =
+                        candidate_from_details(&provider_id, &external_id, &media_type, details);
+                    let mut hinted = parsed.clone();
+                    if !candidate.title.is_empty() {
+                        hinted.title = candidate.title.clone();
+                    }
+                    hinted.year = candidate.year.or(hinted.year);
+                    hinted
+                        .field_sources
+                        .insert("title".to_string(), hint.source.clone());
+                    hinted.confidence = 1.0;
+                    hinted.parser_provider = hint.source.clone();
+                    return Ok(Some((hinted, candidate)));
+                }
+                Err(error) if provider_guard::unavailable(&error).is_some() => return Err(error),
+                Err(error) => tracing::warn!(
+                    "Failed to resolve {} id {} for {}: {}",
+                    provider_id,
+                    external_id,
+                    file.path,
+                    error
+                ),
+            }
+        }
     }
 
-    let is_tv = parsed.season.is_some() || parsed.episode.is_some();
-    let mut candidates = Vec::new();
+    Ok(None)
+}
 
-    if let Some(api_key) = settings.tmdb_api_key.as_deref() {
-        let tmdb_candidates = if is_tv {
-            scraper::search_tv_tmdb(client, &parsed.title, parsed.year, api_key)
-                .await?
-                .into_iter()
-                .map(|show| IdentifyCandidate {
-                    provider: "tmdb".to_string(),
-                    external_id: show.tmdb_id.unwrap_or_default().to_string(),
-                    media_type: "tv".to_string(),
-                    title: show.name.clone(),
-                    original_title: show.original_name.clone(),
-                    year: show
-                        .first_air_date
-                        .as_deref()
-                        .and_then(|s| s.split('-').next())
-                        .and_then(|s| s.parse::<u32>().ok()),
-                    score: 0.0,
-                    overview: show.overview.clone(),
-                    poster_url: show.poster_url.clone(),
-                    backdrop_url: show.backdrop_url.clone(),
-                    metadata: serde_json::to_value(show).unwrap_or_default(),
-                })
-                .collect::<Vec<_>>()
+/// IMDb / TVDB ID 经 TMDb /find 换算为 TMDb 作品，结果记入外部 ID 交叉引用
+async fn find_tmdb_target(
+    db: &SqlitePool,
+    client: &Client,
+    settings: &RuntimeSettings,
+    hint: &id_hints::IdHint,
+    media_type: &str,
+) -> anyhow::Result<Option<(String, String, String)>> {
+    let Some(api_key) = settings.tmdb_api_key.as_deref() else {
+        return Ok(None);
+    };
+    if settings.provider_offline {
+        return Ok(None);
+    }
+    match id_hints::find_tmdb_id(
+        client,
+        api_key,
+        &hint.provider,
+        &hint.external_id,
+        media_type == "tv",
+    )
+    .await
+    {
+        Ok(Some((id, kind))) => {
+            let title = external_ids::TitleRef::new("tmdb", &id, &kind);
+            let ids = BTreeMap::from([(hint.provider.clone(), hint.external_id.clone())]);
+            external_ids::store(db, &title, &ids, external_ids::SOURCE_PROVIDER).await?;
+            Ok(Some(("tmdb".to_string(), id, kind)))
+        }
+        Ok(None) => Ok(None),
+        Err(error) if provider_guard::unavailable(&error).is_some() => Err(error),
+        Err(error) => {
+            tracing::warn!(
+                "TMDb find failed for {} {}: {}",
+                hint.provider,
+                hint.external_id,
+                error
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// 按标题别名直接取详情，别名标题作为候选的其他标题参与评分；
+/// 别名指向的数据源未启用或取详情失败时回退到搜索
+async fn identify_from_alias(
+    db: &SqlitePool,
+    registry: &ProviderRegistry,
+    file: &MediaFile,
+    parsed: &ParsedTitle,
+) -> anyhow::Result<Option<IdentifyCandidate>> {
+    // 有季集标记时只匹配剧集别名，否则不限类型
+    let episodic =
+        parsed.season.is_some() || parsed.episode.is_some() || parsed.absolute_episode.is_some();
+    let Some(alias) = title_aliases::lookup(
+        db,
+        &parsed.title,
+        parsed.year.map(|year| year as i32),
+        episodic.then_some("tv"),
+        &file.path,
+    )
+    .await?
+    else {
+        return Ok(None);
+    };
+    let Some(provider) = registry.get(&alias.provider) else {
+        return Ok(None);
+    };
+
+    match provider
+        .details(&alias.external_id, &alias.media_type)
+        .await
+    {
+        Ok(details) => {
+            title_aliases::record_hit(db, &alias.id).await?;
+            let mut candidate = candidate_from_details(
+                &alias.provider,
+                &alias.external_id,
+                &alias.media_type,
+                details,
+            );
+            if !candidate.alternative_titles.contains(&alias.title) {
+                candidate.alternative_titles.push(alias.title);
+            }
+            Ok(Some(candidate))
+        }
+        Err(error) if provider_guard::unavailable(&error).is_some() => Err(error),
+        Err(error) => {
+            tracing::warn!(
+                "Failed to resolve alias {} -> {}:{} for {}: {}",
+                alias.title,
+                alias.provider,
+                alias.external_id,
+                file.path,
+                error
+            );
+            Ok(None)
+        }
+    }
+}
+
+fn candidate_from_details(
+    provider: &str,
+    external_id: &str,
+    media_type: &str,
+    details: Value,
+) -> IdentifyCandidate {
+    let text = |key: &str| details.get(key).and_then(Value::as_str).map(str::to_string);
+    IdentifyCandidate {
+        provider: provider.to_string(),
+        external_id: external_id.to_string(),
+        media_type: media_type.to_string(),
+        title: text("title").or_else(|| text("name")).unwrap_or_default(),
+        original_title: text("original_title"),
+        year: details
+            .get("year")
+            .and_then(Value::as_u64)
+            .map(|v| v as u32),
+        score: 1.0,
+        overview: text("overview"),
+        poster_url: text("poster_url"),
+        backdrop_url: text("backdrop_url"),
+        alternative_titles: metadata_provider::alternative_titles(&details),
+        metadata: details,
+    }
+}
+
+/// 多数据源查询结果
+#[derive(Debug, Clone, Default)]
+pub struct CandidateSearch {
+    pub candidates: Vec<IdentifyCandidate>,
+    /// 熔断中而被跳过的数据源
+    pub skipped_providers: Vec<String>,
+}
+
+/// 并发查询各数据源，合并去重后统一排序；单个数据源失败只记录日志
+pub async fn query_candidates(
+    providers: &[Arc<dyn MetadataProvider>],
+    parsed: &ParsedTitle,
+) -> anyhow::Result<Vec<IdentifyCandidate>> {
+    search_candidates(providers, parsed)
+        .await
+        .map(|search| search.candidates)
+}
+
+/// 同 [`query_candidates`]，并返回被跳过的数据源
+///
+/// 熔断中的数据源被跳过，结果只来自其余数据源；全部数据源都不可用时返回错误，
+/// 其中有熔断的返回熔断错误，由调用方等待后重试。
+pub async fn search_candidates(
+    providers: &[Arc<dyn MetadataProvider>],
+    parsed: &ParsedTitle,
+) -> anyhow::Result<CandidateSearch> {
+    if parsed.title.trim().is_empty() || providers.is_empty() {
+        return Ok(CandidateSearch::default());
+    }
+
+    let query = ProviderQuery {
+        title: parsed.title.clone(),
+        year: parsed.year,
+        media_type: if parsed.season.is_some() || parsed.episode.is_some() {
+            "tv".to_string()
         } else {
-            scraper::search_movie_tmdb(client, &parsed.title, parsed.year, api_key)
-                .await?
-                .into_iter()
-                .map(|movie| IdentifyCandidate {
-                    provider: "tmdb".to_string(),
-                    external_id: movie.tmdb_id.unwrap_or_default().to_string(),
-                    media_type: "movie".to_string(),
-                    title: movie.title.clone(),
-                    original_title: movie.original_title.clone(),
-                    year: movie.year,
-                    score: 0.0,
-                    overview: movie.overview.clone(),
-                    poster_url: movie.poster_url.clone(),
-                    backdrop_url: movie.backdrop_url.clone(),
-                    metadata: serde_json::to_value(movie).unwrap_or_default(),
-                })
-                .collect::<Vec<_>>()
-        };
-        candidates.extend(tmdb_candidates);
+            "movie".to_string()
+        },
+    };
+    let results = join_all(providers.iter().map(|provider| provider.search(&query))).await;
+
+    let mut candidates: Vec<IdentifyCandidate> = Vec::new();
+    let mut skipped_providers = Vec::new();
+    let mut circuit_error = None;
+    let mut last_error = None;
+    let mut succeeded = false;
+    for (provider, result) in providers.iter().zip(results) {
+        match result {
+            Ok(found) => {
+                succeeded = true;
+                for candidate in found {
+                    let duplicate = candidates.iter().any(|existing| {
+                        existing.provider == candidate.provider
+                            && existing.external_id == candidate.external_id
+                    });
+                    if !duplicate {
+                        candidates.push(candidate);
+                    }
+                }
+            }
+            Err(error) if provider_guard::unavailable(&error).is_some() => {
+                tracing::warn!("Provider {} skipped: {}", provider.id(), error);
+                skipped_providers.push(provider.id().to_string());
+                circuit_error.get_or_insert(error);
+            }
+            Err(error) => {
+                tracing::warn!("Provider {} search failed: {}", provider.id(), error);
+                last_error = Some(error);
+            }
+        }
     }
 
-    candidates.extend(search_bangumi(client, settings, parsed, is_tv).await?);
-    Ok(rank_candidates(parsed, &candidates))
+    if !succeeded {
+        if let Some(error) = circuit_error.or(last_error) {
+            return Err(error);
+        }
+    }
+    Ok(CandidateSearch {
+        candidates: rank_candidates(parsed, &candidates),
+        skipped_providers,
+    })
 }
 
 fn rank_candidates(
@@ -407,14 +1077,14 @@ fn rank_candidates(
     candidates: &[IdentifyCandidate],
 ) -> Vec<IdentifyCandidate> {
     let mut ranked = candidates.to_vec();
-    let normalized_query = normalize_title(&parsed.title);
     for candidate in &mut ranked {
-        let title_score = jaro_winkler(&normalized_query, &normalize_title(&candidate.title));
+        let title_score = title_similarity(&parsed.title, &candidate.title);
         let alt_score = candidate
             .original_title
-            .as_deref()
-            .map(|value| jaro_winkler(&normalized_query, &normalize_title(value)))
-            .unwrap_or(0.0);
+            .iter()
+            .chain(&candidate.alternative_titles)
+            .map(|value| title_similarity(&parsed.title, value))
+            .fold(0.0, f64::max);
         let best = title_score.max(alt_score);
         let year_bonus = match (parsed.year, candidate.year) {
             (Some(lhs), Some(rhs)) if lhs == rhs => 0.12,
@@ -427,175 +1097,18 @@ fn rank_candidates(
     ranked
 }
 
-async fn search_bangumi(
-    client: &Client,
-    settings: &RuntimeSettings,
-    parsed: &ParsedTitle,
-    is_tv: bool,
-) -> anyhow::Result<Vec<IdentifyCandidate>> {
-    let query = urlencoding::encode(&parsed.title);
-    let mut request = client
-        .get(format!(
-            "{}/search/subject/{}?type={}",
-            scraper::bangumi_api_base_url(),
-            query,
-            if is_tv { 2 } else { 1 }
-        ))
-        .header("User-Agent", "cine/1.2");
-    if let Some(token) = settings.bgm_api_key.as_deref() {
-        request = request.bearer_auth(token);
-    }
-    let payload: Value = request.send().await?.json().await?;
-    let items = payload
-        .get("list")
-        .and_then(Value::as_array)
-        .cloned()
-        .unwrap_or_default();
-
-    Ok(items
-        .into_iter()
-        .take(8)
-        .map(|item| IdentifyCandidate {
-            provider: "bgm".to_string(),
-            external_id: item
-                .get("id")
-                .and_then(Value::as_i64)
-                .unwrap_or_default()
-                .to_string(),
-            media_type: if is_tv {
-                "tv".to_string()
-            } else {
-                "movie".to_string()
-            },
-            title: item
-                .get("name_cn")
-                .and_then(Value::as_str)
-                .filter(|value| !value.is_empty())
-                .or_else(|| item.get("name").and_then(Value::as_str))
-                .unwrap_or("Unknown")
-                .to_string(),
-            original_title: item.get("name").and_then(Value::as_str).map(str::to_string),
-            year: item
-                .get("date")
-                .and_then(Value::as_str)
-                .and_then(|value| value.split('-').next())
-                .and_then(|value| value.parse::<u32>().ok()),
-            score: 0.0,
-            overview: item
-                .get("summary")
-                .and_then(Value::as_str)
-                .map(str::to_string),
-            poster_url: item
-                .get("images")
-                .and_then(|images| images.get("large"))
-                .and_then(Value::as_str)
-                .map(str::to_string),
-            backdrop_url: None,
-            metadata: item,
-        })
-        .collect())
-}
-
-async fn parse_with_cloudflare_ai(
-    client: &Client,
-    settings: &RuntimeSettings,
-    filename: &str,
-) -> anyhow::Result<Option<ParsedTitle>> {
-    if settings.ai_mode == "disabled" {
-        return Ok(None);
-    }
-    let account_id = match settings.cloudflare_account_id.as_deref() {
-        Some(value) if !value.is_empty() => value,
-        _ => return Ok(None),
-    };
-    let token = match settings.cloudflare_api_token.as_deref() {
-        Some(value) if !value.is_empty() => value,
-        _ => return Ok(None),
-    };
-    let base = settings.cloudflare_ai_base_url.clone().unwrap_or_else(|| {
-        format!("https://api.cloudflare.com/client/v4/accounts/{account_id}/ai/v1")
-    });
-    let payload = json!({
-        "model": settings.cloudflare_ai_model,
-        "response_format": { "type": "json_object" },
-        "messages": [
-            {
-                "role": "system",
-                "content": "Extract a JSON object with title, year, season, episode, is_special, special_type, confidence. Use only information present in the filename. Return JSON only."
-            },
-            { "role": "user", "content": filename }
-        ]
-    });
-    let response: Value = client
-        .post(format!("{base}/chat/completions"))
-        .bearer_auth(token)
-        .json(&payload)
-        .send()
-        .await?
-        .json()
-        .await
-        .context("failed to parse Cloudflare AI response")?;
-    let content = response
-        .get("choices")
-        .and_then(Value::as_array)
-        .and_then(|choices| choices.first())
-        .and_then(|choice| choice.get("message"))
-        .and_then(|message| message.get("content"))
-        .and_then(Value::as_str);
-
-    let Some(content) = content else {
-        return Ok(None);
-    };
-    let data: Value = serde_json::from_str(content.trim()).unwrap_or_default();
-    let title = data
-        .get("title")
-        .and_then(Value::as_str)
-        .unwrap_or_default()
-        .trim()
-        .to_string();
-    if title.is_empty() {
-        return Ok(None);
-    }
-
-    Ok(Some(ParsedTitle {
-        title,
-        year: data
-            .get("year")
-            .and_then(Value::as_u64)
-            .map(|value| value as u32),
-        season: data
-            .get("season")
-            .and_then(Value::as_u64)
-            .map(|value| value as u32),
-        episode: data
-            .get("episode")
-            .and_then(Value::as_u64)
-            .map(|value| value as u32),
-        is_special: data
-            .get("is_special")
-            .and_then(Value::as_bool)
-            .unwrap_or(false),
-        special_type: data
-            .get("special_type")
-            .and_then(Value::as_str)
-            .map(str::to_string),
-        confidence: data
-            .get("confidence")
-            .and_then(Value::as_f64)
-            .unwrap_or(0.72),
-        parser_provider: "cloudflare_ai".to_string(),
-        ai_disabled_reason: None,
-    }))
-}
-
 async fn can_use_ai(db: &SqlitePool, settings: &RuntimeSettings) -> anyhow::Result<bool> {
-    if settings.ai_budget_mode != "strict_free" {
+    // 只有计费端点受每日额度限制
+    let paid =
+        ai_parser::AiEndpoint::from_settings(settings).is_none_or(|endpoint| endpoint.is_paid());
+    if settings.ai_budget_mode != "strict_free" || !paid {
         return Ok(true);
     }
     let today = Utc::now().date_naive().to_string();
     let used: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM ai_usage_logs WHERE provider = 'cloudflare' AND date(created_at) = date(?)",
+        "SELECT COUNT(*) FROM ai_usage_logs WHERE provider = ? AND date(created_at) = date(?)",
     )
+    .bind(&settings.ai_backend)
     .bind(today)
     .fetch_one(db)
     .await
@@ -605,14 +1118,15 @@ async fn can_use_ai(db: &SqlitePool, settings: &RuntimeSettings) -> anyhow::Resu
 
 async fn record_ai_usage(
     db: &SqlitePool,
-    settings: &RuntimeSettings,
+    endpoint: &ai_parser::AiEndpoint,
     request_key: &str,
 ) -> anyhow::Result<()> {
     sqlx::query(
-        "INSERT INTO ai_usage_logs (id, provider, model, request_key, created_at) VALUES (?, 'cloudflare', ?, ?, ?)",
+        "INSERT INTO ai_usage_logs (id, provider, model, request_key, created_at) VALUES (?, ?, ?, ?, ?)",
     )
     .bind(Uuid::new_v4().to_string())
-    .bind(&settings.cloudflare_ai_model)
+    .bind(&endpoint.backend)
+    .bind(&endpoint.model)
     .bind(request_key)
     .bind(Utc::now())
     .execute(db)
@@ -620,62 +1134,10 @@ async fn record_ai_usage(
     Ok(())
 }
 
-async fn fetch_provider_details(
-    client: &Client,
-    settings: &RuntimeSettings,
-    provider: &str,
-    external_id: &str,
-    media_type: &str,
-) -> anyhow::Result<Value> {
-    match provider {
-        "tmdb" => fetch_tmdb_details(client, settings, external_id, media_type).await,
-        "bgm" => fetch_bangumi_details(client, settings, external_id, media_type).await,
-        _ => Err(anyhow::anyhow!("unsupported provider: {provider}")),
-    }
-}
-
-async fn fetch_tmdb_details(
-    client: &Client,
-    settings: &RuntimeSettings,
-    external_id: &str,
-    media_type: &str,
-) -> anyhow::Result<Value> {
-    let api_key = settings
-        .tmdb_api_key
-        .as_deref()
-        .ok_or_else(|| anyhow::anyhow!("TMDB API key not configured"))?;
-    let endpoint = if media_type == "tv" { "tv" } else { "movie" };
-    let url = format!(
-        "{}/{endpoint}/{external_id}?api_key={api_key}&language=zh-CN",
-        scraper::tmdb_api_base_url()
-    );
-    let payload: Value = client.get(url).send().await?.json().await?;
-    Ok(tmdb_details_from_payload(&payload, media_type))
-}
-
-async fn fetch_bangumi_details(
-    client: &Client,
-    settings: &RuntimeSettings,
-    external_id: &str,
-    media_type: &str,
-) -> anyhow::Result<Value> {
-    let mut request = client
-        .get(format!(
-            "{}/v0/subjects/{external_id}",
-            scraper::bangumi_api_base_url()
-        ))
-        .header("User-Agent", "cine/1.2");
-    if let Some(token) = settings.bgm_api_key.as_deref() {
-        request = request.bearer_auth(token);
-    }
-    let payload: Value = request.send().await?.json().await?;
-    Ok(bangumi_details_from_payload(&payload, media_type))
-}
-
+/// 未识别出季/集时补充剧集的总季数与总集数
 async fn enrich_tmdb_tv_metadata(
     client: &Client,
     api_key: &str,
-    external_id: &str,
     metadata: &mut Value,
 ) -> anyhow::Result<()> {
     let Some(tmdb_id) = metadata.get("tmdb_id").and_then(Value::as_u64) else {
@@ -686,13 +1148,14 @@ async fn enrich_tmdb_tv_metadata(
     let episode_number = metadata.get("episode_number").and_then(Value::as_u64);
 
     if season_number.is_none() || episode_number.is_none() {
-        let seasons = client
-            .get(format!(
+        let seasons = provider_guard::send(
+            "tmdb",
+            client.get(format!(
                 "{}/tv/{tmdb_id}?api_key={api_key}&append_to_response=season/1",
                 scraper::tmdb_api_base_url()
-            ))
-            .send()
-            .await?;
+            )),
+        )
+        .await?;
         let seasons_payload: Value = seasons.json().await.unwrap_or_default();
         if let Some(object) = metadata.as_object_mut() {
             object.insert(
@@ -710,55 +1173,132 @@ async fn enrich_tmdb_tv_metadata(
                     .unwrap_or(Value::Null),
             );
         }
+    }
+
+    Ok(())
+}
+
+/// 拉取本集（多集文件为每一集）的标题、简介、首播日期、时长、评分与剧照，
+/// 通过数据源的季/条目单集列表获取，TMDb 与 Bangumi 共用
+async fn enrich_episode_metadata(
+    provider: &Arc<dyn MetadataProvider>,
+    external_id: &str,
+    details: &mut Value,
+) -> anyhow::Result<()> {
+    let Some(season) = details.get("season_number").and_then(Value::as_u64) else {
+        return Ok(());
+    };
+    if details
+        .get("episode_number")
+        .and_then(Value::as_u64)
+        .is_none()
+    {
         return Ok(());
     }
+    let episodes = provider.episodes(external_id, Some(season as u32)).await?;
+    merge_episode_metadata(details, &episodes);
+    Ok(())
+}
 
-    let season_number = season_number.unwrap();
-    let episode_number = episode_number.unwrap();
-    let episode_url = format!(
-        "{}/tv/{external_id}/season/{season_number}/episode/{episode_number}?api_key={api_key}&language=zh-CN",
-        scraper::tmdb_api_base_url()
+/// 把单集列表中与文件对应的各集写入详情：`episodes` 数组供多集 NFO 使用，
+/// 首集同时写入 `episode_title` 等扁平字段供重命名模板与单集 NFO 使用
+fn merge_episode_metadata(details: &mut Value, episodes: &[ProviderEpisode]) {
+    let season = details.get("season_number").and_then(Value::as_u64);
+    let Some(first) = details.get("episode_number").and_then(Value::as_u64) else {
+        return;
+    };
+    let last = details
+        .get("episode_number_end")
+        .and_then(Value::as_u64)
+        .filter(|last| *last > first)
+        .unwrap_or(first);
+
+    let matched: Vec<&ProviderEpisode> = (first..=last)
+        .filter_map(|number| {
+            episodes.iter().find(|episode| {
+                u64::from(episode.episode_number) == number
+                    && episode
+                        .season_number
+                        .is_none_or(|s| Some(u64::from(s)) == season)
+            })
+        })
+        .collect();
+    let Some(object) = details.as_object_mut() else {
+        return;
+    };
+    if let Some(season) = season {
+        object.insert("season_name".to_string(), json!(format!("Season {season}")));
+    }
+    let Some(episode) = matched.first() else {
+        return;
+    };
+    object.insert("episode_title".to_string(), json!(episode.name));
+    object.insert("episode_overview".to_string(), json!(episode.overview));
+    object.insert("episode_air_date".to_string(), json!(episode.air_date));
+    object.insert("episode_runtime".to_string(), json!(episode.runtime));
+    object.insert("episode_rating".to_string(), json!(episode.rating));
+    object.insert("episode_still_url".to_string(), json!(episode.still_url));
+    object.insert(
+        "episodes".to_string(),
+        Value::Array(
+            matched
+                .iter()
+                .map(|episode| serde_json::to_value(episode).unwrap_or_default())
+                .collect(),
+        ),
     );
-    let episode_payload: Value = client.get(episode_url).send().await?.json().await?;
-
-    if let Some(object) = metadata.as_object_mut() {
-        object.insert("season_number".to_string(), json!(season_number));
-        object.insert("episode_number".to_string(), json!(episode_number));
-        object.insert(
-            "episode_title".to_string(),
-            episode_payload.get("name").cloned().unwrap_or(Value::Null),
-        );
-        object.insert(
-            "episode_overview".to_string(),
-            episode_payload
-                .get("overview")
-                .cloned()
-                .unwrap_or(Value::Null),
-        );
-        object.insert(
-            "episode_air_date".to_string(),
-            episode_payload
-                .get("air_date")
-                .cloned()
-                .unwrap_or(Value::Null),
-        );
-        object.insert(
-            "episode_still_url".to_string(),
-            episode_payload
-                .get("still_path")
-                .and_then(Value::as_str)
-                .map(|path| Value::String(format!("https://image.tmdb.org/t/p/w780{path}")))
-                .unwrap_or(Value::Null),
-        );
-        object.insert(
-            "season_name".to_string(),
-            episode_payload
-                .get("season_number")
-                .map(|_| Value::String(format!("Season {}", season_number)))
-                .unwrap_or(Value::Null),
-        );
+}
+
+/// 绝对集数与季/集编号互相补全：TMDb 依据各季集数，Bangumi 依据条目集列表
+async fn map_absolute_episode(
+    provider: &Arc<dyn MetadataProvider>,
+    selection: &ApplySelection,
+    parsed: &ParsedTitle,
+    details: &mut Value,
+) -> anyhow::Result<()> {
+    let season = parsed.season;
+    let episode = parsed.episode;
+    let absolute = parsed.absolute_episode;
+    if absolute.is_none() && (season.is_none() || episode.is_none()) {
+        return Ok(());
     }
 
+    // 集列表经数据源（含响应缓存与离线模式）获取，与单集元数据共用
+    let mapped = match selection.provider.as_str() {
+        "tmdb" => {
+            let episodes = provider.episodes(&selection.external_id, None).await?;
+            let seasons = episode_map::season_counts_from_episodes(&episodes);
+            match (absolute, season, episode) {
+                (Some(absolute), None, _) => episode_map::absolute_to_season(&seasons, absolute)
+                    .map(|(season, episode)| (season, episode, absolute)),
+                (_, Some(season), Some(episode)) => {
+                    episode_map::season_to_absolute(&seasons, season, episode)
+                        .map(|absolute| (season, episode, absolute))
+                }
+                _ => None,
+            }
+        }
+        "bgm" => match absolute {
+            // Bangumi 每季单独成条目，条目内集数即本季集数，季号取自条目名
+            Some(absolute) if season.is_none() => {
+                let episodes = provider.episodes(&selection.external_id, None).await?;
+                let season = episode_map::bangumi_subject_season(details).unwrap_or(1);
+                episode_map::bangumi_absolute_to_episode(&episodes, absolute)
+                    .map(|episode| (season, episode, absolute))
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+
+    let Some((season, episode, absolute)) = mapped else {
+        return Ok(());
+    };
+    if let Some(object) = details.as_object_mut() {
+        object.insert("season_number".to_string(), json!(season));
+        object.insert("episode_number".to_string(), json!(episode));
+        object.insert("absolute_number".to_string(), json!(absolute));
+    }
     Ok(())
 }
 
@@ -779,6 +1319,15 @@ fn merge_detected_context(details: &mut Value, parsed: &ParsedTitle) {
         if parsed.episode.is_some() {
             object.insert("episode_number".to_string(), json!(parsed.episode));
         }
+        if parsed.episode_end.is_some() {
+            object.insert("episode_number_end".to_string(), json!(parsed.episode_end));
+        }
+        if parsed.absolute_episode.is_some() {
+            object.insert(
+                "absolute_number".to_string(),
+                json!(parsed.absolute_episode),
+            );
+        }
         if parsed.is_special {
             object.insert("is_special".to_string(), json!(true));
         }
@@ -788,15 +1337,27 @@ fn merge_detected_context(details: &mut Value, parsed: &ParsedTitle) {
     }
 }
 
-fn normalize_title(value: &str) -> String {
-    value
+/// 标题匹配用的归一化：中日文折叠（繁简、全半角、中文数字、假名罗马字）后只保留字母数字并转小写
+pub fn normalize_title(value: &str) -> String {
+    cjk::fold(value)
         .chars()
         .filter(|ch| ch.is_alphanumeric())
         .collect::<String>()
         .to_lowercase()
 }
 
-fn tmdb_details_from_payload(payload: &Value, media_type: &str) -> Value {
+/// 两个标题归一化后的相似度；任一侧明确是日文（假名或带长音符号）时，
+/// 两侧再合并罗马字长音等写法，使 `Koukaku Kidoutai` 与 `Kōkaku Kidōtai` 一致
+pub fn title_similarity(lhs: &str, rhs: &str) -> f64 {
+    let (mut lhs_key, mut rhs_key) = (normalize_title(lhs), normalize_title(rhs));
+    if cjk::has_japanese_hint(lhs) || cjk::has_japanese_hint(rhs) {
+        lhs_key = cjk::fold_romaji(&lhs_key);
+        rhs_key = cjk::fold_romaji(&rhs_key);
+    }
+    jaro_winkler(&lhs_key, &rhs_key)
+}
+
+pub(crate) fn tmdb_details_from_payload(payload: &Value, media_type: &str) -> Value {
     let year = payload
         .get("release_date")
         .or_else(|| payload.get("first_air_date"))
@@ -816,10 +1377,12 @@ fn tmdb_details_from_payload(payload: &Value, media_type: &str) -> Value {
         "release_date": payload.get("release_date").or_else(|| payload.get("first_air_date")).and_then(Value::as_str),
         "year": year,
         "media_type": media_type,
+        "collection": payload.get("belongs_to_collection").and_then(crate::services::collections::collection_ref_from_tmdb),
+        "external_ids": external_ids::tmdb_external_ids(payload),
     })
 }
 
-fn bangumi_details_from_payload(payload: &Value, media_type: &str) -> Value {
+pub(crate) fn bangumi_details_from_payload(payload: &Value, media_type: &str) -> Value {
     let year = payload
         .get("date")
         .and_then(Value::as_str)
@@ -844,17 +1407,24 @@ fn bangumi_details_from_payload(payload: &Value, media_type: &str) -> Value {
 #[cfg(test)]
 mod tests {
     use super::{
-        bangumi_details_from_payload, merge_detected_context, normalize_title, parse_with_rules,
-        rank_candidates, tmdb_details_from_payload, IdentifyCandidate, ParsedTitle, AUTO_THRESHOLD,
+        bangumi_details_from_payload, merge_detected_context, merge_episode_metadata,
+        normalize_title, parse_with_rules, rank_candidates, title_similarity,
+        tmdb_details_from_payload, IdentifyCandidate, ParsedTitle, ProviderEpisode, AUTO_THRESHOLD,
+        REVIEW_THRESHOLD,
     };
     use crate::models::MediaFile;
     use chrono::Utc;
     use serde_json::json;
 
     fn make_file(name: &str) -> MediaFile {
+        make_file_at(&format!("/library/{name}"))
+    }
+
+    fn make_file_at(path: &str) -> MediaFile {
+        let name = path.rsplit('/').next().unwrap_or(path);
         MediaFile {
             id: "file-1".to_string(),
-            path: format!("/library/{name}"),
+            path: path.to_string(),
             name: name.to_string(),
             size: 1024,
             file_type: "video".to_string(),
@@ -868,6 +1438,9 @@ mod tests {
             detected_year: None,
             detected_season: None,
             detected_episode: None,
+            detected_episode_end: None,
+            detected_absolute_episode: None,
+            release_info: None,
             parser_provider: None,
             parse_version: None,
             confidence_score: None,
@@ -877,6 +1450,10 @@ mod tests {
             locked_match_provider: None,
             locked_match_external_id: None,
             ai_disabled_reason: None,
+            movie_id: None,
+            show_id: None,
+            season_id: None,
+            episode_id: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             last_modified: Utc::now(),
@@ -891,9 +1468,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn normalize_title_folds_cjk_variants() {
+        assert_eq!(
+            normalize_title("進擊的巨人 第二季"),
+            normalize_title("进击的巨人 第2季")
+        );
+        assert_eq!(
+            normalize_title("ＳＰＹ×ＦＡＭＩＬＹ"),
+            normalize_title("SPY×FAMILY")
+        );
+        assert_eq!(
+            normalize_title("しんげきのきょじん"),
+            normalize_title("Shingeki no Kyojin")
+        );
+        assert_eq!(title_similarity("Kōkaku Kidōtai", "Koukaku Kidoutai"), 1.0);
+        assert!(title_similarity("Good Boys", "God Boys") < 1.0);
+    }
+
     #[test]
     fn parse_with_rules_marks_episode_for_review_bias() {
-        let parsed = parse_with_rules(&make_file("The.Last.of.Us.S01E03.2160p.mkv"));
+        let parsed = parse_with_rules(&make_file("The.Last.of.Us.S01E03.2160p.mkv"), None);
 
         assert_eq!(parsed.season, Some(1));
         assert_eq!(parsed.episode, Some(3));
@@ -901,14 +1496,119 @@ mod tests {
         assert!(parsed.confidence < AUTO_THRESHOLD);
     }
 
+    #[test]
+    fn parse_with_rules_detects_multi_episode_range() {
+        let parsed = parse_with_rules(&make_file("Friends.S02E12-E13.1080p.mkv"), None);
+
+        assert_eq!(parsed.season, Some(2));
+        assert_eq!(parsed.episode, Some(12));
+        assert_eq!(parsed.episode_end, Some(13));
+    }
+
+    #[test]
+    fn parse_with_rules_uses_season_and_show_folders() {
+        let parsed = parse_with_rules(
+            &make_file_at("/library/TV/Breaking Bad (2008)/Season 2/02.mkv"),
+            None,
+        );
+
+        assert_eq!(parsed.title, "Breaking Bad");
+        assert_eq!(parsed.year, Some(2008));
+        assert_eq!(parsed.season, Some(2));
+        assert_eq!(parsed.episode, Some(2));
+        assert_eq!(parsed.field_sources["title"], "show_folder");
+        assert_eq!(parsed.field_sources["year"], "show_folder");
+        assert_eq!(parsed.field_sources["season"], "season_folder");
+        assert_eq!(parsed.field_sources["episode"], "file_name");
+    }
+
+    #[test]
+    fn parse_with_rules_keeps_file_name_fields_over_folders() {
+        let parsed = parse_with_rules(
+            &make_file_at("/library/Frieren (2023)/第二季/Frieren S01E05.mkv"),
+            None,
+        );
+
+        assert_eq!(parsed.title, "Frieren");
+        assert_eq!(parsed.season, Some(1));
+        assert_eq!(parsed.episode, Some(5));
+        assert_eq!(parsed.year, Some(2023));
+        assert_eq!(parsed.field_sources["season"], "file_name");
+        assert_eq!(parsed.field_sources["year"], "show_folder");
+    }
+
+    #[test]
+    fn parse_with_rules_ignores_unrelated_or_generic_folders() {
+        let unrelated =
+            parse_with_rules(&make_file_at("/library/Other (1999)/Inception.mkv"), None);
+        assert_eq!(unrelated.title, "Inception");
+        assert_eq!(unrelated.year, None);
+
+        let generic = parse_with_rules(&make_file_at("/data/Movies/01.mkv"), None);
+        assert!(!generic.field_sources.contains_key("title"));
+        assert!(generic.confidence < REVIEW_THRESHOLD);
+    }
+
     #[test]
     fn parse_with_rules_marks_specials() {
-        let parsed = parse_with_rules(&make_file("Frieren.Special.E01.mkv"));
+        let parsed = parse_with_rules(&make_file("Frieren.Special.E01.mkv"), None);
 
         assert!(parsed.is_special);
         assert_eq!(parsed.special_type.as_deref(), Some("special"));
     }
 
+    #[test]
+    fn parse_with_rules_uses_anime_parser_for_fansub_names() {
+        let parsed = parse_with_rules(
+            &make_file("[Lilith-Raws] Sousou no Frieren - 05v2 [Baha][WEB-DL][1080p][CHT].mp4"),
+            None,
+        );
+        assert_eq!(parsed.title, "Sousou no Frieren");
+        assert_eq!(parsed.absolute_episode, Some(5));
+        assert_eq!(parsed.episode, None);
+
+        let special = parse_with_rules(
+            &make_file("[VCB-Studio] Mushishi [OVA01][Ma10p_1080p].mkv"),
+            Some("anime"),
+        );
+        assert_eq!(special.title, "Mushishi");
+        assert_eq!(special.season, Some(0));
+        assert_eq!(special.episode, Some(1));
+        assert_eq!(special.special_type.as_deref(), Some("ova"));
+    }
+
+    #[test]
+    fn merge_episode_metadata_fills_episode_fields() {
+        let episode = |number: u32, name: &str| ProviderEpisode {
+            season_number: Some(2),
+            episode_number: number,
+            name: Some(name.to_string()),
+            runtime: Some(24),
+            rating: Some(8.0),
+            still_url: Some(format!("https://img.example/{number}.jpg")),
+            ..Default::default()
+        };
+        let episodes = vec![
+            episode(11, "Eleven"),
+            episode(12, "Twelve"),
+            episode(13, "Thirteen"),
+        ];
+
+        let mut details =
+            json!({ "season_number": 2, "episode_number": 12, "episode_number_end": 13 });
+        merge_episode_metadata(&mut details, &episodes);
+        assert_eq!(details["episode_title"], "Twelve");
+        assert_eq!(details["episode_runtime"], 24);
+        assert_eq!(details["episode_still_url"], "https://img.example/12.jpg");
+        assert_eq!(details["season_name"], "Season 2");
+        assert_eq!(details["episodes"].as_array().unwrap().len(), 2);
+        assert_eq!(details["episodes"][1]["name"], "Thirteen");
+
+        let mut other_season = json!({ "season_number": 3, "episode_number": 12 });
+        merge_episode_metadata(&mut other_season, &episodes);
+        assert!(other_season.get("episode_title").is_none());
+    }
+
     #[test]
     fn ranking_prefers_exact_title_and_year() {
         let parsed = ParsedTitle {
@@ -916,11 +1616,14 @@ mod tests {
             year: Some(2024),
             season: Some(1),
             episode: Some(1),
+            episode_end: None,
+            absolute_episode: None,
             is_special: false,
             special_type: None,
             confidence: 0.8,
             parser_provider: "rules".to_string(),
             ai_disabled_reason: None,
+            field_sources: Default::default(),
         };
 
         let ranked = rank_candidates(
@@ -937,6 +1640,7 @@ mod tests {
                     overview: None,
                     poster_url: None,
                     backdrop_url: None,
+                    alternative_titles: Vec::new(),
                     metadata: json!({}),
                 },
                 IdentifyCandidate {
@@ -950,6 +1654,7 @@ mod tests {
                     overview: None,
                     poster_url: None,
                     backdrop_url: None,
+                    alternative_titles: Vec::new(),
                     metadata: json!({}),
                 },
             ],
@@ -959,6 +1664,51 @@ mod tests {
         assert!(ranked[0].score > ranked[1].score);
     }
 
+    #[test]
+    fn ranking_matches_alternative_titles() {
+        let parsed = ParsedTitle {
+            title: "Spirited Away".to_string(),
+            year: Some(2001),
+            season: None,
+            episode: None,
+            episode_end: None,
+            absolute_episode: None,
+            is_special: false,
+            special_type: None,
+            confidence: 0.8,
+            parser_provider: "rules".to_string(),
+            ai_disabled_reason: None,
+            field_sources: Default::default(),
+        };
+        let candidate =
+            |id: &str, title: &str, alternative_titles: Vec<String>| IdentifyCandidate {
+                provider: "tmdb".to_string(),
+                external_id: id.to_string(),
+                media_type: "movie".to_string(),
+                title: title.to_string(),
+                original_title: Some("千と千尋の神隠し".to_string()),
+                year: Some(2001),
+                score: 0.0,
+                overview: None,
+                poster_url: None,
+                backdrop_url: None,
+                alternative_titles,
+                metadata: json!({}),
+            };
+
+        let ranked = rank_candidates(
+            &parsed,
+            &[
+                candidate("1", "千与千寻", Vec::new()),
+                candidate("2", "千与千寻", vec!["Spirited Away".to_string()]),
+            ],
+        );
+
+        assert_eq!(ranked[0].external_id, "2");
+        assert!(ranked[0].score >= AUTO_THRESHOLD);
+        assert!(ranked[1].score < REVIEW_THRESHOLD);
+    }
+
     #[test]
     fn tmdb_details_payload_maps_movie_fields() {
         let metadata = tmdb_details_from_payload(
@@ -980,6 +1730,32 @@ mod tests {
         assert_eq!(metadata["title"], "Dune");
         assert_eq!(metadata["year"], 2021);
         assert_eq!(metadata["media_type"], "movie");
+        assert!(metadata["collection"].is_null());
+    }
+
+    #[test]
+    fn tmdb_details_payload_keeps_collection() {
+        let metadata = tmdb_details_from_payload(
+            &json!({
+                "id": 603,
+                "title": "The Matrix",
+                "belongs_to_collection": {
+                    "id": 2344,
+                    "name": "The Matrix Collection",
+                    "poster_path": "/collection.jpg",
+                    "backdrop_path": null
+                }
+            }),
+            "movie",
+        );
+
+        assert_eq!(metadata["collection"]["id"], "2344");
+        assert_eq!(metadata["collection"]["name"], "The Matrix Collection");
+        assert_eq!(
+            metadata["collection"]["poster_url"],
+            "https://image.tmdb.org/t/p/w500/collection.jpg"
+        );
+        assert!(metadata["collection"]["backdrop_url"].is_null());
     }
 
     #[test]
@@ -1018,11 +1794,14 @@ mod tests {
             year: Some(2023),
             season: Some(1),
             episode: Some(3),
+            episode_end: Some(4),
+            absolute_episode: None,
             is_special: true,
             special_type: Some("special".to_string()),
             confidence: 0.78,
             parser_provider: "rules".to_string(),
             ai_disabled_reason: None,
+            field_sources: Default::default(),
         };
 
         merge_detected_context(&mut metadata, &parsed);
@@ -1030,6 +1809,7 @@ mod tests {
         assert_eq!(metadata["year"], 2023);
         assert_eq!(metadata["season_number"], 1);
         assert_eq!(metadata["episode_number"], 3);
+        assert_eq!(metadata["episode_number_end"], 4);
         assert_eq!(metadata["is_special"], true);
         assert_eq!(metadata["special_type"], "special");
     }
@@ -1,27 +1,259 @@
 use crate::models::OperationLog;
 use chrono::Utc;
+use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
+use std::collections::HashSet;
+use std::path::Path;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
+/// 会改变文件路径、可通过“移回原处”撤销的操作
+const RELOCATING_ACTIONS: [&str; 4] = ["rename", "move", "trash", "restore"];
+
+/// 撤销时执行的逆操作
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum InverseOp {
+    /// 将文件从 `from` 移回 `to`（重命名、移动、回收站、恢复）
+    Move { from: String, to: String },
+    /// 删除操作产生的副本（复制）
+    DeleteFile { path: String },
+    /// 重新创建被删除的空目录
+    CreateDir { path: String },
+}
+
+/// 撤销前需满足的文件系统状态，防止覆盖或删除已被外部修改的文件
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Precondition {
+    /// 必须存在的路径
+    #[serde(default)]
+    pub exists: Vec<String>,
+    /// 必须不存在的路径
+    #[serde(default)]
+    pub absent: Vec<String>,
+    /// `exists` 中第一个路径的预期大小（字节）
+    pub size: Option<u64>,
+}
+
+impl Precondition {
+    /// 检查前置条件，不满足时返回说明原因的错误
+    pub async fn check(&self) -> anyhow::Result<()> {
+        self.check_with(&PathOverlay::default()).await
+    }
+
+    /// 在模拟的文件系统状态上检查前置条件（用于整批撤销的预演）
+    async fn check_with(&self, overlay: &PathOverlay) -> anyhow::Result<()> {
+        for path in &self.exists {
+            if !overlay.exists(path).await {
+                return Err(anyhow::anyhow!("Precondition failed: {} is missing", path));
+            }
+        }
+        for path in &self.absent {
+            if overlay.exists(path).await {
+                return Err(anyhow::anyhow!(
+                    "Precondition failed: {} already exists",
+                    path
+                ));
+            }
+        }
+        if let (Some(expected), Some(path)) = (self.size, self.exists.first()) {
+            // 模拟产生的路径无法确认大小，仅检查磁盘上的真实文件
+            if overlay.present.contains(path) {
+                return Ok(());
+            }
+            let actual = tokio::fs::metadata(path).await?.len();
+            if actual != expected {
+                return Err(anyhow::anyhow!(
+                    "Precondition failed: {} changed size ({} -> {} bytes)",
+                    path,
+                    expected,
+                    actual
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 叠加在真实文件系统之上的路径变化，用于预演多个逆操作的累积效果
+#[derive(Default)]
+struct PathOverlay {
+    present: HashSet<String>,
+    gone: HashSet<String>,
+}
+
+impl PathOverlay {
+    async fn exists(&self, path: &str) -> bool {
+        if self.present.contains(path) {
+            return true;
+        }
+        !self.gone.contains(path) && tokio::fs::try_exists(path).await.unwrap_or(false)
+    }
+
+    fn add(&mut self, path: &str) {
+        self.gone.remove(path);
+        self.present.insert(path.to_string());
+    }
+
+    fn remove(&mut self, path: &str) {
+        self.present.remove(path);
+        self.gone.insert(path.to_string());
+    }
+
+    /// 记录逆操作执行后的路径变化
+    fn apply(&mut self, inverse: &InverseOp) {
+        match inverse {
+            InverseOp::Move { from, to } => {
+                self.remove(from);
+                self.add(to);
+            }
+            InverseOp::DeleteFile { path } => self.remove(path),
+            InverseOp::CreateDir { path } => self.add(path),
+        }
+    }
+}
+
+/// 一条待写入的操作日志
+pub struct JournalEntry<'a> {
+    pub action: &'a str,
+    pub file_id: Option<&'a str>,
+    pub old_path: &'a str,
+    pub new_path: Option<&'a str>,
+    pub inverse: Option<InverseOp>,
+    pub precondition: Precondition,
+    pub batch_id: Option<&'a str>,
+}
+
+impl<'a> JournalEntry<'a> {
+    /// 关联到批次（任务 ID 或批量请求 ID）
+    pub fn in_batch(mut self, batch_id: Option<&'a str>) -> Self {
+        self.batch_id = batch_id;
+        self
+    }
+
+    /// 路径变更类操作：逆操作为将文件从新路径移回原路径
+    pub fn relocation(
+        action: &'a str,
+        file_id: Option<&'a str>,
+        old_path: &'a str,
+        new_path: &'a str,
+    ) -> Self {
+        // 仅大小写变化时原路径在大小写不敏感的文件系统上“仍存在”，不能要求其不存在
+        let case_only = old_path != new_path && old_path.to_lowercase() == new_path.to_lowercase();
+        Self {
+            action,
+            file_id,
+            old_path,
+            new_path: Some(new_path),
+            inverse: Some(InverseOp::Move {
+                from: new_path.to_string(),
+                to: old_path.to_string(),
+            }),
+            precondition: Precondition {
+                exists: vec![new_path.to_string()],
+                absent: if case_only {
+                    Vec::new()
+                } else {
+                    vec![old_path.to_string()]
+                },
+                size: None,
+            },
+            batch_id: None,
+        }
+    }
+
+    /// 复制：逆操作为删除副本，且副本大小必须未变
+    pub fn copy(file_id: Option<&'a str>, source: &'a str, copy_path: &'a str, size: u64) -> Self {
+        Self {
+            action: "copy",
+            file_id,
+            old_path: source,
+            new_path: Some(copy_path),
+            inverse: Some(InverseOp::DeleteFile {
+                path: copy_path.to_string(),
+            }),
+            precondition: Precondition {
+                exists: vec![copy_path.to_string()],
+                absent: Vec::new(),
+                size: Some(size),
+            },
+            batch_id: None,
+        }
+    }
+
+    /// 删除空目录：逆操作为重新创建目录
+    pub fn dir_removal(path: &'a str) -> Self {
+        Self {
+            action: "delete_dir",
+            file_id: None,
+            old_path: path,
+            new_path: None,
+            inverse: Some(InverseOp::CreateDir {
+                path: path.to_string(),
+            }),
+            precondition: Precondition {
+                exists: Vec::new(),
+                absent: vec![path.to_string()],
+                size: None,
+            },
+            batch_id: None,
+        }
+    }
+}
+
+/// 写入一条操作日志，返回日志 ID
+pub async fn record_entry(db: &SqlitePool, entry: JournalEntry<'_>) -> anyhow::Result<String> {
+    let id = Uuid::new_v4().to_string();
+    let inverse = entry
+        .inverse
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()?;
+    let precondition = serde_json::to_string(&entry.precondition)?;
+
+    sqlx::query(
+        "INSERT INTO operation_logs (id, action, file_id, old_path, new_path, inverse, precondition, batch_id, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(&id)
+    .bind(entry.action)
+    .bind(entry.file_id)
+    .bind(entry.old_path)
+    .bind(entry.new_path)
+    .bind(inverse)
+    .bind(precondition)
+    .bind(entry.batch_id)
+    .bind(Utc::now())
+    .execute(db)
+    .await?;
+    Ok(id)
+}
+
 /// 记录一次文件操作
+///
+/// 路径变更类操作自动附带“移回原路径”的逆操作；其他操作（如永久删除）不可撤销。
 pub async fn record_operation(
     db: &SqlitePool,
     action: &str,
     file_id: Option<&str>,
     old_path: &str,
     new_path: Option<&str>,
+    batch_id: Option<&str>,
 ) -> anyhow::Result<()> {
-    sqlx::query(
-        "INSERT INTO operation_logs (id, action, file_id, old_path, new_path, created_at) VALUES (?, ?, ?, ?, ?, ?)"
-    )
-    .bind(Uuid::new_v4().to_string())
-    .bind(action)
-    .bind(file_id)
-    .bind(old_path)
-    .bind(new_path)
-    .bind(Utc::now())
-    .execute(db)
-    .await?;
+    let entry = match new_path {
+        Some(new_path) if RELOCATING_ACTIONS.contains(&action) => {
+            JournalEntry::relocation(action, file_id, old_path, new_path)
+        }
+        _ => JournalEntry {
+            action,
+            file_id,
+            old_path,
+            new_path,
+            inverse: None,
+            precondition: Precondition::default(),
+            batch_id: None,
+        },
+    };
+    record_entry(db, entry.in_batch(batch_id)).await?;
     Ok(())
 }
 
@@ -35,3 +267,281 @@ pub async fn get_recent_logs(db: &SqlitePool, limit: i64) -> anyhow::Result<Vec<
     .await?;
     Ok(logs)
 }
+
+/// 解析日志中的逆操作与前置条件（兼容未保存逆操作的旧日志）
+fn inverse_of(log: &OperationLog) -> anyhow::Result<Option<(InverseOp, Precondition)>> {
+    if let Some(inverse) = log.inverse.as_deref() {
+        let inverse: InverseOp = serde_json::from_str(inverse)?;
+        let precondition = log
+            .precondition
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()?
+            .unwrap_or_default();
+        return Ok(Some((inverse, precondition)));
+    }
+
+    match log.new_path.as_deref() {
+        Some(new_path) if RELOCATING_ACTIONS.contains(&log.action.as_str()) => {
+            let entry = JournalEntry::relocation(
+                &log.action,
+                log.file_id.as_deref(),
+                &log.old_path,
+                new_path,
+            );
+            Ok(entry.inverse.map(|inverse| (inverse, entry.precondition)))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// 撤销一条操作日志：检查前置条件后执行逆操作，并标记日志为已撤销
+pub async fn undo_operation(db: &SqlitePool, log_id: &str) -> anyhow::Result<OperationLog> {
+    let log: OperationLog = sqlx::query_as("SELECT * FROM operation_logs WHERE id = ?")
+        .bind(log_id)
+        .fetch_one(db)
+        .await?;
+
+    if log.undone_at.is_some() {
+        return Err(anyhow::anyhow!("Operation has already been undone"));
+    }
+
+    let (inverse, precondition) = inverse_of(&log)?
+        .ok_or_else(|| anyhow::anyhow!("Operation '{}' cannot be undone", log.action))?;
+
+    precondition.check().await?;
+    apply_inverse(db, log.file_id.as_deref(), &inverse).await?;
+
+    let undone_at = Utc::now();
+    sqlx::query("UPDATE operation_logs SET undone_at = ? WHERE id = ?")
+        .bind(undone_at)
+        .bind(log_id)
+        .execute(db)
+        .await?;
+
+    Ok(OperationLog {
+        undone_at: Some(undone_at),
+        ..log
+    })
+}
+
+/// 执行逆操作并同步数据库
+async fn apply_inverse(
+    db: &SqlitePool,
+    file_id: Option<&str>,
+    inverse: &InverseOp,
+) -> anyhow::Result<()> {
+    match inverse {
+        InverseOp::Move { from, to } => {
+            if let Some(parent) = Path::new(to).parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            if from != to && from.to_lowercase() == to.to_lowercase() {
+                // 大小写不敏感的文件系统上需经临时名中转
+                let temp = Path::new(from).with_file_name(format!(".{}.cine-undo", Uuid::new_v4()));
+                tokio::fs::rename(from, &temp).await?;
+                tokio::fs::rename(&temp, to).await?;
+            } else {
+                tokio::fs::rename(from, to).await?;
+            }
+
+            if let Some(file_id) = file_id {
+                let name = Path::new(to)
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .ok_or_else(|| anyhow::anyhow!("Invalid target path"))?;
+                sqlx::query(
+                    "UPDATE media_files SET path = ?, name = ?, updated_at = ? WHERE id = ?",
+                )
+                .bind(to)
+                .bind(name)
+                .bind(Utc::now())
+                .bind(file_id)
+                .execute(db)
+                .await?;
+            }
+        }
+        InverseOp::DeleteFile { path } => {
+            tokio::fs::remove_file(path).await?;
+        }
+        InverseOp::CreateDir { path } => {
+            tokio::fs::create_dir_all(path).await?;
+        }
+    }
+    Ok(())
+}
+
+/// 整批撤销中单条日志的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchUndoStatus {
+    /// 预演：可以撤销
+    Ready,
+    /// 预演：前置条件不满足，将会失败
+    Blocked,
+    /// 已撤销
+    Undone,
+    /// 撤销失败
+    Failed,
+    /// 已撤销过或不可撤销，跳过
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BatchUndoItem {
+    pub log_id: String,
+    pub action: String,
+    pub old_path: String,
+    pub new_path: Option<String>,
+    pub status: BatchUndoStatus,
+    pub error: Option<String>,
+}
+
+/// 整批撤销报告（预演或实际执行）
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BatchUndoReport {
+    pub batch_id: String,
+    pub dry_run: bool,
+    pub total: usize,
+    pub undone: usize,
+    pub blocked: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    /// 按撤销顺序（与原操作相反）排列
+    pub items: Vec<BatchUndoItem>,
+}
+
+impl BatchUndoReport {
+    fn new(batch_id: &str, dry_run: bool, items: Vec<BatchUndoItem>) -> Self {
+        let count = |status| items.iter().filter(|i| i.status == status).count();
+        Self {
+            batch_id: batch_id.to_string(),
+            dry_run,
+            total: items.len(),
+            undone: count(BatchUndoStatus::Undone),
+            blocked: count(BatchUndoStatus::Blocked),
+            failed: count(BatchUndoStatus::Failed),
+            skipped: count(BatchUndoStatus::Skipped),
+            items,
+        }
+    }
+}
+
+/// 获取某批次的操作日志，按撤销顺序（最新在前）排列
+pub async fn get_batch_logs(db: &SqlitePool, batch_id: &str) -> anyhow::Result<Vec<OperationLog>> {
+    let logs = sqlx::query_as::<_, OperationLog>(
+        "SELECT * FROM operation_logs WHERE batch_id = ? ORDER BY created_at DESC, rowid DESC",
+    )
+    .bind(batch_id)
+    .fetch_all(db)
+    .await?;
+    Ok(logs)
+}
+
+fn batch_item(log: &OperationLog, status: BatchUndoStatus, error: Option<String>) -> BatchUndoItem {
+    BatchUndoItem {
+        log_id: log.id.clone(),
+        action: log.action.clone(),
+        old_path: log.old_path.clone(),
+        new_path: log.new_path.clone(),
+        status,
+        error,
+    }
+}
+
+/// 预演整批撤销：按相反顺序模拟执行，报告每条日志能否撤销
+pub async fn preview_batch_undo(
+    db: &SqlitePool,
+    batch_id: &str,
+) -> anyhow::Result<BatchUndoReport> {
+    let logs = get_batch_logs(db, batch_id).await?;
+    if logs.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No operations found for batch {}",
+            batch_id
+        ));
+    }
+
+    let mut overlay = PathOverlay::default();
+    let mut items = Vec::with_capacity(logs.len());
+    for log in &logs {
+        if log.undone_at.is_some() {
+            items.push(batch_item(
+                log,
+                BatchUndoStatus::Skipped,
+                Some("Already undone".to_string()),
+            ));
+            continue;
+        }
+        let Some((inverse, precondition)) = inverse_of(log)? else {
+            items.push(batch_item(
+                log,
+                BatchUndoStatus::Skipped,
+                Some(format!("Operation '{}' cannot be undone", log.action)),
+            ));
+            continue;
+        };
+        match precondition.check_with(&overlay).await {
+            Ok(()) => {
+                overlay.apply(&inverse);
+                items.push(batch_item(log, BatchUndoStatus::Ready, None));
+            }
+            Err(e) => items.push(batch_item(
+                log,
+                BatchUndoStatus::Blocked,
+                Some(e.to_string()),
+            )),
+        }
+    }
+
+    Ok(BatchUndoReport::new(batch_id, true, items))
+}
+
+/// 整批撤销：按相反顺序逐条撤销，单条失败不影响其余条目
+pub async fn undo_batch(
+    db: &SqlitePool,
+    batch_id: &str,
+    mut ctx: crate::services::task_queue::TaskContext,
+) -> anyhow::Result<BatchUndoReport> {
+    let logs = get_batch_logs(db, batch_id).await?;
+    let total = logs.len().max(1);
+    let mut items = Vec::with_capacity(logs.len());
+
+    for (index, log) in logs.iter().enumerate() {
+        if ctx.check_pause().await {
+            return Err(anyhow::anyhow!("Undo task cancelled"));
+        }
+
+        let item = if log.undone_at.is_some() {
+            batch_item(
+                log,
+                BatchUndoStatus::Skipped,
+                Some("Already undone".to_string()),
+            )
+        } else if matches!(inverse_of(log), Ok(None)) {
+            batch_item(
+                log,
+                BatchUndoStatus::Skipped,
+                Some(format!("Operation '{}' cannot be undone", log.action)),
+            )
+        } else {
+            match undo_operation(db, &log.id).await {
+                Ok(_) => batch_item(log, BatchUndoStatus::Undone, None),
+                Err(e) => {
+                    tracing::error!("Failed to undo operation {}: {}", log.id, e);
+                    batch_item(log, BatchUndoStatus::Failed, Some(e.to_string()))
+                }
+            }
+        };
+        items.push(item);
+
+        let completed = index + 1;
+        ctx.report_progress(
+            (completed as f64 / total as f64) * 100.0,
+            Some(&format!("Undoing {}/{} operations", completed, total)),
+        )
+        .await;
+    }
+
+    Ok(BatchUndoReport::new(batch_id, false, items))
+}
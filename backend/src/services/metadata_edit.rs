@@ -0,0 +1,349 @@
+//! 元数据人工编辑、字段锁与历史
+//!
+//! `metadata` 中的单个字段可人工修改并锁定。重新刮削时锁定字段保留原值、其余字段取新结果，
+//! 本次差异（含因锁定未覆盖的字段）连同旧版本记入 metadata_history；人工修改与回滚同样先保存旧版本，
+//! 任一历史版本都可回滚。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+
+pub const SOURCE_EDIT: &str = "edit";
+pub const SOURCE_SCRAPE: &str = "scrape";
+pub const SOURCE_REVERT: &str = "revert";
+
+/// 可人工编辑与锁定的字段
+pub const EDITABLE_FIELDS: [&str; 12] = [
+    "title",
+    "original_title",
+    "overview",
+    "year",
+    "release_date",
+    "rating",
+    "poster_url",
+    "backdrop_url",
+    "episode_title",
+    "episode_overview",
+    "episode_air_date",
+    "episode_still_url",
+];
+
+/// 单个字段的变化
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+    /// 字段已锁定，新值未写入
+    pub locked: bool,
+}
+
+/// 文件当前的元数据与字段锁
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MetadataState {
+    pub file_id: String,
+    pub metadata: Value,
+    pub locked_fields: Vec<String>,
+}
+
+/// 一条历史版本：`metadata` / `locked_fields` 为本次变化之前的内容
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HistoryEntry {
+    pub id: i64,
+    pub file_id: String,
+    /// edit / scrape / revert
+    pub source: String,
+    pub metadata: Value,
+    pub locked_fields: Vec<String>,
+    pub changes: Vec<FieldChange>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(FromRow)]
+struct HistoryRow {
+    id: i64,
+    file_id: String,
+    source: String,
+    metadata: String,
+    locked_fields: Option<String>,
+    changes: String,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<HistoryRow> for HistoryEntry {
+    type Error = anyhow::Error;
+
+    fn try_from(row: HistoryRow) -> anyhow::Result<Self> {
+        Ok(Self {
+            id: row.id,
+            file_id: row.file_id,
+            source: row.source,
+            metadata: serde_json::from_str(&row.metadata)?,
+            locked_fields: parse_locks(row.locked_fields.as_deref()),
+            changes: serde_json::from_str(&row.changes)?,
+            created_at: row.created_at,
+        })
+    }
+}
+
+/// 检查人工提交的字段：只允许可编辑字段，年份为整数、评分为数字、其余为字符串，均可为 null
+pub fn validate_fields(fields: &Map<String, Value>) -> Result<(), String> {
+    if fields.is_empty() {
+        return Err("fields is empty".to_string());
+    }
+    for (field, value) in fields {
+        if !EDITABLE_FIELDS.contains(&field.as_str()) {
+            return Err(format!("field is not editable: {field}"));
+        }
+        let valid = match field.as_str() {
+            _ if value.is_null() => true,
+            "year" => value.as_u64().is_some(),
+            "rating" => value.is_number(),
+            _ => value.is_string(),
+        };
+        if !valid {
+            return Err(format!("invalid value for {field}"));
+        }
+    }
+    Ok(())
+}
+
+/// 检查字段锁名称
+pub fn validate_lock_names(fields: &[String]) -> Result<(), String> {
+    match fields
+        .iter()
+        .find(|field| !EDITABLE_FIELDS.contains(&field.as_str()))
+    {
+        Some(field) => Err(format!("field is not editable: {field}")),
+        None => Ok(()),
+    }
+}
+
+/// 可编辑字段中 `old` 与 `new` 不同的字段；`locked` 中的字段标记为未覆盖
+pub fn diff(old: &Value, new: &Value, locked: &[String]) -> Vec<FieldChange> {
+    EDITABLE_FIELDS
+        .iter()
+        .filter_map(|field| {
+            let old_value = old.get(*field).cloned().unwrap_or(Value::Null);
+            let new_value = new.get(*field).cloned().unwrap_or(Value::Null);
+            (old_value != new_value).then(|| FieldChange {
+                field: field.to_string(),
+                old: old_value,
+                new: new_value,
+                locked: locked.iter().any(|locked| locked == field),
+            })
+        })
+        .collect()
+}
+
+pub async fn get(db: &SqlitePool, file_id: &str) -> anyhow::Result<Option<MetadataState>> {
+    let row: Option<(Option<String>, Option<String>)> =
+        sqlx::query_as("SELECT metadata, locked_fields FROM media_files WHERE id = ?")
+            .bind(file_id)
+            .fetch_optional(db)
+            .await?;
+    Ok(row.map(|(metadata, locked_fields)| MetadataState {
+        file_id: file_id.to_string(),
+        metadata: metadata
+            .and_then(|metadata| serde_json::from_str(&metadata).ok())
+            .unwrap_or_else(|| Value::Object(Map::new())),
+        locked_fields: parse_locks(locked_fields.as_deref()),
+    }))
+}
+
+/// 人工修改字段；`lock` 为真时同时锁定这些字段。返回 None 表示文件不存在
+pub async fn edit(
+    db: &SqlitePool,
+    file_id: &str,
+    fields: &Map<String, Value>,
+    lock: bool,
+) -> anyhow::Result<Option<MetadataState>> {
+    let Some(current) = get(db, file_id).await? else {
+        return Ok(None);
+    };
+    let mut metadata = current.metadata.clone();
+    let object = metadata
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("metadata of {file_id} is not an object"))?;
+    for (field, value) in fields {
+        object.insert(field.clone(), value.clone());
+    }
+    let mut locked_fields = current.locked_fields.clone();
+    if lock {
+        locked_fields.extend(fields.keys().cloned());
+    }
+    let locked_fields = normalize_locks(locked_fields);
+
+    let changes = diff(&current.metadata, &metadata, &[]);
+    if changes.is_empty() && locked_fields == current.locked_fields {
+        return Ok(Some(current));
+    }
+    record(db, &current, SOURCE_EDIT, &changes).await?;
+    save(db, file_id, &metadata, &locked_fields).await.map(Some)
+}
+
+/// 加锁 / 解锁字段（不改动元数据，不记历史）
+pub async fn set_locks(
+    db: &SqlitePool,
+    file_id: &str,
+    lock: &[String],
+    unlock: &[String],
+) -> anyhow::Result<Option<MetadataState>> {
+    let Some(current) = get(db, file_id).await? else {
+        return Ok(None);
+    };
+    let locked_fields = normalize_locks(
+        current
+            .locked_fields
+            .iter()
+            .chain(lock)
+            .filter(|field| !unlock.contains(field))
+            .cloned()
+            .collect(),
+    );
+    save(db, file_id, &current.metadata, &locked_fields)
+        .await
+        .map(Some)
+}
+
+/// 重新刮削时合并新详情：锁定字段改回原值，有差异时把旧版本与差异记入历史
+///
+/// 文件此前没有元数据（首次识别）时不做处理。返回本次差异。
+pub async fn merge_scrape(
+    db: &SqlitePool,
+    file_id: &str,
+    details: &mut Value,
+) -> anyhow::Result<Vec<FieldChange>> {
+    let Some(current) = get(db, file_id).await? else {
+        return Ok(Vec::new());
+    };
+    if current
+        .metadata
+        .as_object()
+        .is_none_or(|object| object.is_empty())
+    {
+        return Ok(Vec::new());
+    }
+
+    let changes = diff(&current.metadata, details, &current.locked_fields);
+    if let Some(object) = details.as_object_mut() {
+        for field in &current.locked_fields {
+            match current.metadata.get(field) {
+                Some(value) => object.insert(field.clone(), value.clone()),
+                None => object.remove(field),
+            };
+        }
+    }
+    if !changes.is_empty() {
+        record(db, &current, SOURCE_SCRAPE, &changes).await?;
+    }
+    Ok(changes)
+}
+
+/// 文件的历史版本，新的在前
+pub async fn history(
+    db: &SqlitePool,
+    file_id: &str,
+    limit: i64,
+    offset: i64,
+) -> anyhow::Result<Vec<HistoryEntry>> {
+    let rows = sqlx::query_as::<_, HistoryRow>(
+        "SELECT * FROM metadata_history WHERE file_id = ? ORDER BY id DESC LIMIT ? OFFSET ?",
+    )
+    .bind(file_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(db)
+    .await?;
+    rows.into_iter().map(HistoryEntry::try_from).collect()
+}
+
+/// 回滚到某个历史版本（元数据与字段锁一并恢复），回滚前的内容同样记入历史。
+/// 返回 None 表示文件或历史版本不存在
+pub async fn revert(
+    db: &SqlitePool,
+    file_id: &str,
+    history_id: i64,
+) -> anyhow::Result<Option<MetadataState>> {
+    let row = sqlx::query_as::<_, HistoryRow>(
+        "SELECT * FROM metadata_history WHERE id = ? AND file_id = ?",
+    )
+    .bind(history_id)
+    .bind(file_id)
+    .fetch_optional(db)
+    .await?;
+    let Some(entry) = row.map(HistoryEntry::try_from).transpose()? else {
+        return Ok(None);
+    };
+    let Some(current) = get(db, file_id).await? else {
+        return Ok(None);
+    };
+    let changes = diff(&current.metadata, &entry.metadata, &[]);
+    record(db, &current, SOURCE_REVERT, &changes).await?;
+    save(db, file_id, &entry.metadata, &entry.locked_fields)
+        .await
+        .map(Some)
+}
+
+async fn record(
+    db: &SqlitePool,
+    previous: &MetadataState,
+    source: &str,
+    changes: &[FieldChange],
+) -> anyhow::Result<()> {
+    sqlx::query(
+        "INSERT INTO metadata_history (file_id, source, metadata, locked_fields, changes, created_at)
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&previous.file_id)
+    .bind(source)
+    .bind(serde_json::to_string(&previous.metadata)?)
+    .bind(serde_json::to_string(&previous.locked_fields)?)
+    .bind(serde_json::to_string(changes)?)
+    .bind(Utc::now())
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+async fn save(
+    db: &SqlitePool,
+    file_id: &str,
+    metadata: &Value,
+    locked_fields: &[String],
+) -> anyhow::Result<MetadataState> {
+    sqlx::query(
+        "UPDATE media_files SET metadata = ?, locked_fields = ?, updated_at = ? WHERE id = ?",
+    )
+    .bind(serde_json::to_string(metadata)?)
+    .bind(
+        (!locked_fields.is_empty())
+            .then(|| serde_json::to_string(locked_fields))
+            .transpose()?,
+    )
+    .bind(Utc::now())
+    .bind(file_id)
+    .execute(db)
+    .await?;
+    Ok(MetadataState {
+        file_id: file_id.to_string(),
+        metadata: metadata.clone(),
+        locked_fields: locked_fields.to_vec(),
+    })
+}
+
+fn parse_locks(raw: Option<&str>) -> Vec<String> {
+    raw.and_then(|raw| serde_json::from_str(raw).ok())
+        .unwrap_or_default()
+}
+
+/// 去重并按可编辑字段的顺序排列
+fn normalize_locks(fields: Vec<String>) -> Vec<String> {
+    EDITABLE_FIELDS
+        .iter()
+        .filter(|field| fields.iter().any(|locked| locked == *field))
+        .map(|field| field.to_string())
+        .collect()
+}
@@ -0,0 +1,1323 @@
+//! 元数据源抽象：TMDb、Bangumi 与 WASM 插件统一实现 [`MetadataProvider`]
+//!
+//! 识别流程按媒体库（监控目录）配置的数据源列表并发查询，再统一排序。
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use utoipa::ToSchema;
+
+use crate::services::identify::{self, IdentifyCandidate, RuntimeSettings};
+use crate::services::plugin::{PluginInfo, PluginManager, PluginSearchQuery, CAPABILITY_METADATA};
+use crate::services::provider_cache::CachedProvider;
+use crate::services::{collections, episode_map, people, provider_guard, scraper};
+
+pub type ProviderFuture<'a, T> = Pin<Box<dyn Future<Output = anyhow::Result<T>> + Send + 'a>>;
+
+/// 插件数据源 id 前缀（`plugin:<插件 id>`）
+pub const PLUGIN_PREFIX: &str = "plugin:";
+
+/// 未配置语言偏好时使用的元数据语言
+pub const DEFAULT_LANGUAGE: &str = "zh-CN";
+
+/// TMDb 未翻译单集的占位标题（`第 3 集`、`Episode 3`）
+static PLACEHOLDER_EPISODE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^(episode\s*\d+|第\s*\d+\s*[集话話])$").unwrap());
+
+/// 搜索请求
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProviderQuery {
+    pub title: String,
+    pub year: Option<u32>,
+    /// movie 或 tv
+    pub media_type: String,
+}
+
+/// 单集信息
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ProviderEpisode {
+    pub season_number: Option<u32>,
+    pub episode_number: u32,
+    pub absolute_number: Option<u32>,
+    pub name: Option<String>,
+    pub overview: Option<String>,
+    pub air_date: Option<String>,
+    pub still_url: Option<String>,
+    /// 时长（分钟）
+    pub runtime: Option<u32>,
+    /// 评分（0~10）
+    pub rating: Option<f64>,
+}
+
+/// 图片资源
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ProviderArtwork {
+    pub poster_url: Option<String>,
+    pub backdrop_url: Option<String>,
+}
+
+impl ProviderArtwork {
+    /// 从标准化详情中提取海报与背景图
+    pub fn from_details(details: &Value) -> Self {
+        Self {
+            poster_url: details
+                .get("poster_url")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            backdrop_url: details
+                .get("backdrop_url")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+        }
+    }
+}
+
+/// 数据源描述（供前端配置媒体库数据源）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProviderInfo {
+    pub id: String,
+    pub name: String,
+    /// builtin 或 plugin
+    pub kind: String,
+}
+
+/// 元数据源：搜索、详情、剧集列表、图片
+pub trait MetadataProvider: Send + Sync {
+    /// 候选结果与应用选择中使用的 provider 标识
+    fn id(&self) -> &str;
+
+    fn name(&self) -> &str;
+
+    fn kind(&self) -> &str {
+        "builtin"
+    }
+
+    /// 元数据语言回退链；为空表示数据源不区分语言
+    fn languages(&self) -> &[String] {
+        &[]
+    }
+
+    fn search<'a>(&'a self, query: &'a ProviderQuery)
+        -> ProviderFuture<'a, Vec<IdentifyCandidate>>;
+
+    /// 返回标准化详情（title/overview/poster_url/year 等字段）
+    fn details<'a>(
+        &'a self,
+        external_id: &'a str,
+        media_type: &'a str,
+    ) -> ProviderFuture<'a, Value>;
+
+    /// 剧集列表；`season` 为空时返回全部正片
+    fn episodes<'a>(
+        &'a self,
+        external_id: &'a str,
+        season: Option<u32>,
+    ) -> ProviderFuture<'a, Vec<ProviderEpisode>>;
+
+    fn artwork<'a>(
+        &'a self,
+        external_id: &'a str,
+        media_type: &'a str,
+    ) -> ProviderFuture<'a, ProviderArtwork> {
+        Box::pin(async move {
+            let details = self.details(external_id, media_type).await?;
+            Ok(ProviderArtwork::from_details(&details))
+        })
+    }
+
+    /// 电影系列详情（名称、简介、图片与 `parts` 作品列表）；数据源不支持系列时返回 `None`
+    fn collection<'a>(&'a self, _external_id: &'a str) -> ProviderFuture<'a, Option<Value>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            id: self.id().to_string(),
+            name: self.name().to_string(),
+            kind: self.kind().to_string(),
+        }
+    }
+}
+
+/// TMDb 数据源（需要 API Key）
+pub struct TmdbProvider {
+    client: Client,
+    api_key: String,
+    languages: Vec<String>,
+}
+
+impl TmdbProvider {
+    pub fn new(client: Client, api_key: impl Into<String>) -> Self {
+        Self {
+            client,
+            api_key: api_key.into(),
+            languages: vec![DEFAULT_LANGUAGE.to_string()],
+        }
+    }
+
+    /// 设置语言回退链，首个语言用于搜索与详情请求
+    pub fn with_languages(mut self, languages: Vec<String>) -> Self {
+        if !languages.is_empty() {
+            self.languages = languages;
+        }
+        self
+    }
+
+    fn primary_language(&self) -> &str {
+        self.languages
+            .first()
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_LANGUAGE)
+    }
+
+    async fn search_candidates(
+        &self,
+        query: &ProviderQuery,
+    ) -> anyhow::Result<Vec<IdentifyCandidate>> {
+        let language = Some(self.primary_language());
+        if query.media_type == "tv" {
+            Ok(scraper::search_tv_tmdb(
+                &self.client,
+                &query.title,
+                query.year,
+                &self.api_key,
+                language,
+            )
+            .await?
+            .into_iter()
+            .map(|show| IdentifyCandidate {
+                provider: "tmdb".to_string(),
+                external_id: show.tmdb_id.unwrap_or_default().to_string(),
+                media_type: "tv".to_string(),
+                title: show.name.clone(),
+                original_title: show.original_name.clone(),
+                year: show
+                    .first_air_date
+                    .as_deref()
+                    .and_then(|s| s.split('-').next())
+                    .and_then(|s| s.parse::<u32>().ok()),
+                score: 0.0,
+                overview: show.overview.clone(),
+                poster_url: show.poster_url.clone(),
+                backdrop_url: show.backdrop_url.clone(),
+                alternative_titles: Vec::new(),
+                metadata: serde_json::to_value(show).unwrap_or_default(),
+            })
+            .collect())
+        } else {
+            Ok(scraper::search_movie_tmdb(
+                &self.client,
+                &query.title,
+                query.year,
+                &self.api_key,
+                language,
+            )
+            .await?
+            .into_iter()
+            .map(|movie| IdentifyCandidate {
+                provider: "tmdb".to_string(),
+                external_id: movie.tmdb_id.unwrap_or_default().to_string(),
+                media_type: "movie".to_string(),
+                title: movie.title.clone(),
+                original_title: movie.original_title.clone(),
+                year: movie.year,
+                score: 0.0,
+                overview: movie.overview.clone(),
+                poster_url: movie.poster_url.clone(),
+                backdrop_url: movie.backdrop_url.clone(),
+                alternative_titles: Vec::new(),
+                metadata: serde_json::to_value(movie).unwrap_or_default(),
+            })
+            .collect())
+        }
+    }
+
+    /// 详情连同各语言翻译、别名与演职员表一次取回，再按语言链回退标题与简介
+    async fn fetch_details(&self, external_id: &str, media_type: &str) -> anyhow::Result<Value> {
+        let (endpoint, credits) = if media_type == "tv" {
+            ("tv", "aggregate_credits")
+        } else {
+            ("movie", "credits")
+        };
+        let url = format!(
+            "{}/{endpoint}/{external_id}?api_key={}&language={}&append_to_response=alternative_titles,translations,external_ids,{credits}",
+            scraper::tmdb_api_base_url(),
+            self.api_key,
+            self.primary_language()
+        );
+        let payload: Value = provider_guard::send("tmdb", self.client.get(url))
+            .await?
+            .json()
+            .await?;
+        let mut details = identify::tmdb_details_from_payload(&payload, media_type);
+        localize_tmdb_details(&mut details, &payload, &self.languages);
+        people::apply_tmdb_credits(&mut details, &payload);
+        Ok(details)
+    }
+
+    /// 系列详情按首选语言请求，简介缺失时由后续语言补全
+    async fn fetch_collection(&self, external_id: &str) -> anyhow::Result<Option<Value>> {
+        let mut collection = None;
+        for language in &self.languages {
+            let url = format!(
+                "{}/collection/{external_id}?api_key={}&language={language}",
+                scraper::tmdb_api_base_url(),
+                self.api_key
+            );
+            let payload: Value = provider_guard::send("tmdb", self.client.get(url))
+                .await?
+                .json()
+                .await?;
+            let fetched = collections::collection_from_tmdb_payload(&payload);
+            let merged = collections::merge_collection_translation(collection, fetched);
+            let complete = collections::has_overview(&merged);
+            collection = Some(merged);
+            if complete {
+                break;
+            }
+        }
+        Ok(collection)
+    }
+
+    /// 按语言链逐个请求季信息，缺失或占位的单集标题、简介由后续语言补全
+    async fn fetch_season(
+        &self,
+        external_id: &str,
+        season: u32,
+    ) -> anyhow::Result<Vec<ProviderEpisode>> {
+        let mut episodes = self
+            .fetch_season_in(external_id, season, self.primary_language())
+            .await?;
+        for language in self.languages.iter().skip(1) {
+            if !episodes.iter().any(episode_needs_translation) {
+                break;
+            }
+            match self.fetch_season_in(external_id, season, language).await {
+                Ok(fallback) => merge_episode_translations(&mut episodes, &fallback),
+                Err(error) => {
+                    tracing::warn!(
+                        "TMDb season {season} of {external_id} in {language} failed: {}",
+                        error
+                    );
+                    break;
+                }
+            }
+        }
+        Ok(episodes)
+    }
+
+    async fn fetch_season_in(
+        &self,
+        external_id: &str,
+        season: u32,
+        language: &str,
+    ) -> anyhow::Result<Vec<ProviderEpisode>> {
+        let url = format!(
+            "{}/tv/{external_id}/season/{season}?api_key={}&language={language}",
+            scraper::tmdb_api_base_url(),
+            self.api_key
+        );
+        let payload: Value = provider_guard::send("tmdb", self.client.get(url))
+            .await?
+            .json()
+            .await?;
+        Ok(episodes_from_tmdb_season(&payload))
+    }
+
+    async fn fetch_episodes(
+        &self,
+        external_id: &str,
+        season: Option<u32>,
+    ) -> anyhow::Result<Vec<ProviderEpisode>> {
+        if let Some(season) = season {
+            return self.fetch_season(external_id, season).await;
+        }
+
+        let counts =
+            episode_map::fetch_tmdb_season_counts(&self.client, &self.api_key, external_id).await?;
+        let mut episodes = Vec::new();
+        for count in counts.iter().filter(|count| count.season > 0) {
+            let mut season_episodes = self.fetch_season(external_id, count.season).await?;
+            for episode in &mut season_episodes {
+                episode.absolute_number = episode.season_number.and_then(|season| {
+                    episode_map::season_to_absolute(&counts, season, episode.episode_number)
+                });
+            }
+            episodes.extend(season_episodes);
+        }
+        Ok(episodes)
+    }
+}
+
+impl MetadataProvider for TmdbProvider {
+    fn id(&self) -> &str {
+        "tmdb"
+    }
+
+    fn name(&self) -> &str {
+        "TMDb"
+    }
+
+    fn languages(&self) -> &[String] {
+        &self.languages
+    }
+
+    fn search<'a>(
+        &'a self,
+        query: &'a ProviderQuery,
+    ) -> ProviderFuture<'a, Vec<IdentifyCandidate>> {
+        Box::pin(self.search_candidates(query))
+    }
+
+    fn details<'a>(
+        &'a self,
+        external_id: &'a str,
+        media_type: &'a str,
+    ) -> ProviderFuture<'a, Value> {
+        Box::pin(self.fetch_details(external_id, media_type))
+    }
+
+    fn episodes<'a>(
+        &'a self,
+        external_id: &'a str,
+        season: Option<u32>,
+    ) -> ProviderFuture<'a, Vec<ProviderEpisode>> {
+        Box::pin(self.fetch_episodes(external_id, season))
+    }
+
+    fn collection<'a>(&'a self, external_id: &'a str) -> ProviderFuture<'a, Option<Value>> {
+        Box::pin(self.fetch_collection(external_id))
+    }
+}
+
+/// Bangumi 数据源（Token 可选）
+pub struct BangumiProvider {
+    client: Client,
+    token: Option<String>,
+    languages: Vec<String>,
+}
+
+impl BangumiProvider {
+    pub fn new(client: Client, token: Option<String>) -> Self {
+        Self {
+            client,
+            token,
+            languages: Vec::new(),
+        }
+    }
+
+    /// 设置语言回退链：决定标题取中文名还是原名
+    pub fn with_languages(mut self, languages: Vec<String>) -> Self {
+        self.languages = languages;
+        self
+    }
+
+    async fn fetch(&self, url: String) -> anyhow::Result<Value> {
+        let mut request = self.client.get(url).header("User-Agent", "cine/1.2");
+        if let Some(token) = self.token.as_deref() {
+            request = request.bearer_auth(token);
+        }
+        Ok(provider_guard::send("bgm", request).await?.json().await?)
+    }
+
+    async fn search_candidates(
+        &self,
+        query: &ProviderQuery,
+    ) -> anyhow::Result<Vec<IdentifyCandidate>> {
+        let is_tv = query.media_type == "tv";
+        let payload: Value = self
+            .fetch(format!(
+                "{}/search/subject/{}?type={}",
+                scraper::bangumi_api_base_url(),
+                urlencoding::encode(&query.title),
+                if is_tv { 2 } else { 1 }
+            ))
+            .await?;
+        let items = payload
+            .get("list")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(items
+            .into_iter()
+            .take(8)
+            .map(|item| IdentifyCandidate {
+                provider: "bgm".to_string(),
+                external_id: item
+                    .get("id")
+                    .and_then(Value::as_i64)
+                    .unwrap_or_default()
+                    .to_string(),
+                media_type: query.media_type.clone(),
+                title: item
+                    .get("name_cn")
+                    .and_then(Value::as_str)
+                    .filter(|value| !value.is_empty())
+                    .or_else(|| item.get("name").and_then(Value::as_str))
+                    .unwrap_or("Unknown")
+                    .to_string(),
+                original_title: item.get("name").and_then(Value::as_str).map(str::to_string),
+                year: item
+                    .get("date")
+                    .and_then(Value::as_str)
+                    .and_then(|value| value.split('-').next())
+                    .and_then(|value| value.parse::<u32>().ok()),
+                score: 0.0,
+                overview: item
+                    .get("summary")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                poster_url: item
+                    .get("images")
+                    .and_then(|images| images.get("large"))
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                backdrop_url: None,
+                alternative_titles: ["name", "name_cn"]
+                    .iter()
+                    .filter_map(|key| non_empty(item.get(*key)))
+                    .collect(),
+                metadata: item,
+            })
+            .collect())
+    }
+
+    async fn fetch_details(&self, external_id: &str, media_type: &str) -> anyhow::Result<Value> {
+        let payload: Value = self
+            .fetch(format!(
+                "{}/v0/subjects/{external_id}",
+                scraper::bangumi_api_base_url()
+            ))
+            .await?;
+        let mut details = identify::bangumi_details_from_payload(&payload, media_type);
+        localize_bangumi_details(&mut details, &payload, &self.languages);
+
+        // 演职员表单独请求，失败时不影响识别
+        let base = scraper::bangumi_api_base_url();
+        let (persons, characters) = futures::join!(
+            self.fetch(format!("{base}/v0/subjects/{external_id}/persons")),
+            self.fetch(format!("{base}/v0/subjects/{external_id}/characters")),
+        );
+        match (persons, characters) {
+            (Ok(persons), Ok(characters)) => {
+                people::apply_bangumi_credits(&mut details, &persons, &characters)
+            }
+            (Err(error), _) | (_, Err(error)) => {
+                tracing::warn!(
+                    "Failed to fetch Bangumi credits for {}: {}",
+                    external_id,
+                    error
+                )
+            }
+        }
+        Ok(details)
+    }
+
+    async fn fetch_episodes(
+        &self,
+        external_id: &str,
+        season: Option<u32>,
+    ) -> anyhow::Result<Vec<ProviderEpisode>> {
+        let payload: Value = self
+            .fetch(format!(
+                "{}/v0/episodes?subject_id={external_id}&type=0&limit=1000",
+                scraper::bangumi_api_base_url()
+            ))
+            .await?;
+        let mut episodes = episodes_from_bangumi(&payload);
+        // Bangumi 每个条目即一季，指定季号时直接标注
+        if let Some(season) = season {
+            for episode in &mut episodes {
+                episode.season_number = Some(season);
+            }
+        }
+        Ok(episodes)
+    }
+}
+
+impl MetadataProvider for BangumiProvider {
+    fn id(&self) -> &str {
+        "bgm"
+    }
+
+    fn name(&self) -> &str {
+        "Bangumi"
+    }
+
+    fn languages(&self) -> &[String] {
+        &self.languages
+    }
+
+    fn search<'a>(
+        &'a self,
+        query: &'a ProviderQuery,
+    ) -> ProviderFuture<'a, Vec<IdentifyCandidate>> {
+        Box::pin(self.search_candidates(query))
+    }
+
+    fn details<'a>(
+        &'a self,
+        external_id: &'a str,
+        media_type: &'a str,
+    ) -> ProviderFuture<'a, Value> {
+        Box::pin(self.fetch_details(external_id, media_type))
+    }
+
+    fn episodes<'a>(
+        &'a self,
+        external_id: &'a str,
+        season: Option<u32>,
+    ) -> ProviderFuture<'a, Vec<ProviderEpisode>> {
+        Box::pin(self.fetch_episodes(external_id, season))
+    }
+}
+
+/// WASM 插件数据源：`search` 为必需导出，`details`/`episodes`/`artwork` 为可选导出
+pub struct PluginProvider {
+    manager: Arc<PluginManager>,
+    info: PluginInfo,
+    id: String,
+}
+
+impl PluginProvider {
+    pub fn new(manager: Arc<PluginManager>, info: PluginInfo) -> Self {
+        let id = format!("{PLUGIN_PREFIX}{}", info.id);
+        Self { manager, info, id }
+    }
+
+    async fn call_json<T: serde::de::DeserializeOwned>(
+        &self,
+        function: &str,
+        input: Value,
+    ) -> anyhow::Result<T> {
+        let output = self
+            .manager
+            .call(&self.info.id, function, input.to_string())
+            .await?;
+        Ok(serde_json::from_str(&output)?)
+    }
+
+    async fn search_candidates(
+        &self,
+        query: &ProviderQuery,
+    ) -> anyhow::Result<Vec<IdentifyCandidate>> {
+        let result = self
+            .manager
+            .search(
+                &self.info.id,
+                PluginSearchQuery {
+                    keyword: query.title.clone(),
+                    year: query.year,
+                    media_type: query.media_type.clone(),
+                },
+            )
+            .await?;
+
+        Ok(result
+            .results
+            .into_iter()
+            .map(|item| IdentifyCandidate {
+                provider: self.id.clone(),
+                external_id: item.remote_id.clone(),
+                media_type: query.media_type.clone(),
+                title: item.title.clone(),
+                original_title: None,
+                year: item.year,
+                score: 0.0,
+                overview: None,
+                poster_url: item.poster_url.clone(),
+                backdrop_url: None,
+                alternative_titles: Vec::new(),
+                metadata: serde_json::to_value(&item).unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    async fn fetch_details(&self, external_id: &str, media_type: &str) -> anyhow::Result<Value> {
+        let mut details: Value = self
+            .call_json(
+                "details",
+                json!({ "remote_id": external_id, "media_type": media_type }),
+            )
+            .await?;
+        if let Some(object) = details.as_object_mut() {
+            object.insert("provider".to_string(), json!(self.id));
+            object
+                .entry("media_type")
+                .or_insert_with(|| json!(media_type));
+        }
+        Ok(details)
+    }
+}
+
+impl MetadataProvider for PluginProvider {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.info.name
+    }
+
+    fn kind(&self) -> &str {
+        "plugin"
+    }
+
+    fn search<'a>(
+        &'a self,
+        query: &'a ProviderQuery,
+    ) -> ProviderFuture<'a, Vec<IdentifyCandidate>> {
+        Box::pin(self.search_candidates(query))
+    }
+
+    fn details<'a>(
+        &'a self,
+        external_id: &'a str,
+        media_type: &'a str,
+    ) -> ProviderFuture<'a, Value> {
+        Box::pin(self.fetch_details(external_id, media_type))
+    }
+
+    fn episodes<'a>(
+        &'a self,
+        external_id: &'a str,
+        season: Option<u32>,
+    ) -> ProviderFuture<'a, Vec<ProviderEpisode>> {
+        Box::pin(self.call_json(
+            "episodes",
+            json!({ "remote_id": external_id, "season": season }),
+        ))
+    }
+
+    fn artwork<'a>(
+        &'a self,
+        external_id: &'a str,
+        media_type: &'a str,
+    ) -> ProviderFuture<'a, ProviderArtwork> {
+        Box::pin(async move {
+            match self
+                .call_json(
+                    "artwork",
+                    json!({ "remote_id": external_id, "media_type": media_type }),
+                )
+                .await
+            {
+                Ok(artwork) => Ok(artwork),
+                Err(_) => {
+                    let details = self.fetch_details(external_id, media_type).await?;
+                    Ok(ProviderArtwork::from_details(&details))
+                }
+            }
+        })
+    }
+}
+
+/// 已注册的数据源集合
+#[derive(Default, Clone)]
+pub struct ProviderRegistry {
+    providers: Vec<Arc<dyn MetadataProvider>>,
+}
+
+impl ProviderRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册内置数据源与声明了元数据能力的插件：配置了 Key 的 TMDb、Bangumi、`plugin:<id>`
+    pub async fn from_settings(
+        client: &Client,
+        settings: &RuntimeSettings,
+        plugins: &Arc<PluginManager>,
+    ) -> Self {
+        let mut registry = Self::new();
+        if let Some(api_key) = settings.tmdb_api_key.as_deref() {
+            registry.register(Arc::new(
+                TmdbProvider::new(client.clone(), api_key)
+                    .with_languages(settings.languages.clone()),
+            ));
+        }
+        registry.register(Arc::new(
+            BangumiProvider::new(client.clone(), settings.bgm_api_key.clone())
+                .with_languages(settings.languages.clone()),
+        ));
+        let mut loaded = plugins.list_plugins().await;
+        loaded.sort_by(|lhs, rhs| lhs.id.cmp(&rhs.id));
+        for info in loaded {
+            if info.has_capability(CAPABILITY_METADATA) {
+                registry.register(Arc::new(PluginProvider::new(plugins.clone(), info)));
+            }
+        }
+        registry
+    }
+
+    /// 注册数据源，同 id 覆盖旧实现
+    pub fn register(&mut self, provider: Arc<dyn MetadataProvider>) {
+        self.providers
+            .retain(|existing| existing.id() != provider.id());
+        self.providers.push(provider);
+    }
+
+    /// 为所有数据源套上响应缓存；`offline` 时只读缓存
+    pub fn with_cache(self, db: SqlitePool, offline: bool) -> Self {
+        Self {
+            providers: self
+                .providers
+                .into_iter()
+                .map(|provider| {
+                    Arc::new(CachedProvider::new(provider, db.clone(), offline))
+                        as Arc<dyn MetadataProvider>
+                })
+                .collect(),
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<Arc<dyn MetadataProvider>> {
+        self.providers
+            .iter()
+            .find(|provider| provider.id() == id)
+            .cloned()
+    }
+
+    pub fn list(&self) -> Vec<ProviderInfo> {
+        self.providers
+            .iter()
+            .map(|provider| provider.info())
+            .collect()
+    }
+
+    /// 按媒体库配置挑选数据源；未配置时使用全部，未注册的 id 会被忽略
+    pub fn select(&self, enabled: Option<&[String]>) -> Vec<Arc<dyn MetadataProvider>> {
+        match enabled {
+            None => self.providers.clone(),
+            Some(ids) => ids.iter().filter_map(|id| self.get(id)).collect(),
+        }
+    }
+}
+
+/// 注册表缓存键：语言链与离线模式
+type RegistryKey = (Vec<String>, bool);
+
+/// 一次任务（或一次请求）内共用的数据源注册表
+///
+/// 注册表依赖运行时设置（语言链、离线模式），按这两项缓存，同一任务处理多个文件时只构建一次。
+#[derive(Clone)]
+pub struct ProviderSession {
+    plugins: Arc<PluginManager>,
+    registries: Arc<tokio::sync::Mutex<HashMap<RegistryKey, ProviderRegistry>>>,
+}
+
+impl ProviderSession {
+    pub fn new(plugins: Arc<PluginManager>) -> Self {
+        Self {
+            plugins,
+            registries: Arc::default(),
+        }
+    }
+
+    /// 按设置取注册表（已套上响应缓存），首次使用时构建
+    pub async fn registry(
+        &self,
+        db: &SqlitePool,
+        client: &Client,
+        settings: &RuntimeSettings,
+    ) -> ProviderRegistry {
+        let key: RegistryKey = (settings.languages.clone(), settings.provider_offline);
+        let mut registries = self.registries.lock().await;
+        if let Some(registry) = registries.get(&key) {
+            return registry.clone();
+        }
+        let registry = ProviderRegistry::from_settings(client, settings, &self.plugins)
+            .await
+            .with_cache(db.clone(), settings.provider_offline);
+        registries.insert(key, registry.clone());
+        registry
+    }
+}
+
+/// 解析监控目录的 `providers` 列（JSON 字符串数组），空值表示全部启用
+pub fn parse_provider_list(raw: Option<&str>) -> Option<Vec<String>> {
+    let raw = raw?.trim();
+    if raw.is_empty() {
+        return None;
+    }
+    serde_json::from_str::<Vec<String>>(raw).ok()
+}
+
+/// 从 (目录, providers) 列表中找出包含该文件的最深目录的数据源配置
+pub fn match_library_providers(
+    folders: &[(String, Option<String>)],
+    file_path: &str,
+) -> Option<Vec<String>> {
+    folders
+        .iter()
+        .filter(|(folder, _)| Path::new(file_path).starts_with(folder))
+        .max_by_key(|(folder, _)| folder.len())
+        .and_then(|(_, providers)| parse_provider_list(providers.as_deref()))
+}
+
+/// 从 (目录, 类型) 列表中找出包含该文件的最深目录的媒体库类型
+pub fn match_library_type(folders: &[(String, Option<String>)], file_path: &str) -> Option<String> {
+    folders
+        .iter()
+        .filter(|(folder, _)| Path::new(file_path).starts_with(folder))
+        .max_by_key(|(folder, _)| folder.len())
+        .and_then(|(_, library_type)| library_type.clone())
+}
+
+/// 读取所有启用媒体库的 (目录, 类型)，供批量解析时逐个匹配
+pub async fn library_types(db: &SqlitePool) -> anyhow::Result<Vec<(String, Option<String>)>> {
+    Ok(
+        sqlx::query_as("SELECT path, library_type FROM watch_folders WHERE enabled = 1")
+            .fetch_all(db)
+            .await?,
+    )
+}
+
+/// 查询包含该文件的最深媒体库（监控目录）路径
+pub async fn library_root(db: &SqlitePool, file_path: &str) -> anyhow::Result<Option<String>> {
+    Ok(library_types(db)
+        .await?
+        .into_iter()
+        .map(|(folder, _)| folder)
+        .filter(|folder| Path::new(file_path).starts_with(folder))
+        .max_by_key(String::len))
+}
+
+/// 查询文件所属媒体库的类型（`anime` 库使用字幕组命名解析）
+pub async fn library_type(db: &SqlitePool, file_path: &str) -> anyhow::Result<Option<String>> {
+    Ok(match_library_type(&library_types(db).await?, file_path))
+}
+
+/// 查询文件所属媒体库（监控目录）配置的数据源列表
+pub async fn library_providers(
+    db: &SqlitePool,
+    file_path: &str,
+) -> anyhow::Result<Option<Vec<String>>> {
+    let folders: Vec<(String, Option<String>)> =
+        sqlx::query_as("SELECT path, providers FROM watch_folders WHERE enabled = 1")
+            .fetch_all(db)
+            .await?;
+    Ok(match_library_providers(&folders, file_path))
+}
+
+/// 解析语言列表：JSON 数组或以逗号 / 空白 / `→` 分隔的字符串，空值视为未配置
+pub fn parse_language_list(raw: Option<&str>) -> Option<Vec<String>> {
+    let raw = raw?.trim();
+    if raw.is_empty() {
+        return None;
+    }
+    let languages: Vec<String> = match serde_json::from_str::<Vec<String>>(raw) {
+        Ok(list) => list,
+        Err(_) => raw
+            .split(|ch: char| ch == ',' || ch == '→' || ch.is_whitespace())
+            .map(str::to_string)
+            .collect(),
+    };
+    let mut unique: Vec<String> = Vec::new();
+    for language in languages {
+        let language = language.trim().trim_matches(|ch| ch == '>' || ch == '-');
+        if !language.is_empty()
+            && !unique
+                .iter()
+                .any(|seen| seen.eq_ignore_ascii_case(language))
+        {
+            unique.push(language.to_string());
+        }
+    }
+    (!unique.is_empty()).then_some(unique)
+}
+
+/// 从 (目录, languages) 列表中找出包含该文件的最深目录的语言偏好
+pub fn match_library_languages(
+    folders: &[(String, Option<String>)],
+    file_path: &str,
+) -> Option<Vec<String>> {
+    folders
+        .iter()
+        .filter(|(folder, _)| Path::new(file_path).starts_with(folder))
+        .max_by_key(|(folder, _)| folder.len())
+        .and_then(|(_, languages)| parse_language_list(languages.as_deref()))
+}
+
+/// 查询文件所属媒体库（监控目录）配置的语言回退链
+pub async fn library_languages(
+    db: &SqlitePool,
+    file_path: &str,
+) -> anyhow::Result<Option<Vec<String>>> {
+    let folders: Vec<(String, Option<String>)> =
+        sqlx::query_as("SELECT path, languages FROM watch_folders WHERE enabled = 1")
+            .fetch_all(db)
+            .await?;
+    Ok(match_library_languages(&folders, file_path))
+}
+
+/// `zh-CN` 形式的语言标签是否匹配 TMDb 翻译条目（无地区时匹配该语言任意地区）
+fn translation_matches(tag: &str, translation: &Value) -> bool {
+    let mut parts = tag.splitn(2, ['-', '_']);
+    let language = parts.next().unwrap_or_default();
+    let region = parts.next();
+    let field = |key: &str| {
+        translation
+            .get(key)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+    };
+    field("iso_639_1").eq_ignore_ascii_case(language)
+        && region.is_none_or(|region| field("iso_3166_1").eq_ignore_ascii_case(region))
+}
+
+/// 按语言链回退 TMDb 标题与简介，并记录原始语言、各语言标题与别名
+///
+/// `payload` 需包含 `append_to_response=alternative_titles,translations` 的结果；
+/// 语言链均无翻译时，标题与简介回退到作品原始语言。
+pub fn localize_tmdb_details(details: &mut Value, payload: &Value, languages: &[String]) {
+    let translations: &[Value] = payload
+        .pointer("/translations/translations")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let original_language = payload.get("original_language").and_then(Value::as_str);
+    let translated = |tag: &str, keys: &[&str]| {
+        translations
+            .iter()
+            .filter(|translation| translation_matches(tag, translation))
+            .find_map(|translation| {
+                let data = translation.get("data")?;
+                keys.iter().find_map(|key| non_empty(data.get(*key)))
+            })
+    };
+    let pick = |keys: &[&str]| {
+        languages
+            .iter()
+            .map(String::as_str)
+            .chain(original_language)
+            .find_map(|tag| translated(tag, keys))
+    };
+    let title = pick(&["title", "name"]);
+    let overview = pick(&["overview"]);
+
+    let mut localized_titles = serde_json::Map::new();
+    for translation in translations {
+        let field = |key: &str| {
+            translation
+                .get(key)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+        };
+        let wanted = languages
+            .iter()
+            .any(|tag| translation_matches(tag, translation))
+            || original_language == Some(field("iso_639_1"));
+        let Some(name) = translation
+            .get("data")
+            .and_then(|data| non_empty(data.get("title")).or_else(|| non_empty(data.get("name"))))
+        else {
+            continue;
+        };
+        if wanted {
+            let tag = format!("{}-{}", field("iso_639_1"), field("iso_3166_1"));
+            localized_titles.entry(tag).or_insert(json!(name));
+        }
+    }
+
+    let alias_list = payload
+        .pointer("/alternative_titles/titles")
+        .or_else(|| payload.pointer("/alternative_titles/results"))
+        .and_then(Value::as_array);
+    let aliases: Vec<String> = alias_list
+        .into_iter()
+        .flatten()
+        .filter_map(|alias| non_empty(alias.get("title")))
+        .collect();
+
+    let Some(object) = details.as_object_mut() else {
+        return;
+    };
+    if let Some(title) = title {
+        object.insert("title".to_string(), json!(title));
+    }
+    let missing_overview = object
+        .get("overview")
+        .and_then(Value::as_str)
+        .is_none_or(str::is_empty);
+    if let Some(overview) = overview.filter(|_| missing_overview) {
+        object.insert("overview".to_string(), json!(overview));
+    }
+    object.insert("original_language".to_string(), json!(original_language));
+    object.insert(
+        "localized_titles".to_string(),
+        Value::Object(localized_titles),
+    );
+    object.insert("aliases".to_string(), json!(dedupe_titles(aliases, object)));
+}
+
+/// Bangumi 条目只有原名与中文名：语言链中中文优先则取中文名，日文优先则取原名；
+/// 别名来自 infobox 的「别名」
+pub fn localize_bangumi_details(details: &mut Value, payload: &Value, languages: &[String]) {
+    let name = non_empty(payload.get("name"));
+    let name_cn = non_empty(payload.get("name_cn"));
+    let title = languages.iter().find_map(|tag| {
+        let language = tag.split(['-', '_']).next().unwrap_or_default();
+        match language.to_ascii_lowercase().as_str() {
+            "zh" => name_cn.clone(),
+            "ja" => name.clone(),
+            _ => None,
+        }
+    });
+
+    let mut aliases = Vec::new();
+    for entry in payload
+        .get("infobox")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let key = entry.get("key").and_then(Value::as_str).unwrap_or_default();
+        if !matches!(key, "别名" | "中文名" | "英文名" | "日文名") {
+            continue;
+        }
+        match entry.get("value") {
+            Some(Value::String(value)) if !value.is_empty() => aliases.push(value.clone()),
+            Some(Value::Array(values)) => {
+                aliases.extend(values.iter().filter_map(|value| {
+                    non_empty(value.get("v")).or_else(|| non_empty(Some(value)))
+                }))
+            }
+            _ => {}
+        }
+    }
+
+    let mut localized_titles = serde_json::Map::new();
+    if let Some(name_cn) = &name_cn {
+        localized_titles.insert("zh-CN".to_string(), json!(name_cn));
+    }
+    if let Some(name) = &name {
+        localized_titles.insert("ja-JP".to_string(), json!(name));
+    }
+
+    let Some(object) = details.as_object_mut() else {
+        return;
+    };
+    if let Some(title) = title {
+        object.insert("title".to_string(), json!(title));
+    }
+    object.insert(
+        "localized_titles".to_string(),
+        Value::Object(localized_titles),
+    );
+    object.insert("aliases".to_string(), json!(dedupe_titles(aliases, object)));
+}
+
+/// 去重并剔除与主标题、原名相同的别名
+fn dedupe_titles(titles: Vec<String>, details: &serde_json::Map<String, Value>) -> Vec<String> {
+    let mut seen: Vec<String> = ["title", "original_title"]
+        .iter()
+        .filter_map(|key| details.get(*key).and_then(Value::as_str))
+        .map(str::to_lowercase)
+        .collect();
+    let mut unique = Vec::new();
+    for title in titles {
+        let key = title.to_lowercase();
+        if !seen.contains(&key) {
+            seen.push(key);
+            unique.push(title);
+        }
+    }
+    unique
+}
+
+/// 标准化详情中的其他语言标题与别名（不含主标题），供候选排序匹配
+pub fn alternative_titles(details: &Value) -> Vec<String> {
+    let title = details.get("title").and_then(Value::as_str);
+    let mut titles: Vec<String> = Vec::new();
+    let localized = details
+        .get("localized_titles")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(|map| map.values());
+    let aliases = details
+        .get("aliases")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten();
+    for value in localized.chain(aliases) {
+        if let Some(value) = value.as_str().filter(|value| !value.is_empty()) {
+            if Some(value) != title && !titles.iter().any(|seen| seen == value) {
+                titles.push(value.to_string());
+            }
+        }
+    }
+    titles
+}
+
+/// 为候选补充语言回退后的标题与别名（一次详情请求，只用于推荐候选）
+pub async fn localize_candidate(
+    provider: &dyn MetadataProvider,
+    candidate: &mut IdentifyCandidate,
+) {
+    match provider
+        .details(&candidate.external_id, &candidate.media_type)
+        .await
+    {
+        Ok(details) => apply_localized_details(candidate, &details),
+        Err(error) => tracing::debug!(
+            "{} localized details for {} failed: {}",
+            provider.name(),
+            candidate.external_id,
+            error
+        ),
+    }
+}
+
+/// 把语言回退后的详情套用到搜索候选：标题、缺失的简介与其他语言标题
+fn apply_localized_details(candidate: &mut IdentifyCandidate, details: &Value) {
+    if let Some(title) = non_empty(details.get("title")) {
+        if title != candidate.title {
+            candidate.alternative_titles.push(candidate.title.clone());
+        }
+        candidate.title = title;
+    }
+    if candidate.overview.as_deref().is_none_or(str::is_empty) {
+        candidate.overview = non_empty(details.get("overview"));
+    }
+    for title in alternative_titles(details) {
+        if title != candidate.title && !candidate.alternative_titles.contains(&title) {
+            candidate.alternative_titles.push(title);
+        }
+    }
+}
+
+/// 单集标题缺失、为占位标题或缺少简介时需要用后续语言补全
+pub fn episode_needs_translation(episode: &ProviderEpisode) -> bool {
+    episode
+        .name
+        .as_deref()
+        .is_none_or(|name| PLACEHOLDER_EPISODE_RE.is_match(name.trim()))
+        || episode.overview.is_none()
+}
+
+/// 用另一种语言的单集列表补全缺失的标题与简介（按季号、集号对应）
+pub fn merge_episode_translations(episodes: &mut [ProviderEpisode], fallback: &[ProviderEpisode]) {
+    for episode in episodes.iter_mut() {
+        let Some(other) = fallback.iter().find(|other| {
+            other.episode_number == episode.episode_number
+                && other.season_number == episode.season_number
+        }) else {
+            continue;
+        };
+        let placeholder = |name: &Option<String>| {
+            name.as_deref()
+                .is_none_or(|name| PLACEHOLDER_EPISODE_RE.is_match(name.trim()))
+        };
+        if placeholder(&episode.name) && !placeholder(&other.name) {
+            episode.name = other.name.clone();
+        }
+        if episode.overview.is_none() {
+            episode.overview = other.overview.clone();
+        }
+    }
+}
+
+/// TMDb `/tv/{id}/season/{n}` 载荷 → 单集列表
+pub fn episodes_from_tmdb_season(payload: &Value) -> Vec<ProviderEpisode> {
+    payload
+        .get("episodes")
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    Some(ProviderEpisode {
+                        season_number: item
+                            .get("season_number")
+                            .and_then(Value::as_u64)
+                            .map(|v| v as u32),
+                        episode_number: item.get("episode_number")?.as_u64()? as u32,
+                        absolute_number: None,
+                        name: non_empty(item.get("name")),
+                        overview: non_empty(item.get("overview")),
+                        air_date: non_empty(item.get("air_date")),
+                        still_url: item
+                            .get("still_path")
+                            .and_then(Value::as_str)
+                            .map(|path| format!("https://image.tmdb.org/t/p/w780{path}")),
+                        runtime: item
+                            .get("runtime")
+                            .and_then(Value::as_u64)
+                            .filter(|minutes| *minutes > 0)
+                            .map(|minutes| minutes as u32),
+                        rating: item
+                            .get("vote_average")
+                            .and_then(Value::as_f64)
+                            .filter(|rating| *rating > 0.0),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Bangumi `/v0/episodes` 载荷 → 正片单集列表（`sort` 作为绝对集数）
+pub fn episodes_from_bangumi(payload: &Value) -> Vec<ProviderEpisode> {
+    payload
+        .get("data")
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter(|item| item.get("type").and_then(Value::as_u64).unwrap_or(0) == 0)
+                .filter_map(|item| {
+                    let sort = item.get("sort")?.as_f64()? as u32;
+                    Some(ProviderEpisode {
+                        season_number: None,
+                        episode_number: item
+                            .get("ep")
+                            .and_then(Value::as_f64)
+                            .map(|ep| ep as u32)
+                            .unwrap_or(sort),
+                        absolute_number: Some(sort),
+                        name: non_empty(item.get("name_cn"))
+                            .or_else(|| non_empty(item.get("name"))),
+                        overview: non_empty(item.get("desc")),
+                        air_date: non_empty(item.get("airdate")),
+                        still_url: None,
+                        runtime: bangumi_runtime(item),
+                        rating: None,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Bangumi 单集时长：优先 `duration_seconds`，其次 `duration`（`00:24:00`）
+fn bangumi_runtime(item: &Value) -> Option<u32> {
+    let seconds = item
+        .get("duration_seconds")
+        .and_then(Value::as_u64)
+        .or_else(|| {
+            let duration = item.get("duration")?.as_str()?;
+            duration.split(':').try_fold(0u64, |total, part| {
+                Some(total * 60 + part.parse::<u64>().ok()?)
+            })
+        })
+        .filter(|seconds| *seconds > 0)?;
+    Some(((seconds + 30) / 60).max(1) as u32)
+}
+
+fn non_empty(value: Option<&Value>) -> Option<String> {
+    value
+        .and_then(Value::as_str)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
@@ -1,30 +1,48 @@
+pub mod ai_parser;
+pub mod anime_parser;
 pub mod cache;
+pub mod catalog;
+pub mod cjk;
+pub mod collections;
 pub mod dedupe;
 pub mod distributed;
 pub mod empty_dirs;
+pub mod episode_map;
+pub mod external_ids;
 pub mod file_ops;
 pub mod hasher;
 pub mod hasher_parallel;
 pub mod history;
+pub mod id_hints;
 pub mod identify;
 pub mod library_service;
 pub mod log;
+pub mod metadata_edit;
+pub mod metadata_provider;
 pub mod metrics;
 pub mod nfo;
+pub mod people;
 pub mod plugin;
 pub mod poster;
 pub mod progress_estimator;
 pub mod progress_hub;
+pub mod provider_cache;
+pub mod provider_guard;
 pub mod quality;
 pub mod queries;
+pub mod release_parser;
 pub mod renamer;
+pub mod reparse;
+pub mod review_queue;
 pub mod scanner;
 pub mod scheduler;
 pub mod scraper;
+pub mod sibling_apply;
 pub mod smart_cache;
 pub mod subtitle;
 pub mod task_executors;
 pub mod task_queue;
+pub mod title_aliases;
 pub mod trash;
 pub mod video;
 pub mod watcher;
@@ -35,6 +35,13 @@ pub async fn generate_nfo_file(
 
     let nfo_content = if media_type == "movie" {
         generate_movie_nfo(metadata)?
+    } else if metadata
+        .get("episode_number")
+        .and_then(|e| e.as_u64())
+        .is_some()
+    {
+        // 单集文件：生成 episodedetails（多集文件每集一个块）
+        generate_episode_nfo(metadata)?
     } else {
         generate_tvshow_nfo(metadata)?
     };
@@ -77,13 +84,16 @@ fn generate_movie_nfo(metadata: &Value) -> anyhow::Result<String> {
     <year>{}</year>
     <plot>{}</plot>
     <rating>{}</rating>
-    <tmdbid>{}</tmdbid>
+    <tmdbid>{}</tmdbid>{}{}{}
 </movie>"#,
         escape_xml(title),
         year,
         escape_xml(overview),
         rating,
-        tmdb_id
+        tmdb_id,
+        unique_ids_xml(metadata),
+        set_xml(metadata),
+        people_xml(metadata)
     );
 
     Ok(nfo)
@@ -120,18 +130,248 @@ fn generate_tvshow_nfo(metadata: &Value) -> anyhow::Result<String> {
     <premiered>{}</premiered>
     <plot>{}</plot>
     <rating>{}</rating>
-    <tmdbid>{}</tmdbid>
+    <tmdbid>{}</tmdbid>{}{}
 </tvshow>"#,
         escape_xml(name),
         first_air_date,
         escape_xml(overview),
         rating,
-        tmdb_id
+        tmdb_id,
+        unique_ids_xml(metadata),
+        people_xml(metadata)
     );
 
     Ok(nfo)
 }
 
+/// 生成剧集 NFO：文件包含的每一集输出一个 `<episodedetails>` 块
+///
+/// 多集信息优先取自 `episodes` 数组，缺失时单集使用 `episode_title` 等字段。
+fn generate_episode_nfo(metadata: &Value) -> anyhow::Result<String> {
+    let show_title = metadata
+        .get("title")
+        .or_else(|| metadata.get("name"))
+        .and_then(|t| t.as_str())
+        .unwrap_or("Unknown");
+    let season = metadata
+        .get("season_number")
+        .and_then(|s| s.as_u64())
+        .unwrap_or(1);
+    let first = metadata
+        .get("episode_number")
+        .and_then(|e| e.as_u64())
+        .ok_or_else(|| anyhow::anyhow!("Missing episode_number"))?;
+    let last = metadata
+        .get("episode_number_end")
+        .and_then(|e| e.as_u64())
+        .filter(|last| *last > first)
+        .unwrap_or(first);
+    let episodes = metadata.get("episodes").and_then(|e| e.as_array());
+    let tmdb_id = metadata
+        .get("tmdb_id")
+        .and_then(|id| id.as_u64())
+        .map(|id| id.to_string())
+        .unwrap_or_default();
+
+    let mut nfo = String::from(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
+    for number in first..=last {
+        let episode = episodes.and_then(|list| {
+            list.iter()
+                .find(|e| e.get("episode_number").and_then(|n| n.as_u64()) == Some(number))
+        });
+        let field = |key: &str, single_key: &str| -> String {
+            episode
+                .and_then(|e| e.get(key))
+                .or_else(|| {
+                    if first == last {
+                        metadata.get(single_key)
+                    } else {
+                        None
+                    }
+                })
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string()
+        };
+        let title = field("name", "episode_title");
+        let title = if title.is_empty() {
+            format!("Episode {}", number)
+        } else {
+            title
+        };
+
+        let number_field = |key: &str, single_key: &str| -> String {
+            episode
+                .and_then(|e| e.get(key))
+                .or_else(|| {
+                    if first == last {
+                        metadata.get(single_key)
+                    } else {
+                        None
+                    }
+                })
+                .and_then(|v| v.as_f64())
+                .map(|v| v.to_string())
+                .unwrap_or_default()
+        };
+
+        nfo.push_str(&format!(
+            r#"
+<episodedetails>
+    <title>{}</title>
+    <showtitle>{}</showtitle>
+    <season>{}</season>
+    <episode>{}</episode>
+    <plot>{}</plot>
+    <aired>{}</aired>
+    <runtime>{}</runtime>
+    <rating>{}</rating>
+    <thumb>{}</thumb>
+    <tmdbid>{}</tmdbid>{}
+</episodedetails>"#,
+            escape_xml(&title),
+            escape_xml(show_title),
+            season,
+            number,
+            escape_xml(&field("overview", "episode_overview")),
+            field("air_date", "episode_air_date"),
+            number_field("runtime", "episode_runtime"),
+            number_field("rating", "episode_rating"),
+            escape_xml(&field("still_url", "episode_still_url")),
+            tmdb_id,
+            unique_ids_xml(metadata)
+        ));
+    }
+
+    Ok(nfo)
+}
+
+/// 外部 ID：每个 ID 一个 `<uniqueid>`，匹配所用数据源的 ID 标为默认并排在最前
+fn unique_ids_xml(metadata: &Value) -> String {
+    let Some(ids) = metadata.get("external_ids").and_then(|ids| ids.as_object()) else {
+        return String::new();
+    };
+    let default = metadata
+        .get("provider")
+        .and_then(|p| p.as_str())
+        .unwrap_or("tmdb");
+    let mut entries: Vec<(&str, &str)> = ids
+        .iter()
+        .filter_map(|(kind, value)| {
+            value
+                .as_str()
+                .filter(|value| !value.is_empty())
+                .map(|value| (kind.as_str(), value))
+        })
+        .collect();
+    entries.sort_by_key(|(kind, _)| *kind != default);
+
+    entries
+        .into_iter()
+        .map(|(kind, value)| {
+            format!(
+                "\n    <uniqueid type=\"{}\"{}>{}</uniqueid>",
+                nfo_id_type(kind),
+                if kind == default {
+                    " default=\"true\""
+                } else {
+                    ""
+                },
+                escape_xml(value)
+            )
+        })
+        .collect()
+}
+
+/// Kodi / Jellyfin 使用的 uniqueid 类型名（Bangumi 插件为 `bangumi`）
+fn nfo_id_type(kind: &str) -> &str {
+    match kind {
+        "bgm" => "bangumi",
+        other => other,
+    }
+}
+
+/// 所属电影系列：`<set><name>..</name><overview>..</overview></set>`
+fn set_xml(metadata: &Value) -> String {
+    let Some(name) = metadata
+        .get("collection")
+        .and_then(|c| c.get("name"))
+        .and_then(|n| n.as_str())
+        .filter(|n| !n.is_empty())
+    else {
+        return String::new();
+    };
+    let overview = metadata
+        .get("collection")
+        .and_then(|c| c.get("overview"))
+        .and_then(|o| o.as_str())
+        .unwrap_or("");
+    format!(
+        r#"
+    <set>
+        <name>{}</name>
+        <overview>{}</overview>
+    </set>"#,
+        escape_xml(name),
+        escape_xml(overview)
+    )
+}
+
+/// 演职员：`cast` 输出为 `<actor>`，`crew` 中的导演输出为 `<director>`，编剧输出为 `<credits>`
+fn people_xml(metadata: &Value) -> String {
+    let text = |person: &Value, key: &str| {
+        person
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string()
+    };
+    let mut xml = String::new();
+
+    let cast = metadata.get("cast").and_then(|c| c.as_array());
+    for (index, actor) in cast.into_iter().flatten().enumerate() {
+        let name = text(actor, "name");
+        if name.is_empty() {
+            continue;
+        }
+        let order = actor
+            .get("order")
+            .and_then(|o| o.as_u64())
+            .unwrap_or(index as u64);
+        xml.push_str(&format!(
+            r#"
+    <actor>
+        <name>{}</name>
+        <role>{}</role>
+        <order>{}</order>
+        <thumb>{}</thumb>
+    </actor>"#,
+            escape_xml(&name),
+            escape_xml(&text(actor, "character")),
+            order,
+            escape_xml(&text(actor, "profile_url"))
+        ));
+    }
+
+    let crew = metadata.get("crew").and_then(|c| c.as_array());
+    let mut seen = Vec::new();
+    for person in crew.into_iter().flatten() {
+        let name = text(person, "name");
+        let tag = match text(person, "department").as_str() {
+            "Directing" => "director",
+            "Writing" => "credits",
+            _ => continue,
+        };
+        if name.is_empty() || seen.contains(&(tag, name.clone())) {
+            continue;
+        }
+        xml.push_str(&format!("\n    <{tag}>{}</{tag}>", escape_xml(&name)));
+        seen.push((tag, name));
+    }
+
+    xml
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename = "movie")]
 pub struct MovieNfo {
@@ -147,6 +387,49 @@ pub struct MovieNfo {
     pub fanart: Option<String>,
     pub tmdbid: Option<String>,
     pub id: Option<String>,
+    #[serde(default)]
+    pub imdbid: Option<String>,
+    /// Kodi/Jellyfin 的 `<uniqueid type="tmdb">12345</uniqueid>`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub uniqueid: Vec<UniqueId>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UniqueId {
+    #[serde(rename = "@type", default)]
+    pub kind: Option<String>,
+    #[serde(rename = "@default", default)]
+    pub default: Option<bool>,
+    #[serde(rename = "$text", default)]
+    pub value: String,
+}
+
+impl MovieNfo {
+    /// NFO 中声明的外部 ID（数据源小写，如 `tmdb`、`imdb`、`tvdb`），默认 uniqueid 排在最前
+    pub fn external_ids(&self) -> Vec<(String, String)> {
+        let mut unique: Vec<&UniqueId> = self.uniqueid.iter().collect();
+        unique.sort_by_key(|id| !id.default.unwrap_or(false));
+
+        let mut ids: Vec<(String, String)> = unique
+            .into_iter()
+            .filter_map(|id| {
+                let kind = id.kind.as_deref()?.trim().to_ascii_lowercase();
+                let value = id.value.trim();
+                (!kind.is_empty() && !value.is_empty()).then(|| (kind, value.to_string()))
+            })
+            .collect();
+        let mut push = |kind: &str, value: Option<&str>| {
+            if let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) {
+                if !ids.iter().any(|(k, v)| k == kind && v == value) {
+                    ids.push((kind.to_string(), value.to_string()));
+                }
+            }
+        };
+        push("tmdb", self.tmdbid.as_deref());
+        push("imdb", self.imdbid.as_deref());
+        push("imdb", self.id.as_deref().filter(|id| id.starts_with("tt")));
+        ids
+    }
 }
 
 /// 读取并解析 NFO 文件
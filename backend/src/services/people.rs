@@ -0,0 +1,407 @@
+//! 演职人员：从识别结果的 `cast` / `crew` 写入 people 与 credits，并缓存头像
+//!
+//! 识别结果中的演职员为数据源无关的统一格式：
+//! `cast` 每项含 `id`、`name`、`original_name`、`character`、`order`、`profile_url`，
+//! `crew` 每项含 `id`、`name`、`original_name`、`job`、`department`、`profile_url`。
+//! 人员以「数据源:人员 ID」为主键，同一人参与多部作品时共享同一条记录。
+
+use std::path::Path;
+
+use chrono::Utc;
+use futures::stream::{self, StreamExt};
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+
+use crate::models::{Person, PersonCredit, TitleCredit};
+use crate::services::catalog::{self, Level};
+use crate::services::poster;
+
+/// 每部作品保存的演员数上限（按数据源给出的顺序）
+const MAX_CAST: usize = 30;
+
+/// 同时下载的头像数
+const PROFILE_DOWNLOADS: usize = 4;
+
+const TMDB_PROFILE_BASE: &str = "https://image.tmdb.org/t/p/w185";
+
+const PERSON_FIELDS: &str =
+    "p.id, p.provider, p.external_id, p.name, p.original_name, p.profile_url, p.profile_path, \
+    (SELECT COUNT(DISTINCT COALESCE('m' || c.movie_id, 's' || c.show_id)) FROM credits c WHERE c.person_id = p.id) AS title_count, \
+    p.created_at, p.updated_at";
+
+fn text(value: Option<&Value>) -> Option<String> {
+    value
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn id_text(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::Number(number) => Some(number.to_string()),
+        value => text(Some(value)),
+    }
+}
+
+/// 职务对应的演职员表角色：导演与编剧之外的幕后人员不保存
+pub fn crew_role(department: &str, job: &str) -> Option<&'static str> {
+    match department {
+        "Directing" if job == "Director" => Some("director"),
+        "Writing" => Some("writer"),
+        _ => None,
+    }
+}
+
+/// 解析 TMDb `credits`（电影）或 `aggregate_credits`（剧集）并写入详情的 `cast` / `crew`
+///
+/// 剧集的汇总演职员表中，一人可饰演多个角色、担任多个职务，角色取首个，职务逐个展开。
+pub fn apply_tmdb_credits(details: &mut Value, payload: &Value) {
+    let Some(credits) = payload
+        .get("aggregate_credits")
+        .or_else(|| payload.get("credits"))
+    else {
+        return;
+    };
+    let profile = |person: &Value| {
+        text(person.get("profile_path")).map(|path| format!("{TMDB_PROFILE_BASE}{path}"))
+    };
+
+    let cast: Vec<Value> = credits
+        .get("cast")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .take(MAX_CAST)
+        .filter_map(|person| {
+            let character = text(person.get("character")).or_else(|| {
+                person
+                    .get("roles")
+                    .and_then(Value::as_array)
+                    .and_then(|roles| roles.iter().find_map(|role| text(role.get("character"))))
+            });
+            Some(json!({
+                "id": id_text(person.get("id"))?,
+                "name": text(person.get("name"))?,
+                "original_name": text(person.get("original_name")),
+                "character": character,
+                "order": person.get("order").and_then(Value::as_i64),
+                "profile_url": profile(person),
+            }))
+        })
+        .collect();
+
+    let mut crew = Vec::new();
+    for person in credits
+        .get("crew")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let (Some(id), Some(name)) = (id_text(person.get("id")), text(person.get("name"))) else {
+            continue;
+        };
+        let department = text(person.get("department")).unwrap_or_default();
+        let jobs: Vec<String> = match person.get("jobs").and_then(Value::as_array) {
+            Some(jobs) => jobs.iter().filter_map(|job| text(job.get("job"))).collect(),
+            None => text(person.get("job")).into_iter().collect(),
+        };
+        for job in jobs {
+            if crew_role(&department, &job).is_some() {
+                crew.push(json!({
+                    "id": id,
+                    "name": name,
+                    "original_name": text(person.get("original_name")),
+                    "job": job,
+                    "department": department,
+                    "profile_url": profile(person),
+                }));
+            }
+        }
+    }
+
+    if let Some(object) = details.as_object_mut() {
+        object.insert("cast".to_string(), Value::Array(cast));
+        object.insert("crew".to_string(), Value::Array(crew));
+    }
+}
+
+/// Bangumi 人员关系对应的部门：导演归入 Directing，原作、脚本、系列构成归入 Writing
+fn bangumi_department(relation: &str) -> Option<(&'static str, &'static str)> {
+    match relation {
+        "导演" | "总导演" | "监督" => Some(("Directing", "Director")),
+        "原作" => Some(("Writing", "Original Story")),
+        "脚本" | "编剧" => Some(("Writing", "Screenplay")),
+        "系列构成" => Some(("Writing", "Series Composition")),
+        _ => None,
+    }
+}
+
+fn bangumi_image(item: &Value) -> Option<String> {
+    let images = item.get("images")?;
+    text(images.get("large")).or_else(|| text(images.get("medium")))
+}
+
+/// 解析 Bangumi `/v0/subjects/{id}/persons` 与 `/characters` 并写入详情的 `cast` / `crew`
+///
+/// 演员取自角色的声优（`actors`），主角排在配角之前。
+pub fn apply_bangumi_credits(details: &mut Value, persons: &Value, characters: &Value) {
+    let mut roles: Vec<&Value> = characters
+        .as_array()
+        .map(|items| items.iter().collect())
+        .unwrap_or_default();
+    roles
+        .sort_by_key(|character| character.get("relation").and_then(Value::as_str) != Some("主角"));
+
+    let cast: Vec<Value> = roles
+        .into_iter()
+        .flat_map(|character| {
+            let name = text(character.get("name"));
+            character
+                .get("actors")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .map(move |actor| (name.clone(), actor))
+        })
+        .take(MAX_CAST)
+        .enumerate()
+        .filter_map(|(order, (character, actor))| {
+            Some(json!({
+                "id": id_text(actor.get("id"))?,
+                "name": text(actor.get("name"))?,
+                "original_name": Value::Null,
+                "character": character,
+                "order": order,
+                "profile_url": bangumi_image(actor),
+            }))
+        })
+        .collect();
+
+    let crew: Vec<Value> = persons
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|person| {
+            let relation = text(person.get("relation"))?;
+            let (department, job) = bangumi_department(&relation)?;
+            Some(json!({
+                "id": id_text(person.get("id"))?,
+                "name": text(person.get("name"))?,
+                "original_name": Value::Null,
+                "job": job,
+                "department": department,
+                "profile_url": bangumi_image(person),
+            }))
+        })
+        .collect();
+
+    if let Some(object) = details.as_object_mut() {
+        object.insert("cast".to_string(), Value::Array(cast));
+        object.insert("crew".to_string(), Value::Array(crew));
+    }
+}
+
+fn title_column(level: Level) -> anyhow::Result<&'static str> {
+    match level {
+        Level::Movie => Ok("movie_id"),
+        Level::Show => Ok("show_id"),
+        _ => anyhow::bail!("credits are stored per movie or show"),
+    }
+}
+
+/// 用识别结果中的 `cast` / `crew` 替换作品的演职员表
+///
+/// 结果不含演职员信息（如插件数据源或离线缓存的旧结果）时保留已有记录。
+pub async fn store_credits(
+    db: &SqlitePool,
+    provider: &str,
+    level: Level,
+    title_id: &str,
+    details: &Value,
+) -> anyhow::Result<()> {
+    let column = title_column(level)?;
+    let cast = details.get("cast").and_then(Value::as_array);
+    let crew = details.get("crew").and_then(Value::as_array);
+    if cast.is_none() && crew.is_none() {
+        return Ok(());
+    }
+
+    let now = Utc::now();
+    let mut tx = db.begin().await?;
+    sqlx::query(&format!("DELETE FROM credits WHERE {column} = ?"))
+        .bind(title_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let entries = cast
+        .into_iter()
+        .flatten()
+        .map(|person| (person, "actor"))
+        .chain(crew.into_iter().flatten().filter_map(|person| {
+            let department = person.get("department").and_then(Value::as_str)?;
+            let job = person.get("job").and_then(Value::as_str)?;
+            Some((person, crew_role(department, job)?))
+        }));
+    for (sort_order, (person, role)) in entries.enumerate() {
+        let (Some(external_id), Some(name)) = (id_text(person.get("id")), text(person.get("name")))
+        else {
+            continue;
+        };
+        let person_id = catalog::title_id(provider, &external_id);
+        sqlx::query(
+            "INSERT INTO people (id, provider, external_id, name, original_name, profile_url, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET
+                 name = excluded.name,
+                 original_name = COALESCE(excluded.original_name, people.original_name),
+                 profile_path = CASE WHEN excluded.profile_url IS NULL OR excluded.profile_url = people.profile_url THEN people.profile_path END,
+                 profile_url = COALESCE(excluded.profile_url, people.profile_url),
+                 updated_at = excluded.updated_at",
+        )
+        .bind(&person_id)
+        .bind(provider)
+        .bind(&external_id)
+        .bind(&name)
+        .bind(text(person.get("original_name")))
+        .bind(text(person.get("profile_url")))
+        .bind(now)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+
+        let sort_order = person
+            .get("order")
+            .and_then(Value::as_i64)
+            .unwrap_or(sort_order as i64);
+        sqlx::query(&format!(
+            "INSERT INTO credits (person_id, {column}, role, character, job, sort_order) VALUES (?, ?, ?, ?, ?, ?)"
+        ))
+        .bind(&person_id)
+        .bind(title_id)
+        .bind(role)
+        .bind(text(person.get("character")))
+        .bind(text(person.get("job")))
+        .bind(sort_order)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// 作品的演职员表：演员按顺序在前，其后为导演、编剧
+pub async fn credits_for(
+    db: &SqlitePool,
+    level: Level,
+    title_id: &str,
+) -> anyhow::Result<Vec<TitleCredit>> {
+    let column = title_column(level)?;
+    let credits = sqlx::query_as::<_, TitleCredit>(&format!(
+        "SELECT p.id AS person_id, p.name, p.original_name, p.profile_url, p.profile_path,
+                c.role, c.character, c.job, c.sort_order
+         FROM credits c JOIN people p ON p.id = c.person_id
+         WHERE c.{column} = ?
+         ORDER BY CASE c.role WHEN 'actor' THEN 0 WHEN 'director' THEN 1 ELSE 2 END, c.sort_order, c.id"
+    ))
+    .bind(title_id)
+    .fetch_all(db)
+    .await?;
+    Ok(credits)
+}
+
+/// 列出人员，可按姓名模糊搜索
+pub async fn list_people(
+    db: &SqlitePool,
+    search: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> anyhow::Result<Vec<Person>> {
+    let people = sqlx::query_as::<_, Person>(&format!(
+        "SELECT {PERSON_FIELDS} FROM people p
+         WHERE ?1 IS NULL OR p.name LIKE '%' || ?1 || '%' OR p.original_name LIKE '%' || ?1 || '%'
+         ORDER BY p.name COLLATE NOCASE LIMIT ?2 OFFSET ?3"
+    ))
+    .bind(search)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(db)
+    .await?;
+    Ok(people)
+}
+
+pub async fn get_person(db: &SqlitePool, id: &str) -> anyhow::Result<Option<Person>> {
+    let person = sqlx::query_as::<_, Person>(&format!(
+        "SELECT {PERSON_FIELDS} FROM people p WHERE p.id = ?"
+    ))
+    .bind(id)
+    .fetch_optional(db)
+    .await?;
+    Ok(person)
+}
+
+/// 人员参与的全部作品（电影与剧集），按年份倒序
+pub async fn titles_for_person(
+    db: &SqlitePool,
+    person_id: &str,
+) -> anyhow::Result<Vec<PersonCredit>> {
+    let credits = sqlx::query_as::<_, PersonCredit>(
+        "SELECT 'movie' AS media_type, m.id AS title_id, m.title, m.year, m.poster_url, c.role, c.character, c.job
+         FROM credits c JOIN movies m ON m.id = c.movie_id
+         WHERE c.person_id = ?1
+         UNION ALL
+         SELECT 'tv' AS media_type, s.id AS title_id, s.title, s.year, s.poster_url, c.role, c.character, c.job
+         FROM credits c JOIN shows s ON s.id = c.show_id
+         WHERE c.person_id = ?1
+         ORDER BY year DESC, title",
+    )
+    .bind(person_id)
+    .fetch_all(db)
+    .await?;
+    Ok(credits)
+}
+
+/// 下载作品演职员中尚未缓存的头像到 `cache_dir/<数据源>/<人员 ID>.jpg`，返回新下载的数量
+pub async fn cache_profile_images(
+    db: &SqlitePool,
+    cache_dir: &Path,
+    level: Level,
+    title_id: &str,
+) -> anyhow::Result<usize> {
+    let column = title_column(level)?;
+    let pending: Vec<(String, String, String, String)> = sqlx::query_as(&format!(
+        "SELECT DISTINCT p.id, p.provider, p.external_id, p.profile_url
+         FROM credits c JOIN people p ON p.id = c.person_id
+         WHERE c.{column} = ? AND p.profile_url IS NOT NULL AND p.profile_path IS NULL"
+    ))
+    .bind(title_id)
+    .fetch_all(db)
+    .await?;
+
+    let downloaded: Vec<(String, String)> = stream::iter(pending)
+        .map(|(id, provider, external_id, url)| async move {
+            let path = cache_dir.join(provider).join(format!("{external_id}.jpg"));
+            match poster::download_image(&url, &path).await {
+                Ok(()) => Some((id, path.to_string_lossy().to_string())),
+                Err(error) => {
+                    tracing::warn!("Failed to download profile image for {}: {}", id, error);
+                    None
+                }
+            }
+        })
+        .buffer_unordered(PROFILE_DOWNLOADS)
+        .filter_map(|result| async move { result })
+        .collect()
+        .await;
+
+    for (id, path) in &downloaded {
+        sqlx::query("UPDATE people SET profile_path = ?, updated_at = ? WHERE id = ?")
+            .bind(path)
+            .bind(Utc::now())
+            .bind(id)
+            .execute(db)
+            .await?;
+    }
+    Ok(downloaded.len())
+}
@@ -14,6 +14,9 @@ use anyhow::Context;
 #[cfg(feature = "plugins")]
 use extism::{Manifest, Plugin, Wasm};
 
+/// 插件能力：元数据源（搜索、详情、剧集列表、图片）
+pub const CAPABILITY_METADATA: &str = "metadata";
+
 /// 插件信息
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PluginInfo {
@@ -21,6 +24,17 @@ pub struct PluginInfo {
     pub version: String,
     pub id: String,
     pub supported_sources: Vec<String>,
+    /// 插件声明的能力，如 `metadata`
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+impl PluginInfo {
+    pub fn has_capability(&self, capability: &str) -> bool {
+        self.capabilities
+            .iter()
+            .any(|declared| declared == capability)
+    }
 }
 
 /// 搜索请求 (Host -> Guest)
@@ -147,40 +161,41 @@ impl PluginManager {
     }
 
     /// 执行搜索
-    #[cfg(feature = "plugins")]
     pub async fn search(
         &self,
         plugin_id: &str,
         query: PluginSearchQuery,
     ) -> Result<PluginSearchResult> {
+        let query_json = serde_json::to_string(&query)?;
+        let result_json = self.call(plugin_id, "search", query_json).await?;
+        let result: PluginSearchResult = serde_json::from_str(&result_json)?;
+        Ok(result)
+    }
+
+    /// 调用插件导出函数（JSON 字符串进出），供详情/剧集/图片等扩展接口使用
+    #[cfg(feature = "plugins")]
+    pub async fn call(&self, plugin_id: &str, function: &str, input: String) -> Result<String> {
         let wasm_bytes = {
             let plugins = self.plugins.read().await;
             plugins.get(plugin_id).map(|p| p.wasm_bytes.clone())
         };
 
         if let Some(bytes) = wasm_bytes {
-            let query_json = serde_json::to_string(&query)?;
-
-            let result_json = tokio::task::spawn_blocking(move || {
+            let function = function.to_string();
+            let output = tokio::task::spawn_blocking(move || {
                 let manifest = Manifest::new([Wasm::data(bytes)]);
                 let mut plugin = Plugin::new(&manifest, [], true)?;
-                plugin.call::<String, String>("search", query_json)
+                plugin.call::<String, String>(&function, input)
             })
             .await??;
-
-            let result: PluginSearchResult = serde_json::from_str(&result_json)?;
-            Ok(result)
+            Ok(output)
         } else {
             Err(anyhow::anyhow!("Plugin not found: {}", plugin_id))
         }
     }
 
     #[cfg(not(feature = "plugins"))]
-    pub async fn search(
-        &self,
-        _plugin_id: &str,
-        _query: PluginSearchQuery,
-    ) -> Result<PluginSearchResult> {
+    pub async fn call(&self, _plugin_id: &str, _function: &str, _input: String) -> Result<String> {
         Err(anyhow::anyhow!(
             "Plugin support is not compiled into this build"
         ))
@@ -87,3 +87,19 @@ pub async fn download_media_images(
 
     Ok((poster_path, backdrop_path))
 }
+
+/// 下载单集剧照，保存为 Kodi/Jellyfin 识别的 `<文件名>-thumb.jpg`
+pub async fn download_episode_still(file_path: &str, still_url: &str) -> anyhow::Result<PathBuf> {
+    let media_path = Path::new(file_path);
+    let media_dir = media_path
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("Invalid file path"))?;
+    let media_name = media_path
+        .file_stem()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| anyhow::anyhow!("Invalid file name"))?;
+
+    let path = media_dir.join(format!("{}-thumb.jpg", media_name));
+    download_image(still_url, &path).await?;
+    Ok(path)
+}
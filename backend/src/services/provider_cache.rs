@@ -0,0 +1,455 @@
+//! 元数据源响应缓存（SQLite）
+//!
+//! 搜索、详情、剧集列表按 数据源 + 接口 + 参数 缓存：未过期直接返回；过期但仍在
+//! stale 窗口内先返回旧数据并在后台刷新；离线模式只读缓存，不访问网络。
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, Utc};
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+
+use crate::services::identify::IdentifyCandidate;
+use crate::services::metadata_provider::{
+    MetadataProvider, ProviderArtwork, ProviderEpisode, ProviderFuture, ProviderQuery,
+};
+
+/// 缓存有效期策略
+#[derive(Debug, Clone, Copy)]
+pub struct CachePolicy {
+    /// 过期前视为新鲜
+    pub ttl: Duration,
+    /// 过期后仍可先返回旧数据、后台刷新的时长
+    pub stale: Duration,
+}
+
+impl CachePolicy {
+    /// 搜索结果变化较快：1 天新鲜，7 天内可先用旧数据
+    pub fn search() -> Self {
+        Self {
+            ttl: Duration::days(1),
+            stale: Duration::days(7),
+        }
+    }
+
+    /// 详情与剧集列表：7 天新鲜，30 天内可先用旧数据
+    pub fn details() -> Self {
+        Self {
+            ttl: Duration::days(7),
+            stale: Duration::days(30),
+        }
+    }
+}
+
+/// 缓存命中状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    Fresh,
+    Stale,
+    Expired,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct CacheEntry {
+    pub cache_key: String,
+    pub provider: String,
+    pub endpoint: String,
+    pub params: String,
+    pub payload: String,
+    pub fetched_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub hit_count: i64,
+}
+
+impl CacheEntry {
+    pub fn freshness(&self, policy: &CachePolicy, now: DateTime<Utc>) -> Freshness {
+        if now < self.expires_at {
+            Freshness::Fresh
+        } else if now < self.expires_at + policy.stale {
+            Freshness::Stale
+        } else {
+            Freshness::Expired
+        }
+    }
+}
+
+/// 缓存条目概要（不含响应体）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct CacheEntrySummary {
+    pub cache_key: String,
+    pub provider: String,
+    pub endpoint: String,
+    pub params: String,
+    pub size: i64,
+    pub fetched_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub hit_count: i64,
+}
+
+/// 按数据源、接口汇总的缓存统计
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct CacheStats {
+    pub provider: String,
+    pub endpoint: String,
+    pub entries: i64,
+    pub expired: i64,
+    pub hits: i64,
+    pub size: i64,
+}
+
+/// 缓存键：`provider:endpoint:参数 JSON`（serde_json 对象键有序，结果稳定）
+pub fn cache_key(provider: &str, endpoint: &str, params: &Value) -> String {
+    format!("{provider}:{endpoint}:{params}")
+}
+
+pub async fn lookup(
+    db: &SqlitePool,
+    provider: &str,
+    endpoint: &str,
+    params: &Value,
+) -> anyhow::Result<Option<CacheEntry>> {
+    let key = cache_key(provider, endpoint, params);
+    let entry: Option<CacheEntry> =
+        sqlx::query_as("SELECT * FROM provider_cache WHERE cache_key = ?")
+            .bind(&key)
+            .fetch_optional(db)
+            .await?;
+    if entry.is_some() {
+        sqlx::query("UPDATE provider_cache SET hit_count = hit_count + 1 WHERE cache_key = ?")
+            .bind(&key)
+            .execute(db)
+            .await?;
+    }
+    Ok(entry)
+}
+
+pub async fn store(
+    db: &SqlitePool,
+    provider: &str,
+    endpoint: &str,
+    params: &Value,
+    payload: &str,
+    ttl: Duration,
+) -> anyhow::Result<()> {
+    let now = Utc::now();
+    sqlx::query(
+        "INSERT INTO provider_cache (cache_key, provider, endpoint, params, payload, fetched_at, expires_at, hit_count)
+         VALUES (?, ?, ?, ?, ?, ?, ?, 0)
+         ON CONFLICT(cache_key) DO UPDATE SET payload = excluded.payload, fetched_at = excluded.fetched_at, expires_at = excluded.expires_at",
+    )
+    .bind(cache_key(provider, endpoint, params))
+    .bind(provider)
+    .bind(endpoint)
+    .bind(params.to_string())
+    .bind(payload)
+    .bind(now)
+    .bind(now + ttl)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+pub async fn list_entries(
+    db: &SqlitePool,
+    provider: Option<&str>,
+    limit: i64,
+) -> anyhow::Result<Vec<CacheEntrySummary>> {
+    Ok(sqlx::query_as(
+        "SELECT cache_key, provider, endpoint, params, LENGTH(payload) AS size, fetched_at, expires_at, hit_count
+         FROM provider_cache
+         WHERE (? IS NULL OR provider = ?)
+         ORDER BY fetched_at DESC
+         LIMIT ?",
+    )
+    .bind(provider)
+    .bind(provider)
+    .bind(limit)
+    .fetch_all(db)
+    .await?)
+}
+
+pub async fn stats(db: &SqlitePool) -> anyhow::Result<Vec<CacheStats>> {
+    Ok(sqlx::query_as(
+        "SELECT provider, endpoint, COUNT(*) AS entries,
+                SUM(CASE WHEN expires_at <= ? THEN 1 ELSE 0 END) AS expired,
+                SUM(hit_count) AS hits, SUM(LENGTH(payload)) AS size
+         FROM provider_cache
+         GROUP BY provider, endpoint
+         ORDER BY provider, endpoint",
+    )
+    .bind(Utc::now())
+    .fetch_all(db)
+    .await?)
+}
+
+/// 清理缓存；条件为空时清空全部，`expired_only` 只删除已过期条目
+pub async fn purge(
+    db: &SqlitePool,
+    provider: Option<&str>,
+    endpoint: Option<&str>,
+    expired_only: bool,
+) -> anyhow::Result<u64> {
+    let result = sqlx::query(
+        "DELETE FROM provider_cache
+         WHERE (? IS NULL OR provider = ?)
+           AND (? IS NULL OR endpoint = ?)
+           AND (? = 0 OR expires_at <= ?)",
+    )
+    .bind(provider)
+    .bind(provider)
+    .bind(endpoint)
+    .bind(endpoint)
+    .bind(expired_only)
+    .bind(Utc::now())
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// 正在后台刷新的缓存键：同一条目过期后被并发读取时只刷新一次
+static REFRESHING: Lazy<Mutex<HashSet<String>>> = Lazy::new(Default::default);
+
+/// 占用一个刷新中的缓存键，释放时移除；已有刷新在进行时返回 None
+struct RefreshGuard(String);
+
+impl RefreshGuard {
+    fn acquire(key: String) -> Option<Self> {
+        if !REFRESHING.lock().unwrap().insert(key.clone()) {
+            return None;
+        }
+        Some(Self(key))
+    }
+}
+
+impl Drop for RefreshGuard {
+    fn drop(&mut self) {
+        REFRESHING.lock().unwrap().remove(&self.0);
+    }
+}
+
+/// 带缓存的数据源包装
+pub struct CachedProvider {
+    inner: Arc<dyn MetadataProvider>,
+    db: SqlitePool,
+    offline: bool,
+}
+
+impl CachedProvider {
+    pub fn new(inner: Arc<dyn MetadataProvider>, db: SqlitePool, offline: bool) -> Self {
+        Self { inner, db, offline }
+    }
+
+    /// 区分语言的数据源按语言链分别缓存
+    fn scoped(&self, mut params: Value) -> Value {
+        let languages = self.inner.languages();
+        if let Some(object) = params.as_object_mut().filter(|_| !languages.is_empty()) {
+            object.insert("languages".to_string(), json!(languages));
+        }
+        params
+    }
+
+    /// `complete` 返回 false 的旧条目按已过期处理：先重新获取，失败时再用旧数据
+    async fn cached<T, F, Fut>(
+        &self,
+        endpoint: &'static str,
+        params: Value,
+        policy: CachePolicy,
+        complete: fn(&str, &T) -> bool,
+        fetch: F,
+    ) -> anyhow::Result<T>
+    where
+        T: Serialize + DeserializeOwned + Send + Sync + 'static,
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<T>> + Send + 'static,
+    {
+        let provider = self.inner.id().to_string();
+        let params = self.scoped(params);
+        let entry = lookup(&self.db, &provider, endpoint, &params)
+            .await
+            .unwrap_or_else(|error| {
+                tracing::warn!("Provider cache lookup failed: {}", error);
+                None
+            });
+        let cached = entry.and_then(|entry| {
+            let value = serde_json::from_str::<T>(&entry.payload).ok()?;
+            let freshness = if complete(&provider, &value) {
+                entry.freshness(&policy, Utc::now())
+            } else {
+                Freshness::Expired
+            };
+            Some((freshness, value))
+        });
+
+        let expired = match cached {
+            Some((Freshness::Fresh, value)) => return Ok(value),
+            Some((_, value)) if self.offline => return Ok(value),
+            None if self.offline => {
+                anyhow::bail!("offline mode: {provider} {endpoint} response is not cached")
+            }
+            Some((Freshness::Stale, value)) => {
+                let Some(guard) = RefreshGuard::acquire(cache_key(&provider, endpoint, &params))
+                else {
+                    return Ok(value);
+                };
+                let db = self.db.clone();
+                tokio::spawn(async move {
+                    let _guard = guard;
+                    match fetch().await {
+                        Ok(fresh) => {
+                            if let Err(error) =
+                                write(&db, &provider, endpoint, &params, &fresh, policy).await
+                            {
+                                tracing::warn!("Provider cache refresh failed: {}", error);
+                            }
+                        }
+                        Err(error) => {
+                            tracing::debug!("Provider {} revalidate failed: {}", provider, error)
+                        }
+                    }
+                });
+                return Ok(value);
+            }
+            Some((Freshness::Expired, value)) => Some(value),
+            None => None,
+        };
+
+        match fetch().await {
+            Ok(value) => {
+                if let Err(error) =
+                    write(&self.db, &provider, endpoint, &params, &value, policy).await
+                {
+                    tracing::warn!("Provider cache store failed: {}", error);
+                }
+                Ok(value)
+            }
+            // 网络不可用时，已超出 stale 窗口的旧数据也好过识别失败
+            Err(error) => match expired {
+                Some(value) => {
+                    tracing::warn!(
+                        "Provider {} unavailable, serving expired cache: {}",
+                        provider,
+                        error
+                    );
+                    Ok(value)
+                }
+                None => Err(error),
+            },
+        }
+    }
+}
+
+/// TMDb 详情在加入外部 ID 之前写入的缓存缺少 `external_ids`，应用时会导致 NFO 缺少 uniqueid
+fn details_complete(provider: &str, details: &Value) -> bool {
+    provider != "tmdb" || details.get("external_ids").is_some()
+}
+
+async fn write<T: Serialize>(
+    db: &SqlitePool,
+    provider: &str,
+    endpoint: &str,
+    params: &Value,
+    value: &T,
+    policy: CachePolicy,
+) -> anyhow::Result<()> {
+    let payload = serde_json::to_string(value)?;
+    store(db, provider, endpoint, params, &payload, policy.ttl).await
+}
+
+impl MetadataProvider for CachedProvider {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn kind(&self) -> &str {
+        self.inner.kind()
+    }
+
+    fn languages(&self) -> &[String] {
+        self.inner.languages()
+    }
+
+    fn search<'a>(
+        &'a self,
+        query: &'a ProviderQuery,
+    ) -> ProviderFuture<'a, Vec<IdentifyCandidate>> {
+        let inner = self.inner.clone();
+        let owned = query.clone();
+        Box::pin(self.cached(
+            "search",
+            json!({ "title": query.title, "year": query.year, "media_type": query.media_type }),
+            CachePolicy::search(),
+            |_, _| true,
+            move || async move { inner.search(&owned).await },
+        ))
+    }
+
+    fn details<'a>(
+        &'a self,
+        external_id: &'a str,
+        media_type: &'a str,
+    ) -> ProviderFuture<'a, Value> {
+        let inner = self.inner.clone();
+        let (id, kind) = (external_id.to_string(), media_type.to_string());
+        Box::pin(self.cached(
+            "details",
+            json!({ "external_id": external_id, "media_type": media_type }),
+            CachePolicy::details(),
+            details_complete,
+            move || async move { inner.details(&id, &kind).await },
+        ))
+    }
+
+    fn episodes<'a>(
+        &'a self,
+        external_id: &'a str,
+        season: Option<u32>,
+    ) -> ProviderFuture<'a, Vec<ProviderEpisode>> {
+        let inner = self.inner.clone();
+        let id = external_id.to_string();
+        Box::pin(self.cached(
+            "episodes",
+            json!({ "external_id": external_id, "season": season }),
+            CachePolicy::details(),
+            |_, _| true,
+            move || async move { inner.episodes(&id, season).await },
+        ))
+    }
+
+    fn artwork<'a>(
+        &'a self,
+        external_id: &'a str,
+        media_type: &'a str,
+    ) -> ProviderFuture<'a, ProviderArtwork> {
+        let inner = self.inner.clone();
+        let (id, kind) = (external_id.to_string(), media_type.to_string());
+        Box::pin(self.cached(
+            "artwork",
+            json!({ "external_id": external_id, "media_type": media_type }),
+            CachePolicy::details(),
+            |_, _| true,
+            move || async move { inner.artwork(&id, &kind).await },
+        ))
+    }
+
+    fn collection<'a>(&'a self, external_id: &'a str) -> ProviderFuture<'a, Option<Value>> {
+        let inner = self.inner.clone();
+        let id = external_id.to_string();
+        Box::pin(self.cached(
+            "collection",
+            json!({ "external_id": external_id }),
+            CachePolicy::details(),
+            |_, _| true,
+            move || async move { inner.collection(&id).await },
+        ))
+    }
+}
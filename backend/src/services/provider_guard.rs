@@ -0,0 +1,387 @@
+//! 元数据源请求保护：按数据源的令牌桶限速、429/5xx 重试与熔断
+//!
+//! 所有对 TMDb / Bangumi 的 HTTP 请求都应通过 [`send`] 发出。连续失败达到阈值后熔断，
+//! 冷却期内直接返回 [`ProviderUnavailable`]，批量刮削据此暂停等待而不是逐个文件失败。
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// 单次请求最多重试次数
+const MAX_RETRIES: u32 = 3;
+/// 单次重试最长等待；Retry-After 更长时直接熔断
+const MAX_RETRY_WAIT: Duration = Duration::from_secs(30);
+/// 连续失败多少次后熔断
+const FAILURE_THRESHOLD: u32 = 5;
+/// 熔断冷却时间
+const COOLDOWN: Duration = Duration::from_secs(60);
+
+static GUARDS: Lazy<DashMap<String, Arc<ProviderGuard>>> = Lazy::new(DashMap::new);
+
+/// 令牌桶参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub capacity: f64,
+    pub refill_per_sec: f64,
+}
+
+impl RateLimit {
+    /// 各数据源默认限速：TMDb 约 40 次/10 秒，Bangumi 更保守
+    pub fn for_provider(provider: &str) -> Self {
+        match provider {
+            "tmdb" => Self {
+                capacity: 20.0,
+                refill_per_sec: 4.0,
+            },
+            "bgm" => Self {
+                capacity: 5.0,
+                refill_per_sec: 2.0,
+            },
+            _ => Self {
+                capacity: 10.0,
+                refill_per_sec: 5.0,
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TokenBucket {
+    limit: RateLimit,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            limit,
+            tokens: limit.capacity,
+            updated: now,
+        }
+    }
+
+    /// 取一个令牌；不足时返回需要等待的时长
+    pub fn try_take(&mut self, now: Instant) -> Option<Duration> {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.limit.refill_per_sec).min(self.limit.capacity);
+        self.updated = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                (1.0 - self.tokens) / self.limit.refill_per_sec,
+            ))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+/// 熔断器：冷却结束后放行一个试探请求，成功则恢复，失败则重新熔断
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    threshold: u32,
+    failures: u32,
+    open_until: Option<Instant>,
+    trial_in_flight: bool,
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: u32) -> Self {
+        Self {
+            threshold,
+            failures: 0,
+            open_until: None,
+            trial_in_flight: false,
+        }
+    }
+
+    pub fn state(&self, now: Instant) -> CircuitState {
+        match self.open_until {
+            None => CircuitState::Closed,
+            Some(until) if now < until => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// 是否放行请求；拒绝时返回剩余冷却时间
+    pub fn allow(&mut self, now: Instant) -> Result<(), Duration> {
+        match self.state(now) {
+            CircuitState::Closed => Ok(()),
+            CircuitState::Open => Err(self
+                .open_until
+                .map(|until| until.saturating_duration_since(now))
+                .unwrap_or(COOLDOWN)),
+            CircuitState::HalfOpen if self.trial_in_flight => Err(Duration::from_secs(1)),
+            CircuitState::HalfOpen => {
+                self.trial_in_flight = true;
+                Ok(())
+            }
+        }
+    }
+
+    pub fn record_success(&mut self) {
+        self.failures = 0;
+        self.open_until = None;
+        self.trial_in_flight = false;
+    }
+
+    /// 记录失败；试探失败、连续失败达到阈值或要求等待超过 [`MAX_RETRY_WAIT`] 时熔断，
+    /// 冷却取 `cooldown` 与默认值的较大者
+    pub fn record_failure(&mut self, now: Instant, cooldown: Option<Duration>) {
+        self.failures += 1;
+        let half_open = self.trial_in_flight;
+        self.trial_in_flight = false;
+        let long_wait = cooldown.is_some_and(|cooldown| cooldown > MAX_RETRY_WAIT);
+        if half_open || long_wait || self.failures >= self.threshold {
+            self.open_until = Some(now + cooldown.unwrap_or(COOLDOWN).max(COOLDOWN));
+        }
+    }
+
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+}
+
+/// 数据源暂不可用（熔断中）
+#[derive(Debug, Clone)]
+pub struct ProviderUnavailable {
+    pub provider: String,
+    pub retry_after: Duration,
+}
+
+impl std::fmt::Display for ProviderUnavailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "provider {} is temporarily unavailable (circuit open, retry in {}s)",
+            self.provider,
+            self.retry_after.as_secs().max(1)
+        )
+    }
+}
+
+impl std::error::Error for ProviderUnavailable {}
+
+/// 从错误链中找出熔断错误
+pub fn unavailable(error: &anyhow::Error) -> Option<&ProviderUnavailable> {
+    error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<ProviderUnavailable>())
+}
+
+/// 数据源运行状态（健康检查展示）
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProviderStatus {
+    pub provider: String,
+    pub circuit: CircuitState,
+    /// 熔断剩余秒数
+    pub retry_after_secs: Option<u64>,
+    pub consecutive_failures: u32,
+    pub requests: u64,
+    pub throttled: u64,
+    pub retries: u64,
+    pub failures: u64,
+    pub last_error: Option<String>,
+}
+
+pub struct ProviderGuard {
+    provider: String,
+    bucket: Mutex<TokenBucket>,
+    breaker: Mutex<CircuitBreaker>,
+    requests: AtomicU64,
+    throttled: AtomicU64,
+    retries: AtomicU64,
+    failures: AtomicU64,
+    last_error: Mutex<Option<String>>,
+}
+
+impl ProviderGuard {
+    fn new(provider: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            bucket: Mutex::new(TokenBucket::new(
+                RateLimit::for_provider(provider),
+                Instant::now(),
+            )),
+            breaker: Mutex::new(CircuitBreaker::new(FAILURE_THRESHOLD)),
+            requests: AtomicU64::new(0),
+            throttled: AtomicU64::new(0),
+            retries: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+            last_error: Mutex::new(None),
+        }
+    }
+
+    fn admit(&self) -> Result<(), ProviderUnavailable> {
+        self.breaker
+            .lock()
+            .unwrap()
+            .allow(Instant::now())
+            .map_err(|retry_after| ProviderUnavailable {
+                provider: self.provider.clone(),
+                retry_after,
+            })
+    }
+
+    async fn acquire(&self) {
+        loop {
+            let wait = self.bucket.lock().unwrap().try_take(Instant::now());
+            match wait {
+                None => return,
+                Some(wait) => tokio::time::sleep(wait).await,
+            }
+        }
+    }
+
+    fn succeed(&self) {
+        self.breaker.lock().unwrap().record_success();
+    }
+
+    fn fail(&self, message: String, cooldown: Option<Duration>) {
+        self.failures.fetch_add(1, Ordering::Relaxed);
+        self.breaker
+            .lock()
+            .unwrap()
+            .record_failure(Instant::now(), cooldown);
+        tracing::warn!("Provider {} request failed: {}", self.provider, message);
+        *self.last_error.lock().unwrap() = Some(message);
+    }
+
+    pub fn status(&self) -> ProviderStatus {
+        let now = Instant::now();
+        let breaker = self.breaker.lock().unwrap();
+        let circuit = breaker.state(now);
+        ProviderStatus {
+            provider: self.provider.clone(),
+            circuit,
+            retry_after_secs: breaker
+                .open_until
+                .filter(|_| circuit == CircuitState::Open)
+                .map(|until| until.saturating_duration_since(now).as_secs()),
+            consecutive_failures: breaker.failures(),
+            requests: self.requests.load(Ordering::Relaxed),
+            throttled: self.throttled.load(Ordering::Relaxed),
+            retries: self.retries.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+            last_error: self.last_error.lock().unwrap().clone(),
+        }
+    }
+}
+
+fn guard(provider: &str) -> Arc<ProviderGuard> {
+    GUARDS
+        .entry(provider.to_string())
+        .or_insert_with(|| Arc::new(ProviderGuard::new(provider)))
+        .clone()
+}
+
+/// 所有已使用过的数据源状态，按名称排序
+pub fn statuses() -> Vec<ProviderStatus> {
+    let mut statuses: Vec<_> = GUARDS.iter().map(|entry| entry.value().status()).collect();
+    statuses.sort_by(|lhs, rhs| lhs.provider.cmp(&rhs.provider));
+    statuses
+}
+
+/// 熔断中的数据源
+pub fn open_circuits() -> Vec<ProviderUnavailable> {
+    statuses()
+        .into_iter()
+        .filter(|status| status.circuit == CircuitState::Open)
+        .map(|status| ProviderUnavailable {
+            provider: status.provider,
+            retry_after: Duration::from_secs(status.retry_after_secs.unwrap_or(0)),
+        })
+        .collect()
+}
+
+/// 重置数据源状态（测试与手动恢复用）
+pub fn reset(provider: &str) {
+    GUARDS.remove(provider);
+}
+
+/// 解析 `Retry-After`：秒数或 HTTP 日期
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let delta = at.with_timezone(&chrono::Utc) - chrono::Utc::now();
+    Some(delta.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// 指数退避：0.5s、1s、2s…
+fn backoff(attempt: u32) -> Duration {
+    Duration::from_millis(500 * 2u64.pow(attempt.min(6))).min(MAX_RETRY_WAIT)
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// 经限速、重试与熔断保护发送请求
+///
+/// 429/5xx 与连接错误会按 `Retry-After` 或指数退避重试；其余错误状态码（如 404）
+/// 直接作为错误返回，不计入熔断。
+pub async fn send(provider: &str, request: RequestBuilder) -> anyhow::Result<Response> {
+    let guard = guard(provider);
+    let mut attempt = 0;
+
+    loop {
+        // 重试沿用首次放行（半开状态下只有一个试探请求）
+        if attempt == 0 {
+            guard.admit()?;
+        }
+        guard.acquire().await;
+        let request = request
+            .try_clone()
+            .ok_or_else(|| anyhow::anyhow!("request to {provider} cannot be retried"))?;
+        guard.requests.fetch_add(1, Ordering::Relaxed);
+
+        let wait = match request.send().await {
+            Ok(response) if is_retryable(response.status()) => {
+                let status = response.status();
+                if status == StatusCode::TOO_MANY_REQUESTS {
+                    guard.throttled.fetch_add(1, Ordering::Relaxed);
+                }
+                let wait = retry_after(response.headers()).unwrap_or_else(|| backoff(attempt));
+                if attempt >= MAX_RETRIES || wait > MAX_RETRY_WAIT {
+                    guard.fail(format!("HTTP {status}"), Some(wait));
+                    anyhow::bail!("{provider} responded with {status}");
+                }
+                wait
+            }
+            Ok(response) => {
+                guard.succeed();
+                return Ok(response.error_for_status()?);
+            }
+            Err(error) => {
+                if attempt >= MAX_RETRIES || !(error.is_connect() || error.is_timeout()) {
+                    guard.fail(error.to_string(), None);
+                    return Err(error.into());
+                }
+                backoff(attempt)
+            }
+        };
+
+        guard.retries.fetch_add(1, Ordering::Relaxed);
+        attempt += 1;
+        tokio::time::sleep(wait).await;
+    }
+}
@@ -1,4 +1,5 @@
 use crate::models::VideoInfo;
+use crate::services::release_parser::ReleaseInfo;
 
 /// 计算媒体质量得分 (0-100)
 /// 算法考虑：分辨率、码率、是否有中文字幕
@@ -45,8 +46,9 @@ pub fn calculate_quality_score(info: &VideoInfo) -> i32 {
     // 4. 来源质量得分 (Max 10)
     if let Some(ref source) = info.source {
         match source.as_str() {
-            "BluRay" => score += 10,
+            "Remux" | "BluRay" => score += 10,
             "iTunes" | "WEB-DL" => score += 7,
+            "WEBRip" => score += 5,
             "HDTV" => score += 3,
             _ => {}
         }
@@ -71,3 +73,39 @@ pub fn calculate_quality_score(info: &VideoInfo) -> i32 {
     // 归一化处理（如果有溢出则上限100）
     score.min(100)
 }
+
+/// 结合发布名计算得分：ffprobe 未提供（或未安装）时，分辨率、来源、HDR、声道取自发布名；
+/// REPACK / PROPER 修订版额外加分，便于去重时优先保留
+pub fn calculate_file_quality_score(info: Option<&VideoInfo>, release: &ReleaseInfo) -> i32 {
+    let mut merged = info.cloned().unwrap_or_default();
+
+    if merged.width.is_none() && merged.height.is_none() {
+        if let Some(height) = release.resolution_height() {
+            merged.height = Some(height);
+            merged.width = Some(height * 16 / 9);
+        }
+    }
+    if merged.source.is_none() {
+        merged.source = release.source.clone();
+    }
+    let has_hdr_flag = merged.is_dolby_vision.unwrap_or(false)
+        || merged.is_hdr10_plus.unwrap_or(false)
+        || merged.is_hdr.unwrap_or(false);
+    if !has_hdr_flag {
+        match release.hdr.as_deref() {
+            Some("DV") => merged.is_dolby_vision = Some(true),
+            Some("HDR10+") => merged.is_hdr10_plus = Some(true),
+            Some(_) => merged.is_hdr = Some(true),
+            None => {}
+        }
+    }
+    if merged.audio_channels.is_none() {
+        merged.audio_channels = release.channel_count();
+    }
+
+    let mut score = calculate_quality_score(&merged);
+    if release.repack || release.proper {
+        score += 2;
+    }
+    score.min(100)
+}
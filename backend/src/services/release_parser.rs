@@ -0,0 +1,498 @@
+//! 结构化发布名解析
+//!
+//! 从 `Title.2019.2160p.UHD.BluRay.REMUX.DV.HDR.HEVC.TrueHD.7.1.Atmos-GROUP.mkv` 这类发布名中
+//! 提取标题、年份、季集以及分辨率、来源、编码、HDR、音轨、发布组、版本、REPACK/PROPER 与语言标记，
+//! 每个字段附带置信度。标题截止到第一个技术标记（年份、季集、分辨率等）之前。
+
+use std::collections::BTreeMap;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::services::{anime_parser, scraper};
+
+/// 标记边界：发布名中的分隔符（`.`、`_` 已在解析前替换为空格）
+const SEP: &str = r"[\s\[\](){}\-+]";
+
+fn token_re(pattern: &str) -> Regex {
+    Regex::new(&format!(r"(?i)(?:^|{SEP})(?P<t>{pattern})(?:$|{SEP})")).unwrap()
+}
+
+pub(crate) static PAREN_YEAR_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[(\[]((?:19|20)\d{2})[)\]]").unwrap());
+static YEAR_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?:19|20)\d{2}").unwrap());
+/// 语言检测按分隔符切出的词（空白含全角空格等多字节字符）
+static WORD_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"[^\s\[\](){}\-+&,]+").unwrap());
+
+static RESOLUTION_RE: Lazy<Regex> = Lazy::new(|| token_re(r"(?:2160|1080|720|576|480)[pi]"));
+static RESOLUTION_DIMENSIONS_RE: Lazy<Regex> =
+    Lazy::new(|| token_re(r"(?:3840|1920|1280|720)x(?:2160|1080|720|576|480)"));
+static RESOLUTION_ALIAS_RE: Lazy<Regex> = Lazy::new(|| token_re(r"4K|UHD"));
+
+static SOURCE_RES: Lazy<Vec<(Regex, &'static str, f64)>> = Lazy::new(|| {
+    vec![
+        (token_re(r"REMUX|BDREMUX"), "Remux", 0.95),
+        (
+            token_re(r"Blu-?Ray|BDRip|BRRip|BD(?:25|50|66|100)?"),
+            "BluRay",
+            0.95,
+        ),
+        (token_re(r"WEB-?Rip"), "WEBRip", 0.95),
+        (token_re(r"WEB-?DL|WEB"), "WEB-DL", 0.9),
+        (token_re(r"iTunes"), "iTunes", 0.9),
+        (token_re(r"HDTV|PDTV|HDTVRip"), "HDTV", 0.95),
+        (token_re(r"DVDRip|DVD-?R|DVD(?:5|9)?"), "DVDRip", 0.9),
+        (token_re(r"HDCAM|CAMRip|TELESYNC"), "CAM", 0.9),
+    ]
+});
+
+static VIDEO_CODEC_RES: Lazy<Vec<(Regex, &'static str, f64)>> = Lazy::new(|| {
+    vec![
+        (token_re(r"x265|HEVC|H ?265"), "HEVC", 0.95),
+        (token_re(r"x264|AVC|H ?264"), "AVC", 0.95),
+        (token_re(r"AV1"), "AV1", 0.9),
+        (token_re(r"VC-?1"), "VC-1", 0.9),
+        (token_re(r"XviD|DivX"), "XviD", 0.9),
+        (token_re(r"MPEG-?2"), "MPEG-2", 0.85),
+    ]
+});
+
+/// 按优先级排列：同时出现 DV 与 HDR 时取 DV
+static HDR_RES: Lazy<Vec<(Regex, &'static str, f64)>> = Lazy::new(|| {
+    vec![
+        (token_re(r"DV|DoVi|Dolby ?Vision"), "DV", 0.9),
+        (token_re(r"HDR10\+|HDR10Plus|HDR10P"), "HDR10+", 0.95),
+        (token_re(r"HDR10"), "HDR10", 0.95),
+        (token_re(r"HDR"), "HDR", 0.9),
+        (token_re(r"HLG"), "HLG", 0.9),
+    ]
+});
+
+/// 音轨标记可紧跟声道（`DDP5.1`、`AAC2.0`）
+fn audio_re(pattern: &str) -> Regex {
+    token_re(&format!(r"(?:{pattern})(?:[1-7] ?[01])?"))
+}
+
+static AUDIO_RES: Lazy<Vec<(Regex, &'static str, f64)>> = Lazy::new(|| {
+    vec![
+        (
+            audio_re(r"TrueHD(?: [1-7] [01])? ?Atmos|Atmos ?TrueHD"),
+            "TrueHD Atmos",
+            0.95,
+        ),
+        (audio_re(r"TrueHD"), "TrueHD", 0.95),
+        (audio_re(r"DTS-?HD ?MA|DTS-?HD"), "DTS-HD MA", 0.9),
+        (audio_re(r"DTS-?X"), "DTS-X", 0.9),
+        (audio_re(r"DDP|DD\+|EAC3|E-AC-?3"), "DDP", 0.9),
+        (audio_re(r"Atmos"), "Atmos", 0.8),
+        (audio_re(r"DTS"), "DTS", 0.9),
+        (audio_re(r"AC3|DD|Dolby ?Digital"), "AC3", 0.85),
+        (audio_re(r"FLAC"), "FLAC", 0.95),
+        (audio_re(r"L?PCM"), "PCM", 0.85),
+        (audio_re(r"AAC"), "AAC", 0.9),
+        (audio_re(r"Opus"), "Opus", 0.85),
+        (audio_re(r"MP3"), "MP3", 0.85),
+    ]
+});
+
+/// 声道在原始名称上匹配（`5.1` 的点不能替换成空格）
+static AUDIO_CHANNELS_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:^|[\s._\[(-]|DDP|DD\+|DD|E?AC3|AAC|DTS|FLAC|Atmos|TrueHD|PCM|Opus)([12567])\.([01])(?:$|[\s._\])-])")
+        .unwrap()
+});
+
+static EDITION_RES: Lazy<Vec<(Regex, &'static str)>> = Lazy::new(|| {
+    vec![
+        (token_re(r"Director'?s ?Cut"), "Director's Cut"),
+        (token_re(r"Extended(?: Cut| Edition)?"), "Extended"),
+        (token_re(r"Unrated"), "Unrated"),
+        (token_re(r"Uncut"), "Uncut"),
+        (token_re(r"Theatrical(?: Cut)?"), "Theatrical"),
+        (token_re(r"IMAX"), "IMAX"),
+        (token_re(r"Remastered"), "Remastered"),
+        (token_re(r"Criterion(?: Collection)?"), "Criterion"),
+        (token_re(r"Special Edition"), "Special Edition"),
+        (token_re(r"Ultimate Edition"), "Ultimate Edition"),
+        (token_re(r"Anniversary Edition"), "Anniversary Edition"),
+        (token_re(r"Final Cut"), "Final Cut"),
+        (token_re(r"Open Matte"), "Open Matte"),
+    ]
+});
+
+static REPACK_RE: Lazy<Regex> = Lazy::new(|| token_re(r"REPACK\d?|RERIP"));
+static PROPER_RE: Lazy<Regex> = Lazy::new(|| token_re(r"PROPER"));
+
+/// 语言标记：缩写不区分大小写；完整单词只认全大写（避免 `The English Patient`）
+static LANGUAGE_TOKENS: &[(&str, &str)] = &[
+    ("MULTI", "multi"),
+    ("DUAL", "dual"),
+    ("CHS", "zh-hans"),
+    ("GB", "zh-hans"),
+    ("CHT", "zh-hant"),
+    ("BIG5", "zh-hant"),
+    ("CHI", "zh"),
+    ("ENG", "en"),
+    ("JPN", "ja"),
+    ("JAP", "ja"),
+    ("KOR", "ko"),
+    ("VOSTFR", "fr"),
+    ("TRUEFRENCH", "fr"),
+];
+static LANGUAGE_WORDS: &[(&str, &str)] = &[
+    ("FRENCH", "fr"),
+    ("GERMAN", "de"),
+    ("ITALIAN", "it"),
+    ("SPANISH", "es"),
+    ("RUSSIAN", "ru"),
+    ("JAPANESE", "ja"),
+    ("KOREAN", "ko"),
+    ("ENGLISH", "en"),
+    ("CHINESE", "zh"),
+];
+static CJK_LANGUAGE_RES: Lazy<Vec<(Regex, &'static str)>> = Lazy::new(|| {
+    vec![
+        (Regex::new(r"简体|简中|简日|简英|简繁").unwrap(), "zh-hans"),
+        (Regex::new(r"繁体|繁中|繁日|繁英|简繁").unwrap(), "zh-hant"),
+        (Regex::new(r"中字|中文字幕|中英|双语").unwrap(), "zh"),
+        (Regex::new(r"国语|國語").unwrap(), "zh"),
+        (Regex::new(r"粤语|粵語").unwrap(), "yue"),
+    ]
+});
+
+static TRAILING_GROUP_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"-\s*([A-Za-z0-9][A-Za-z0-9_]*(?:@[A-Za-z0-9]+)?)\s*(?:\[[^\]]*\])?$").unwrap()
+});
+static LEADING_GROUP_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*\[([^\]]+)\]").unwrap());
+
+/// 看起来像技术标记而非发布组的结尾片段（`WEB-DL`、`DTS-HD`、`Blu-Ray`）
+const NOT_GROUPS: &[&str] = &["dl", "hd", "rip", "ray", "ma", "x", "1", "2", "3"];
+
+/// 结构化解析结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ReleaseInfo {
+    pub title: String,
+    pub year: Option<u32>,
+    pub season: Option<u32>,
+    pub episode: Option<u32>,
+    pub episode_end: Option<u32>,
+    /// `2160p` / `1080p` / `720p` / `576p` / `480p`
+    pub resolution: Option<String>,
+    /// `Remux` / `BluRay` / `WEB-DL` / `WEBRip` / `iTunes` / `HDTV` / `DVDRip` / `CAM`
+    pub source: Option<String>,
+    /// `HEVC` / `AVC` / `AV1` / `VC-1` / `XviD` / `MPEG-2`
+    pub video_codec: Option<String>,
+    /// `DV` / `HDR10+` / `HDR10` / `HDR` / `HLG`
+    pub hdr: Option<String>,
+    pub audio: Option<String>,
+    /// `7.1` / `5.1` / `2.0`
+    pub audio_channels: Option<String>,
+    pub release_group: Option<String>,
+    pub edition: Option<String>,
+    pub repack: bool,
+    pub proper: bool,
+    /// 小写语言代码（`zh-hans`、`en`、`multi` 等）
+    #[serde(default)]
+    pub languages: Vec<String>,
+    /// 不含季信息的绝对集数（番剧 `- 05`、`[05]`）
+    pub absolute_episode: Option<u32>,
+    /// 发布版本（`05v2` → 2）
+    pub version: Option<u32>,
+    /// 特别篇：`ova` / `special`（归入第 0 季）
+    pub special_type: Option<String>,
+    /// 合集（`[01-12]`）
+    #[serde(default)]
+    pub batch: bool,
+    /// 字幕语言（字幕组 `[CHS]`、`[简繁内封]` 等标记）
+    #[serde(default)]
+    pub subtitles: Vec<String>,
+    /// 其他标题（`葬送的芙莉莲 / Sousou no Frieren` 中的后者）
+    #[serde(default)]
+    pub alternative_titles: Vec<String>,
+    /// 各字段置信度（0~1），未识别的字段不出现
+    #[serde(default)]
+    pub confidence: BTreeMap<String, f64>,
+}
+
+impl ReleaseInfo {
+    /// 分辨率对应的像素高度
+    pub fn resolution_height(&self) -> Option<u32> {
+        self.resolution
+            .as_deref()
+            .and_then(|r| r.trim_end_matches(['p', 'i']).parse().ok())
+    }
+
+    /// 声道字符串换算为声道数（`5.1` → 6）
+    pub fn channel_count(&self) -> Option<u32> {
+        let (main, lfe) = self.audio_channels.as_deref()?.split_once('.')?;
+        Some(main.parse::<u32>().ok()? + lfe.parse::<u32>().ok()?)
+    }
+}
+
+/// 解析文件名（去掉扩展名）
+pub fn parse(filename: &str) -> ReleaseInfo {
+    parse_release_name(scraper::strip_extension(filename))
+}
+
+/// 按媒体库类型解析文件名：番剧库（`anime`）始终走字幕组命名解析
+pub fn parse_for_library(filename: &str, library_type: Option<&str>) -> ReleaseInfo {
+    if library_type == Some("anime") {
+        anime_parser::parse(filename)
+    } else {
+        parse(filename)
+    }
+}
+
+/// 解析不含扩展名的发布名或目录名；带字幕组方括号特征的名称交给 [`anime_parser`]
+pub fn parse_release_name(name: &str) -> ReleaseInfo {
+    if anime_parser::looks_like_fansub(name) {
+        anime_parser::parse_anime_name(name)
+    } else {
+        parse_scene_name(name)
+    }
+}
+
+/// 按欧美发布名规则解析（`Title.2019.1080p.BluRay.x264-GROUP`）
+pub(crate) fn parse_scene_name(name: &str) -> ReleaseInfo {
+    // `.`、`_` 与空格等价；单字节替换保证偏移量与原字符串一致
+    let normalized = name.replace(['.', '_'], " ");
+    let find = |re: &Regex| {
+        re.captures(&normalized)
+            .and_then(|c| c.name("t"))
+            .map(|m| (m.start(), m.as_str().to_string()))
+    };
+    let first_of = |patterns: &[(Regex, &'static str, f64)]| {
+        patterns.iter().find_map(|(re, value, confidence)| {
+            find(re).map(|(start, _)| (start, value.to_string(), *confidence))
+        })
+    };
+
+    let mut info = ReleaseInfo::default();
+    let mut scan = Scan {
+        cut: name.len(),
+        confidence: BTreeMap::new(),
+    };
+
+    let resolution = find(&RESOLUTION_RE)
+        .map(|(start, token)| (start, format!("{}p", &token[..token.len() - 1]), 0.95))
+        .or_else(|| {
+            find(&RESOLUTION_DIMENSIONS_RE).map(|(start, token)| {
+                let height = token.rsplit(['x', 'X']).next().unwrap_or_default();
+                (start, format!("{height}p"), 0.9)
+            })
+        })
+        .or_else(|| find(&RESOLUTION_ALIAS_RE).map(|(start, _)| (start, "2160p".to_string(), 0.8)));
+    let resolution = scan.set("resolution", resolution);
+    let source = scan.set("source", first_of(&SOURCE_RES));
+    let video_codec = scan.set("video_codec", first_of(&VIDEO_CODEC_RES));
+    let hdr = scan.set("hdr", first_of(&HDR_RES));
+    let audio = scan.set("audio", first_of(&AUDIO_RES));
+    let audio_channels = scan.set(
+        "audio_channels",
+        AUDIO_CHANNELS_RE.captures(name).map(|caps| {
+            let start = caps.get(1).map_or(0, |m| m.start());
+            (start, format!("{}.{}", &caps[1], &caps[2]), 0.85)
+        }),
+    );
+    let edition = scan.set(
+        "edition",
+        EDITION_RES
+            .iter()
+            .find_map(|(re, value)| find(re).map(|(start, _)| (start, value.to_string(), 0.9))),
+    );
+    let repack = scan.set(
+        "repack",
+        find(&REPACK_RE).map(|(start, t)| (start, t, 0.95)),
+    );
+    let proper = scan.set(
+        "proper",
+        find(&PROPER_RE).map(|(start, t)| (start, t, 0.95)),
+    );
+
+    // 语言
+    let mut languages = Vec::new();
+    for word in WORD_RE.find_iter(&normalized) {
+        let (start, token) = (word.start(), word.as_str());
+        let upper = token.to_ascii_uppercase();
+        let code = LANGUAGE_TOKENS
+            .iter()
+            .find(|(t, _)| *t == upper)
+            .or_else(|| LANGUAGE_WORDS.iter().find(|(t, _)| *t == token))
+            .map(|(_, code)| *code);
+        if let Some(code) = code {
+            push_language(&mut languages, code);
+            scan.cut_at(start);
+        }
+    }
+    for (re, code) in CJK_LANGUAGE_RES.iter() {
+        if re.is_match(name) {
+            push_language(&mut languages, code);
+        }
+    }
+    let languages_confidence = (!languages.is_empty()).then_some((0, String::new(), 0.8));
+    scan.set("languages", languages_confidence);
+
+    // 季集
+    let mut season = None;
+    let mut episode = None;
+    if let Some(caps) = scraper::SEASON_EPISODE_RE.captures(name) {
+        let start = caps.get(0).map_or(0, |m| m.start());
+        season = scan.set(
+            "season",
+            caps.get(1).map(|m| (start, m.as_str().to_string(), 0.95)),
+        );
+        episode = scan.set(
+            "episode",
+            caps.get(2).map(|m| (start, m.as_str().to_string(), 0.95)),
+        );
+    } else if let Some(caps) = scraper::EP_RE.captures_iter(name).find(|caps| {
+        // `E01` 必须位于词首，避免 `Once Upon a Time 1984` 里的 `e 1984`
+        let start = caps.get(0).map_or(0, |m| m.start());
+        !caps[0].starts_with(|c: char| c.is_ascii_alphabetic())
+            || name[..start]
+                .chars()
+                .next_back()
+                .is_none_or(|c| !c.is_ascii_alphanumeric())
+    }) {
+        let start = caps.get(0).map_or(0, |m| m.start());
+        episode = scan.set(
+            "episode",
+            caps.get(1).map(|m| (start, m.as_str().to_string(), 0.7)),
+        );
+    }
+    if let Some(m) = scraper::MULTI_EPISODE_RE.find(name) {
+        scan.cut_at(m.start());
+    }
+    let absolute_episode = scraper::absolute_episode_in(name).map(|(start, episode)| {
+        scan.cut_at(start);
+        episode
+    });
+
+    // 年份：括号年份优先，其次标题之后（技术标记之前）的最后一个年份，再次技术标记之后的第一个年份
+    let year = if let Some(caps) = PAREN_YEAR_RE.captures(name) {
+        let start = caps.get(0).map_or(0, |m| m.start());
+        scan.set("year", Some((start, caps[1].to_string(), 0.95)))
+    } else {
+        // 相邻年份（`2049.2017`）共用分隔符，需逐个检查边界而非用带边界的正则
+        let is_sep =
+            |c: Option<char>| c.is_none_or(|c| c.is_whitespace() || "[](){}-+".contains(c));
+        let years: Vec<_> = YEAR_RE
+            .find_iter(&normalized)
+            .filter(|m| m.start() > 0)
+            .filter(|m| {
+                is_sep(normalized[..m.start()].chars().next_back())
+                    && is_sep(normalized[m.end()..].chars().next())
+            })
+            .map(|m| (m.start(), m.as_str().to_string()))
+            .collect();
+        let before_cut = years
+            .iter()
+            .rev()
+            .find(|(start, _)| *start <= scan.cut)
+            .cloned();
+        match before_cut {
+            Some((start, year)) => scan.set("year", Some((start, year, 0.8))),
+            None => years.first().map(|(_, year)| {
+                scan.confidence.insert("year".to_string(), 0.6);
+                year.clone()
+            }),
+        }
+    };
+
+    // 发布组：结尾 `-GROUP`（需有技术标记在前），其次开头 `[Group]`
+    let trailing_group = TRAILING_GROUP_RE
+        .captures(name)
+        .and_then(|c| c.get(1))
+        .filter(|g| scan.cut < name.len() && g.start() > scan.cut)
+        .map(|g| g.as_str().to_string())
+        .filter(|g| !NOT_GROUPS.contains(&g.to_ascii_lowercase().as_str()))
+        .filter(|g| !g.chars().all(|c| c.is_ascii_digit()));
+    let release_group = match trailing_group {
+        Some(group) => {
+            scan.confidence.insert("release_group".to_string(), 0.85);
+            Some(group)
+        }
+        None => LEADING_GROUP_RE
+            .captures(name)
+            .and_then(|c| c.get(1))
+            .map(|g| g.as_str().trim().to_string())
+            .filter(|g| !g.is_empty() && !g.chars().all(|c| c.is_ascii_digit()))
+            .inspect(|_| {
+                scan.confidence.insert("release_group".to_string(), 0.7);
+            }),
+    };
+
+    info.title = clean_title(&name[..scan.cut]);
+    if !info.title.is_empty() {
+        let confidence = if scan.cut < name.len() { 0.9 } else { 0.6 };
+        scan.confidence.insert("title".to_string(), confidence);
+    }
+    info.confidence = scan.confidence;
+    info.year = year.and_then(|y| y.parse().ok());
+    info.season = season.and_then(|s| s.parse().ok());
+    info.episode = episode.and_then(|e| e.parse().ok());
+    info.episode_end = scraper::episode_range_in(name)
+        .filter(|(start, _)| Some(*start) == info.episode)
+        .map(|(_, end)| end);
+    info.absolute_episode = absolute_episode;
+    info.resolution = resolution;
+    info.source = source;
+    info.video_codec = video_codec;
+    info.hdr = hdr;
+    info.audio = audio;
+    info.audio_channels = audio_channels;
+    info.release_group = release_group;
+    info.edition = edition;
+    info.repack = repack.is_some();
+    info.proper = proper.is_some();
+    info.languages = languages;
+    info
+}
+
+/// 解析过程状态：标题截止位置与各字段置信度
+struct Scan {
+    /// 第一个技术标记的位置，标题在此截止
+    cut: usize,
+    confidence: BTreeMap<String, f64>,
+}
+
+impl Scan {
+    fn cut_at(&mut self, start: usize) {
+        if start > 0 {
+            self.cut = self.cut.min(start);
+        }
+    }
+
+    /// 记录字段置信度并在该标记处截断标题，返回字段值
+    fn set(&mut self, field: &str, found: Option<(usize, String, f64)>) -> Option<String> {
+        let (start, value, confidence) = found?;
+        self.confidence.insert(field.to_string(), confidence);
+        self.cut_at(start);
+        Some(value)
+    }
+}
+
+fn push_language(languages: &mut Vec<String>, code: &str) {
+    if !languages.iter().any(|l| l == code) {
+        languages.push(code.to_string());
+    }
+}
+
+/// 清理截取出的标题：去掉开头的 `[Group]`、残留括号与分隔符；没有空格的点分标题还原为空格
+fn clean_title(raw: &str) -> String {
+    let mut title = LEADING_GROUP_RE.replace(raw, "").to_string();
+    title = title.replace("()", "").replace("[]", "");
+    if !title.trim().contains(char::is_whitespace) {
+        title = title.replace(['.', '_'], " ");
+    }
+    title = title
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_matches(|c: char| {
+            c.is_whitespace() || matches!(c, '.' | '-' | '_' | '[' | ']' | '(' | ')' | ',')
+        })
+        .to_string();
+    title
+}
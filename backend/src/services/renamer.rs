@@ -1,16 +1,69 @@
 use crate::models::MediaFile;
+use crate::services::release_parser::{self, ReleaseInfo};
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use utoipa::ToSchema;
+
+/// 单个文件名允许的最大字节数（ext4 / NTFS / SMB 共同上限）
+pub const MAX_NAME_BYTES: usize = 255;
+
+/// 多集文件的末集：元数据优先，其次识别结果，最后从文件名解析
+fn episode_range_end(
+    file: &MediaFile,
+    metadata: Option<&serde_json::Value>,
+    start: u32,
+) -> Option<u32> {
+    metadata
+        .and_then(|m| m.get("episode_end").or_else(|| m.get("episode_number_end")))
+        .and_then(|e| e.as_u64())
+        .map(|e| e as u32)
+        .or_else(|| file.detected_episode_end.map(|e| e as u32))
+        .or_else(|| {
+            crate::services::scraper::parse_episode_range(&file.name)
+                .filter(|(first, _)| *first == start)
+                .map(|(_, end)| end)
+        })
+        .filter(|end| *end > start)
+}
 
 /// 生成新文件名
+///
+/// 模板中的 `/` 表示子目录（如 `{collection}/{title} ({year}).{ext}`），各段分别替换并清理，
+/// 变量值中的 `/` 不会产生目录；值为空的目录段省略。
 pub fn generate_new_name(file: &MediaFile, template: &str) -> Option<String> {
+    let (dirs, name) = match template.rsplit_once('/') {
+        Some((dirs, name)) => (Some(dirs), name),
+        None => (None, template),
+    };
+    let mut segments = Vec::new();
+    for dir in dirs.into_iter().flat_map(|dirs| dirs.split('/')) {
+        let segment = render_segment(file, dir)?;
+        if !segment.trim().is_empty() {
+            segments.push(segment);
+        }
+    }
+    segments.push(render_segment(file, name)?);
+    Some(segments.join("/"))
+}
+
+/// 替换单个路径段中的模板变量并清理无效字符
+fn render_segment(file: &MediaFile, template: &str) -> Option<String> {
     // 解析元数据（如果存在）
     let metadata: Option<serde_json::Value> = file
         .metadata
         .as_ref()
         .and_then(|m| serde_json::from_str(m).ok());
 
+    // 发布名信息：入库时已解析，旧数据即时解析；视频信息缺失时作为补充
+    let release: ReleaseInfo = file
+        .release_info
+        .as_ref()
+        .and_then(|r| serde_json::from_str(r).ok())
+        .unwrap_or_else(|| release_parser::parse(&file.name));
+
     let mut new_name = template.to_string();
 
     // 替换模板变量
@@ -34,24 +87,28 @@ pub fn generate_new_name(file: &MediaFile, template: &str) -> Option<String> {
     }
 
     // {year} - 年份
-    if let Some(year) = metadata.as_ref().and_then(|m| {
-        // 优先尝试直接获取年份（支持数字或字符串）
-        if let Some(y) = m.get("year") {
-            if let Some(n) = y.as_u64() {
-                return Some(n as u32);
-            }
-            if let Some(s) = y.as_str() {
-                if let Ok(n) = s.parse::<u32>() {
-                    return Some(n);
+    if let Some(year) = metadata
+        .as_ref()
+        .and_then(|m| {
+            // 优先尝试直接获取年份（支持数字或字符串）
+            if let Some(y) = m.get("year") {
+                if let Some(n) = y.as_u64() {
+                    return Some(n as u32);
+                }
+                if let Some(s) = y.as_str() {
+                    if let Ok(n) = s.parse::<u32>() {
+                        return Some(n);
+                    }
                 }
             }
-        }
-        // 尝试从 release_date 提取
-        m.get("release_date")
-            .and_then(|y| y.as_str())
-            .and_then(|s| s.split('-').next())
-            .and_then(|y| y.parse::<u32>().ok())
-    }) {
+            // 尝试从 release_date 提取
+            m.get("release_date")
+                .and_then(|y| y.as_str())
+                .and_then(|s| s.split('-').next())
+                .and_then(|y| y.parse::<u32>().ok())
+        })
+        .or(release.year)
+    {
         new_name = new_name.replace("{year}", &year.to_string());
     }
 
@@ -81,7 +138,7 @@ pub fn generate_new_name(file: &MediaFile, template: &str) -> Option<String> {
         }
     }
 
-    // {episode:02d} - 集数（格式化）
+    // {episode:02d} - 集数（格式化），多集文件输出 01-E02
     let episode_re = Regex::new(r"\{episode(?::(\d+)d)?\}").unwrap();
     if let Some(caps) = episode_re.captures(&new_name) {
         let format = caps
@@ -89,24 +146,60 @@ pub fn generate_new_name(file: &MediaFile, template: &str) -> Option<String> {
             .and_then(|m| m.as_str().parse::<usize>().ok())
             .unwrap_or(2);
 
-        if let Some(episode) = metadata
+        let episode = metadata
             .as_ref()
             .and_then(|m| m.get("episode"))
             .and_then(|e| e.as_u64())
             .map(|e| e as u32)
-        {
-            let formatted = format!("{:0width$}", episode, width = format);
-            new_name = episode_re.replace(&new_name, &formatted).to_string();
-        } else {
-            // 从文件名提取
-            let (_, _, _, episode) = crate::services::scraper::parse_filename(&file.name);
-            if let Some(e) = episode {
-                let formatted = format!("{:0width$}", e, width = format);
-                new_name = episode_re.replace(&new_name, &formatted).to_string();
+            .or_else(|| {
+                // 从文件名提取
+                let (_, _, _, episode) = crate::services::scraper::parse_filename(&file.name);
+                episode
+            });
+
+        if let Some(e) = episode {
+            let mut formatted = format!("{:0width$}", e, width = format);
+            if let Some(end) = episode_range_end(file, metadata.as_ref(), e) {
+                formatted.push_str(&format!("-E{:0width$}", end, width = format));
             }
+            new_name = episode_re.replace(&new_name, &formatted).to_string();
         }
     }
 
+    // {absolute:03d} - 绝对集数（番剧），默认补零到 2 位
+    let absolute_re = Regex::new(r"\{absolute(?::(\d+)d)?\}").unwrap();
+    if let Some(caps) = absolute_re.captures(&new_name) {
+        let format = caps
+            .get(1)
+            .and_then(|m| m.as_str().parse::<usize>().ok())
+            .unwrap_or(2);
+
+        if let Some(absolute) = metadata
+            .as_ref()
+            .and_then(|m| m.get("absolute_number"))
+            .and_then(|a| a.as_u64())
+            .map(|a| a as u32)
+            .or_else(|| file.detected_absolute_episode.map(|a| a as u32))
+            .or_else(|| crate::services::scraper::parse_absolute_episode(&file.name))
+        {
+            let formatted = format!("{:0width$}", absolute, width = format);
+            new_name = absolute_re.replace(&new_name, &formatted).to_string();
+        }
+    }
+
+    // {episode_title} - 单集标题；缺失时连同前面的 ` - ` 一起移除
+    if new_name.contains("{episode_title}") {
+        let episode_title = metadata
+            .as_ref()
+            .and_then(|m| m.get("episode_title"))
+            .and_then(|t| t.as_str())
+            .filter(|t| !t.trim().is_empty());
+        if episode_title.is_none() {
+            new_name = new_name.replace(" - {episode_title}", "");
+        }
+        fill_placeholder(&mut new_name, "episode_title", episode_title);
+    }
+
     // {ext} - 扩展名
     let ext = Path::new(&file.name)
         .extension()
@@ -122,137 +215,487 @@ pub fn generate_new_name(file: &MediaFile, template: &str) -> Option<String> {
 
     // {resolution} - 分辨率 (4K, 1080p, 720p等)
     if new_name.contains("{resolution}") {
-        if let Some(ref info) = video_info {
-            if let (Some(w), Some(h)) = (info.width, info.height) {
-                let resolution = if w >= 3840 || h >= 2160 {
-                    "4K"
-                } else if w >= 1920 || h >= 1080 {
-                    "1080p"
-                } else if w >= 1280 || h >= 720 {
-                    "720p"
-                } else {
-                    "SD"
-                };
-                new_name = new_name.replace("{resolution}", resolution);
-            } else {
-                // 没有分辨率信息时，移除{resolution}
-                new_name = new_name.replace(" [{resolution}]", "");
-                new_name = new_name.replace("{resolution}", "");
-            }
-        } else {
-            // 没有视频信息时，移除{resolution}
-            new_name = new_name.replace(" [{resolution}]", "");
-            new_name = new_name.replace("{resolution}", "");
-        }
+        let resolution = video_info
+            .as_ref()
+            .and_then(|info| Some(resolution_label(info.width?, info.height?)))
+            .or_else(|| release_resolution_label(&release));
+        fill_placeholder(&mut new_name, "resolution", resolution);
     }
 
     // {quality} - 质量标签（综合分辨率、HDR、来源等）
-    if let Some(ref info) = video_info {
+    if new_name.contains("{quality}") {
         let mut quality_parts = Vec::new();
+        match video_info {
+            Some(ref info) => {
+                // 分辨率
+                if let (Some(w), Some(h)) = (info.width, info.height) {
+                    let resolution = resolution_label(w, h);
+                    if resolution != "SD" {
+                        quality_parts.push(resolution);
+                    }
+                }
 
-        // 分辨率
-        if let (Some(w), Some(h)) = (info.width, info.height) {
-            if w >= 3840 || h >= 2160 {
-                quality_parts.push("4K");
-            } else if w >= 1920 || h >= 1080 {
-                quality_parts.push("1080p");
-            } else if w >= 1280 || h >= 720 {
-                quality_parts.push("720p");
-            }
-        }
-
-        // HDR信息（优先级：DV > HDR10+ > HDR）
-        if info.is_dolby_vision.unwrap_or(false) {
-            quality_parts.push("DV");
-        } else if info.is_hdr10_plus.unwrap_or(false) {
-            quality_parts.push("HDR10+");
-        } else if info.is_hdr.unwrap_or(false) {
-            quality_parts.push("HDR");
-        }
+                // HDR信息（优先级：DV > HDR10+ > HDR）
+                if let Some(hdr) = hdr_label(info) {
+                    quality_parts.push(hdr);
+                }
 
-        // 来源（只在有值时添加）
-        if let Some(ref source) = info.source {
-            match source.as_str() {
-                "BluRay" => quality_parts.push("BluRay"),
-                "iTunes" => quality_parts.push("iTunes"),
-                "WEB-DL" => quality_parts.push("WEB-DL"),
-                "HDTV" => quality_parts.push("HDTV"),
-                _ => {}
+                // 来源（只在有值时添加）
+                if let Some(ref source) = info.source {
+                    match source.as_str() {
+                        "BluRay" => quality_parts.push("BluRay"),
+                        "iTunes" => quality_parts.push("iTunes"),
+                        "WEB-DL" => quality_parts.push("WEB-DL"),
+                        "HDTV" => quality_parts.push("HDTV"),
+                        _ => {}
+                    }
+                }
+            }
+            None => {
+                // 没有视频信息时使用发布名中的分辨率、HDR 与来源
+                if let Some(resolution) = release_resolution_label(&release) {
+                    if resolution != "SD" {
+                        quality_parts.push(resolution);
+                    }
+                }
+                quality_parts.extend(release.hdr.as_deref());
+                quality_parts.extend(release.source.as_deref());
             }
         }
 
         // 如果模板中有{quality}但质量信息为空，则移除整个{quality}标签（包括方括号）
-        if new_name.contains("{quality}") {
-            if !quality_parts.is_empty() {
-                new_name = new_name.replace("{quality}", &quality_parts.join(" "));
-            } else {
-                // 移除 {quality} 及其可能的方括号和空格
-                new_name = new_name.replace(" [{quality}]", "");
-                new_name = new_name.replace("{quality}", "");
-            }
-        }
-    } else if new_name.contains("{quality}") {
-        // 没有视频信息时，也移除{quality}
-        new_name = new_name.replace(" [{quality}]", "");
-        new_name = new_name.replace("{quality}", "");
+        let quality = (!quality_parts.is_empty()).then(|| quality_parts.join(" "));
+        fill_placeholder(&mut new_name, "quality", quality.as_deref());
     }
 
     // {hdr} - HDR类型
     if new_name.contains("{hdr}") {
-        if let Some(ref info) = video_info {
-            let hdr_type = if info.is_dolby_vision.unwrap_or(false) {
-                "DV"
-            } else if info.is_hdr10_plus.unwrap_or(false) {
-                "HDR10+"
-            } else if info.is_hdr.unwrap_or(false) {
-                "HDR"
-            } else {
-                ""
-            };
-            if !hdr_type.is_empty() {
-                new_name = new_name.replace("{hdr}", hdr_type);
-            } else {
-                // 如果没有HDR信息，移除{hdr}及其可能的方括号和空格
-                new_name = new_name.replace(" [{hdr}]", "");
-                new_name = new_name.replace("{hdr}", "");
-            }
-        } else {
-            // 没有视频信息时，移除{hdr}
-            new_name = new_name.replace(" [{hdr}]", "");
-            new_name = new_name.replace("{hdr}", "");
-        }
+        let hdr = video_info
+            .as_ref()
+            .and_then(hdr_label)
+            .or(release.hdr.as_deref());
+        fill_placeholder(&mut new_name, "hdr", hdr);
     }
 
     // {source} - 来源
     if new_name.contains("{source}") {
-        if let Some(ref info) = video_info {
-            if let Some(ref source) = info.source {
-                new_name = new_name.replace("{source}", source);
-            } else {
-                // 没有来源信息时，移除{source}
-                new_name = new_name.replace(" [{source}]", "");
-                new_name = new_name.replace("{source}", "");
-            }
-        } else {
-            // 没有视频信息时，移除{source}
-            new_name = new_name.replace(" [{source}]", "");
-            new_name = new_name.replace("{source}", "");
-        }
+        let source = video_info
+            .as_ref()
+            .and_then(|info| info.source.as_deref())
+            .or(release.source.as_deref());
+        fill_placeholder(&mut new_name, "source", source);
     }
 
+    // {collection} - 所属电影系列，用于按系列归档
+    let collection = metadata
+        .as_ref()
+        .and_then(|m| m.get("collection"))
+        .and_then(|c| c.get("name"))
+        .and_then(|n| n.as_str())
+        .filter(|n| !n.trim().is_empty());
+    fill_placeholder(&mut new_name, "collection", collection);
+
+    // {codec} / {audio} / {group} / {edition} - 发布名中的编码、音轨、发布组与版本
+    fill_placeholder(&mut new_name, "codec", release.video_codec.as_deref());
+    let audio = release
+        .audio
+        .as_ref()
+        .map(|audio| match &release.audio_channels {
+            Some(channels) => format!("{audio} {channels}"),
+            None => audio.clone(),
+        });
+    fill_placeholder(&mut new_name, "audio", audio.as_deref());
+    fill_placeholder(&mut new_name, "group", release.release_group.as_deref());
+    fill_placeholder(&mut new_name, "edition", release.edition.as_deref());
+
     // 清理无效字符
     new_name = sanitize_filename(&new_name);
 
     Some(new_name)
 }
 
+/// 按像素尺寸给出分辨率标签
+fn resolution_label(width: u32, height: u32) -> &'static str {
+    if width >= 3840 || height >= 2160 {
+        "4K"
+    } else if width >= 1920 || height >= 1080 {
+        "1080p"
+    } else if width >= 1280 || height >= 720 {
+        "720p"
+    } else {
+        "SD"
+    }
+}
+
+/// 发布名中的分辨率，统一为与视频信息相同的标签
+fn release_resolution_label(release: &ReleaseInfo) -> Option<&'static str> {
+    release
+        .resolution_height()
+        .map(|height| resolution_label(0, height))
+}
+
+/// HDR 标签（优先级：DV > HDR10+ > HDR）
+fn hdr_label(info: &crate::models::VideoInfo) -> Option<&'static str> {
+    if info.is_dolby_vision.unwrap_or(false) {
+        Some("DV")
+    } else if info.is_hdr10_plus.unwrap_or(false) {
+        Some("HDR10+")
+    } else if info.is_hdr.unwrap_or(false) {
+        Some("HDR")
+    } else {
+        None
+    }
+}
+
+/// 替换模板变量；没有值时连同前导的 ` [..]` 一起移除
+fn fill_placeholder(name: &mut String, tag: &str, value: Option<&str>) {
+    let placeholder = format!("{{{tag}}}");
+    if !name.contains(&placeholder) {
+        return;
+    }
+    match value {
+        Some(value) => *name = name.replace(&placeholder, value),
+        None => {
+            *name = name.replace(&format!(" [{placeholder}]"), "");
+            *name = name.replace(&placeholder, "");
+        }
+    }
+}
+
+/// Windows/Linux/SMB 文件名无效字符
+const INVALID_CHARS: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
 /// 清理文件名中的无效字符
 pub(crate) fn sanitize_filename(name: &str) -> String {
-    // Windows/Linux 文件名无效字符
-    let invalid_chars = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
-    name.chars()
-        .filter(|c| !invalid_chars.contains(c))
-        .collect()
+    let cleaned: String = name
+        .chars()
+        .filter(|c| !INVALID_CHARS.contains(c) && !c.is_control())
+        .collect();
+    // SMB / Windows 不允许以点或空格结尾
+    cleaned.trim_end_matches(['.', ' ']).to_string()
+}
+
+/// 按字节上限截断文件名，保留扩展名且不切断 UTF-8 字符
+fn truncate_name(name: &str, max_bytes: usize) -> String {
+    if name.len() <= max_bytes {
+        return name.to_string();
+    }
+    let (stem, ext) = split_name(name);
+    let ext_part = ext.map(|e| format!(".{}", e)).unwrap_or_default();
+    let stem = truncate_bytes(stem, max_bytes.saturating_sub(ext_part.len()));
+    format!("{}{}", stem.trim_end(), ext_part)
+}
+
+/// 截断到不超过 `max_bytes` 的最近字符边界
+fn truncate_bytes(value: &str, max_bytes: usize) -> &str {
+    let mut end = max_bytes.min(value.len());
+    while end > 0 && !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    &value[..end]
+}
+
+/// 拆分主文件名与扩展名（隐藏文件如 `.nfo` 视为无扩展名）
+fn split_name(name: &str) -> (&str, Option<&str>) {
+    match name.rfind('.') {
+        Some(idx) if idx > 0 && idx < name.len() - 1 => (&name[..idx], Some(&name[idx + 1..])),
+        _ => (name, None),
+    }
+}
+
+/// 冲突解决策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// 自动修复：清理非法字符、截断超长名称、为冲突目标追加 ` (n)` 后缀
+    #[default]
+    Suffix,
+    /// 跳过存在问题的文件
+    Skip,
+    /// 存在任何问题时整个计划不可执行
+    Fail,
+}
+
+/// 重命名计划中检测到的问题
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RenameIssue {
+    /// 同一批次中多个文件映射到同一目标（大小写不敏感比较）
+    DuplicateTarget,
+    /// 目标目录中已存在同名文件（大小写不敏感比较）
+    TargetExists,
+    /// 仅大小写变化，执行时需经临时名中转
+    CaseOnly,
+    /// 文件名超过 255 字节
+    NameTooLong,
+    /// 文件名包含非法字符
+    InvalidChars,
+}
+
+impl RenameIssue {
+    /// 仅提示、不阻止执行的问题
+    fn is_informational(self) -> bool {
+        matches!(self, RenameIssue::CaseOnly)
+    }
+}
+
+/// 计划中单个文件的处理动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanAction {
+    Rename,
+    Unchanged,
+    Skip,
+    Blocked,
+}
+
+/// 重命名计划条目
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RenamePlanItem {
+    pub file_id: String,
+    pub old_name: String,
+    pub old_path: String,
+    /// 模板生成的原始目标名
+    pub proposed_name: String,
+    /// 解决冲突后的最终目标名
+    pub new_name: String,
+    pub new_path: String,
+    pub action: PlanAction,
+    pub issues: Vec<RenameIssue>,
+}
+
+/// 重命名计划（执行前供用户确认）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RenamePlan {
+    pub policy: ConflictPolicy,
+    pub items: Vec<RenamePlanItem>,
+    pub rename_count: usize,
+    pub skipped_count: usize,
+    pub blocked_count: usize,
+    /// 计划是否可以执行（Fail 策略下存在问题时为 false）
+    pub executable: bool,
+}
+
+impl RenamePlan {
+    /// 按计划顺序返回需要执行的 (file_id, new_name)
+    pub fn rename_items(&self) -> Vec<(String, String)> {
+        self.items
+            .iter()
+            .filter(|item| item.action == PlanAction::Rename)
+            .map(|item| (item.file_id.clone(), item.new_name.clone()))
+            .collect()
+    }
+}
+
+/// 大小写不敏感的名称键（SMB / macOS / Windows 共享均不区分大小写）
+fn name_key(name: &str) -> String {
+    name.to_lowercase()
+}
+
+/// 读取目录现有条目（小写），用于检测已存在的目标
+fn list_dir_keys(dir: &Path) -> HashSet<String> {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| name_key(&entry.file_name().to_string_lossy()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 生成带编号后缀的候选名，例如 `Movie (2).mkv`
+fn suffixed_name(name: &str, n: usize) -> String {
+    let (stem, ext) = split_name(name);
+    let suffix = format!(" ({})", n);
+    let ext_part = ext.map(|e| format!(".{}", e)).unwrap_or_default();
+    let stem = truncate_bytes(
+        stem,
+        MAX_NAME_BYTES.saturating_sub(suffix.len() + ext_part.len()),
+    );
+    format!("{}{}{}", stem.trim_end(), suffix, ext_part)
+}
+
+/// 模板子目录的目标位置：文件所在目录已是该子目录时原地不动，否则建在文件所在目录下
+fn target_dir(source_dir: &Path, sub_dir: Option<&str>) -> PathBuf {
+    match sub_dir {
+        Some(sub_dir) if !source_dir.ends_with(sub_dir) => source_dir.join(sub_dir),
+        _ => source_dir.to_path_buf(),
+    }
+}
+
+/// 记录问题（同一问题只记一次）
+fn push_issue(issues: &mut Vec<RenameIssue>, issue: RenameIssue) {
+    if !issues.contains(&issue) {
+        issues.push(issue);
+    }
+}
+
+/// 生成重命名计划
+///
+/// 按 `proposals` 顺序模拟执行：已计划重命名的文件会释放其原名称，
+/// 因此链式改名（A→B、B→C）只要顺序正确即可通过检查。
+pub fn plan_renames(
+    files: &[MediaFile],
+    proposals: &[(String, String)],
+    policy: ConflictPolicy,
+) -> RenamePlan {
+    let files_by_id: HashMap<&str, &MediaFile> =
+        files.iter().map(|file| (file.id.as_str(), file)).collect();
+    // 目录 -> 当前被占用的名称（随计划推进更新）
+    let mut occupied: HashMap<PathBuf, HashSet<String>> = HashMap::new();
+    // 目录 -> 本批次已分配的目标名称
+    let mut claimed: HashMap<PathBuf, HashSet<String>> = HashMap::new();
+    let mut items = Vec::with_capacity(proposals.len());
+
+    for (file_id, proposed) in proposals {
+        let Some(file) = files_by_id.get(file_id.as_str()) else {
+            continue;
+        };
+        let old_path = PathBuf::from(&file.path);
+        let source_dir = old_path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let mut issues = Vec::new();
+        // 模板生成的子目录（如电影系列）：目录与文件名分开检查，每级目录同样清理与截断
+        let (sub_dir, proposed_name) = match proposed.rsplit_once('/') {
+            Some((sub_dir, name)) => {
+                let mut segments = Vec::new();
+                for segment in sub_dir.split('/') {
+                    let mut segment = sanitize_filename(segment);
+                    if segment.len() > MAX_NAME_BYTES {
+                        push_issue(&mut issues, RenameIssue::NameTooLong);
+                        segment = truncate_bytes(&segment, MAX_NAME_BYTES)
+                            .trim_end()
+                            .to_string();
+                    }
+                    if !segment.is_empty() {
+                        segments.push(segment);
+                    }
+                }
+                if segments.join("/") != sub_dir {
+                    push_issue(&mut issues, RenameIssue::InvalidChars);
+                }
+                ((!segments.is_empty()).then(|| segments.join("/")), name)
+            }
+            None => (None, proposed.as_str()),
+        };
+        let dir = target_dir(&source_dir, sub_dir.as_deref());
+        let occupied_in_dir = occupied
+            .entry(dir.clone())
+            .or_insert_with(|| list_dir_keys(&dir));
+        let claimed_in_dir = claimed.entry(dir.clone()).or_default();
+
+        let mut name = proposed_name.to_string();
+
+        let sanitized = sanitize_filename(&name);
+        if sanitized != name {
+            push_issue(&mut issues, RenameIssue::InvalidChars);
+            name = sanitized;
+        }
+        if name.len() > MAX_NAME_BYTES {
+            push_issue(&mut issues, RenameIssue::NameTooLong);
+            name = truncate_name(&name, MAX_NAME_BYTES);
+        }
+
+        let old_key = name_key(&file.name);
+        let mut key = name_key(&name);
+        // 文件已位于该子目录时视为同一目录，重复执行不会再套一层
+        let same_dir = dir == source_dir;
+        let unchanged = same_dir && name == file.name;
+        let case_only = same_dir && !unchanged && key == old_key;
+        if case_only {
+            issues.push(RenameIssue::CaseOnly);
+        }
+
+        if !unchanged && !case_only {
+            if claimed_in_dir.contains(&key) {
+                issues.push(RenameIssue::DuplicateTarget);
+            } else if occupied_in_dir.contains(&key) {
+                issues.push(RenameIssue::TargetExists);
+            }
+        }
+
+        let has_problem = issues.iter().any(|issue| !issue.is_informational());
+        let collides = issues.iter().any(|issue| {
+            matches!(
+                issue,
+                RenameIssue::DuplicateTarget | RenameIssue::TargetExists
+            )
+        });
+
+        let action = if name.trim().is_empty() {
+            // 清理后为空的名称无法自动修复
+            match policy {
+                ConflictPolicy::Fail => PlanAction::Blocked,
+                _ => PlanAction::Skip,
+            }
+        } else if unchanged {
+            PlanAction::Unchanged
+        } else if !has_problem {
+            PlanAction::Rename
+        } else {
+            match policy {
+                ConflictPolicy::Suffix => {
+                    if collides {
+                        let mut n = 1;
+                        loop {
+                            let candidate = suffixed_name(&name, n);
+                            let candidate_key = name_key(&candidate);
+                            if !occupied_in_dir.contains(&candidate_key)
+                                && !claimed_in_dir.contains(&candidate_key)
+                            {
+                                name = candidate;
+                                key = candidate_key;
+                                break;
+                            }
+                            n += 1;
+                        }
+                    }
+                    PlanAction::Rename
+                }
+                ConflictPolicy::Skip => PlanAction::Skip,
+                ConflictPolicy::Fail => PlanAction::Blocked,
+            }
+        };
+
+        // 即使跳过也登记目标，保证后续同名文件仍被识别为批内重复
+        claimed_in_dir.insert(key.clone());
+        if action == PlanAction::Rename {
+            occupied
+                .entry(source_dir.clone())
+                .or_insert_with(|| list_dir_keys(&source_dir))
+                .remove(&old_key);
+            occupied.entry(dir.clone()).or_default().insert(key);
+        }
+
+        let new_path = dir.join(&name);
+        items.push(RenamePlanItem {
+            file_id: file.id.clone(),
+            old_name: file.name.clone(),
+            old_path: file.path.clone(),
+            proposed_name: proposed.clone(),
+            new_name: match sub_dir {
+                Some(sub_dir) => format!("{sub_dir}/{name}"),
+                None => name,
+            },
+            new_path: new_path.to_string_lossy().to_string(),
+            action,
+            issues,
+        });
+    }
+
+    let count = |action: PlanAction| items.iter().filter(|item| item.action == action).count();
+    let rename_count = count(PlanAction::Rename);
+    let skipped_count = count(PlanAction::Skip);
+    let blocked_count = count(PlanAction::Blocked);
+
+    RenamePlan {
+        policy,
+        executable: blocked_count == 0,
+        rename_count,
+        skipped_count,
+        blocked_count,
+        items,
+    }
 }
 
 /// 批量执行物理磁盘重命名
@@ -262,6 +705,7 @@ pub async fn batch_rename(
     mut ctx: crate::services::task_queue::TaskContext,
 ) -> anyhow::Result<()> {
     let total = rename_items.len();
+    let batch_id = ctx.task_id().to_string();
 
     for (index, (file_id, new_name)) in rename_items.into_iter().enumerate() {
         // 检查暂停/取消
@@ -270,7 +714,7 @@ pub async fn batch_rename(
         }
 
         // 执行单个文件重命名
-        if let Err(e) = rename_file(db, &file_id, &new_name).await {
+        if let Err(e) = rename_file(db, &file_id, &new_name, Some(&batch_id)).await {
             tracing::error!("Failed to rename file {}: {}", file_id, e);
         }
 
@@ -287,8 +731,13 @@ pub async fn batch_rename(
     Ok(())
 }
 
-/// 执行单个文件重命名
-pub async fn rename_file(db: &SqlitePool, file_id: &str, new_name: &str) -> anyhow::Result<()> {
+/// 执行单个文件重命名，`batch_id` 用于将操作日志关联到所属批次
+pub async fn rename_file(
+    db: &SqlitePool,
+    file_id: &str,
+    new_name: &str,
+    batch_id: Option<&str>,
+) -> anyhow::Result<()> {
     // 获取文件信息
     let file: MediaFile = sqlx::query_as("SELECT * FROM media_files WHERE id = ?")
         .bind(file_id)
@@ -296,16 +745,42 @@ pub async fn rename_file(db: &SqlitePool, file_id: &str, new_name: &str) -> anyh
         .await?;
 
     let old_path = PathBuf::from(&file.path);
-    let mut new_path = old_path.clone();
-    new_path.set_file_name(new_name);
+    let source_dir = old_path.parent().map(Path::to_path_buf).unwrap_or_default();
+    // `new_name` 可能带有模板生成的子目录（如 `系列名/电影名.mkv`）
+    let (sub_dir, file_name) = match new_name.rsplit_once('/') {
+        Some((sub_dir, name)) => (Some(sub_dir), name.to_string()),
+        None => (None, new_name.to_string()),
+    };
+    let dir = target_dir(&source_dir, sub_dir);
+    let new_path = dir.join(&file_name);
+
+    let case_only =
+        dir == source_dir && file.name != file_name && name_key(&file.name) == name_key(&file_name);
+
+    // 执行前再次确认，避免覆盖计划生成后新出现的同名文件
+    if !case_only && tokio::fs::try_exists(&new_path).await.unwrap_or(false) {
+        return Err(anyhow::anyhow!(
+            "Target already exists: {}",
+            new_path.display()
+        ));
+    }
 
-    // 重命名文件
-    tokio::fs::rename(&old_path, &new_path).await?;
+    if case_only {
+        // 大小写不敏感的文件系统上直接改名可能是空操作，经临时名中转
+        let temp_path = old_path.with_file_name(format!(".{}.cine-rename", uuid::Uuid::new_v4()));
+        tokio::fs::rename(&old_path, &temp_path).await?;
+        tokio::fs::rename(&temp_path, &new_path).await?;
+    } else {
+        if let Some(parent) = new_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::rename(&old_path, &new_path).await?;
+    }
 
     // 更新数据库
     sqlx::query("UPDATE media_files SET path = ?, name = ?, updated_at = ? WHERE id = ?")
         .bind(new_path.to_string_lossy().to_string())
-        .bind(new_name)
+        .bind(&file_name)
         .bind(chrono::Utc::now())
         .bind(file_id)
         .execute(db)
@@ -318,60 +793,9 @@ pub async fn rename_file(db: &SqlitePool, file_id: &str, new_name: &str) -> anyh
         Some(file_id),
         &file.path,
         Some(new_path.to_string_lossy().as_ref()),
+        batch_id,
     )
     .await;
 
     Ok(())
 }
-
-/// 撤销一次重命名操作
-pub async fn undo_rename_by_log(db: &SqlitePool, log_id: &str) -> anyhow::Result<()> {
-    let log: crate::models::OperationLog =
-        sqlx::query_as("SELECT * FROM operation_logs WHERE id = ?")
-            .bind(log_id)
-            .fetch_one(db)
-            .await?;
-
-    if log.action != "rename" {
-        return Err(anyhow::anyhow!(
-            "Only rename operations can be undone by this function"
-        ));
-    }
-
-    let current_path = log
-        .new_path
-        .ok_or_else(|| anyhow::anyhow!("Missing new_path in log"))?;
-    let target_path = log.old_path;
-
-    // 1. 物理磁盘恢复
-    if Path::new(&current_path).exists() {
-        tokio::fs::rename(&current_path, &target_path).await?;
-    } else {
-        return Err(anyhow::anyhow!(
-            "Current file not found at {}",
-            current_path
-        ));
-    }
-
-    // 2. 数据库恢复
-    let new_name = Path::new(&target_path)
-        .file_name()
-        .and_then(|n| n.to_str())
-        .ok_or_else(|| anyhow::anyhow!("Invalid target path"))?;
-
-    sqlx::query("UPDATE media_files SET path = ?, name = ?, updated_at = ? WHERE id = ?")
-        .bind(&target_path)
-        .bind(new_name)
-        .bind(chrono::Utc::now())
-        .bind(&log.file_id)
-        .execute(db)
-        .await?;
-
-    // 3. 删除该条日志
-    sqlx::query("DELETE FROM operation_logs WHERE id = ?")
-        .bind(log_id)
-        .execute(db)
-        .await?;
-
-    Ok(())
-}
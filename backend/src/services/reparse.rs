@@ -0,0 +1,364 @@
+//! 解析版本升级后的重新识别
+//!
+//! `parse_version` 与当前 [`identify::PARSE_VERSION`] 不同、且未锁定匹配的文件会重新规则解析：
+//! - 解析结果与识别时的快照一致：只更新版本号
+//! - 解析结果变化：重新识别（不使用 AI），匹配或季集变化时写入 reparse_diffs 等待批准，
+//!   批准前不改动已有元数据，也不会在后续任务中重复识别；匹配与季集都未变化时同样只更新版本号
+//! - 重新识别没有推荐候选：保持原样并记下已用当前版本尝试过，解析版本再次升级前不再自动重试
+
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+
+use crate::config::AppConfig;
+use crate::models::MediaFile;
+use crate::services::identify::{self, ApplySelection, IdentifyCandidate, ParsedTitle};
+use crate::services::metadata_provider::{self, ProviderSession};
+use crate::services::review_queue::AutoIdentifyOptions;
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_APPLIED: &str = "applied";
+pub const STATUS_REJECTED: &str = "rejected";
+
+/// 单个文件的重新解析结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReparseOutcome {
+    /// 解析或匹配未变化，已更新版本号
+    Unchanged,
+    /// 已记录差异，等待批准
+    Changed,
+    /// 重新识别没有推荐候选
+    Unresolved,
+}
+
+/// 重新识别任务的统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ReparseSummary {
+    pub unchanged: usize,
+    pub changed: usize,
+    pub unresolved: usize,
+    pub failed: usize,
+}
+
+/// 一条待批准（或已处理）的差异
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReparseDiff {
+    pub file_id: String,
+    pub file_name: String,
+    pub file_path: String,
+    /// pending / applied / rejected
+    pub status: String,
+    pub old_parse_version: Option<String>,
+    pub new_parse_version: String,
+    pub old_parse: ParsedTitle,
+    pub new_parse: ParsedTitle,
+    pub old_provider: Option<String>,
+    pub old_external_id: Option<String>,
+    /// 重新识别推荐的候选
+    pub candidate: IdentifyCandidate,
+    /// 变化的字段：match / season / episode / episode_end / absolute_episode
+    pub changes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(FromRow)]
+struct DiffRow {
+    file_id: String,
+    file_name: String,
+    file_path: String,
+    status: String,
+    old_parse_version: Option<String>,
+    new_parse_version: String,
+    old_parse: String,
+    new_parse: String,
+    old_provider: Option<String>,
+    old_external_id: Option<String>,
+    candidate: String,
+    changes: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<DiffRow> for ReparseDiff {
+    type Error = anyhow::Error;
+
+    fn try_from(row: DiffRow) -> anyhow::Result<Self> {
+        Ok(Self {
+            file_id: row.file_id,
+            file_name: row.file_name,
+            file_path: row.file_path,
+            status: row.status,
+            old_parse_version: row.old_parse_version,
+            new_parse_version: row.new_parse_version,
+            old_parse: serde_json::from_str(&row.old_parse)?,
+            new_parse: serde_json::from_str(&row.new_parse)?,
+            old_provider: row.old_provider,
+            old_external_id: row.old_external_id,
+            candidate: serde_json::from_str(&row.candidate)?,
+            changes: serde_json::from_str(&row.changes)?,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+const DIFF_FIELDS: &str = "d.file_id, f.name AS file_name, f.path AS file_path, d.status, \
+    d.old_parse_version, d.new_parse_version, d.old_parse, d.new_parse, d.old_provider, \
+    d.old_external_id, d.candidate, d.changes, d.created_at, d.updated_at";
+
+/// 解析版本落后于当前版本、未锁定匹配、且当前版本下尚未尝试过的视频文件
+pub async fn stale_file_ids(db: &SqlitePool) -> anyhow::Result<Vec<String>> {
+    let ids: Vec<(String,)> = sqlx::query_as(
+        "SELECT id FROM media_files
+         WHERE file_type = 'video' AND parse_version IS NOT NULL AND parse_version != ?1
+           AND locked_match_provider IS NULL
+           AND (reparse_attempt_version IS NULL OR reparse_attempt_version != ?1)
+         ORDER BY path",
+    )
+    .bind(identify::PARSE_VERSION)
+    .fetch_all(db)
+    .await?;
+    Ok(ids.into_iter().map(|(id,)| id).collect())
+}
+
+/// 重新解析单个文件，必要时重新识别并记录差异
+pub async fn reparse_file(
+    db: &SqlitePool,
+    client: &Client,
+    config: &AppConfig,
+    providers: &ProviderSession,
+    file: &MediaFile,
+) -> anyhow::Result<ReparseOutcome> {
+    let snapshot: Option<String> =
+        sqlx::query_scalar("SELECT parse_snapshot FROM media_files WHERE id = ?")
+            .bind(&file.id)
+            .fetch_one(db)
+            .await?;
+    let old_parse = snapshot
+        .and_then(|snapshot| serde_json::from_str::<ParsedTitle>(&snapshot).ok())
+        .or_else(|| identify::stored_parse(file));
+    let library_type = metadata_provider::library_type(db, &file.path).await?;
+    let new_parse = identify::parse_with_rules(file, library_type.as_deref());
+
+    if old_parse
+        .as_ref()
+        .is_some_and(|old_parse| same_parse(old_parse, &new_parse))
+    {
+        mark_current(db, &file.id, &new_parse).await?;
+        return Ok(ReparseOutcome::Unchanged);
+    }
+
+    let preview = identify::preview_file(db, client, config, providers, file, false).await?;
+    let Some(candidate) = preview.recommended else {
+        record_attempt(db, &file.id).await?;
+        return Ok(ReparseOutcome::Unresolved);
+    };
+    let changes = changed_fields(file, &new_parse, &candidate);
+    if changes.is_empty() {
+        mark_current(db, &file.id, &new_parse).await?;
+        return Ok(ReparseOutcome::Unchanged);
+    }
+
+    let old_parse = old_parse.unwrap_or_else(|| new_parse.clone());
+    let now = Utc::now();
+    sqlx::query(
+        "INSERT INTO reparse_diffs (file_id, status, old_parse_version, new_parse_version, old_parse, new_parse,
+             old_provider, old_external_id, candidate, changes, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(file_id) DO UPDATE SET
+             status = excluded.status,
+             old_parse_version = excluded.old_parse_version,
+             new_parse_version = excluded.new_parse_version,
+             old_parse = excluded.old_parse,
+             new_parse = excluded.new_parse,
+             old_provider = excluded.old_provider,
+             old_external_id = excluded.old_external_id,
+             candidate = excluded.candidate,
+             changes = excluded.changes,
+             updated_at = excluded.updated_at",
+    )
+    .bind(&file.id)
+    .bind(STATUS_PENDING)
+    .bind(&file.parse_version)
+    .bind(identify::PARSE_VERSION)
+    .bind(serde_json::to_string(&old_parse)?)
+    .bind(serde_json::to_string(&new_parse)?)
+    .bind(&file.match_provider)
+    .bind(&file.match_external_id)
+    .bind(serde_json::to_string(&candidate)?)
+    .bind(serde_json::to_string(&changes)?)
+    .bind(now)
+    .bind(now)
+    .execute(db)
+    .await?;
+    // 差异待审核期间不再重复识别，审核结果会更新解析版本
+    record_attempt(db, &file.id).await?;
+    Ok(ReparseOutcome::Changed)
+}
+
+/// 列出差异，`status` 为空时返回全部
+pub async fn list(
+    db: &SqlitePool,
+    status: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> anyhow::Result<Vec<ReparseDiff>> {
+    let rows = sqlx::query_as::<_, DiffRow>(&format!(
+        "SELECT {DIFF_FIELDS} FROM reparse_diffs d JOIN media_files f ON f.id = d.file_id
+         WHERE ?1 IS NULL OR d.status = ?1
+         ORDER BY f.path LIMIT ?2 OFFSET ?3"
+    ))
+    .bind(status)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(db)
+    .await?;
+    rows.into_iter().map(ReparseDiff::try_from).collect()
+}
+
+pub async fn get(db: &SqlitePool, file_id: &str) -> anyhow::Result<Option<ReparseDiff>> {
+    let row = sqlx::query_as::<_, DiffRow>(&format!(
+        "SELECT {DIFF_FIELDS} FROM reparse_diffs d JOIN media_files f ON f.id = d.file_id
+         WHERE d.file_id = ?"
+    ))
+    .bind(file_id)
+    .fetch_optional(db)
+    .await?;
+    row.map(ReparseDiff::try_from).transpose()
+}
+
+/// 批准差异：写入新的解析字段后应用推荐候选
+pub async fn approve(
+    db: &SqlitePool,
+    client: &Client,
+    config: &AppConfig,
+    providers: &ProviderSession,
+    file_id: &str,
+    options: AutoIdentifyOptions,
+) -> anyhow::Result<Value> {
+    let diff = get(db, file_id)
+        .await?
+        .filter(|diff| diff.status == STATUS_PENDING)
+        .ok_or_else(|| anyhow::anyhow!("pending reparse diff not found: {file_id}"))?;
+    let parse = &diff.new_parse;
+    sqlx::query(
+        "UPDATE media_files
+         SET detected_title = ?, detected_year = ?, detected_season = ?, detected_episode = ?,
+             detected_episode_end = ?, detected_absolute_episode = ?
+         WHERE id = ?",
+    )
+    .bind(&parse.title)
+    .bind(parse.year.map(|value| value as i32))
+    .bind(parse.season.map(|value| value as i32))
+    .bind(parse.episode.map(|value| value as i32))
+    .bind(parse.episode_end.map(|value| value as i32))
+    .bind(parse.absolute_episode.map(|value| value as i32))
+    .bind(file_id)
+    .execute(db)
+    .await?;
+
+    let selection = ApplySelection {
+        file_id: file_id.to_string(),
+        provider: diff.candidate.provider.clone(),
+        external_id: diff.candidate.external_id.clone(),
+        media_type: diff.candidate.media_type.clone(),
+        lock_match: false,
+        download_images: options.download_images,
+        generate_nfo: options.generate_nfo,
+    };
+    let metadata = identify::apply_selection(db, client, config, providers, &selection).await?;
+    set_status(db, file_id, STATUS_APPLIED).await?;
+    Ok(metadata)
+}
+
+/// 拒绝差异：保留原匹配，版本号更新为当前版本，后续任务不再处理
+pub async fn reject(db: &SqlitePool, file_id: &str) -> anyhow::Result<bool> {
+    let Some(diff) = get(db, file_id)
+        .await?
+        .filter(|diff| diff.status == STATUS_PENDING)
+    else {
+        return Ok(false);
+    };
+    mark_current(db, file_id, &diff.new_parse).await?;
+    set_status(db, file_id, STATUS_REJECTED).await?;
+    Ok(true)
+}
+
+/// 两次解析是否一致（标题按归一化比较）
+fn same_parse(old: &ParsedTitle, new: &ParsedTitle) -> bool {
+    identify::title_similarity(&old.title, &new.title) >= 1.0
+        && old.year == new.year
+        && old.season == new.season
+        && old.episode == new.episode
+        && old.episode_end == new.episode_end
+        && old.absolute_episode == new.absolute_episode
+        && old.is_special == new.is_special
+}
+
+/// 与当前匹配相比变化的字段；新解析缺失的季集不算变化（可能由绝对集数映射得到）
+fn changed_fields(
+    file: &MediaFile,
+    new_parse: &ParsedTitle,
+    candidate: &IdentifyCandidate,
+) -> Vec<String> {
+    let mut changes = Vec::new();
+    if file.match_provider.as_deref() != Some(candidate.provider.as_str())
+        || file.match_external_id.as_deref() != Some(candidate.external_id.as_str())
+    {
+        changes.push("match".to_string());
+    }
+    for (field, stored, parsed) in [
+        ("season", file.detected_season, new_parse.season),
+        ("episode", file.detected_episode, new_parse.episode),
+        (
+            "episode_end",
+            file.detected_episode_end,
+            new_parse.episode_end,
+        ),
+        (
+            "absolute_episode",
+            file.detected_absolute_episode,
+            new_parse.absolute_episode,
+        ),
+    ] {
+        if parsed.is_some() && stored.map(|value| value as u32) != parsed {
+            changes.push(field.to_string());
+        }
+    }
+    changes
+}
+
+async fn mark_current(db: &SqlitePool, file_id: &str, parsed: &ParsedTitle) -> anyhow::Result<()> {
+    sqlx::query("UPDATE media_files SET parse_version = ?, parse_snapshot = ? WHERE id = ?")
+        .bind(identify::PARSE_VERSION)
+        .bind(serde_json::to_string(parsed)?)
+        .bind(file_id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// 记下当前解析版本已尝试过重新识别，避免每次任务都重复查询
+async fn record_attempt(db: &SqlitePool, file_id: &str) -> anyhow::Result<()> {
+    sqlx::query("UPDATE media_files SET reparse_attempt_version = ? WHERE id = ?")
+        .bind(identify::PARSE_VERSION)
+        .bind(file_id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+async fn set_status(db: &SqlitePool, file_id: &str, status: &str) -> anyhow::Result<()> {
+    sqlx::query("UPDATE reparse_diffs SET status = ?, updated_at = ? WHERE file_id = ?")
+        .bind(status)
+        .bind(Utc::now())
+        .bind(file_id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,334 @@
+//! 自动识别与复核队列
+//!
+//! 后台识别任务对每个文件执行识别预览：推荐候选达到自动应用阈值时直接应用，
+//! 否则连同解析结果与候选列表写入 identify_review_queue 等待人工处理。
+//! 锁定了匹配（`locked_match_*`）的文件不会被自动识别覆盖；人工接受或选择候选时记为标题别名。
+//!
+//! media_files.review_state 的取值：
+//! `auto_applied`（自动应用）、`pending_review`（待复核）、`applied`（人工应用）、`rejected`（已拒绝）。
+
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+
+use crate::config::AppConfig;
+use crate::models::MediaFile;
+use crate::services::identify::{
+    self, ApplySelection, IdentifyCandidate, IdentifyPreview, ParsedTitle,
+};
+use crate::services::metadata_provider::ProviderSession;
+
+pub const STATE_AUTO_APPLIED: &str = "auto_applied";
+pub const STATE_PENDING_REVIEW: &str = "pending_review";
+pub const STATE_REJECTED: &str = "rejected";
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_ACCEPTED: &str = "accepted";
+pub const STATUS_REJECTED: &str = "rejected";
+
+/// 自动识别与接受复核项时的应用选项
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+pub struct AutoIdentifyOptions {
+    pub allow_ai: bool,
+    pub download_images: bool,
+    pub generate_nfo: bool,
+}
+
+impl Default for AutoIdentifyOptions {
+    fn default() -> Self {
+        Self {
+            allow_ai: true,
+            download_images: true,
+            generate_nfo: true,
+        }
+    }
+}
+
+/// 单个文件的自动识别结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoOutcome {
+    /// 得分达到阈值，已自动应用
+    Applied,
+    /// 已写入复核队列
+    Queued,
+    /// 匹配已锁定，跳过
+    Locked,
+}
+
+/// 复核队列中的一项
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReviewItem {
+    pub file_id: String,
+    pub file_name: String,
+    pub file_path: String,
+    /// pending / accepted / rejected
+    pub status: String,
+    /// 入队原因：no_candidates / low_score / low_confidence / needs_review
+    pub reason: String,
+    pub parse: ParsedTitle,
+    pub candidates: Vec<IdentifyCandidate>,
+    pub recommended: Option<IdentifyCandidate>,
+    /// 推荐候选的得分
+    pub score: Option<f64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(FromRow)]
+struct ReviewRow {
+    file_id: String,
+    file_name: String,
+    file_path: String,
+    status: String,
+    reason: String,
+    parse: String,
+    candidates: String,
+    recommended: Option<String>,
+    score: Option<f64>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<ReviewRow> for ReviewItem {
+    type Error = anyhow::Error;
+
+    fn try_from(row: ReviewRow) -> anyhow::Result<Self> {
+        Ok(Self {
+            file_id: row.file_id,
+            file_name: row.file_name,
+            file_path: row.file_path,
+            status: row.status,
+            reason: row.reason,
+            parse: serde_json::from_str(&row.parse)?,
+            candidates: serde_json::from_str(&row.candidates)?,
+            recommended: row
+                .recommended
+                .as_deref()
+                .map(serde_json::from_str)
+                .transpose()?,
+            score: row.score,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+const REVIEW_FIELDS: &str =
+    "q.file_id, f.name AS file_name, f.path AS file_path, q.status, q.reason, \
+    q.parse, q.candidates, q.recommended, q.score, q.created_at, q.updated_at";
+
+/// 尚未识别、未锁定、也未进入复核流程的视频文件
+pub async fn unidentified_file_ids(db: &SqlitePool) -> anyhow::Result<Vec<String>> {
+    let ids: Vec<(String,)> = sqlx::query_as(
+        "SELECT id FROM media_files
+         WHERE file_type = 'video' AND match_provider IS NULL AND locked_match_provider IS NULL
+           AND review_state IS NULL
+         ORDER BY path",
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(ids.into_iter().map(|(id,)| id).collect())
+}
+
+/// 识别单个文件：高分结果自动应用，其余写入复核队列
+pub async fn auto_identify_file(
+    db: &SqlitePool,
+    client: &Client,
+    config: &AppConfig,
+    providers: &ProviderSession,
+    file: &MediaFile,
+    options: AutoIdentifyOptions,
+) -> anyhow::Result<AutoOutcome> {
+    if file.locked_match_provider.is_some() && file.locked_match_external_id.is_some() {
+        return Ok(AutoOutcome::Locked);
+    }
+
+    let preview =
+        identify::preview_file(db, client, config, providers, file, options.allow_ai).await?;
+    let (Some(recommended), None) = (&preview.recommended, preview.review_reason()) else {
+        enqueue(db, &preview).await?;
+        return Ok(AutoOutcome::Queued);
+    };
+
+    let selection = ApplySelection {
+        file_id: file.id.clone(),
+        provider: recommended.provider.clone(),
+        external_id: recommended.external_id.clone(),
+        media_type: recommended.media_type.clone(),
+        lock_match: false,
+        download_images: options.download_images,
+        generate_nfo: options.generate_nfo,
+    };
+    identify::apply_selection(db, client, config, providers, &selection).await?;
+    sqlx::query("UPDATE media_files SET review_state = ?, parser_provider = ? WHERE id = ?")
+        .bind(STATE_AUTO_APPLIED)
+        .bind(&preview.parse.parser_provider)
+        .bind(&file.id)
+        .execute(db)
+        .await?;
+    Ok(AutoOutcome::Applied)
+}
+
+/// 写入（或刷新）复核项，并把文件标记为待复核
+pub async fn enqueue(db: &SqlitePool, preview: &IdentifyPreview) -> anyhow::Result<()> {
+    let reason = preview.review_reason().unwrap_or("needs_review");
+    let now = Utc::now();
+    let mut tx = db.begin().await?;
+    sqlx::query(
+        "INSERT INTO identify_review_queue (file_id, status, reason, parse, candidates, recommended, score, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(file_id) DO UPDATE SET
+             status = excluded.status,
+             reason = excluded.reason,
+             parse = excluded.parse,
+             candidates = excluded.candidates,
+             recommended = excluded.recommended,
+             score = excluded.score,
+             updated_at = excluded.updated_at",
+    )
+    .bind(&preview.file_id)
+    .bind(STATUS_PENDING)
+    .bind(reason)
+    .bind(serde_json::to_string(&preview.parse)?)
+    .bind(serde_json::to_string(&preview.candidates)?)
+    .bind(
+        preview
+            .recommended
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?,
+    )
+    .bind(preview.recommended.as_ref().map(|candidate| candidate.score))
+    .bind(now)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query("UPDATE media_files SET review_state = ?, updated_at = ? WHERE id = ?")
+        .bind(STATE_PENDING_REVIEW)
+        .bind(now)
+        .bind(&preview.file_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// 列出复核项，`status` 为空时返回全部
+pub async fn list(
+    db: &SqlitePool,
+    status: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> anyhow::Result<Vec<ReviewItem>> {
+    let rows = sqlx::query_as::<_, ReviewRow>(&format!(
+        "SELECT {REVIEW_FIELDS} FROM identify_review_queue q JOIN media_files f ON f.id = q.file_id
+         WHERE ?1 IS NULL OR q.status = ?1
+         ORDER BY q.created_at, f.path LIMIT ?2 OFFSET ?3"
+    ))
+    .bind(status)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(db)
+    .await?;
+    rows.into_iter().map(ReviewItem::try_from).collect()
+}
+
+pub async fn get(db: &SqlitePool, file_id: &str) -> anyhow::Result<Option<ReviewItem>> {
+    let row = sqlx::query_as::<_, ReviewRow>(&format!(
+        "SELECT {REVIEW_FIELDS} FROM identify_review_queue q JOIN media_files f ON f.id = q.file_id
+         WHERE q.file_id = ?"
+    ))
+    .bind(file_id)
+    .fetch_optional(db)
+    .await?;
+    row.map(ReviewItem::try_from).transpose()
+}
+
+/// 应用成功后关闭待复核项（手动应用同样会调用）
+pub async fn mark_accepted(db: &SqlitePool, file_id: &str) -> anyhow::Result<()> {
+    sqlx::query(
+        "UPDATE identify_review_queue SET status = ?, updated_at = ? WHERE file_id = ? AND status = ?",
+    )
+    .bind(STATUS_ACCEPTED)
+    .bind(Utc::now())
+    .bind(file_id)
+    .bind(STATUS_PENDING)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// 接受推荐候选
+pub async fn accept(
+    db: &SqlitePool,
+    client: &Client,
+    config: &AppConfig,
+    providers: &ProviderSession,
+    file_id: &str,
+    options: AutoIdentifyOptions,
+) -> anyhow::Result<Value> {
+    let item = get(db, file_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("review item not found: {file_id}"))?;
+    let recommended = item
+        .recommended
+        .ok_or_else(|| anyhow::anyhow!("review item has no recommended candidate: {file_id}"))?;
+    let selection = ApplySelection {
+        file_id: file_id.to_string(),
+        provider: recommended.provider,
+        external_id: recommended.external_id,
+        media_type: recommended.media_type,
+        lock_match: false,
+        download_images: options.download_images,
+        generate_nfo: options.generate_nfo,
+    };
+    let metadata = identify::apply_selection(db, client, config, providers, &selection).await?;
+    identify::learn_alias_best_effort(db, &selection).await;
+    Ok(metadata)
+}
+
+/// 选择指定候选（或任意 provider / external_id）并应用
+pub async fn choose(
+    db: &SqlitePool,
+    client: &Client,
+    config: &AppConfig,
+    providers: &ProviderSession,
+    selection: &ApplySelection,
+) -> anyhow::Result<Value> {
+    if get(db, &selection.file_id).await?.is_none() {
+        anyhow::bail!("review item not found: {}", selection.file_id);
+    }
+    let metadata = identify::apply_selection(db, client, config, providers, selection).await?;
+    identify::learn_alias_best_effort(db, selection).await;
+    Ok(metadata)
+}
+
+/// 拒绝复核项：文件标记为已拒绝，后续自动识别不再处理
+pub async fn reject(db: &SqlitePool, file_id: &str) -> anyhow::Result<bool> {
+    let now = Utc::now();
+    let mut tx = db.begin().await?;
+    let updated = sqlx::query(
+        "UPDATE identify_review_queue SET status = ?, updated_at = ? WHERE file_id = ? AND status = ?",
+    )
+    .bind(STATUS_REJECTED)
+    .bind(now)
+    .bind(file_id)
+    .bind(STATUS_PENDING)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    if updated > 0 {
+        sqlx::query("UPDATE media_files SET review_state = ?, updated_at = ? WHERE id = ?")
+            .bind(STATE_REJECTED)
+            .bind(now)
+            .bind(file_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(updated > 0)
+}
@@ -5,6 +5,7 @@ use std::path::Path;
 use uuid::Uuid;
 
 use crate::models::MediaFile;
+use crate::services::release_parser;
 use tokio::sync::mpsc;
 
 // 批量插入的批次大小优化，适应高 IOPS 环境
@@ -55,6 +56,11 @@ pub async fn scan_directory(
         total_inserted
     });
 
+    // 番剧库按字幕组命名解析，扫描前一次性读取各媒体库类型
+    let library_types = crate::services::metadata_provider::library_types(db)
+        .await
+        .unwrap_or_default();
+
     let mut file_count = 0u64;
     let mut total_size = 0i64;
     let mut file_type_counts = std::collections::HashMap::new();
@@ -95,14 +101,26 @@ pub async fn scan_directory(
             .unwrap()
             .as_secs() as i64;
 
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+        // 视频文件入库时即解析发布名，供重命名模板与质量评分使用
+        let release_info = (file_type == "video")
+            .then(|| {
+                let path = path.to_string_lossy();
+                let library_type =
+                    crate::services::metadata_provider::match_library_type(&library_types, &path);
+                let release = release_parser::parse_for_library(&name, library_type.as_deref());
+                serde_json::to_string(&release).ok()
+            })
+            .flatten();
+
         let file = MediaFile {
             id: Uuid::new_v4().to_string(),
             path: path.to_string_lossy().to_string(),
-            name: path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("unknown")
-                .to_string(),
+            name,
             size,
             file_type: file_type.clone(),
             hash_xxhash: None,
@@ -115,6 +133,9 @@ pub async fn scan_directory(
             detected_year: None,
             detected_season: None,
             detected_episode: None,
+            detected_episode_end: None,
+            detected_absolute_episode: None,
+            release_info,
             parser_provider: None,
             parse_version: None,
             confidence_score: None,
@@ -124,6 +145,10 @@ pub async fn scan_directory(
             locked_match_provider: None,
             locked_match_external_id: None,
             ai_disabled_reason: None,
+            movie_id: None,
+            show_id: None,
+            season_id: None,
+            episode_id: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             last_modified: chrono::DateTime::from_timestamp(modified, 0).unwrap_or(Utc::now()),
@@ -186,10 +211,11 @@ async fn batch_insert_files(db: &SqlitePool, files: &[MediaFile]) -> anyhow::Res
     for file in files {
         sqlx::query(
             r#"
-            INSERT INTO media_files (id, path, name, size, file_type, last_modified, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO media_files (id, path, name, size, file_type, release_info, last_modified, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
             ON CONFLICT(path) DO UPDATE SET
                 size = excluded.size,
+                release_info = excluded.release_info,
                 last_modified = excluded.last_modified,
                 updated_at = excluded.updated_at
             "#
@@ -199,6 +225,7 @@ async fn batch_insert_files(db: &SqlitePool, files: &[MediaFile]) -> anyhow::Res
         .bind(&file.name)
         .bind(file.size)
         .bind(&file.file_type)
+        .bind(&file.release_info)
         .bind(file.last_modified.to_rfc3339())
         .bind(file.created_at.to_rfc3339())
         .bind(file.updated_at.to_rfc3339())
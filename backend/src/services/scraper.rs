@@ -1,23 +1,50 @@
 use crate::config::AppConfig;
 use crate::models::{MediaFile, MovieMetadata, TVShowMetadata};
-use crate::services::identify;
+use crate::services::metadata_provider::ProviderSession;
+use crate::services::plugin::PluginManager;
+use crate::services::task_queue::TaskContext;
+use crate::services::{identify, provider_guard, release_parser};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use reqwest::Client;
 use serde_json::Value;
 use sqlx::SqlitePool;
+use std::sync::Arc;
 
 // 预编译正则表达式（避免每次调用时重新编译）
-static YEAR_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b(19|20)\d{2}\b").unwrap());
-
-static SEASON_EPISODE_RE: Lazy<Regex> = Lazy::new(|| {
+pub(crate) static SEASON_EPISODE_RE: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"(?i)(?:S|Season|第)\s*(\d+)\s*[. -]*\s*(?:E|Episode|集)\s*(\d+)").unwrap()
 });
 
-static EP_RE: Lazy<Regex> =
+pub(crate) static EP_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?i)(?:EP|E|Episode|集|第)\s*[. -]?\s*(\d+)").unwrap());
 
-static WHITESPACE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s+").unwrap());
+// 多集：S01E01-E02、S01E01E02、S01E01-02、第01-02集
+pub(crate) static MULTI_EPISODE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)S(\d{1,2})\s*E(\d{1,4})((?:\s*-\s*(?:S\d{1,2})?E?\d{1,4}|E\d{1,4})+)\b|第\s*(\d{1,4})\s*[-~至]\s*(\d{1,4})\s*集")
+        .unwrap()
+});
+
+static EPISODE_NUMBER_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)(?:S\d{1,2})?E?(\d{1,4})").unwrap());
+
+// 绝对集数：`Title - 137 [1080p]`、`Title - 05v2`、`[Group][Title][137]`
+static ABSOLUTE_EPISODE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:^|[\s\]_])-\s*(\d{1,4})(?:v\d+)?(?:\s*END)?\s*(?:[\[(]|$)|\[(\d{2,3})(?:v\d+)?(?:\s*END)?\]")
+        .unwrap()
+});
+
+// 季目录：`Season 2`、`S02`、`Series 3`、`第二季`、`Show Season 2`；`Specials` 视为第 0 季
+static SEASON_FOLDER_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:^|[\s._-])(?:season|series)\s*[._ -]?\s*(\d{1,2})\s*$|(?:^|[\s._-])S(\d{1,2})\s*$|第\s*([0-9一二三四五六七八九十两]{1,3})\s*季\s*$|^\s*(specials?|SP)\s*$")
+        .unwrap()
+});
+
+// 季目录内仅含集号的文件名：`02.mkv`、`E02.mkv`、`02 - Title.mkv`
+static BARE_EPISODE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^\s*(?:EP?|第)?\s*(\d{1,4})(?:v\d+)?\s*(?:集|话|話)?\s*(?:[-._ \[(]|$)")
+        .unwrap()
+});
 
 pub(crate) fn tmdb_api_base_url() -> String {
     std::env::var("CINE_TMDB_API_BASE_URL")
@@ -33,46 +60,119 @@ pub(crate) fn bangumi_api_base_url() -> String {
         .to_string()
 }
 
-/// 从文件名提取影视信息（使用预编译正则表达式）
+/// 去掉文件扩展名（最后一个 `.` 之后的部分）
+pub(crate) fn strip_extension(filename: &str) -> &str {
+    filename.rsplit_once('.').map_or(filename, |(stem, _)| stem)
+}
+
+/// 从文件名提取影视信息（标题、年份、季、集）
+///
+/// 完整的发布名字段（分辨率、来源、编码、发布组等）见 [`release_parser::parse`]。
 pub fn parse_filename(filename: &str) -> (String, Option<u32>, Option<u32>, Option<u32>) {
-    // 移除扩展名
-    let name = filename.rsplit('.').skip(1).collect::<Vec<_>>().join(".");
+    let info = release_parser::parse(filename);
+    (info.title, info.year, info.season, info.episode)
+}
+
+/// 从目录名提取影视信息（目录名没有扩展名，`Mr. Robot (2015)` 中的点需保留）
+pub fn parse_folder_name(folder: &str) -> (String, Option<u32>, Option<u32>, Option<u32>) {
+    let info = release_parser::parse_release_name(folder);
+    (info.title, info.year, info.season, info.episode)
+}
 
-    // 提取年份
-    let year = YEAR_RE
-        .find(&name)
-        .and_then(|m| m.as_str().parse::<u32>().ok());
+/// 从文件名提取多集范围（首集, 末集），单集文件返回 None
+///
+/// `S01E01-E03` 与 `S01E01E02E03` 均视为连续区间。
+pub fn parse_episode_range(filename: &str) -> Option<(u32, u32)> {
+    episode_range_in(strip_extension(filename))
+}
 
-    // 提取剧集信息：S01E01, S1E1, 第1集, EP01
-    let (season, episode) = if let Some(caps) = SEASON_EPISODE_RE.captures(&name) {
+/// 同 [`parse_episode_range`]，但输入不含扩展名
+pub(crate) fn episode_range_in(name: &str) -> Option<(u32, u32)> {
+    let caps = MULTI_EPISODE_RE.captures(name)?;
+
+    let (start, last) = if let Some(start) = caps.get(2) {
+        let start = start.as_str().parse::<u32>().ok()?;
+        let last = EPISODE_NUMBER_RE
+            .captures_iter(caps.get(3)?.as_str())
+            .filter_map(|c| c.get(1)?.as_str().parse::<u32>().ok())
+            .max()?;
+        (start, last)
+    } else {
         (
-            caps.get(1).and_then(|m| m.as_str().parse::<u32>().ok()),
-            caps.get(2).and_then(|m| m.as_str().parse::<u32>().ok()),
+            caps.get(4)?.as_str().parse::<u32>().ok()?,
+            caps.get(5)?.as_str().parse::<u32>().ok()?,
         )
+    };
+
+    (last > start).then_some((start, last))
+}
+
+/// 解析季目录名，返回季号与去掉季标记后的剩余部分（如 `Show Season 2` 中的 `Show`）
+pub fn parse_season_folder(folder: &str) -> Option<(u32, String)> {
+    let caps = SEASON_FOLDER_RE.captures(folder)?;
+    let season = if caps.get(4).is_some() {
+        0
+    } else if let Some(number) = caps.get(1).or_else(|| caps.get(2)) {
+        number.as_str().parse::<u32>().ok()?
     } else {
-        // 尝试其他格式：EP01, E01
-        let episode = EP_RE
-            .captures(&name)
-            .and_then(|caps| caps.get(1))
-            .and_then(|m| m.as_str().parse::<u32>().ok());
-        (None, episode)
+        let number = caps.get(3)?.as_str();
+        number
+            .parse::<u32>()
+            .ok()
+            .or_else(|| chinese_number(number))?
     };
+    let rest = folder[..caps.get(0)?.start()]
+        .trim_matches(|c: char| c.is_whitespace() || matches!(c, '.' | '-' | '_'))
+        .to_string();
+    Some((season, rest))
+}
 
-    // 清理标题（移除年份、集数等信息）
-    let mut title = name.clone();
-    if let Some(year_match) = YEAR_RE.find(&name) {
-        title = title.replace(year_match.as_str(), "").trim().to_string();
-    }
-    title = SEASON_EPISODE_RE.replace_all(&title, "").to_string();
-    title = EP_RE.replace_all(&title, "").to_string();
+/// 解析季目录内只含集号的文件名（`02.mkv`、`E02 - Title.mkv`）
+pub fn parse_bare_episode(filename: &str) -> Option<u32> {
+    BARE_EPISODE_RE
+        .captures(strip_extension(filename))?
+        .get(1)?
+        .as_str()
+        .parse::<u32>()
+        .ok()
+        .filter(|n| *n > 0 && !(1900..2100).contains(n))
+}
 
-    // 清理残留的括号和多余空格
-    title = title.replace("()", "").replace("[]", "");
-    title = WHITESPACE_RE.replace_all(&title, " ").to_string();
+/// 中文数字（一 ~ 九十九）转整数
+pub(crate) fn chinese_number(text: &str) -> Option<u32> {
+    let digit = |c: char| "零一二三四五六七八九".find(c).map(|i| (i / 3) as u32);
+    let chars: Vec<char> = text
+        .chars()
+        .map(|c| if c == '两' { '二' } else { c })
+        .collect();
+    match chars.as_slice() {
+        ['十'] => Some(10),
+        [d] => digit(*d).filter(|n| *n > 0),
+        ['十', d] => Some(10 + digit(*d)?),
+        [t, '十'] => Some(digit(*t)? * 10),
+        [t, '十', d] => Some(digit(*t)? * 10 + digit(*d)?),
+        _ => None,
+    }
+}
 
-    title = title.trim().to_string();
+/// 从文件名提取绝对集数（无季信息的番剧发布格式），含季号的文件名返回 None
+pub fn parse_absolute_episode(filename: &str) -> Option<u32> {
+    absolute_episode_in(strip_extension(filename)).map(|(_, episode)| episode)
+}
 
-    (title, year, season, episode)
+/// 同 [`parse_absolute_episode`]，但输入不含扩展名；同时返回集数标记的起始位置
+pub(crate) fn absolute_episode_in(name: &str) -> Option<(usize, u32)> {
+    if SEASON_EPISODE_RE.is_match(name) {
+        return None;
+    }
+    let caps = ABSOLUTE_EPISODE_RE.captures(name)?;
+    let episode = caps
+        .get(1)
+        .or_else(|| caps.get(2))
+        .and_then(|m| m.as_str().parse::<u32>().ok())
+        // 排除 `Movie - 2019` 这类年份
+        .filter(|n| *n > 0 && !(1900..2100).contains(n))?;
+    Some((caps.get(0)?.start(), episode))
 }
 
 /// 从 TMDB 搜索电影（使用共享 HTTP 客户端）
@@ -81,6 +181,7 @@ pub async fn search_movie_tmdb(
     title: &str,
     year: Option<u32>,
     api_key: &str,
+    language: Option<&str>,
 ) -> anyhow::Result<Vec<MovieMetadata>> {
     let mut url = format!(
         "{}/search/movie?api_key={}&query={}",
@@ -88,6 +189,9 @@ pub async fn search_movie_tmdb(
         api_key,
         urlencoding::encode(title)
     );
+    if let Some(language) = language {
+        url.push_str(&format!("&language={}", language));
+    }
 
     if let Some(y) = year {
         url.push_str(&format!("&year={}", y));
@@ -96,7 +200,7 @@ pub async fn search_movie_tmdb(
     crate::services::metrics::METRICS
         .scrape_requests_total
         .inc();
-    let response = client.get(&url).send().await?;
+    let response = provider_guard::send("tmdb", client.get(&url)).await?;
     let json: Value = response.json().await?;
 
     let results = json
@@ -156,6 +260,7 @@ pub async fn search_tv_tmdb(
     title: &str,
     year: Option<u32>,
     api_key: &str,
+    language: Option<&str>,
 ) -> anyhow::Result<Vec<TVShowMetadata>> {
     let mut url = format!(
         "{}/search/tv?api_key={}&query={}",
@@ -163,6 +268,9 @@ pub async fn search_tv_tmdb(
         api_key,
         urlencoding::encode(title)
     );
+    if let Some(language) = language {
+        url.push_str(&format!("&language={}", language));
+    }
 
     if let Some(y) = year {
         url.push_str(&format!("&first_air_date_year={}", y));
@@ -171,7 +279,7 @@ pub async fn search_tv_tmdb(
     crate::services::metrics::METRICS
         .scrape_requests_total
         .inc();
-    let response = client.get(&url).send().await?;
+    let response = provider_guard::send("tmdb", client.get(&url)).await?;
     let json: Value = response.json().await?;
 
     let results = json
@@ -223,16 +331,20 @@ pub async fn search_tv_tmdb(
 
 /// 执行元数据刮削（使用共享 HTTP 客户端）
 pub async fn scrape_metadata(
+    db: &SqlitePool,
     client: &Client,
     file: &MediaFile,
     selected_tmdb_id: Option<u32>,
     auto_match: bool,
     config: &AppConfig,
 ) -> anyhow::Result<Value> {
-    let api_key = config
+    // 按文件所属媒体库的语言链搜索，与识别流程一致
+    let settings = identify::load_library_settings(db, config, &file.path).await?;
+    let api_key = settings
         .tmdb_api_key
-        .as_ref()
+        .as_deref()
         .ok_or_else(|| anyhow::anyhow!("TMDB API key not configured"))?;
+    let language = settings.languages.first().map(String::as_str);
 
     // 解析文件名
     let (title, year, season, episode) = parse_filename(&file.name);
@@ -242,7 +354,7 @@ pub async fn scrape_metadata(
 
     if is_tv_show {
         // 搜索剧集
-        let shows = search_tv_tmdb(client, &title, year, api_key).await?;
+        let shows = search_tv_tmdb(client, &title, year, api_key, language).await?;
 
         if shows.is_empty() {
             return Err(anyhow::anyhow!("No TV show found"));
@@ -263,7 +375,7 @@ pub async fn scrape_metadata(
         Ok(serde_json::to_value(selected)?)
     } else {
         // 搜索电影
-        let movies = search_movie_tmdb(client, &title, year, api_key).await?;
+        let movies = search_movie_tmdb(client, &title, year, api_key, language).await?;
 
         if movies.is_empty() {
             return Err(anyhow::anyhow!("No movie found"));
@@ -291,6 +403,7 @@ pub async fn scrape_metadata(
 pub struct BatchScrapeMetadataParams<'a> {
     pub auto_match: bool,
     pub config: &'a AppConfig,
+    pub plugins: &'a Arc<PluginManager>,
     pub download_images: bool,
     pub generate_nfo: bool,
     pub max_concurrent: usize,
@@ -308,11 +421,14 @@ pub async fn batch_scrape_metadata(
     let BatchScrapeMetadataParams {
         auto_match,
         config,
+        plugins,
         download_images,
         generate_nfo,
         max_concurrent,
         ctx,
     } = params;
+    // 整个任务共用一份数据源注册表
+    let providers = ProviderSession::new(plugins.clone());
 
     // 1. 获取文件列表
     let mut files = Vec::new();
@@ -332,6 +448,7 @@ pub async fn batch_scrape_metadata(
         .map(|(index, file)| {
             let client = client.clone();
             let config = config.clone();
+            let providers = providers.clone();
             let sub_ctx = ctx.duplicate();
             let db = db.clone();
 
@@ -342,61 +459,41 @@ pub async fn batch_scrape_metadata(
                     return (file.id.clone(), Err("Task cancelled".to_string()));
                 }
 
-                let result = match identify::preview_file(&db, &client, &config, &file, true).await {
-                    Ok(preview) => {
-                        let chosen = if auto_match {
-                            preview.recommended
-                        } else {
-                            None
-                        };
-                        if let Some(recommended) = chosen {
-                            let metadata = match identify::apply_selection(
-                                &db,
-                                &client,
-                                &config,
-                                &identify::ApplySelection {
-                                    file_id: file.id.clone(),
-                                    provider: recommended.provider,
-                                    external_id: recommended.external_id,
-                                    media_type: recommended.media_type,
-                                    lock_match: false,
-                                    download_images,
-                                    generate_nfo,
-                                },
-                            )
-                            .await
-                            {
-                                Ok(metadata) => metadata,
-                                Err(err) => return (file.id.clone(), Err(err.to_string())),
-                            };
-                        // 1. 下载图片和生成 NFO
-                        // 2. 执行视频质量分析
-                        let video_info = crate::services::video::extract_video_info(&file.path).await.ok();
-                        let quality_score = video_info.as_ref().map(crate::services::quality::calculate_quality_score);
-
-                        // 3. 更新数据库
-                        let tmdb_id = metadata.get("tmdb_id").and_then(|v| v.as_u64()).map(|v| v as u32);
-                        let metadata_json = serde_json::to_string(&metadata).unwrap_or_default();
-                        let video_info_json = video_info.as_ref().map(|info| serde_json::to_string(info).unwrap_or_default());
-
-                        let _ = sqlx::query(
-                            "UPDATE media_files SET metadata = ?, video_info = ?, tmdb_id = ?, quality_score = ?, updated_at = ? WHERE id = ?"
+                // 数据源熔断时暂停等待恢复后重试该文件，而不是让剩余文件逐个失败
+                let mut circuit_waits = 0;
+                let result = loop {
+                    let attempt = scrape_single_file(
+                        &db,
+                        &client,
+                        &config,
+                        &providers,
+                        &file,
+                        (auto_match, download_images, generate_nfo),
+                    )
+                    .await;
+                    let open = match &attempt {
+                        Err(error) if circuit_waits < MAX_CIRCUIT_WAITS => {
+                            provider_guard::unavailable(error).cloned()
+                        }
+                        _ => None,
+                    };
+                    let Some(open) = open else {
+                        break attempt.map_err(|e| e.to_string());
+                    };
+                    circuit_waits += 1;
+                    sub_ctx
+                        .report_progress(
+                            (index as f64 / total as f64) * 100.0,
+                            Some(&format!(
+                                "Paused: {} circuit open, resuming in {}s",
+                                open.provider,
+                                open.retry_after.as_secs().max(1)
+                            )),
                         )
-                        .bind(metadata_json)
-                        .bind(video_info_json)
-                        .bind(tmdb_id)
-                        .bind(quality_score)
-                        .bind(chrono::Utc::now())
-                        .bind(&file.id)
-                        .execute(&db)
                         .await;
-
-                            Ok(metadata)
-                        } else {
-                            Err("No matching candidate found".to_string())
-                        }
+                    if wait_for_provider(&mut sub_ctx, open.retry_after).await {
+                        return (file.id.clone(), Err("Task cancelled".to_string()));
                     }
-                    Err(e) => Err(e.to_string()),
                 };
 
                 // 报告总体进度
@@ -418,3 +515,78 @@ pub async fn batch_scrape_metadata(
 
     Ok(results)
 }
+
+/// 单个文件熔断等待的最多次数，超过后按失败处理
+const MAX_CIRCUIT_WAITS: u32 = 5;
+
+/// 熔断冷却期内等待；期间响应暂停/取消，返回 true 表示任务已取消
+async fn wait_for_provider(ctx: &mut TaskContext, retry_after: std::time::Duration) -> bool {
+    let deadline = tokio::time::Instant::now() + retry_after.max(std::time::Duration::from_secs(1));
+    while tokio::time::Instant::now() < deadline {
+        if ctx.is_cancelled().await || ctx.check_pause().await {
+            return true;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+    false
+}
+
+/// 识别并应用单个文件：预览 → 自动选择 → 应用 → 质量分析 → 写库
+async fn scrape_single_file(
+    db: &SqlitePool,
+    client: &Client,
+    config: &AppConfig,
+    providers: &ProviderSession,
+    file: &MediaFile,
+    (auto_match, download_images, generate_nfo): (bool, bool, bool),
+) -> anyhow::Result<Value> {
+    let preview = identify::preview_file(db, client, config, providers, file, true).await?;
+    // 不采用缺少部分数据源的结果：返回熔断错误，由批量任务暂停后重试该文件
+    if let Some(open) = provider_guard::open_circuits()
+        .into_iter()
+        .find(|open| preview.skipped_providers.contains(&open.provider))
+    {
+        return Err(open.into());
+    }
+    let chosen = if auto_match {
+        preview.recommended
+    } else {
+        None
+    };
+    let Some(recommended) = chosen else {
+        anyhow::bail!("No matching candidate found");
+    };
+    let selection = identify::ApplySelection {
+        file_id: file.id.clone(),
+        provider: recommended.provider,
+        external_id: recommended.external_id,
+        media_type: recommended.media_type,
+        lock_match: false,
+        download_images,
+        generate_nfo,
+    };
+    let metadata = identify::apply_selection(db, client, config, providers, &selection).await?;
+
+    // 元数据、图片与 NFO 已由 apply_selection 处理，这里只补充视频质量分析
+    let video_info = crate::services::video::extract_video_info(&file.path)
+        .await
+        .ok();
+    let release = release_parser::parse(&file.name);
+    let quality_score =
+        crate::services::quality::calculate_file_quality_score(video_info.as_ref(), &release);
+    let video_info_json = video_info
+        .as_ref()
+        .map(|info| serde_json::to_string(info).unwrap_or_default());
+
+    sqlx::query(
+        "UPDATE media_files SET video_info = ?, quality_score = ?, updated_at = ? WHERE id = ?",
+    )
+    .bind(video_info_json)
+    .bind(quality_score)
+    .bind(chrono::Utc::now())
+    .bind(&file.id)
+    .execute(db)
+    .await?;
+
+    Ok(metadata)
+}
@@ -0,0 +1,216 @@
+//! 把一个文件的识别结果应用到同目录 / 同剧集的其他文件
+//!
+//! 先按人工选择应用锚点文件，再遍历范围内的其他视频文件：规则解析出的标题与锚点一致的
+//! 复用同一数据源 ID，季 / 集取各自的解析结果；标题不一致、已锁定到其他条目或已匹配到
+//! 其他条目的文件不做修改，只在报告中列出。
+//!
+//! 范围：
+//! - `folder`：与锚点文件位于同一目录
+//! - `series`：锚点位于季目录（`Season 1`、`S01` 等）时取上一级剧集目录下的全部文件，否则同 `folder`
+
+use std::path::Path;
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use strsim::jaro_winkler;
+use utoipa::ToSchema;
+
+use crate::config::AppConfig;
+use crate::models::MediaFile;
+use crate::services::identify::{self, ApplySelection};
+use crate::services::metadata_provider::{self, ProviderSession};
+use crate::services::scraper;
+
+pub const SCOPE_FOLDER: &str = "folder";
+pub const SCOPE_SERIES: &str = "series";
+
+/// 归一化标题相似度达到该值即视为同一作品（容忍发布名里的少量差异）
+const TITLE_AGREEMENT: f64 = 0.92;
+
+/// 已应用的兄弟文件
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SiblingApplied {
+    pub file_id: String,
+    pub file_name: String,
+    pub season: Option<u32>,
+    pub episode: Option<u32>,
+}
+
+/// 未应用的兄弟文件及原因
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SiblingConflict {
+    pub file_id: String,
+    pub file_name: String,
+    pub parsed_title: String,
+    /// title_mismatch / locked / matched_elsewhere / apply_failed
+    pub reason: String,
+    /// 已有匹配（`provider:external_id`）或失败信息
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SiblingReport {
+    pub file_id: String,
+    pub scope: String,
+    pub directory: String,
+    /// 锚点文件应用后的元数据
+    pub metadata: Value,
+    pub applied: Vec<SiblingApplied>,
+    pub conflicts: Vec<SiblingConflict>,
+}
+
+/// 应用锚点文件并传播到范围内的兄弟文件
+pub async fn apply_with_siblings(
+    db: &SqlitePool,
+    client: &Client,
+    config: &AppConfig,
+    providers: &ProviderSession,
+    selection: &ApplySelection,
+    scope: &str,
+) -> anyhow::Result<SiblingReport> {
+    let anchor: MediaFile = sqlx::query_as("SELECT * FROM media_files WHERE id = ?")
+        .bind(&selection.file_id)
+        .fetch_one(db)
+        .await?;
+    let library_type = metadata_provider::library_type(db, &anchor.path).await?;
+    let anchor_title = identify::parse_with_rules(&anchor, library_type.as_deref()).title;
+
+    let metadata = identify::apply_selection(db, client, config, providers, selection).await?;
+    identify::learn_alias_best_effort(db, selection).await;
+
+    let directory = scope_directory(&anchor.path, scope);
+    let mut titles = vec![identify::normalize_title(&anchor_title)];
+    for key in ["title", "original_title"] {
+        if let Some(title) = metadata.get(key).and_then(Value::as_str) {
+            titles.push(identify::normalize_title(title));
+        }
+    }
+    titles.retain(|title| !title.is_empty());
+
+    let mut report = SiblingReport {
+        file_id: anchor.id.clone(),
+        scope: scope.to_string(),
+        directory: directory.clone(),
+        metadata,
+        applied: Vec::new(),
+        conflicts: Vec::new(),
+    };
+    let target = (selection.provider.as_str(), selection.external_id.as_str());
+
+    for sibling in sibling_files(db, &anchor, &directory, scope).await? {
+        let parsed = identify::parse_with_rules(&sibling, library_type.as_deref());
+        let conflict = |reason: &str, detail: Option<String>| SiblingConflict {
+            file_id: sibling.id.clone(),
+            file_name: sibling.name.clone(),
+            parsed_title: parsed.title.clone(),
+            reason: reason.to_string(),
+            detail,
+        };
+
+        if let (Some(provider), Some(external_id)) = (
+            sibling.locked_match_provider.as_deref(),
+            sibling.locked_match_external_id.as_deref(),
+        ) {
+            if (provider, external_id) != target {
+                report.conflicts.push(conflict(
+                    "locked",
+                    Some(format!("{provider}:{external_id}")),
+                ));
+            }
+            continue;
+        }
+        if let (Some(provider), Some(external_id)) = (
+            sibling.match_provider.as_deref(),
+            sibling.match_external_id.as_deref(),
+        ) {
+            if (provider, external_id) != target {
+                report.conflicts.push(conflict(
+                    "matched_elsewhere",
+                    Some(format!("{provider}:{external_id}")),
+                ));
+                continue;
+            }
+        }
+        if !title_agrees(&titles, &parsed.title) {
+            report.conflicts.push(conflict("title_mismatch", None));
+            continue;
+        }
+
+        let sibling_selection = ApplySelection {
+            file_id: sibling.id.clone(),
+            ..selection.clone()
+        };
+        match identify::apply_selection(db, client, config, providers, &sibling_selection).await {
+            Ok(details) => report.applied.push(SiblingApplied {
+                file_id: sibling.id.clone(),
+                file_name: sibling.name.clone(),
+                season: number(&details, "season_number"),
+                episode: number(&details, "episode_number"),
+            }),
+            Err(error) => {
+                tracing::warn!("Failed to apply sibling {}: {}", sibling.path, error);
+                report
+                    .conflicts
+                    .push(conflict("apply_failed", Some(error.to_string())));
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// 范围对应的目录；`series` 范围下季目录上溯到剧集目录
+pub fn scope_directory(file_path: &str, scope: &str) -> String {
+    let parent = Path::new(file_path).parent().unwrap_or(Path::new(""));
+    let is_season_folder = parent
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(scraper::parse_season_folder)
+        .is_some();
+    let directory = match parent.parent() {
+        Some(show) if scope == SCOPE_SERIES && is_season_folder => show,
+        _ => parent,
+    };
+    directory.to_string_lossy().to_string()
+}
+
+/// 兄弟文件标题是否与锚点解析标题或所选条目标题一致
+pub fn title_agrees(titles: &[String], title: &str) -> bool {
+    let normalized = identify::normalize_title(title);
+    !normalized.is_empty()
+        && titles.iter().any(|known| {
+            *known == normalized || jaro_winkler(known, &normalized) >= TITLE_AGREEMENT
+        })
+}
+
+async fn sibling_files(
+    db: &SqlitePool,
+    anchor: &MediaFile,
+    directory: &str,
+    scope: &str,
+) -> anyhow::Result<Vec<MediaFile>> {
+    let prefix = format!("{}/", directory.trim_end_matches('/'));
+    let files: Vec<MediaFile> = sqlx::query_as(
+        "SELECT * FROM media_files
+         WHERE file_type = 'video' AND id != ? AND substr(path, 1, length(?2)) = ?2
+         ORDER BY path",
+    )
+    .bind(&anchor.id)
+    .bind(&prefix)
+    .fetch_all(db)
+    .await?;
+    let directory = Path::new(directory);
+    Ok(files
+        .into_iter()
+        .filter(|file| scope == SCOPE_SERIES || Path::new(&file.path).parent() == Some(directory))
+        .collect())
+}
+
+fn number(details: &Value, key: &str) -> Option<u32> {
+    details
+        .get(key)
+        .and_then(Value::as_u64)
+        .map(|value| value as u32)
+}
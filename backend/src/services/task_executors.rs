@@ -4,9 +4,13 @@ use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 
+use crate::services::metadata_provider::ProviderSession;
+use crate::services::plugin::PluginManager;
 use crate::services::smart_cache::SmartCacheManager;
 use crate::services::task_queue::{TaskContext, TaskExecutor};
-use crate::services::{dedupe, hasher, identify, renamer, scanner, scraper};
+use crate::services::{
+    dedupe, hasher, identify, log, renamer, reparse, review_queue, scanner, scraper,
+};
 
 /// 扫描任务执行器
 pub struct ScanExecutor {
@@ -75,17 +79,21 @@ pub struct ScrapeExecutor {
     pub db: SqlitePool,
     pub http_client: reqwest::Client,
     pub config: Arc<crate::config::AppConfig>,
+    pub plugin_manager: Arc<PluginManager>,
 }
 
 impl TaskExecutor for ScrapeExecutor {
     fn execute(
         &self,
-        ctx: TaskContext,
+        mut ctx: TaskContext,
         payload: Value,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<Option<String>>> + Send>> {
         let db = self.db.clone();
         let client = self.http_client.clone();
         let config = self.config.clone();
+        let plugins = self.plugin_manager.clone();
+        // 同一任务内的文件共用一份数据源注册表
+        let providers = ProviderSession::new(plugins.clone());
         Box::pin(async move {
             let operation = payload["operation"].as_str().unwrap_or("scrape");
 
@@ -105,6 +113,7 @@ impl TaskExecutor for ScrapeExecutor {
                         &db,
                         &client,
                         &config,
+                        &providers,
                         std::slice::from_ref(file_id),
                         allow_ai,
                     )
@@ -153,7 +162,9 @@ impl TaskExecutor for ScrapeExecutor {
                         generate_nfo: item["generate_nfo"].as_bool().unwrap_or(false),
                     };
                     let metadata =
-                        identify::apply_selection(&db, &client, &config, &selection).await?;
+                        identify::apply_selection(&db, &client, &config, &providers, &selection)
+                            .await?;
+                    identify::learn_alias_best_effort(&db, &selection).await;
                     applied.push(serde_json::json!({
                         "file_id": selection.file_id,
                         "metadata": metadata
@@ -171,6 +182,109 @@ impl TaskExecutor for ScrapeExecutor {
                 }))?));
             }
 
+            if operation == "identify_auto" {
+                // 未指定文件时处理全部尚未识别的视频
+                let file_ids: Vec<String> = match payload["file_ids"].as_array() {
+                    Some(ids) => ids
+                        .iter()
+                        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                        .collect(),
+                    None => review_queue::unidentified_file_ids(&db).await?,
+                };
+                let options = review_queue::AutoIdentifyOptions {
+                    allow_ai: payload["allow_ai"].as_bool().unwrap_or(true),
+                    download_images: payload["download_images"].as_bool().unwrap_or(true),
+                    generate_nfo: payload["generate_nfo"].as_bool().unwrap_or(true),
+                };
+                let total = file_ids.len().max(1);
+                let (mut applied, mut queued, mut locked, mut failed) = (0, 0, 0, 0);
+
+                for (index, file_id) in file_ids.iter().enumerate() {
+                    if ctx.check_pause().await {
+                        return Err(anyhow::anyhow!("Identify task cancelled"));
+                    }
+                    let file = sqlx::query_as::<_, crate::models::MediaFile>(
+                        "SELECT * FROM media_files WHERE id = ?",
+                    )
+                    .bind(file_id)
+                    .fetch_optional(&db)
+                    .await?;
+                    if let Some(file) = file {
+                        match review_queue::auto_identify_file(
+                            &db, &client, &config, &providers, &file, options,
+                        )
+                        .await
+                        {
+                            Ok(review_queue::AutoOutcome::Applied) => applied += 1,
+                            Ok(review_queue::AutoOutcome::Queued) => queued += 1,
+                            Ok(review_queue::AutoOutcome::Locked) => locked += 1,
+                            Err(error) => {
+                                tracing::warn!("Auto identify failed for {}: {}", file_id, error);
+                                failed += 1;
+                            }
+                        }
+                    }
+                    let progress = ((index + 1) as f64 / total as f64) * 100.0;
+                    ctx.report_progress(
+                        progress,
+                        Some(&format!("Identifying {}/{} files", index + 1, total)),
+                    )
+                    .await;
+                }
+
+                return Ok(Some(serde_json::to_string(&serde_json::json!({
+                    "auto_applied": applied,
+                    "queued": queued,
+                    "locked": locked,
+                    "failed": failed
+                }))?));
+            }
+
+            if operation == "identify_reparse" {
+                // 未指定文件时处理全部解析版本落后的文件
+                let file_ids: Vec<String> = match payload["file_ids"].as_array() {
+                    Some(ids) => ids
+                        .iter()
+                        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                        .collect(),
+                    None => reparse::stale_file_ids(&db).await?,
+                };
+                let total = file_ids.len().max(1);
+                let mut summary = reparse::ReparseSummary::default();
+
+                for (index, file_id) in file_ids.iter().enumerate() {
+                    if ctx.check_pause().await {
+                        return Err(anyhow::anyhow!("Reparse task cancelled"));
+                    }
+                    let file = sqlx::query_as::<_, crate::models::MediaFile>(
+                        "SELECT * FROM media_files WHERE id = ?",
+                    )
+                    .bind(file_id)
+                    .fetch_optional(&db)
+                    .await?;
+                    if let Some(file) = file {
+                        match reparse::reparse_file(&db, &client, &config, &providers, &file).await
+                        {
+                            Ok(reparse::ReparseOutcome::Unchanged) => summary.unchanged += 1,
+                            Ok(reparse::ReparseOutcome::Changed) => summary.changed += 1,
+                            Ok(reparse::ReparseOutcome::Unresolved) => summary.unresolved += 1,
+                            Err(error) => {
+                                tracing::warn!("Reparse failed for {}: {}", file_id, error);
+                                summary.failed += 1;
+                            }
+                        }
+                    }
+                    let progress = ((index + 1) as f64 / total as f64) * 100.0;
+                    ctx.report_progress(
+                        progress,
+                        Some(&format!("Reparsing {}/{} files", index + 1, total)),
+                    )
+                    .await;
+                }
+
+                return Ok(Some(serde_json::to_string(&summary)?));
+            }
+
             let file_ids: Vec<String> = payload["file_ids"]
                 .as_array()
                 .ok_or_else(|| anyhow::anyhow!("Missing file_ids"))?
@@ -182,13 +296,14 @@ impl TaskExecutor for ScrapeExecutor {
             let download_images = payload["download_images"].as_bool().unwrap_or(true);
             let generate_nfo = payload["generate_nfo"].as_bool().unwrap_or(true);
 
-            scraper::batch_scrape_metadata(
+            let results = scraper::batch_scrape_metadata(
                 &db,
                 &client,
                 &file_ids,
                 scraper::BatchScrapeMetadataParams {
                     auto_match,
                     config: &config,
+                    plugins: &plugins,
                     download_images,
                     generate_nfo,
                     max_concurrent: 5,
@@ -197,7 +312,19 @@ impl TaskExecutor for ScrapeExecutor {
             )
             .await?;
 
-            Ok(Some("Batch scrape completed".to_string()))
+            let failed: Vec<_> = results
+                .iter()
+                .filter_map(|(file_id, result)| result.as_ref().err().map(|e| (file_id, e)))
+                .collect();
+            for (file_id, error) in &failed {
+                tracing::warn!("Scrape failed for file {}: {}", file_id, error);
+            }
+
+            Ok(Some(format!(
+                "Batch scrape completed: {} succeeded, {} failed",
+                results.len() - failed.len(),
+                failed.len()
+            )))
         })
     }
 }
@@ -235,6 +362,30 @@ impl TaskExecutor for RenameExecutor {
     }
 }
 
+/// 整批撤销执行器
+pub struct UndoBatchExecutor {
+    pub db: SqlitePool,
+}
+
+impl TaskExecutor for UndoBatchExecutor {
+    fn execute(
+        &self,
+        ctx: TaskContext,
+        payload: Value,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<Option<String>>> + Send>> {
+        let db = self.db.clone();
+        Box::pin(async move {
+            let batch_id = payload["batch_id"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing batch_id"))?
+                .to_string();
+
+            let report = log::undo_batch(&db, &batch_id, ctx).await?;
+            Ok(Some(serde_json::to_string(&report)?))
+        })
+    }
+}
+
 /// 批量哈希执行器（并行处理）
 pub struct BatchHashExecutor {
     pub db: SqlitePool,
@@ -339,6 +490,7 @@ impl TaskExecutor for SimilarScanExecutor {
 mod tests {
     use super::ScrapeExecutor;
     use crate::config::AppConfig;
+    use crate::services::plugin::PluginManager;
     use crate::services::task_queue::{TaskContext, TaskExecutor};
     use serde_json::Value;
     use sqlx::sqlite::SqlitePoolOptions;
@@ -388,6 +540,8 @@ mod tests {
                 tmdb_api_key: None,
                 hash_cache_dir: std::env::temp_dir().join("cine-hash-cache"),
                 trash_dir: std::env::temp_dir().join("cine-trash"),
+                people_cache_dir: std::env::temp_dir().join("cine-people"),
+                collection_cache_dir: std::env::temp_dir().join("cine-collections"),
                 max_file_size: 200_000_000_000,
                 chunk_size: 64 * 1024 * 1024,
                 media_directories: vec![],
@@ -403,6 +557,9 @@ mod tests {
                 worker_heartbeat_interval_secs: 1,
                 worker_task_heartbeat_interval_secs: 1,
             }),
+            plugin_manager: Arc::new(PluginManager::new(
+                std::env::temp_dir().join("cine-plugins"),
+            )),
         };
 
         let result = executor
@@ -0,0 +1,244 @@
+//! 标题别名
+//!
+//! 人工确认的匹配会记为「归一化解析标题 → 数据源 ID」。识别预览时先查别名，
+//! 命中即直接取详情，同一发布名的兄弟剧集和后续下载不再走模糊搜索。
+//! `path_prefix` 可把别名限定在某个目录下，为空时全局生效；多条命中时取最长前缀。
+//! 别名同时按媒体类型与年份区分，年份与文件解析出的年份冲突时不命中。
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::services::identify;
+
+pub const SOURCE_MANUAL: &str = "manual";
+pub const SOURCE_API: &str = "api";
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TitleAlias {
+    pub id: String,
+    pub normalized_title: String,
+    /// 为空表示全局生效
+    pub path_prefix: String,
+    /// 记录时的解析标题
+    pub title: String,
+    /// 为空表示不限年份
+    pub year: Option<i32>,
+    pub provider: String,
+    pub external_id: String,
+    pub media_type: String,
+    /// manual / api
+    pub source: String,
+    /// 识别时命中次数
+    pub hits: i64,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 新建或修改别名
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AliasInput {
+    /// 解析出的（发布名）标题，保存时归一化
+    pub title: String,
+    pub path_prefix: Option<String>,
+    #[serde(default)]
+    pub year: Option<i32>,
+    pub provider: String,
+    pub external_id: String,
+    pub media_type: String,
+}
+
+/// 写入别名；同一标题、路径前缀、媒体类型与年份已存在时改为指向新的数据源 ID
+pub async fn upsert(
+    db: &SqlitePool,
+    input: &AliasInput,
+    source: &str,
+) -> anyhow::Result<TitleAlias> {
+    let normalized = identify::normalize_title(&input.title);
+    if normalized.is_empty() {
+        anyhow::bail!("alias title is empty after normalization: {}", input.title);
+    }
+    let path_prefix = normalize_prefix(input.path_prefix.as_deref());
+    let now = Utc::now();
+    sqlx::query(
+        "INSERT INTO title_aliases (id, normalized_title, path_prefix, title, year, provider, external_id, media_type, source, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(normalized_title, path_prefix, media_type, IFNULL(year, 0)) DO UPDATE SET
+             title = excluded.title,
+             provider = excluded.provider,
+             external_id = excluded.external_id,
+             source = excluded.source,
+             updated_at = excluded.updated_at",
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(&normalized)
+    .bind(&path_prefix)
+    .bind(input.title.trim())
+    .bind(input.year)
+    .bind(&input.provider)
+    .bind(&input.external_id)
+    .bind(&input.media_type)
+    .bind(source)
+    .bind(now)
+    .bind(now)
+    .execute(db)
+    .await?;
+
+    Ok(sqlx::query_as(
+        "SELECT * FROM title_aliases
+             WHERE normalized_title = ? AND path_prefix = ? AND media_type = ? AND year IS ?",
+    )
+    .bind(&normalized)
+    .bind(&path_prefix)
+    .bind(&input.media_type)
+    .bind(input.year)
+    .fetch_one(db)
+    .await?)
+}
+
+/// 查找适用于该文件的别名：标题归一化后相同、路径前缀覆盖文件路径、年份不冲突，取最长前缀；
+/// `media_type` 为空时不限类型
+pub async fn lookup(
+    db: &SqlitePool,
+    title: &str,
+    year: Option<i32>,
+    media_type: Option<&str>,
+    file_path: &str,
+) -> anyhow::Result<Option<TitleAlias>> {
+    let normalized = identify::normalize_title(title);
+    if normalized.is_empty() {
+        return Ok(None);
+    }
+    let aliases: Vec<TitleAlias> =
+        sqlx::query_as("SELECT * FROM title_aliases WHERE normalized_title = ?")
+            .bind(&normalized)
+            .fetch_all(db)
+            .await?;
+    let path = Path::new(file_path);
+    Ok(aliases
+        .into_iter()
+        .filter(|alias| alias.path_prefix.is_empty() || path.starts_with(&alias.path_prefix))
+        .filter(|alias| media_type.is_none_or(|media_type| alias.media_type == media_type))
+        .filter(|alias| alias.year.zip(year).is_none_or(|(lhs, rhs)| lhs == rhs))
+        .max_by_key(|alias| alias.path_prefix.len()))
+}
+
+/// 记录一次命中
+pub async fn record_hit(db: &SqlitePool, id: &str) -> anyhow::Result<()> {
+    sqlx::query("UPDATE title_aliases SET hits = hits + 1, last_used_at = ? WHERE id = ?")
+        .bind(Utc::now())
+        .bind(id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// 列出别名，`query` 按标题或数据源 ID 过滤
+pub async fn list(
+    db: &SqlitePool,
+    query: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> anyhow::Result<Vec<TitleAlias>> {
+    let pattern = query.map(|query| format!("%{}%", query.trim()));
+    let normalized = query.map(|query| format!("%{}%", identify::normalize_title(query)));
+    Ok(sqlx::query_as(
+        "SELECT * FROM title_aliases
+         WHERE ?1 IS NULL OR title LIKE ?1 OR normalized_title LIKE ?2 OR external_id LIKE ?1
+         ORDER BY updated_at DESC, id LIMIT ?3 OFFSET ?4",
+    )
+    .bind(pattern)
+    .bind(normalized)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(db)
+    .await?)
+}
+
+pub async fn get(db: &SqlitePool, id: &str) -> anyhow::Result<Option<TitleAlias>> {
+    Ok(sqlx::query_as("SELECT * FROM title_aliases WHERE id = ?")
+        .bind(id)
+        .fetch_optional(db)
+        .await?)
+}
+
+/// 修改别名，返回 None 表示不存在
+pub async fn update(
+    db: &SqlitePool,
+    id: &str,
+    input: &AliasInput,
+) -> anyhow::Result<Option<TitleAlias>> {
+    let normalized = identify::normalize_title(&input.title);
+    if normalized.is_empty() {
+        anyhow::bail!("alias title is empty after normalization: {}", input.title);
+    }
+    let updated = sqlx::query(
+        "UPDATE title_aliases
+         SET normalized_title = ?, path_prefix = ?, title = ?, year = ?, provider = ?, external_id = ?, media_type = ?, source = ?, updated_at = ?
+         WHERE id = ?",
+    )
+    .bind(&normalized)
+    .bind(normalize_prefix(input.path_prefix.as_deref()))
+    .bind(input.title.trim())
+    .bind(input.year)
+    .bind(&input.provider)
+    .bind(&input.external_id)
+    .bind(&input.media_type)
+    .bind(SOURCE_API)
+    .bind(Utc::now())
+    .bind(id)
+    .execute(db)
+    .await?
+    .rows_affected();
+    if updated == 0 {
+        return Ok(None);
+    }
+    get(db, id).await
+}
+
+pub async fn delete(db: &SqlitePool, id: &str) -> anyhow::Result<bool> {
+    let deleted = sqlx::query("DELETE FROM title_aliases WHERE id = ?")
+        .bind(id)
+        .execute(db)
+        .await?
+        .rows_affected();
+    Ok(deleted > 0)
+}
+
+/// 按当前归一化规则重算 `normalized_title`，返回更新的条数；
+/// 重算后与已有别名冲突的条目保持原值
+pub async fn renormalize(db: &SqlitePool) -> anyhow::Result<u64> {
+    let rows: Vec<(String, String, String)> =
+        sqlx::query_as("SELECT id, title, normalized_title FROM title_aliases")
+            .fetch_all(db)
+            .await?;
+    let mut updated = 0;
+    for (id, title, normalized) in rows {
+        let current = identify::normalize_title(&title);
+        if current.is_empty() || current == normalized {
+            continue;
+        }
+        updated +=
+            sqlx::query("UPDATE OR IGNORE title_aliases SET normalized_title = ? WHERE id = ?")
+                .bind(&current)
+                .bind(&id)
+                .execute(db)
+                .await?
+                .rows_affected();
+    }
+    Ok(updated)
+}
+
+/// 去掉首尾空白与末尾的路径分隔符，`/` 与空值都视为全局
+fn normalize_prefix(prefix: Option<&str>) -> String {
+    prefix
+        .map(str::trim)
+        .map(|prefix| prefix.trim_end_matches(['/', '\\']))
+        .unwrap_or_default()
+        .to_string()
+}
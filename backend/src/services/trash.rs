@@ -43,6 +43,16 @@ pub async fn move_to_trash(
     db: &SqlitePool,
     file_id: &str,
     trash_config: &TrashConfig,
+) -> anyhow::Result<TrashItem> {
+    move_to_trash_in_batch(db, file_id, trash_config, None).await
+}
+
+/// 移动到回收站，并将操作日志关联到批次
+pub async fn move_to_trash_in_batch(
+    db: &SqlitePool,
+    file_id: &str,
+    trash_config: &TrashConfig,
+    batch_id: Option<&str>,
 ) -> anyhow::Result<TrashItem> {
     trash_config.ensure_exists().await?;
 
@@ -92,6 +102,7 @@ pub async fn move_to_trash(
         Some(file_id),
         &file.path,
         Some(&trash_item.trash_path),
+        batch_id,
     )
     .await;
 
@@ -103,6 +114,16 @@ pub async fn restore_from_trash(
     db: &SqlitePool,
     file_id: &str,
     target_path: Option<&str>,
+) -> anyhow::Result<String> {
+    restore_from_trash_in_batch(db, file_id, target_path, None).await
+}
+
+/// 从回收站恢复文件，并将操作日志关联到批次
+pub async fn restore_from_trash_in_batch(
+    db: &SqlitePool,
+    file_id: &str,
+    target_path: Option<&str>,
+    batch_id: Option<&str>,
 ) -> anyhow::Result<String> {
     // 获取文件信息
     let file: MediaFile = sqlx::query_as("SELECT * FROM media_files WHERE id = ?")
@@ -154,6 +175,7 @@ pub async fn restore_from_trash(
         Some(file_id),
         &file.path, // 恢复前路径（在回收站中）
         Some(&restore_path_str),
+        batch_id,
     )
     .await;
 
@@ -181,7 +203,8 @@ pub async fn permanently_delete(db: &SqlitePool, file_id: &str) -> anyhow::Resul
 
     // 记录操作日志
     let _ =
-        crate::services::log::record_operation(db, "delete", Some(file_id), &file.path, None).await;
+        crate::services::log::record_operation(db, "delete", Some(file_id), &file.path, None, None)
+            .await;
 
     // 从数据库删除记录
     sqlx::query("DELETE FROM media_files WHERE id = ?")
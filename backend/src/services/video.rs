@@ -55,6 +55,12 @@ pub async fn extract_video_info(file_path: &str) -> anyhow::Result<VideoInfo> {
         .and_then(|n| n.as_str())
         .map(|s| s.to_string());
 
+    let file_name = std::path::Path::new(file_path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(file_path);
+    let release = crate::services::release_parser::parse(file_name);
+
     let mut video_info = VideoInfo {
         duration,
         width: None,
@@ -67,7 +73,7 @@ pub async fn extract_video_info(file_path: &str) -> anyhow::Result<VideoInfo> {
         is_hdr: Some(false),
         is_dolby_vision: Some(false),
         is_hdr10_plus: Some(false),
-        source: detect_source(file_path),
+        source: release.source.clone(),
         has_chinese_subtitle: Some(false),
         audio_streams: Vec::new(),
         subtitle_streams: Vec::new(),
@@ -120,13 +126,11 @@ pub async fn extract_video_info(file_path: &str) -> anyhow::Result<VideoInfo> {
                     }
                 }
 
-                // Filename based detection as fallback
-                let path_low = file_path.to_lowercase();
-                if path_low.contains("dv") || path_low.contains("dolby vision") {
-                    video_info.is_dolby_vision = Some(true);
-                }
-                if path_low.contains("hdr10+") {
-                    video_info.is_hdr10_plus = Some(true);
+                // 发布名中的 HDR 标记作为补充
+                match release.hdr.as_deref() {
+                    Some("DV") => video_info.is_dolby_vision = Some(true),
+                    Some("HDR10+") => video_info.is_hdr10_plus = Some(true),
+                    _ => {}
                 }
             }
             "audio" => {
@@ -261,21 +265,3 @@ pub async fn generate_thumbnail(
         "Thumbnail generation is not compiled into this build"
     ))
 }
-
-/// 基于文件名探测视频来源
-fn detect_source(path: &str) -> Option<String> {
-    let p = path.to_lowercase();
-    if p.contains("web-dl") || p.contains("webdl") {
-        Some("WEB-DL".to_string())
-    } else if p.contains("itunes") || p.contains(" it ") {
-        Some("iTunes".to_string())
-    } else if p.contains("bluray") || p.contains("bdrip") {
-        Some("BluRay".to_string())
-    } else if p.contains("hdtv") {
-        Some("HDTV".to_string())
-    } else if p.contains("dvdrip") {
-        Some("DVDRip".to_string())
-    } else {
-        None
-    }
-}
@@ -1,5 +1,7 @@
 //! 测试公共模块
 
+use chrono::Utc;
+use serde_json::Value;
 use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -28,6 +30,28 @@ pub async fn create_test_db() -> (SqlitePool, TempDir) {
     (pool, temp_dir)
 }
 
+/// 插入一条视频文件记录，文件名取路径最后一段；`metadata` 为空时不写元数据
+#[allow(dead_code)]
+pub async fn insert_media_file(pool: &SqlitePool, id: &str, path: &str, metadata: Option<&Value>) {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    sqlx::query(
+        "INSERT INTO media_files (id, path, name, size, file_type, metadata, created_at, updated_at, last_modified)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(id)
+    .bind(path)
+    .bind(name)
+    .bind(1000)
+    .bind("video")
+    .bind(metadata.map(Value::to_string))
+    .bind(Utc::now().to_rfc3339())
+    .bind(Utc::now().to_rfc3339())
+    .bind(Utc::now().to_rfc3339())
+    .execute(pool)
+    .await
+    .expect("Failed to insert media file");
+}
+
 /// 创建测试应用状态（尽量集中管理，避免 AppState 字段变更导致测试到处报错）
 #[allow(dead_code)]
 pub async fn create_test_app_state() -> (Arc<cine_backend::handlers::AppState>, TempDir) {
@@ -39,6 +63,8 @@ pub async fn create_test_app_state() -> (Arc<cine_backend::handlers::AppState>,
         tmdb_api_key: None,
         hash_cache_dir: temp_dir.path().join("hash_cache"),
         trash_dir: temp_dir.path().join("trash"),
+        people_cache_dir: temp_dir.path().join("people"),
+        collection_cache_dir: temp_dir.path().join("collections"),
         max_file_size: 200_000_000_000,
         chunk_size: 64 * 1024 * 1024,
         media_directories: vec![],
@@ -59,6 +85,9 @@ pub async fn create_test_app_state() -> (Arc<cine_backend::handlers::AppState>,
         pool.clone(),
         4,
     ));
+    let plugin_manager = Arc::new(cine_backend::services::plugin::PluginManager::new(
+        temp_dir.path().join("plugins"),
+    ));
     task_queue.register_executor(
         cine_backend::services::task_queue::TaskType::Scrape,
         Arc::new(cine_backend::services::task_executors::ScrapeExecutor {
@@ -68,6 +97,7 @@ pub async fn create_test_app_state() -> (Arc<cine_backend::handlers::AppState>,
                 .build()
                 .expect("Failed to create scrape executor HTTP client"),
             config: config.clone(),
+            plugin_manager: plugin_manager.clone(),
         }),
     );
 
@@ -86,9 +116,7 @@ pub async fn create_test_app_state() -> (Arc<cine_backend::handlers::AppState>,
                 config.clone(),
             ),
         ),
-        plugin_manager: Arc::new(cine_backend::services::plugin::PluginManager::new(
-            temp_dir.path().join("plugins"),
-        )),
+        plugin_manager,
     });
 
     (app_state, temp_dir)
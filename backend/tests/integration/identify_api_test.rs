@@ -7,6 +7,8 @@ use axum::{
     body::{to_bytes, Body},
     http::{Request, StatusCode},
 };
+use cine_backend::services::title_aliases::{self, AliasInput};
+use cine_backend::services::{identify, metadata_provider, provider_cache, reparse, review_queue};
 use serde_json::{json, Value};
 use tower::util::ServiceExt;
 
@@ -153,3 +155,1048 @@ async fn test_identify_apply_batch_creates_scrape_task_with_selection_payload()
     assert_eq!(task_payload["selections"][0]["external_id"], "438631");
     assert_eq!(task_payload["selections"][0]["lock_match"], true);
 }
+
+#[tokio::test]
+async fn test_auto_identify_queues_low_confidence_files_for_review() {
+    let (router, app_state, _temp_dir) = common::create_test_router_with_state().await;
+    let pool = &app_state.db;
+    insert_test_file(pool, "file-queued", ".mkv", "/tmp/.mkv").await;
+    insert_test_file(pool, "file-locked", ".mkv", "/tmp/locked.mkv").await;
+    sqlx::query(
+        "UPDATE media_files SET locked_match_provider = 'tmdb', locked_match_external_id = '603' WHERE id = 'file-locked'",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+
+    let unidentified = review_queue::unidentified_file_ids(pool).await.unwrap();
+    assert_eq!(unidentified, vec!["file-queued".to_string()]);
+
+    let options = review_queue::AutoIdentifyOptions {
+        allow_ai: false,
+        download_images: false,
+        generate_nfo: false,
+    };
+    for (file_id, expected) in [
+        ("file-queued", review_queue::AutoOutcome::Queued),
+        ("file-locked", review_queue::AutoOutcome::Locked),
+    ] {
+        let file: cine_backend::models::MediaFile =
+            sqlx::query_as("SELECT * FROM media_files WHERE id = ?")
+                .bind(file_id)
+                .fetch_one(pool)
+                .await
+                .unwrap();
+        let outcome = review_queue::auto_identify_file(
+            pool,
+            &app_state.http_client,
+            &app_state.config,
+            &metadata_provider::ProviderSession::new(app_state.plugin_manager.clone()),
+            &file,
+            options,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, expected);
+    }
+    assert!(review_queue::unidentified_file_ids(pool)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let response = router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/identify/review")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let items: Value = serde_json::from_slice(&body).unwrap();
+    let items = items.as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["file_id"], "file-queued");
+    assert_eq!(items[0]["status"], "pending");
+    assert_eq!(items[0]["reason"], "no_candidates");
+
+    // 没有推荐候选时不能直接接受
+    let post = |uri: &str| {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from("{}"))
+            .unwrap()
+    };
+    let response = router
+        .clone()
+        .oneshot(post("/api/identify/review/file-queued/accept"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response = router
+        .clone()
+        .oneshot(post("/api/identify/review/file-queued/reject"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let (review_state,): (Option<String>,) =
+        sqlx::query_as("SELECT review_state FROM media_files WHERE id = 'file-queued'")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+    assert_eq!(review_state.as_deref(), Some("rejected"));
+
+    let response = router
+        .clone()
+        .oneshot(post("/api/identify/review/file-queued/reject"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response = router
+        .oneshot(post("/api/identify/review/file-missing/accept"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_title_alias_api_crud() {
+    let (router, _app_state, _temp_dir) = common::create_test_router_with_state().await;
+    let request = |method: &str, uri: &str, body: Option<Value>| {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .unwrap()
+    };
+
+    let response = router
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/api/identify/aliases",
+            Some(json!({ "title": "  ", "provider": "tmdb", "external_id": "1", "media_type": "tv" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = router
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/api/identify/aliases",
+            Some(json!({
+                "title": "Shingeki no Kyojin",
+                "provider": "tmdb",
+                "external_id": "1429",
+                "media_type": "tv"
+            })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let created: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(created["normalized_title"], "shingekinokyojin");
+    assert_eq!(created["source"], "api");
+    let id = created["id"].as_str().unwrap().to_string();
+
+    let response = router
+        .clone()
+        .oneshot(request(
+            "PUT",
+            &format!("/api/identify/aliases/{id}"),
+            Some(json!({
+                "title": "Shingeki no Kyojin",
+                "path_prefix": "/media/anime/",
+                "provider": "bgm",
+                "external_id": "55770",
+                "media_type": "tv"
+            })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = router
+        .clone()
+        .oneshot(request("GET", "/api/identify/aliases?q=kyojin", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let aliases: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(aliases.as_array().unwrap().len(), 1);
+    assert_eq!(aliases[0]["provider"], "bgm");
+    assert_eq!(aliases[0]["path_prefix"], "/media/anime");
+
+    let response = router
+        .clone()
+        .oneshot(request(
+            "DELETE",
+            &format!("/api/identify/aliases/{id}"),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = router
+        .oneshot(request(
+            "DELETE",
+            &format!("/api/identify/aliases/{id}"),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_apply_siblings_reuses_match_and_reports_conflicts() {
+    let (router, app_state, _temp_dir) = common::create_test_router_with_state().await;
+    let pool = &app_state.db;
+    // 离线模式：详情只读缓存，不访问网络
+    sqlx::query(
+        "INSERT INTO settings (id, category, key, value) VALUES ('offline', 'general', 'provider_offline_mode', 'true')",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+    provider_cache::store(
+        pool,
+        "bgm",
+        "details",
+        &json!({
+            "external_id": "400602",
+            "media_type": "tv",
+            "languages": [metadata_provider::DEFAULT_LANGUAGE]
+        }),
+        &json!({ "provider": "bgm", "title": "Sousou no Frieren", "name": "Sousou no Frieren", "year": 2023 })
+            .to_string(),
+        chrono::Duration::days(7),
+    )
+    .await
+    .unwrap();
+
+    let show = "/media/anime/Sousou no Frieren";
+    let files = [
+        (
+            "anchor",
+            format!("{show}/Season 1/[SubsPlease] Sousou no Frieren - 01 (1080p).mkv"),
+        ),
+        (
+            "same-folder",
+            format!("{show}/Season 1/[SubsPlease] Sousou no Frieren - 02 (1080p).mkv"),
+        ),
+        (
+            "other-title",
+            format!("{show}/Season 1/[SubsPlease] Dungeon Meshi - 03 (1080p).mkv"),
+        ),
+        (
+            "locked",
+            format!("{show}/Season 1/[SubsPlease] Sousou no Frieren - 04 (1080p).mkv"),
+        ),
+        (
+            "season-2",
+            format!("{show}/Season 2/[SubsPlease] Sousou no Frieren - 01 (1080p).mkv"),
+        ),
+    ];
+    for (id, path) in &files {
+        let name = path.rsplit('/').next().unwrap();
+        insert_test_file(pool, id, name, path).await;
+    }
+    sqlx::query(
+        "UPDATE media_files SET locked_match_provider = 'bgm', locked_match_external_id = '1' WHERE id = 'locked'",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+
+    let apply = |scope: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/api/identify/apply/siblings")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({
+                    "file_id": "anchor",
+                    "provider": "bgm",
+                    "external_id": "400602",
+                    "media_type": "tv",
+                    "scope": scope
+                })
+                .to_string(),
+            ))
+            .unwrap()
+    };
+
+    let response = router.clone().oneshot(apply("shelf")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = router.clone().oneshot(apply("folder")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let report: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(report["directory"], format!("{show}/Season 1"));
+    let applied: Vec<&str> = report["applied"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["file_id"].as_str().unwrap())
+        .collect();
+    assert_eq!(applied, ["same-folder"]);
+    assert_eq!(report["applied"][0]["season"], 1);
+    assert_eq!(report["applied"][0]["episode"], 2);
+    let conflicts: Vec<(&str, &str)> = report["conflicts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| {
+            (
+                item["file_id"].as_str().unwrap(),
+                item["reason"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        conflicts,
+        [("other-title", "title_mismatch"), ("locked", "locked")]
+    );
+
+    let response = router.clone().oneshot(apply("series")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let report: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(report["directory"], show);
+    let applied: Vec<&str> = report["applied"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["file_id"].as_str().unwrap())
+        .collect();
+    assert_eq!(applied, ["same-folder", "season-2"]);
+
+    let (provider, external_id, season, episode): (String, String, Option<i64>, Option<i64>) =
+        sqlx::query_as(
+            "SELECT match_provider, match_external_id, detected_season, detected_episode FROM media_files WHERE id = 'season-2'",
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    assert_eq!((provider.as_str(), external_id.as_str()), ("bgm", "400602"));
+    assert_eq!((season, episode), (Some(2), Some(1)));
+    let untouched: Option<String> =
+        sqlx::query_scalar("SELECT match_provider FROM media_files WHERE id = 'other-title'")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+    assert!(untouched.is_none());
+}
+
+#[tokio::test]
+async fn test_reparse_records_diff_and_applies_on_approval() {
+    let (router, app_state, _temp_dir) = common::create_test_router_with_state().await;
+    let pool = &app_state.db;
+    sqlx::query(
+        "INSERT INTO settings (id, category, key, value) VALUES ('offline', 'general', 'provider_offline_mode', 'true')",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+    provider_cache::store(
+        pool,
+        "bgm",
+        "details",
+        &json!({
+            "external_id": "400602",
+            "media_type": "tv",
+            "languages": [metadata_provider::DEFAULT_LANGUAGE]
+        }),
+        &json!({ "provider": "bgm", "title": "Sousou no Frieren", "name": "Sousou no Frieren", "year": 2023 })
+            .to_string(),
+        chrono::Duration::days(7),
+    )
+    .await
+    .unwrap();
+    title_aliases::upsert(
+        pool,
+        &AliasInput {
+            title: "Sousou no Frieren".to_string(),
+            path_prefix: None,
+            year: None,
+            provider: "bgm".to_string(),
+            external_id: "400602".to_string(),
+            media_type: "tv".to_string(),
+        },
+        title_aliases::SOURCE_API,
+    )
+    .await
+    .unwrap();
+
+    let folder = "/media/anime/Sousou no Frieren/Season 1";
+    let files = [
+        // 旧解析器把集数认错并匹配到了别的条目
+        (
+            "misparsed",
+            "[SubsPlease] Sousou no Frieren - 05 (1080p).mkv",
+            "1",
+            Some(4),
+        ),
+        // 标题解析不同，但匹配与集数都没变
+        (
+            "same-match",
+            "[SubsPlease] Sousou no Frieren - 06 (1080p).mkv",
+            "400602",
+            Some(6),
+        ),
+        (
+            "locked",
+            "[SubsPlease] Sousou no Frieren - 07 (1080p).mkv",
+            "1",
+            Some(7),
+        ),
+    ];
+    for (id, name, external_id, episode) in &files {
+        insert_test_file(pool, id, name, &format!("{folder}/{name}")).await;
+        sqlx::query(
+            "UPDATE media_files
+             SET parse_version = 'identify-v0', match_provider = 'bgm', match_external_id = ?,
+                 detected_title = 'Frieren', detected_season = 1, detected_episode = ?
+             WHERE id = ?",
+        )
+        .bind(external_id)
+        .bind(episode)
+        .bind(id)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+    sqlx::query(
+        "UPDATE media_files SET locked_match_provider = 'bgm', locked_match_external_id = '1' WHERE id = 'locked'",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+
+    let stale = reparse::stale_file_ids(pool).await.unwrap();
+    assert_eq!(stale, ["misparsed", "same-match"]);
+    let mut outcomes = Vec::new();
+    for file_id in &stale {
+        let file: cine_backend::models::MediaFile =
+            sqlx::query_as("SELECT * FROM media_files WHERE id = ?")
+                .bind(file_id)
+                .fetch_one(pool)
+                .await
+                .unwrap();
+        outcomes.push(
+            reparse::reparse_file(
+                pool,
+                &app_state.http_client,
+                &app_state.config,
+                &metadata_provider::ProviderSession::new(app_state.plugin_manager.clone()),
+                &file,
+            )
+            .await
+            .unwrap(),
+        );
+    }
+    assert_eq!(
+        outcomes,
+        [
+            reparse::ReparseOutcome::Changed,
+            reparse::ReparseOutcome::Unchanged
+        ]
+    );
+    // 待审核的差异不会在下次任务中重新识别
+    assert!(reparse::stale_file_ids(pool).await.unwrap().is_empty());
+
+    let request = |method: &str, uri: &str, body: Option<Value>| {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .unwrap()
+    };
+    let response = router
+        .clone()
+        .oneshot(request("GET", "/api/identify/reparse/diffs", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let diffs: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(diffs.as_array().unwrap().len(), 1);
+    assert_eq!(diffs[0]["file_id"], "misparsed");
+    assert_eq!(diffs[0]["old_parse_version"], "identify-v0");
+    assert_eq!(diffs[0]["old_external_id"], "1");
+    assert_eq!(diffs[0]["candidate"]["external_id"], "400602");
+    assert_eq!(diffs[0]["changes"], json!(["match", "episode"]));
+    // 批准前不改动原匹配
+    let external_id: String =
+        sqlx::query_scalar("SELECT match_external_id FROM media_files WHERE id = 'misparsed'")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+    assert_eq!(external_id, "1");
+
+    let response = router
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/api/identify/reparse/diffs/misparsed/approve",
+            Some(json!({ "download_images": false, "generate_nfo": false })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let (external_id, episode, parse_version): (String, Option<i64>, String) = sqlx::query_as(
+        "SELECT match_external_id, detected_episode, parse_version FROM media_files WHERE id = 'misparsed'",
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    assert_eq!(external_id, "400602");
+    assert_eq!(episode, Some(5));
+    assert_eq!(parse_version, identify::PARSE_VERSION);
+
+    let response = router
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/api/identify/reparse/diffs/misparsed/reject",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let response = router
+        .oneshot(request(
+            "POST",
+            "/api/identify/reparse/diffs/same-match/reject",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(reparse::stale_file_ids(pool).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_stale_file_ids_picks_up_previous_version_and_backs_off_unresolved() {
+    let (_router, app_state, _temp_dir) = common::create_test_router_with_state().await;
+    let pool = &app_state.db;
+    insert_test_file(
+        pool,
+        "previous",
+        "Frieren.S01E05.1080p.mkv",
+        "/media/tv/Frieren.S01E05.1080p.mkv",
+    )
+    .await;
+    insert_test_file(
+        pool,
+        "current",
+        "Frieren.S01E06.1080p.mkv",
+        "/media/tv/Frieren.S01E06.1080p.mkv",
+    )
+    .await;
+    // 规则解析不出标题，重新识别没有推荐候选
+    insert_test_file(pool, "untitled", ".mkv", "/tmp/.mkv").await;
+    for (id, version) in [
+        ("previous", "identify-v1"),
+        ("current", identify::PARSE_VERSION),
+        ("untitled", "identify-v1"),
+    ] {
+        sqlx::query(
+            "UPDATE media_files SET parse_version = ?, detected_title = 'Frieren' WHERE id = ?",
+        )
+        .bind(version)
+        .bind(id)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    assert_eq!(
+        reparse::stale_file_ids(pool).await.unwrap(),
+        ["previous", "untitled"]
+    );
+
+    let file: cine_backend::models::MediaFile =
+        sqlx::query_as("SELECT * FROM media_files WHERE id = 'untitled'")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+    let outcome = reparse::reparse_file(
+        pool,
+        &app_state.http_client,
+        &app_state.config,
+        &metadata_provider::ProviderSession::new(app_state.plugin_manager.clone()),
+        &file,
+    )
+    .await
+    .unwrap();
+    assert_eq!(outcome, reparse::ReparseOutcome::Unresolved);
+    // 当前版本已尝试过，下次任务不再重复查询
+    assert_eq!(reparse::stale_file_ids(pool).await.unwrap(), ["previous"]);
+}
+
+#[tokio::test]
+async fn test_external_id_link_resolves_path_hint_to_linked_provider() {
+    let (router, app_state, _temp_dir) = common::create_test_router_with_state().await;
+    let pool = &app_state.db;
+    sqlx::query(
+        "INSERT INTO settings (id, category, key, value) VALUES ('offline', 'general', 'provider_offline_mode', 'true')",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+    provider_cache::store(
+        pool,
+        "bgm",
+        "details",
+        &json!({
+            "external_id": "400602",
+            "media_type": "tv",
+            "languages": [metadata_provider::DEFAULT_LANGUAGE]
+        }),
+        &json!({ "provider": "bgm", "bgm_id": 400602, "title": "葬送的芙莉莲", "year": 2023 })
+            .to_string(),
+        chrono::Duration::days(7),
+    )
+    .await
+    .unwrap();
+    let request = |method: &str, uri: &str, body: Option<Value>| {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .unwrap()
+    };
+    let link = |id_type: &str| {
+        json!({
+            "provider": "bgm",
+            "external_id": "400602",
+            "media_type": "tv",
+            "id_type": id_type,
+            "value": "209867"
+        })
+    };
+
+    let response = router
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/api/identify/external-ids",
+            Some(link("bgm")),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = router
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/api/identify/external-ids",
+            Some(link("tmdb")),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let ids: Value = serde_json::from_slice(&body).unwrap();
+    let pairs: Vec<(&str, &str)> = ids
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|id| {
+            (
+                id["id_type"].as_str().unwrap(),
+                id["source"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(pairs, [("bgm", "provider"), ("tmdb", "manual")]);
+
+    // 未配置 TMDb 时，路径中的 TMDb ID 经交叉引用落到 Bangumi 条目
+    let path = "/media/anime/Sousou no Frieren {tmdb-209867}/Season 1/Frieren - 05.mkv";
+    insert_test_file(pool, "hinted", "Frieren - 05.mkv", path).await;
+    let response = router
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/api/identify/preview",
+            Some(json!({ "file_id": "hinted", "allow_ai": false })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let payload: Value = serde_json::from_slice(&body).unwrap();
+    let result = &payload["results"][0];
+    assert_eq!(result["recommended"]["provider"], "bgm");
+    assert_eq!(result["recommended"]["external_id"], "400602");
+    assert_eq!(result["parse"]["parser_provider"], "path_id");
+    assert_eq!(result["needs_review"], false);
+
+    let response = router
+        .clone()
+        .oneshot(request(
+            "GET",
+            "/api/identify/external-ids/lookup?id_type=tmdb&value=209867&media_type=tv",
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let titles: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(titles.as_array().unwrap().len(), 2);
+    assert_eq!(titles[0]["provider"], "tmdb");
+    assert_eq!(titles[1]["provider"], "bgm");
+
+    let unlink =
+        "/api/identify/external-ids?provider=bgm&external_id=400602&media_type=tv&id_type=tmdb";
+    let response = router
+        .clone()
+        .oneshot(request("DELETE", unlink, None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = router
+        .oneshot(request("DELETE", unlink, None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_locked_metadata_field_survives_rescrape_and_reverts() {
+    let (router, app_state, _temp_dir) = common::create_test_router_with_state().await;
+    let pool = &app_state.db;
+    sqlx::query(
+        "INSERT INTO settings (id, category, key, value) VALUES ('offline', 'general', 'provider_offline_mode', 'true')",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+    provider_cache::store(
+        pool,
+        "bgm",
+        "details",
+        &json!({
+            "external_id": "400602",
+            "media_type": "tv",
+            "languages": [metadata_provider::DEFAULT_LANGUAGE]
+        }),
+        &json!({
+            "provider": "bgm",
+            "bgm_id": 400602,
+            "title": "葬送的芙莉莲",
+            "overview": "勇者一行人打倒魔王之后",
+            "year": 2023
+        })
+        .to_string(),
+        chrono::Duration::days(7),
+    )
+    .await
+    .unwrap();
+    insert_test_file(
+        pool,
+        "frieren",
+        "Frieren - 05.mkv",
+        "/media/anime/Frieren/Season 1/Frieren - 05.mkv",
+    )
+    .await;
+    let request = |method: &str, uri: &str, body: Option<Value>| {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .unwrap()
+    };
+    let apply = json!({
+        "selections": [{
+            "file_id": "frieren",
+            "provider": "bgm",
+            "external_id": "400602",
+            "media_type": "tv"
+        }]
+    });
+
+    let response = router
+        .clone()
+        .oneshot(request("POST", "/api/identify/apply", Some(apply.clone())))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = router
+        .clone()
+        .oneshot(request(
+            "PUT",
+            "/api/files/frieren/metadata",
+            Some(json!({ "fields": { "match_provider": "tmdb" } })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = router
+        .clone()
+        .oneshot(request(
+            "PUT",
+            "/api/files/frieren/metadata",
+            Some(json!({ "fields": { "title": "芙莉莲", "overview": "人工简介" } })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let state: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(state["locked_fields"], json!(["title", "overview"]));
+
+    // 只保留标题的锁，重新刮削后标题保留人工值，简介恢复为数据源内容
+    let response = router
+        .clone()
+        .oneshot(request(
+            "PUT",
+            "/api/files/frieren/metadata/locks",
+            Some(json!({ "unlock": ["overview"] })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = router
+        .clone()
+        .oneshot(request("POST", "/api/identify/apply", Some(apply)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let payload: Value = serde_json::from_slice(&body).unwrap();
+    let metadata = &payload["applied"][0]["metadata"];
+    assert_eq!(metadata["title"], "芙莉莲");
+    assert_eq!(metadata["overview"], "勇者一行人打倒魔王之后");
+
+    let response = router
+        .clone()
+        .oneshot(request("GET", "/api/files/frieren/metadata/history", None))
+        .await
+        .unwrap();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let history: Value = serde_json::from_slice(&body).unwrap();
+    let history = history.as_array().unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0]["source"], "scrape");
+    assert_eq!(
+        history[0]["changes"],
+        json!([
+            { "field": "title", "old": "芙莉莲", "new": "葬送的芙莉莲", "locked": true },
+            { "field": "overview", "old": "人工简介", "new": "勇者一行人打倒魔王之后", "locked": false }
+        ])
+    );
+    assert_eq!(history[1]["source"], "edit");
+
+    // 回滚到人工修改之前
+    let edit_id = history[1]["id"].as_i64().unwrap();
+    let response = router
+        .clone()
+        .oneshot(request(
+            "POST",
+            &format!("/api/files/frieren/metadata/history/{edit_id}/revert"),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let state: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(state["metadata"]["title"], "葬送的芙莉莲");
+    assert_eq!(state["locked_fields"], json!([]));
+
+    let response = router
+        .oneshot(request(
+            "POST",
+            "/api/files/frieren/metadata/history/9999/revert",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_locked_metadata_field_survives_single_scrape() {
+    let (router, app_state, _temp_dir) = common::create_test_router_with_state().await;
+    let pool = &app_state.db;
+    sqlx::query(
+        "INSERT INTO settings (id, category, key, value) VALUES
+            ('offline', 'general', 'provider_offline_mode', 'true'),
+            ('tmdb-key', 'general', 'tmdb_api_key', 'test-key')",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+    provider_cache::store(
+        pool,
+        "tmdb",
+        "details",
+        &json!({
+            "external_id": "603",
+            "media_type": "movie",
+            "languages": [metadata_provider::DEFAULT_LANGUAGE]
+        }),
+        &json!({
+            "provider": "tmdb",
+            "tmdb_id": 603,
+            "title": "The Matrix",
+            "overview": "A computer hacker learns the truth",
+            "year": 1999,
+            "external_ids": { "imdb": "tt0133093" }
+        })
+        .to_string(),
+        chrono::Duration::days(7),
+    )
+    .await
+    .unwrap();
+    insert_test_file(
+        pool,
+        "matrix",
+        "matrix.1999.mkv",
+        "/media/movies/matrix.1999.mkv",
+    )
+    .await;
+    sqlx::query("UPDATE media_files SET metadata = ? WHERE id = 'matrix'")
+        .bind(json!({ "title": "Matrix", "overview": "旧简介", "year": 1999 }).to_string())
+        .execute(pool)
+        .await
+        .unwrap();
+    let request = |method: &str, uri: &str, body: Value| {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let response = router
+        .clone()
+        .oneshot(request(
+            "PUT",
+            "/api/files/matrix/metadata",
+            json!({ "fields": { "title": "黑客帝国" } }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // 单文件刮削同样保留锁定字段，未锁定的字段更新为数据源内容
+    let response = router
+        .oneshot(request(
+            "POST",
+            "/api/scrape",
+            json!({ "file_id": "matrix", "tmdb_id": 603 }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let payload: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(payload["error"], Value::Null);
+    assert_eq!(payload["metadata"]["title"], "黑客帝国");
+    assert_eq!(
+        payload["metadata"]["overview"],
+        "A computer hacker learns the truth"
+    );
+
+    let stored: (String,) = sqlx::query_as("SELECT metadata FROM media_files WHERE id = 'matrix'")
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    let stored: Value = serde_json::from_str(&stored.0).unwrap();
+    assert_eq!(stored["title"], "黑客帝国");
+    assert_eq!(stored["overview"], "A computer hacker learns the truth");
+}
+
+#[tokio::test]
+async fn test_identify_apply_continues_when_alias_learning_fails() {
+    let (router, app_state, _temp_dir) = common::create_test_router_with_state().await;
+    let pool = &app_state.db;
+    sqlx::query(
+        "INSERT INTO settings (id, category, key, value) VALUES ('offline', 'general', 'provider_offline_mode', 'true')",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+    provider_cache::store(
+        pool,
+        "bgm",
+        "details",
+        &json!({
+            "external_id": "400602",
+            "media_type": "tv",
+            "languages": [metadata_provider::DEFAULT_LANGUAGE]
+        }),
+        &json!({ "provider": "bgm", "bgm_id": 400602, "title": "葬送的芙莉莲", "year": 2023 })
+            .to_string(),
+        chrono::Duration::days(7),
+    )
+    .await
+    .unwrap();
+    for episode in ["05", "06"] {
+        let name = format!("Frieren - {episode}.mkv");
+        insert_test_file(
+            pool,
+            &format!("frieren-{episode}"),
+            &name,
+            &format!("/media/anime/Frieren/Season 1/{name}"),
+        )
+        .await;
+    }
+    // 别名表不可写时匹配仍然生效，且不影响后续文件
+    sqlx::query("DROP TABLE title_aliases")
+        .execute(pool)
+        .await
+        .unwrap();
+
+    let selection = |file_id: &str| {
+        json!({
+            "file_id": file_id,
+            "provider": "bgm",
+            "external_id": "400602",
+            "media_type": "tv"
+        })
+    };
+    let response = router
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/identify/apply")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "selections": [selection("frieren-05"), selection("frieren-06")] })
+                        .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let payload: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(payload["applied"].as_array().unwrap().len(), 2);
+    assert_eq!(payload["applied"][1]["metadata"]["title"], "葬送的芙莉莲");
+}
@@ -0,0 +1,189 @@
+//! AI 文件名解析测试（本地模拟 OpenAI 兼容端点）
+
+use std::sync::{Arc, Mutex};
+
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::{Json, Router};
+use cine_backend::services::ai_parser::{self, AiEndpoint};
+use serde_json::{json, Value};
+
+/// 收到的请求：(Authorization 头, 请求体)
+type Captured = Arc<Mutex<Vec<(Option<String>, Value)>>>;
+
+/// 启动本地 `/v1/chat/completions`，固定回复 `content`
+async fn spawn_llm(content: &'static str) -> (String, Captured) {
+    let captured: Captured = Arc::default();
+    let sink = captured.clone();
+    let app = Router::new().route(
+        "/v1/chat/completions",
+        post(move |headers: HeaderMap, Json(body): Json<Value>| {
+            let sink = sink.clone();
+            async move {
+                let auth = headers
+                    .get("authorization")
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string);
+                sink.lock().unwrap().push((auth, body));
+                Json(json!({
+                    "id": "chatcmpl-test",
+                    "object": "chat.completion",
+                    "choices": [
+                        { "index": 0, "message": { "role": "assistant", "content": content } }
+                    ]
+                }))
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("http://{addr}/v1"), captured)
+}
+
+fn local_endpoint(base_url: String, api_key: Option<&str>) -> AiEndpoint {
+    AiEndpoint {
+        backend: ai_parser::BACKEND_OPENAI.to_string(),
+        base_url,
+        api_key: api_key.map(str::to_string),
+        model: "qwen2.5:7b-instruct".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_parse_filename_with_local_endpoint() {
+    let (base_url, captured) = spawn_llm(
+        r#"{"title":"间谍过家家","year":null,"season":2,"episode":3,"is_special":false,"confidence":0.91}"#,
+    )
+    .await;
+    let endpoint = local_endpoint(base_url, None);
+    let filename = "【幻樱字幕组】【间谍过家家 第二季】【03】【GB_MP4】.mp4";
+
+    let parsed = ai_parser::parse_filename(&reqwest::Client::new(), &endpoint, filename)
+        .await
+        .unwrap()
+        .expect("valid reply is accepted");
+    assert_eq!(parsed.title, "间谍过家家");
+    assert_eq!(parsed.season, Some(2));
+    assert_eq!(parsed.episode, Some(3));
+    assert_eq!(parsed.year, None);
+    assert!((parsed.confidence - 0.91).abs() < f64::EPSILON);
+    assert_eq!(parsed.parser_provider, "openai_compatible");
+
+    let requests = captured.lock().unwrap();
+    let (auth, body) = &requests[0];
+    assert!(auth.is_none(), "no key configured, no Authorization header");
+    assert_eq!(body["model"], "qwen2.5:7b-instruct");
+    let messages = body["messages"].as_array().unwrap();
+    assert_eq!(messages[0]["role"], "system");
+    assert!(messages[0]["content"].as_str().unwrap().contains("第二季"));
+    assert_eq!(messages.last().unwrap()["content"], filename);
+}
+
+#[tokio::test]
+async fn test_parse_filename_sends_bearer_key() {
+    let (base_url, captured) = spawn_llm(r#"{"title":"Frieren"}"#).await;
+    let endpoint = local_endpoint(format!("{base_url}/"), Some("sk-local"));
+
+    let parsed = ai_parser::parse_filename(&reqwest::Client::new(), &endpoint, "Frieren - 05.mkv")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(parsed.title, "Frieren");
+    assert!(!parsed.is_special);
+
+    let requests = captured.lock().unwrap();
+    assert_eq!(requests[0].0.as_deref(), Some("Bearer sk-local"));
+}
+
+#[tokio::test]
+async fn test_parse_filename_discards_reply_violating_schema() {
+    let (base_url, _) = spawn_llm(r#"{"title":"葬送的芙莉莲","episode":"五"}"#).await;
+    let endpoint = local_endpoint(base_url, None);
+
+    let parsed =
+        ai_parser::parse_filename(&reqwest::Client::new(), &endpoint, "葬送的芙莉莲 05.mkv")
+            .await
+            .unwrap();
+    assert!(parsed.is_none());
+}
+
+#[tokio::test]
+async fn test_parse_filename_reports_http_errors() {
+    let app = Router::new().route(
+        "/v1/chat/completions",
+        post(|| async {
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                Json(json!({ "error": { "message": "rate limited" } })),
+            )
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    let endpoint = local_endpoint(format!("http://{addr}/v1"), None);
+
+    let error = ai_parser::parse_filename(&reqwest::Client::new(), &endpoint, "Frieren - 05.mkv")
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("429"), "{error}");
+}
+
+#[test]
+fn test_only_paid_backends_count_against_budget() {
+    let endpoint = |backend: &str, base_url: &str| AiEndpoint {
+        backend: backend.to_string(),
+        base_url: base_url.to_string(),
+        api_key: None,
+        model: "test-model".to_string(),
+    };
+    assert!(endpoint(ai_parser::BACKEND_CLOUDFLARE, "http://127.0.0.1:8787").is_paid());
+    assert!(!endpoint(
+        ai_parser::BACKEND_OPENAI,
+        ai_parser::DEFAULT_OPENAI_BASE_URL
+    )
+    .is_paid());
+    assert!(!endpoint(ai_parser::BACKEND_OPENAI, "http://localhost:1234/v1").is_paid());
+    assert!(!endpoint(ai_parser::BACKEND_OPENAI, "http://192.168.1.20:11434/v1").is_paid());
+    assert!(!endpoint(ai_parser::BACKEND_OPENAI, "http://[fd00::2]:8080/v1").is_paid());
+    // 托管的 OpenAI 兼容服务按计费端点处理
+    assert!(endpoint(ai_parser::BACKEND_OPENAI, "https://api.openai.com/v1").is_paid());
+    assert!(endpoint(ai_parser::BACKEND_OPENAI, "https://openrouter.ai/api/v1").is_paid());
+    assert!(endpoint(ai_parser::BACKEND_OPENAI, "not a url").is_paid());
+}
+
+#[test]
+fn test_validate_content_accepts_fenced_json() {
+    let data =
+        ai_parser::validate_content("```json\n{\"title\":\"甄嬛传\",\"year\":2011}\n```").unwrap();
+    assert_eq!(data["title"], "甄嬛传");
+    assert_eq!(data["year"], 2011);
+}
+
+#[test]
+fn test_validate_content_rejects_bad_replies() {
+    assert!(ai_parser::validate_content("not json").is_err());
+    assert!(ai_parser::validate_content(r#"{"year":2011}"#).is_err());
+    assert!(ai_parser::validate_content(r#"{"title":"  "}"#).is_err());
+    assert!(ai_parser::validate_content(r#"{"title":"A","year":20}"#).is_err());
+    assert!(ai_parser::validate_content(r#"{"title":"A","confidence":1.5}"#).is_err());
+}
+
+#[test]
+fn test_build_messages_includes_cjk_few_shot() {
+    let messages = ai_parser::build_messages("example.mkv");
+    assert!(messages.len() > 2);
+    assert_eq!(messages.len() % 2, 0);
+    for answer in messages
+        .iter()
+        .filter(|message| message["role"] == "assistant")
+    {
+        ai_parser::validate_content(answer["content"].as_str().unwrap())
+            .expect("few-shot answers satisfy the schema");
+    }
+}
@@ -0,0 +1,279 @@
+//! 番剧字幕组命名解析测试
+
+use cine_backend::services::{anime_parser, release_parser};
+
+struct Case {
+    name: &'static str,
+    title: &'static str,
+    alternative_titles: &'static [&'static str],
+    year: Option<u32>,
+    season: Option<u32>,
+    episode: Option<u32>,
+    absolute_episode: Option<u32>,
+    episode_end: Option<u32>,
+    version: Option<u32>,
+    special_type: Option<&'static str>,
+    batch: bool,
+    release_group: Option<&'static str>,
+    resolution: Option<&'static str>,
+    subtitles: &'static [&'static str],
+}
+
+const EMPTY: Case = Case {
+    name: "",
+    title: "",
+    alternative_titles: &[],
+    year: None,
+    season: None,
+    episode: None,
+    absolute_episode: None,
+    episode_end: None,
+    version: None,
+    special_type: None,
+    batch: false,
+    release_group: None,
+    resolution: None,
+    subtitles: &[],
+};
+
+const CORPUS: &[Case] = &[
+    Case {
+        name: "[Lilith-Raws] Sousou no Frieren - 05v2 [Baha][WEB-DL][1080p][AVC AAC][CHT][MP4].mp4",
+        title: "Sousou no Frieren",
+        absolute_episode: Some(5),
+        version: Some(2),
+        release_group: Some("Lilith-Raws"),
+        resolution: Some("1080p"),
+        subtitles: &["zh-hant"],
+        ..EMPTY
+    },
+    Case {
+        name: "[ANi] 葬送的芙莉蓮 - 05 [1080P][Baha][WEB-DL][AAC AVC][CHT].mp4",
+        title: "葬送的芙莉蓮",
+        absolute_episode: Some(5),
+        release_group: Some("ANi"),
+        resolution: Some("1080p"),
+        subtitles: &["zh-hant"],
+        ..EMPTY
+    },
+    Case {
+        name: "【喵萌奶茶屋】★10月新番★[葬送的芙莉莲 / Sousou no Frieren][05][1080p][简日双语][招募翻译].mp4",
+        title: "葬送的芙莉莲",
+        alternative_titles: &["Sousou no Frieren"],
+        absolute_episode: Some(5),
+        release_group: Some("喵萌奶茶屋"),
+        resolution: Some("1080p"),
+        subtitles: &["zh-hans", "ja"],
+        ..EMPTY
+    },
+    Case {
+        name: "[SweetSub][Frieren][05][1080P][CHS].mkv",
+        title: "Frieren",
+        absolute_episode: Some(5),
+        release_group: Some("SweetSub"),
+        resolution: Some("1080p"),
+        subtitles: &["zh-hans"],
+        ..EMPTY
+    },
+    Case {
+        name: "[Nekomoe kissaten][Kimetsu no Yaiba][01-26][BDRip 1080p HEVC-10bit FLAC][CHS&JPN]",
+        title: "Kimetsu no Yaiba",
+        absolute_episode: Some(1),
+        episode_end: Some(26),
+        batch: true,
+        release_group: Some("Nekomoe kissaten"),
+        resolution: Some("1080p"),
+        subtitles: &["zh-hans", "ja"],
+        ..EMPTY
+    },
+    Case {
+        name: "[DBD-Raws][进击的巨人 最终季][01-16TV全集][1080P][BDRip][HEVC-10bit][简繁日双语外挂][FLAC][MKV]",
+        title: "进击的巨人 最终季",
+        absolute_episode: Some(1),
+        episode_end: Some(16),
+        batch: true,
+        release_group: Some("DBD-Raws"),
+        resolution: Some("1080p"),
+        subtitles: &["zh-hans", "zh-hant", "ja"],
+        ..EMPTY
+    },
+    Case {
+        name: "[Group] Sousou no Frieren - 01-04 [1080p].mkv",
+        title: "Sousou no Frieren",
+        absolute_episode: Some(1),
+        episode_end: Some(4),
+        batch: true,
+        release_group: Some("Group"),
+        resolution: Some("1080p"),
+        ..EMPTY
+    },
+    Case {
+        name: "[VCB-Studio] Mushishi [OVA01][Ma10p_1080p][x265_flac].mkv",
+        title: "Mushishi",
+        season: Some(0),
+        episode: Some(1),
+        special_type: Some("ova"),
+        release_group: Some("VCB-Studio"),
+        resolution: Some("1080p"),
+        ..EMPTY
+    },
+    Case {
+        name: "[Group] Title - SP02 [1080p].mkv",
+        title: "Title",
+        season: Some(0),
+        episode: Some(2),
+        special_type: Some("special"),
+        release_group: Some("Group"),
+        resolution: Some("1080p"),
+        ..EMPTY
+    },
+    Case {
+        name: "[Group] Title - OVA [1080p].mkv",
+        title: "Title",
+        season: Some(0),
+        special_type: Some("ova"),
+        release_group: Some("Group"),
+        resolution: Some("1080p"),
+        ..EMPTY
+    },
+    Case {
+        name: "[SubsPlease] Spy x Family S2 - 03 (1080p) [ABCD1234].mkv",
+        title: "Spy x Family",
+        season: Some(2),
+        episode: Some(3),
+        release_group: Some("SubsPlease"),
+        resolution: Some("1080p"),
+        ..EMPTY
+    },
+    Case {
+        name: "[Group] Kaguya-sama wa Kokurasetai 2nd Season - 12 END [720p].mkv",
+        title: "Kaguya-sama wa Kokurasetai",
+        season: Some(2),
+        episode: Some(12),
+        release_group: Some("Group"),
+        resolution: Some("720p"),
+        ..EMPTY
+    },
+    Case {
+        name: "[桜都字幕组] 间谍过家家 第二季 [03][1080p][简繁内封].mkv",
+        title: "间谍过家家",
+        season: Some(2),
+        episode: Some(3),
+        release_group: Some("桜都字幕组"),
+        resolution: Some("1080p"),
+        subtitles: &["zh-hans", "zh-hant"],
+        ..EMPTY
+    },
+    Case {
+        name: "[Sakurato] Mob Psycho 100 III [01][1080p][CHS].mkv",
+        title: "Mob Psycho 100 III",
+        absolute_episode: Some(1),
+        release_group: Some("Sakurato"),
+        resolution: Some("1080p"),
+        subtitles: &["zh-hans"],
+        ..EMPTY
+    },
+    Case {
+        name: "[Sakurato] Bocchi the Rock 05 [1080p].mkv",
+        title: "Bocchi the Rock",
+        absolute_episode: Some(5),
+        release_group: Some("Sakurato"),
+        resolution: Some("1080p"),
+        ..EMPTY
+    },
+    Case {
+        name: "[Erai-raws] Re Zero kara Hajimeru Isekai Seikatsu - 05 [1080p][Multiple Subtitle].mkv",
+        title: "Re Zero kara Hajimeru Isekai Seikatsu",
+        absolute_episode: Some(5),
+        release_group: Some("Erai-raws"),
+        resolution: Some("1080p"),
+        subtitles: &["multi"],
+        ..EMPTY
+    },
+    Case {
+        name: "[Group] One Piece - 1071 [1080p].mkv",
+        title: "One Piece",
+        absolute_episode: Some(1071),
+        release_group: Some("Group"),
+        resolution: Some("1080p"),
+        ..EMPTY
+    },
+    Case {
+        name: "[Group] Your Name (2016) [BDRip 1080p].mkv",
+        title: "Your Name",
+        year: Some(2016),
+        release_group: Some("Group"),
+        resolution: Some("1080p"),
+        ..EMPTY
+    },
+];
+
+#[test]
+fn test_anime_parser_corpus() {
+    for case in CORPUS {
+        let info = release_parser::parse(case.name);
+        let name = case.name;
+        assert_eq!(info.title, case.title, "title: {name}");
+        assert_eq!(
+            info.alternative_titles, case.alternative_titles,
+            "alternative_titles: {name}"
+        );
+        assert_eq!(info.year, case.year, "year: {name}");
+        assert_eq!(info.season, case.season, "season: {name}");
+        assert_eq!(info.episode, case.episode, "episode: {name}");
+        assert_eq!(
+            info.absolute_episode, case.absolute_episode,
+            "absolute_episode: {name}"
+        );
+        assert_eq!(info.episode_end, case.episode_end, "episode_end: {name}");
+        assert_eq!(info.version, case.version, "version: {name}");
+        assert_eq!(
+            info.special_type.as_deref(),
+            case.special_type,
+            "special_type: {name}"
+        );
+        assert_eq!(info.batch, case.batch, "batch: {name}");
+        assert_eq!(
+            info.release_group.as_deref(),
+            case.release_group,
+            "release_group: {name}"
+        );
+        assert_eq!(
+            info.resolution.as_deref(),
+            case.resolution,
+            "resolution: {name}"
+        );
+        assert_eq!(info.subtitles, case.subtitles, "subtitles: {name}");
+    }
+}
+
+#[test]
+fn test_looks_like_fansub() {
+    assert!(anime_parser::looks_like_fansub(
+        "[SweetSub][Frieren][05][1080P][CHS].mkv"
+    ));
+    assert!(anime_parser::looks_like_fansub(
+        "[ANi] 葬送的芙莉蓮 - 05.mp4"
+    ));
+    assert!(anime_parser::looks_like_fansub(
+        "【喵萌奶茶屋】[葬送的芙莉莲][05].mp4"
+    ));
+    assert!(!anime_parser::looks_like_fansub(
+        "The.Matrix.1999.1080p.BluRay.x264-SPARKS.mkv"
+    ));
+    assert!(!anime_parser::looks_like_fansub("[Group] Movie.mkv"));
+}
+
+#[test]
+fn test_anime_library_forces_anime_parser() {
+    // 不以方括号开头的名称默认走普通规则；番剧库始终按字幕组规则解析出版本号
+    let name = "Sousou no Frieren - 05v2 [1080p].mkv";
+    let info = release_parser::parse_for_library(name, Some("anime"));
+    assert_eq!(info.title, "Sousou no Frieren");
+    assert_eq!(info.absolute_episode, Some(5));
+    assert_eq!(info.version, Some(2));
+
+    let movie = release_parser::parse_for_library("Heat.1995.1080p.mkv", Some("movie"));
+    assert_eq!(movie.title, "Heat");
+    assert_eq!(movie.version, None);
+}
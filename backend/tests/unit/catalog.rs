@@ -0,0 +1,210 @@
+//! 影视目录测试
+
+use cine_backend::services::catalog::{self, Level};
+#[path = "../common/mod.rs"]
+mod common;
+use common::{create_test_db, insert_media_file};
+use serde_json::json;
+use sqlx::{sqlite::SqlitePoolOptions, Executor};
+
+#[tokio::test]
+async fn test_link_movie_file() {
+    let (pool, _temp_dir) = create_test_db().await;
+    insert_media_file(&pool, "f1", "/media/The.Matrix.1999.1080p.mkv", None).await;
+    insert_media_file(&pool, "f2", "/media/The.Matrix.1999.2160p.mkv", None).await;
+
+    let details = json!({
+        "title": "The Matrix",
+        "original_title": "The Matrix",
+        "year": 1999,
+        "overview": "A hacker learns the truth.",
+        "rating": 8.2,
+        "media_type": "movie",
+    });
+    catalog::link_file(&pool, "f1", "tmdb", "603", "movie", &details)
+        .await
+        .unwrap();
+    catalog::link_file(&pool, "f2", "tmdb", "603", "movie", &details)
+        .await
+        .unwrap();
+
+    let movies = catalog::list_movies(&pool, None, 100, 0).await.unwrap();
+    assert_eq!(movies.len(), 1);
+    assert_eq!(movies[0].id, "tmdb:603");
+    assert_eq!(movies[0].year, Some(1999));
+    assert_eq!(movies[0].file_count, 2);
+
+    let files = catalog::files_for(&pool, Level::Movie, "tmdb:603")
+        .await
+        .unwrap();
+    assert_eq!(files.len(), 2);
+    assert!(files.iter().all(|file| file.show_id.is_none()));
+
+    assert!(!catalog::list_movies(&pool, Some("matrix"), 100, 0)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(catalog::list_movies(&pool, Some("inception"), 100, 0)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_link_multi_episode_file() {
+    let (pool, _temp_dir) = create_test_db().await;
+    insert_media_file(&pool, "f1", "/media/Show.S02E03-E04.mkv", None).await;
+
+    let details = json!({
+        "name": "Breaking Bad",
+        "media_type": "tv",
+        "season_number": 2,
+        "season_name": "Season 2",
+        "episode_number": 3,
+        "episode_number_end": 4,
+        "episodes": [
+            { "season_number": 2, "episode_number": 3, "name": "Bit by a Dead Bee", "runtime": 47 },
+            { "season_number": 2, "episode_number": 4, "name": "Down", "runtime": 47 },
+        ],
+    });
+    catalog::link_file(&pool, "f1", "tmdb", "1396", "tv", &details)
+        .await
+        .unwrap();
+
+    let show = catalog::get_show(&pool, "tmdb:1396")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(show.title, "Breaking Bad");
+    assert_eq!(show.season_count, 1);
+    assert_eq!(show.episode_count, 2);
+    assert_eq!(show.file_count, 1);
+
+    let season = catalog::get_season(&pool, "tmdb:1396", 2)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(season.id, "tmdb:1396:s2");
+    assert_eq!(season.name.as_deref(), Some("Season 2"));
+
+    let episodes = catalog::list_episodes(&pool, &season.id).await.unwrap();
+    assert_eq!(episodes.len(), 2);
+    assert_eq!(episodes[0].title.as_deref(), Some("Bit by a Dead Bee"));
+    assert_eq!(episodes[1].title.as_deref(), Some("Down"));
+    assert_eq!(episodes[0].file_count, 1);
+    assert_eq!(episodes[1].file_count, 0);
+
+    // 重新识别为电影后，剧集关联被清除
+    catalog::link_file(
+        &pool,
+        "f1",
+        "tmdb",
+        "603",
+        "movie",
+        &json!({ "title": "The Matrix" }),
+    )
+    .await
+    .unwrap();
+    let files = catalog::files_for(&pool, Level::Show, "tmdb:1396")
+        .await
+        .unwrap();
+    assert!(files.is_empty());
+}
+
+#[tokio::test]
+async fn test_migration_backfills_identified_files() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let database_url = format!(
+        "sqlite:{}?mode=rwc",
+        temp_dir.path().join("test.db").to_string_lossy()
+    );
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&database_url)
+        .await
+        .unwrap();
+
+    // 先执行目录表之前的迁移，写入旧格式数据，再执行目录迁移
+    let migrator = sqlx::migrate!("./migrations");
+    for migration in migrator.iter().filter(|m| m.version < 21) {
+        pool.execute(migration.sql.as_ref()).await.unwrap();
+    }
+    insert_media_file(&pool, "movie", "/media/Inception.2010.mkv", None).await;
+    insert_media_file(&pool, "episode", "/media/Show.S01E02.mkv", None).await;
+    insert_media_file(&pool, "unknown", "/media/home-video.mkv", None).await;
+    sqlx::query("UPDATE media_files SET tmdb_id = 27205, metadata = ? WHERE id = 'movie'")
+        .bind(json!({ "title": "Inception", "year": 2010 }).to_string())
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "UPDATE media_files SET match_provider = 'bgm', match_external_id = '100', metadata = ? WHERE id = 'episode'",
+    )
+    .bind(
+        json!({
+            "title": "Some Anime",
+            "media_type": "tv",
+            "season_number": 1,
+            "episode_number": 2,
+            "episode_title": "Second",
+        })
+        .to_string(),
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    for migration in migrator.iter().filter(|m| m.version >= 21) {
+        pool.execute(migration.sql.as_ref()).await.unwrap();
+    }
+
+    let movie = catalog::get_movie(&pool, "tmdb:27205")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(movie.title, "Inception");
+    assert_eq!(movie.file_count, 1);
+
+    let show = catalog::get_show(&pool, "bgm:100").await.unwrap().unwrap();
+    assert_eq!(show.episode_count, 1);
+    let episodes = catalog::list_episodes(&pool, "bgm:100:s1").await.unwrap();
+    assert_eq!(episodes[0].title.as_deref(), Some("Second"));
+    let files = catalog::files_for(&pool, Level::Episode, "bgm:100:s1e2")
+        .await
+        .unwrap();
+    assert_eq!(files.len(), 1);
+
+    let unknown: (Option<String>, Option<String>) =
+        sqlx::query_as("SELECT movie_id, show_id FROM media_files WHERE id = 'unknown'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(unknown, (None, None));
+}
+
+#[tokio::test]
+async fn test_catalog_search_matches_aliases() {
+    let (pool, _temp_dir) = create_test_db().await;
+    insert_media_file(&pool, "f1", "/media/Spirited.Away.2001.mkv", None).await;
+
+    let details = json!({
+        "title": "千与千寻",
+        "original_title": "千と千尋の神隠し",
+        "localized_titles": { "en-US": "Spirited Away", "zh-TW": "神隱少女" },
+        "aliases": ["Sen to Chihiro no Kamikakushi"],
+    });
+    catalog::link_file(&pool, "f1", "tmdb", "129", "movie", &details)
+        .await
+        .unwrap();
+
+    let movie = catalog::get_movie(&pool, "tmdb:129")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(movie.aliases.unwrap().contains("Kamikakushi"));
+    for search in ["Spirited", "神隱", "Chihiro"] {
+        let found = catalog::list_movies(&pool, Some(search), 100, 0)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1, "search {search}");
+    }
+}
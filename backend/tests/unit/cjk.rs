@@ -0,0 +1,81 @@
+//! 中日文标题归一化测试
+
+use cine_backend::services::cjk;
+
+#[test]
+fn test_half_width_and_punctuation() {
+    let folded: String = "ＳＰＹ×ＦＡＭＩＬＹ　【１０８０Ｐ】"
+        .chars()
+        .map(cjk::to_half_width)
+        .collect();
+    assert_eq!(folded, "SPY×FAMILY  1080P ");
+}
+
+#[test]
+fn test_traditional_to_simplified() {
+    let folded: String = "鬼滅之刃 進擊的巨人 間諜家家酒"
+        .chars()
+        .map(cjk::to_simplified)
+        .collect();
+    assert_eq!(folded, "鬼灭之刃 进击的巨人 间谍家家酒");
+}
+
+#[test]
+fn test_chinese_numerals_only_in_markers() {
+    assert_eq!(cjk::convert_chinese_numerals("第二季"), "第2季");
+    assert_eq!(cjk::convert_chinese_numerals("第十二集"), "第12集");
+    assert_eq!(cjk::convert_chinese_numerals("物语 二期"), "物语 2期");
+    assert_eq!(cjk::convert_chinese_numerals("一拳超人"), "一拳超人");
+    assert_eq!(cjk::convert_chinese_numerals("三体 第一部"), "三体 第1部");
+}
+
+#[test]
+fn test_kana_to_romaji() {
+    assert_eq!(
+        cjk::kana_to_romaji("しんげきのきょじん"),
+        "shingekinokyojin"
+    );
+    assert_eq!(cjk::kana_to_romaji("チェンソーマン"), "chensoman");
+    assert_eq!(
+        cjk::kana_to_romaji("ぼっち・ざ・ろっく"),
+        "botchi・za・rokku"
+    );
+    assert_eq!(cjk::kana_to_romaji("ジョジョ"), "jojo");
+    assert_eq!(cjk::kana_to_romaji("ファイナル"), "fainaru");
+}
+
+#[test]
+fn test_fold_romaji_long_vowels() {
+    assert_eq!(cjk::fold_romaji("kōkaku kidōtai"), "kokaku kidotai");
+    assert_eq!(cjk::fold_romaji("koukaku kidoutai"), "kokaku kidotai");
+    assert_eq!(cjk::fold_romaji("shimbun"), "shinbun");
+    assert_eq!(cjk::fold_romaji("yuuki"), "yuki");
+    assert_eq!(cjk::fold_romaji("botchi"), "bocchi");
+}
+
+#[test]
+fn test_fold_matches_variants() {
+    assert_eq!(
+        cjk::fold("進擊的巨人 第二季"),
+        cjk::fold("进击的巨人 第2季")
+    );
+    assert_eq!(
+        cjk::fold("ぼっち・ざ・ろっく！"),
+        cjk::fold("Bocchi Za Rokku!")
+    );
+    assert_eq!(cjk::fold("Dungeon Meshi"), "dungeon meshi");
+    assert_eq!(cjk::fold("Kōkaku Kidōtai"), "kokaku kidotai");
+}
+
+#[test]
+fn test_fold_leaves_english_words_alone() {
+    // 长音、m/n、促音写法只在日文词上合并
+    assert_ne!(cjk::fold("Good Boys"), cjk::fold("God Boys"));
+    assert_eq!(cjk::fold("The Room"), "the room");
+    assert_eq!(cjk::fold("House"), "house");
+    assert_eq!(cjk::fold("Watchmen"), "watchmen");
+    assert_eq!(cjk::fold("Shimbun"), "shimbun");
+    assert!(cjk::has_japanese_hint("ぼっち"));
+    assert!(cjk::has_japanese_hint("Kōkaku"));
+    assert!(!cjk::has_japanese_hint("Koukaku"));
+}
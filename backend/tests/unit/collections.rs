@@ -0,0 +1,141 @@
+//! 电影系列测试
+
+use cine_backend::services::catalog;
+use cine_backend::services::collections;
+#[path = "../common/mod.rs"]
+mod common;
+use common::{create_test_db, insert_media_file};
+use serde_json::json;
+
+fn matrix_collection() -> serde_json::Value {
+    collections::collection_from_tmdb_payload(&json!({
+        "id": 2344,
+        "name": "The Matrix Collection",
+        "overview": "",
+        "poster_path": "/matrix-collection.jpg",
+        "parts": [
+            { "id": 604, "title": "The Matrix Reloaded", "release_date": "2003-05-15" },
+            { "id": 624860, "title": "The Matrix Resurrections", "release_date": "" },
+            { "id": 603, "title": "The Matrix", "release_date": "1999-03-31", "poster_path": "/matrix.jpg" },
+        ],
+    }))
+}
+
+#[test]
+fn test_collection_from_tmdb_payload() {
+    let collection = matrix_collection();
+    assert_eq!(collection["id"], "2344");
+    assert_eq!(
+        collection["poster_url"],
+        "https://image.tmdb.org/t/p/w500/matrix-collection.jpg"
+    );
+    assert!(collection["overview"].is_null());
+    assert!(!collections::has_overview(&collection));
+
+    let parts = collection["parts"].as_array().unwrap();
+    let ids: Vec<&str> = parts.iter().map(|p| p["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, ["603", "604", "624860"]);
+    assert_eq!(parts[0]["year"], 1999);
+    assert!(parts[2]["year"].is_null());
+
+    let merged = collections::merge_collection_translation(
+        Some(collection),
+        json!({
+            "name": "黑客帝国（系列）",
+            "overview": "Neo 的故事",
+            "parts": [{ "id": "603", "overview": "一名黑客得知真相。" }],
+        }),
+    );
+    assert_eq!(merged["name"], "The Matrix Collection");
+    assert_eq!(merged["overview"], "Neo 的故事");
+    assert_eq!(merged["parts"][0]["overview"], "一名黑客得知真相。");
+}
+
+#[tokio::test]
+async fn test_collection_owned_and_missing_parts() {
+    let (pool, _temp_dir) = create_test_db().await;
+    insert_media_file(&pool, "f1", "/media/The.Matrix.1999.mkv", None).await;
+
+    let reference = json!({ "id": "2344", "name": "The Matrix Collection" });
+    catalog::link_file(
+        &pool,
+        "f1",
+        "tmdb",
+        "603",
+        "movie",
+        &json!({ "title": "The Matrix", "year": 1999, "collection": reference }),
+    )
+    .await
+    .unwrap();
+
+    // 系列详情尚未取回时，以已关联的电影代替
+    let parts = collections::collection_parts(&pool, "tmdb:2344")
+        .await
+        .unwrap();
+    assert_eq!(parts.len(), 1);
+    assert_eq!(parts[0].movie_id, "tmdb:603");
+
+    let id = collections::store_collection(&pool, "tmdb", &matrix_collection())
+        .await
+        .unwrap();
+    assert_eq!(id.as_deref(), Some("tmdb:2344"));
+
+    let collection = collections::get_collection(&pool, "tmdb:2344")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(collection.part_count, 3);
+    assert_eq!(collection.owned_count, 1);
+
+    let parts = collections::collection_parts(&pool, "tmdb:2344")
+        .await
+        .unwrap();
+    assert_eq!(parts.len(), 3);
+    assert_eq!(parts[0].file_count, 1);
+    assert!(parts[1..].iter().all(|part| part.file_count == 0));
+
+    let movie = catalog::get_movie(&pool, "tmdb:603")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(movie.collection_id.as_deref(), Some("tmdb:2344"));
+
+    // 不含系列字段的结果保留关联，系列为空时清除
+    catalog::link_file(
+        &pool,
+        "f1",
+        "tmdb",
+        "603",
+        "movie",
+        &json!({ "title": "The Matrix" }),
+    )
+    .await
+    .unwrap();
+    let movie = catalog::get_movie(&pool, "tmdb:603")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(movie.collection_id.as_deref(), Some("tmdb:2344"));
+
+    catalog::link_file(
+        &pool,
+        "f1",
+        "tmdb",
+        "603",
+        "movie",
+        &json!({ "title": "The Matrix", "collection": null }),
+    )
+    .await
+    .unwrap();
+    let movie = catalog::get_movie(&pool, "tmdb:603")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(movie.collection_id.is_none());
+
+    let listed = collections::list_collections(&pool, Some("matrix"), 100, 0)
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].owned_count, 0);
+}
@@ -256,3 +256,41 @@ async fn test_find_similar_files_with_bucketed_candidates() {
         .iter()
         .any(|name| name.contains("The.Matrix.1999.1080p")));
 }
+
+#[tokio::test]
+async fn test_find_similar_files_folds_cjk_variants() {
+    let (pool, _temp_dir) = create_test_db().await;
+
+    let fixtures = [
+        "進擊的巨人.第二季.01.mkv",
+        "进击的巨人 第2季 01.mkv",
+        "鬼灭之刃 第1季 01.mkv",
+    ];
+
+    for name in fixtures {
+        sqlx::query(
+            "INSERT INTO media_files (id, path, name, size, file_type, created_at, updated_at, last_modified)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(format!("/path/{}", name))
+        .bind(name)
+        .bind(1000_i64)
+        .bind("video")
+        .bind(Utc::now().to_rfc3339())
+        .bind(Utc::now().to_rfc3339())
+        .bind(Utc::now().to_rfc3339())
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let groups = dedupe::find_similar_files(&pool, 0.9).await.unwrap();
+
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].files.len(), 2);
+    assert!(groups[0]
+        .files
+        .iter()
+        .all(|file| !file.name.starts_with("鬼灭")));
+}
@@ -0,0 +1,127 @@
+//! 绝对集数映射测试
+
+use cine_backend::services::episode_map::{self, SeasonEpisodeCount};
+use cine_backend::services::metadata_provider;
+use serde_json::json;
+
+fn seasons() -> Vec<SeasonEpisodeCount> {
+    episode_map::tmdb_season_counts_from_payload(&json!({
+        "seasons": [
+            { "season_number": 0, "episode_count": 5 },
+            { "season_number": 1, "episode_count": 25 },
+            { "season_number": 2, "episode_count": 12 },
+            { "season_number": 3, "episode_count": 24 }
+        ]
+    }))
+}
+
+#[test]
+fn test_absolute_to_season_skips_specials() {
+    let seasons = seasons();
+    assert_eq!(seasons.len(), 4);
+
+    assert_eq!(episode_map::absolute_to_season(&seasons, 1), Some((1, 1)));
+    assert_eq!(episode_map::absolute_to_season(&seasons, 25), Some((1, 25)));
+    assert_eq!(episode_map::absolute_to_season(&seasons, 26), Some((2, 1)));
+    assert_eq!(episode_map::absolute_to_season(&seasons, 40), Some((3, 3)));
+    assert_eq!(episode_map::absolute_to_season(&seasons, 62), None);
+    assert_eq!(episode_map::absolute_to_season(&seasons, 0), None);
+}
+
+#[test]
+fn test_season_to_absolute_roundtrip() {
+    let seasons = seasons();
+    for absolute in 1..=61 {
+        let (season, episode) = episode_map::absolute_to_season(&seasons, absolute).unwrap();
+        assert_eq!(
+            episode_map::season_to_absolute(&seasons, season, episode),
+            Some(absolute)
+        );
+    }
+    assert_eq!(episode_map::season_to_absolute(&seasons, 2, 13), None);
+    assert_eq!(episode_map::season_to_absolute(&seasons, 0, 1), None);
+}
+
+#[test]
+fn test_season_counts_from_episodes() {
+    let episode = |season: Option<u32>, episode_number: u32| metadata_provider::ProviderEpisode {
+        season_number: season,
+        episode_number,
+        ..Default::default()
+    };
+    let episodes = vec![
+        episode(Some(2), 1),
+        episode(Some(1), 1),
+        episode(Some(1), 2),
+        episode(Some(0), 1),
+        episode(None, 3),
+    ];
+
+    let seasons = episode_map::season_counts_from_episodes(&episodes);
+    assert_eq!(
+        seasons,
+        vec![
+            SeasonEpisodeCount {
+                season: 0,
+                episode_count: 1
+            },
+            SeasonEpisodeCount {
+                season: 1,
+                episode_count: 2
+            },
+            SeasonEpisodeCount {
+                season: 2,
+                episode_count: 1
+            },
+        ]
+    );
+    assert_eq!(episode_map::absolute_to_season(&seasons, 3), Some((2, 1)));
+}
+
+#[test]
+fn test_bangumi_absolute_to_episode() {
+    let episodes = metadata_provider::episodes_from_bangumi(&json!({
+        "data": [
+            { "sort": 13, "ep": 1, "type": 0 },
+            { "sort": 14, "ep": 2, "type": 0 },
+            { "sort": 1, "ep": 1, "type": 1 }
+        ]
+    }));
+    assert_eq!(episodes.len(), 2);
+
+    assert_eq!(
+        episode_map::bangumi_absolute_to_episode(&episodes, 14),
+        Some(2)
+    );
+    assert_eq!(
+        episode_map::bangumi_absolute_to_episode(&episodes, 2),
+        Some(2)
+    );
+    assert_eq!(
+        episode_map::bangumi_absolute_to_episode(&episodes, 30),
+        None
+    );
+}
+
+#[test]
+fn test_bangumi_subject_season() {
+    assert_eq!(
+        episode_map::bangumi_subject_season(&json!({
+            "title": "进击的巨人 第二季",
+            "original_title": "進撃の巨人 Season 2"
+        })),
+        Some(2)
+    );
+    assert_eq!(
+        episode_map::bangumi_subject_season(&json!({
+            "title": "辉夜大小姐想让我告白",
+            "original_title": "かぐや様は告らせたい",
+            "aliases": ["Kaguya-sama: Love is War 3rd Season"]
+        })),
+        Some(3)
+    );
+    assert_eq!(
+        episode_map::bangumi_subject_season(&json!({ "title": "葬送的芙莉莲" })),
+        None
+    );
+}
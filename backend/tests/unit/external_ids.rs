@@ -0,0 +1,109 @@
+//! 外部 ID 交叉引用测试
+
+use std::collections::BTreeMap;
+
+use cine_backend::services::external_ids::{self, TitleRef};
+#[path = "../common/mod.rs"]
+mod common;
+use common::create_test_db;
+use serde_json::json;
+
+#[test]
+fn test_tmdb_external_ids_keeps_database_ids() {
+    let ids = external_ids::tmdb_external_ids(&json!({
+        "id": 1399,
+        "external_ids": {
+            "imdb_id": "tt0944947",
+            "tvdb_id": 121361,
+            "wikidata_id": "Q23572",
+            "tvrage_id": null,
+            "facebook_id": "GameOfThrones"
+        }
+    }));
+    assert_eq!(
+        ids,
+        BTreeMap::from([
+            ("imdb".to_string(), "tt0944947".to_string()),
+            ("tvdb".to_string(), "121361".to_string()),
+            ("wikidata".to_string(), "Q23572".to_string()),
+        ])
+    );
+
+    // 电影详情顶层也带 imdb_id
+    let movie = external_ids::tmdb_external_ids(&json!({ "id": 603, "imdb_id": "tt0133093" }));
+    assert_eq!(movie.get("imdb").map(String::as_str), Some("tt0133093"));
+}
+
+#[test]
+fn test_ids_from_details_includes_own_id() {
+    let ids = external_ids::ids_from_details(&json!({
+        "provider": "tmdb",
+        "tmdb_id": 603,
+        "external_ids": { "imdb": "tt0133093", "tvdb": "" }
+    }));
+    assert_eq!(ids.get("tmdb").map(String::as_str), Some("603"));
+    assert_eq!(ids.get("imdb").map(String::as_str), Some("tt0133093"));
+    assert!(!ids.contains_key("tvdb"));
+}
+
+#[tokio::test]
+async fn test_store_and_resolve_by_any_id() {
+    let (pool, _temp_dir) = create_test_db().await;
+    let matrix = TitleRef::new("tmdb", "603", "movie");
+    let ids = BTreeMap::from([("imdb".to_string(), "tt0133093".to_string())]);
+    external_ids::store(&pool, &matrix, &ids, external_ids::SOURCE_PROVIDER)
+        .await
+        .unwrap();
+
+    let found = external_ids::resolve(&pool, "IMDB", " tt0133093 ", None)
+        .await
+        .unwrap();
+    assert_eq!(found, std::slice::from_ref(&matrix));
+    assert_eq!(
+        external_ids::resolve(&pool, "tmdb", "603", Some("movie"))
+            .await
+            .unwrap(),
+        std::slice::from_ref(&matrix)
+    );
+    // TMDb 电影与剧集 ID 空间不同
+    assert!(external_ids::resolve(&pool, "tmdb", "603", Some("tv"))
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_link_bangumi_to_tmdb_both_ways() {
+    let (pool, _temp_dir) = create_test_db().await;
+    let frieren = TitleRef::new("bgm", "400602", "tv");
+    external_ids::link(&pool, &frieren, "TMDB", "209867")
+        .await
+        .unwrap();
+
+    let known = external_ids::known_ids(&pool, &frieren).await.unwrap();
+    assert_eq!(known.get("bgm").map(String::as_str), Some("400602"));
+    assert_eq!(known.get("tmdb").map(String::as_str), Some("209867"));
+
+    let tmdb_side = TitleRef::new("tmdb", "209867", "tv");
+    let reverse = external_ids::known_ids(&pool, &tmdb_side).await.unwrap();
+    assert_eq!(reverse.get("bgm").map(String::as_str), Some("400602"));
+
+    let found = external_ids::resolve(&pool, "tmdb", "209867", Some("tv"))
+        .await
+        .unwrap();
+    assert_eq!(found, [tmdb_side.clone(), frieren.clone()]);
+
+    // 数据源详情不覆盖人工登记的对应
+    let details_ids = BTreeMap::from([("tmdb".to_string(), "1".to_string())]);
+    external_ids::store(&pool, &frieren, &details_ids, external_ids::SOURCE_PROVIDER)
+        .await
+        .unwrap();
+    let known = external_ids::known_ids(&pool, &frieren).await.unwrap();
+    assert_eq!(known.get("tmdb").map(String::as_str), Some("209867"));
+
+    assert!(!external_ids::unlink(&pool, &frieren, "bgm").await.unwrap());
+    assert!(external_ids::unlink(&pool, &frieren, "tmdb").await.unwrap());
+    assert!(!external_ids::unlink(&pool, &frieren, "tmdb").await.unwrap());
+    let reverse = external_ids::known_ids(&pool, &tmdb_side).await.unwrap();
+    assert!(!reverse.contains_key("bgm"));
+}
@@ -1,4 +1,5 @@
-use cine_backend::services::file_ops;
+use cine_backend::services::task_queue::TaskContext;
+use cine_backend::services::{file_ops, log, renamer};
 #[path = "../common/mod.rs"]
 mod common;
 use chrono::Utc;
@@ -114,3 +115,232 @@ async fn test_move_file_target_exists() {
     assert!(result.error.is_some());
     assert!(result.error.unwrap().contains("already exists"));
 }
+
+async fn insert_file(pool: &sqlx::SqlitePool, path: &std::path::Path, name: &str) -> String {
+    let file_id = uuid::Uuid::new_v4().to_string();
+    sqlx::query(
+        "INSERT INTO media_files (id, path, name, size, file_type, created_at, updated_at, last_modified)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(&file_id)
+    .bind(path.to_string_lossy().to_string())
+    .bind(name)
+    .bind(12)
+    .bind("document")
+    .bind(Utc::now().to_rfc3339())
+    .bind(Utc::now().to_rfc3339())
+    .bind(Utc::now().to_rfc3339())
+    .execute(pool)
+    .await
+    .unwrap();
+    file_id
+}
+
+async fn latest_log(pool: &sqlx::SqlitePool, action: &str) -> cine_backend::models::OperationLog {
+    sqlx::query_as("SELECT * FROM operation_logs WHERE action = ? ORDER BY created_at DESC LIMIT 1")
+        .bind(action)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_undo_move_restores_path() {
+    let (pool, temp_dir) = create_test_db().await;
+    let file_path = create_test_file(&temp_dir, "movie.mkv", b"test content");
+    let target_dir = temp_dir.path().join("target");
+    let file_id = insert_file(&pool, &file_path, "movie.mkv").await;
+
+    let result = file_ops::move_file(&pool, &file_id, target_dir.to_str().unwrap())
+        .await
+        .unwrap();
+    assert!(result.success);
+
+    let entry = latest_log(&pool, "move").await;
+    assert!(entry.inverse.is_some());
+    let undone = log::undo_operation(&pool, &entry.id).await.unwrap();
+    assert!(undone.undone_at.is_some());
+
+    assert!(file_path.exists());
+    assert!(!target_dir.join("movie.mkv").exists());
+    let path: String = sqlx::query_scalar("SELECT path FROM media_files WHERE id = ?")
+        .bind(&file_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(path, file_path.to_string_lossy());
+
+    // 已撤销的日志不能再次撤销
+    assert!(log::undo_operation(&pool, &entry.id).await.is_err());
+}
+
+#[tokio::test]
+async fn test_undo_copy_deletes_copy() {
+    let (pool, temp_dir) = create_test_db().await;
+    let file_path = create_test_file(&temp_dir, "movie.mkv", b"test content");
+    let target_dir = temp_dir.path().join("target");
+    let file_id = insert_file(&pool, &file_path, "movie.mkv").await;
+
+    file_ops::copy_file(&pool, &file_id, target_dir.to_str().unwrap())
+        .await
+        .unwrap();
+    let copied = target_dir.join("movie.mkv");
+    assert!(copied.exists());
+
+    let entry = latest_log(&pool, "copy").await;
+    log::undo_operation(&pool, &entry.id).await.unwrap();
+
+    assert!(!copied.exists());
+    assert!(file_path.exists());
+}
+
+#[tokio::test]
+async fn test_undo_rejected_when_precondition_fails() {
+    let (pool, temp_dir) = create_test_db().await;
+    let file_path = create_test_file(&temp_dir, "movie.mkv", b"test content");
+    let target_dir = temp_dir.path().join("target");
+    let file_id = insert_file(&pool, &file_path, "movie.mkv").await;
+
+    file_ops::copy_file(&pool, &file_id, target_dir.to_str().unwrap())
+        .await
+        .unwrap();
+    let copied = target_dir.join("movie.mkv");
+    // 副本被外部修改后不应被撤销删除
+    std::fs::write(&copied, b"modified by user").unwrap();
+
+    let entry = latest_log(&pool, "copy").await;
+    let err = log::undo_operation(&pool, &entry.id).await.unwrap_err();
+    assert!(err.to_string().contains("Precondition failed"));
+    assert!(copied.exists());
+
+    // 原路径被占用时移动无法撤销
+    file_ops::move_file(
+        &pool,
+        &file_id,
+        temp_dir.path().join("moved").to_str().unwrap(),
+    )
+    .await
+    .unwrap();
+    std::fs::write(&file_path, b"new file").unwrap();
+    let entry = latest_log(&pool, "move").await;
+    assert!(log::undo_operation(&pool, &entry.id).await.is_err());
+    assert!(entry.undone_at.is_none());
+}
+
+#[tokio::test]
+async fn test_permanent_delete_cannot_be_undone() {
+    let (pool, temp_dir) = create_test_db().await;
+    let file_path = create_test_file(&temp_dir, "movie.mkv", b"test content");
+    log::record_operation(
+        &pool,
+        "delete",
+        None,
+        file_path.to_str().unwrap(),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let entry = latest_log(&pool, "delete").await;
+    assert!(entry.inverse.is_none());
+    let err = log::undo_operation(&pool, &entry.id).await.unwrap_err();
+    assert!(err.to_string().contains("cannot be undone"));
+}
+
+#[tokio::test]
+async fn test_undo_batch_reverts_chained_renames_in_reverse() {
+    let (pool, temp_dir) = create_test_db().await;
+    let a = create_test_file(&temp_dir, "a.mkv", b"episode a");
+    let b = create_test_file(&temp_dir, "b.mkv", b"episode b");
+    let a_id = insert_file(&pool, &a, "a.mkv").await;
+    let b_id = insert_file(&pool, &b, "b.mkv").await;
+
+    // 链式改名：b -> c，随后 a -> b
+    renamer::batch_rename(
+        &pool,
+        vec![
+            (b_id.clone(), "c.mkv".to_string()),
+            (a_id.clone(), "b.mkv".to_string()),
+        ],
+        TaskContext::for_test("rename-batch"),
+    )
+    .await
+    .unwrap();
+    assert_eq!(std::fs::read(&b).unwrap(), b"episode a");
+
+    let preview = log::preview_batch_undo(&pool, "rename-batch")
+        .await
+        .unwrap();
+    assert!(preview.dry_run);
+    assert_eq!(preview.total, 2);
+    assert!(preview
+        .items
+        .iter()
+        .all(|i| i.status == log::BatchUndoStatus::Ready));
+    // 预演不改动磁盘
+    assert!(temp_dir.path().join("c.mkv").exists());
+
+    let report = log::undo_batch(&pool, "rename-batch", TaskContext::for_test("undo"))
+        .await
+        .unwrap();
+    assert_eq!(report.undone, 2);
+    assert_eq!(report.failed, 0);
+    assert_eq!(std::fs::read(&a).unwrap(), b"episode a");
+    assert_eq!(std::fs::read(&b).unwrap(), b"episode b");
+    assert!(!temp_dir.path().join("c.mkv").exists());
+
+    // 再次撤销时全部跳过
+    let again = log::preview_batch_undo(&pool, "rename-batch")
+        .await
+        .unwrap();
+    assert_eq!(again.skipped, 2);
+}
+
+#[tokio::test]
+async fn test_undo_batch_reports_partial_failure() {
+    let (pool, temp_dir) = create_test_db().await;
+    let a = create_test_file(&temp_dir, "a.mkv", b"episode a");
+    let b = create_test_file(&temp_dir, "b.mkv", b"episode b");
+    let ids = vec![
+        insert_file(&pool, &a, "a.mkv").await,
+        insert_file(&pool, &b, "b.mkv").await,
+    ];
+    let target_dir = temp_dir.path().join("target");
+
+    let results = file_ops::move_files_batch(
+        &pool,
+        &ids,
+        target_dir.to_str().unwrap(),
+        Some("move-batch"),
+    )
+    .await
+    .unwrap();
+    assert!(results.iter().all(|r| r.success));
+
+    // 原位置被新文件占用，该条撤销应失败
+    std::fs::write(&a, b"new file").unwrap();
+
+    let preview = log::preview_batch_undo(&pool, "move-batch").await.unwrap();
+    assert_eq!(preview.blocked, 1);
+
+    let report = log::undo_batch(&pool, "move-batch", TaskContext::for_test("undo"))
+        .await
+        .unwrap();
+    assert_eq!(report.undone, 1);
+    assert_eq!(report.failed, 1);
+    let failed = report
+        .items
+        .iter()
+        .find(|i| i.status == log::BatchUndoStatus::Failed)
+        .unwrap();
+    assert!(failed.error.as_deref().unwrap().contains("already exists"));
+    assert!(b.exists());
+    assert!(target_dir.join("a.mkv").exists());
+}
+
+#[tokio::test]
+async fn test_preview_unknown_batch_fails() {
+    let (pool, _temp_dir) = create_test_db().await;
+    assert!(log::preview_batch_undo(&pool, "missing").await.is_err());
+}
@@ -0,0 +1,137 @@
+//! NFO 与路径 ID 线索测试
+
+use cine_backend::services::id_hints::{self, IdHint};
+use serde_json::json;
+use std::fs;
+use tempfile::tempdir;
+
+fn ids(hints: &[IdHint]) -> Vec<(&str, &str)> {
+    hints
+        .iter()
+        .map(|h| (h.provider.as_str(), h.external_id.as_str()))
+        .collect()
+}
+
+#[test]
+fn test_path_tokens_recognises_common_styles() {
+    let hints = id_hints::path_tokens(
+        "The Matrix (1999) {tmdb-603} [imdbid-tt0133093] [tvdbid-81189] [bgm=326] {imdb-123}",
+    );
+    assert_eq!(
+        ids(&hints),
+        vec![
+            ("tmdb", "603"),
+            ("imdb", "tt0133093"),
+            ("tvdb", "81189"),
+            ("bgm", "326")
+        ]
+    );
+    assert!(hints.iter().all(|h| h.source == "path_id"));
+
+    assert_eq!(
+        id_hints::strip_path_tokens("Title (2019) {tmdb-12345}"),
+        "Title (2019)"
+    );
+}
+
+#[test]
+fn test_path_hints_prefer_nearest_component() {
+    let hints = id_hints::path_hints(
+        "/media/Breaking Bad (2008) [tmdbid-1396]/Season 1 {tmdb-9}/S01E01 [imdbid-tt0959621].mkv",
+    );
+    assert_eq!(
+        ids(&hints),
+        vec![("imdb", "tt0959621"), ("tmdb", "9"), ("tmdb", "1396")]
+    );
+}
+
+#[tokio::test]
+async fn test_nfo_hints_read_uniqueid_from_movie_nfo() {
+    let dir = tempdir().unwrap();
+    let movie = dir.path().join("Inception.2010.1080p.mkv");
+    fs::write(&movie, "").unwrap();
+    fs::write(
+        dir.path().join("Inception.2010.1080p.nfo"),
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<movie>
+    <title>Inception</title>
+    <uniqueid type="imdb">tt1375666</uniqueid>
+    <uniqueid type="tmdb" default="true">27205</uniqueid>
+</movie>"#,
+    )
+    .unwrap();
+
+    let hints = id_hints::nfo_hints(movie.to_str().unwrap(), false).await;
+    assert_eq!(ids(&hints), vec![("tmdb", "27205"), ("imdb", "tt1375666")]);
+    assert!(hints
+        .iter()
+        .all(|h| h.source == "nfo" && h.media_type.as_deref() == Some("movie")));
+}
+
+#[tokio::test]
+async fn test_nfo_hints_find_tvshow_nfo_above_season_folder() {
+    let dir = tempdir().unwrap();
+    let season = dir.path().join("Show").join("Season 1");
+    fs::create_dir_all(&season).unwrap();
+    let episode = season.join("Show.S01E01.mkv");
+    fs::write(&episode, "").unwrap();
+    // 单集 NFO 的 ID 不应被当作剧集 ID
+    fs::write(
+        season.join("Show.S01E01.nfo"),
+        "<episodedetails><tmdbid>999</tmdbid></episodedetails>",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("Show").join("tvshow.nfo"),
+        r#"<tvshow>
+    <title>Show</title>
+    <thumb aspect="poster">a.jpg</thumb>
+    <thumb aspect="banner">b.jpg</thumb>
+    <uniqueid type="tvdb">81189</uniqueid>
+    <tmdbid>1396</tmdbid>
+</tvshow>"#,
+    )
+    .unwrap();
+
+    let hints = id_hints::collect_hints(episode.to_str().unwrap(), true).await;
+    assert_eq!(ids(&hints), vec![("tvdb", "81189"), ("tmdb", "1396")]);
+    assert!(hints.iter().all(|h| h.media_type.as_deref() == Some("tv")));
+}
+
+#[test]
+fn test_scan_nfo_text_reads_links() {
+    let found = id_hints::scan_nfo_text(
+        "https://www.themoviedb.org/tv/1396-breaking-bad\nhttps://www.imdb.com/title/tt0903747/",
+    );
+    assert_eq!(
+        found,
+        vec![
+            (
+                "tmdb".to_string(),
+                "1396".to_string(),
+                Some("tv".to_string())
+            ),
+            ("imdb".to_string(), "tt0903747".to_string(), None),
+        ]
+    );
+}
+
+#[test]
+fn test_tmdb_find_result_prefers_requested_type() {
+    let payload = json!({
+        "movie_results": [{ "id": 603 }],
+        "tv_results": [{ "id": 1396 }]
+    });
+    assert_eq!(
+        id_hints::tmdb_find_result(&payload, false),
+        Some(("603".to_string(), "movie".to_string()))
+    );
+    assert_eq!(
+        id_hints::tmdb_find_result(&payload, true),
+        Some(("1396".to_string(), "tv".to_string()))
+    );
+    assert_eq!(
+        id_hints::tmdb_find_result(&json!({ "movie_results": [] }), true),
+        None
+    );
+}
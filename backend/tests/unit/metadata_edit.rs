@@ -0,0 +1,147 @@
+//! 元数据人工编辑、字段锁与历史测试
+
+use cine_backend::services::metadata_edit;
+#[path = "../common/mod.rs"]
+mod common;
+use common::{create_test_db, insert_media_file};
+use serde_json::{json, Value};
+
+fn fields(value: Value) -> serde_json::Map<String, Value> {
+    value.as_object().cloned().unwrap()
+}
+
+#[test]
+fn test_validate_fields() {
+    assert!(metadata_edit::validate_fields(&fields(json!({
+        "title": "黑客帝国",
+        "year": 1999,
+        "rating": 8.7,
+        "poster_url": null
+    })))
+    .is_ok());
+    assert!(metadata_edit::validate_fields(&fields(json!({}))).is_err());
+    assert!(metadata_edit::validate_fields(&fields(json!({ "provider": "tmdb" }))).is_err());
+    assert!(metadata_edit::validate_fields(&fields(json!({ "year": "1999" }))).is_err());
+    assert!(metadata_edit::validate_fields(&fields(json!({ "title": 1 }))).is_err());
+}
+
+#[test]
+fn test_diff_marks_locked_fields() {
+    let changes = metadata_edit::diff(
+        &json!({ "title": "A", "overview": "old", "cast": ["x"] }),
+        &json!({ "title": "B", "overview": "old", "rating": 7.0, "cast": ["y"] }),
+        &["title".to_string()],
+    );
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0].field, "title");
+    assert!(changes[0].locked);
+    assert_eq!(changes[1].field, "rating");
+    assert_eq!(changes[1].old, Value::Null);
+    assert!(!changes[1].locked);
+}
+
+#[tokio::test]
+async fn test_edit_locks_and_scrape_keeps_locked_fields() {
+    let (pool, _temp_dir) = create_test_db().await;
+    insert_media_file(
+        &pool,
+        "f1",
+        "/media/f1.mkv",
+        Some(&json!({ "title": "The Matrix", "overview": "Neo", "year": 1999 })),
+    )
+    .await;
+
+    let edited = metadata_edit::edit(&pool, "f1", &fields(json!({ "title": "黑客帝国" })), true)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(edited.metadata["title"], "黑客帝国");
+    assert_eq!(edited.locked_fields, ["title"]);
+
+    let mut details = json!({ "title": "The Matrix", "overview": "Neo wakes up", "year": 1999 });
+    let changes = metadata_edit::merge_scrape(&pool, "f1", &mut details)
+        .await
+        .unwrap();
+    assert_eq!(details["title"], "黑客帝国");
+    assert_eq!(details["overview"], "Neo wakes up");
+    assert_eq!(changes.len(), 2);
+    assert!(changes
+        .iter()
+        .any(|change| change.field == "title" && change.locked));
+    assert!(changes
+        .iter()
+        .any(|change| change.field == "overview" && !change.locked));
+
+    let history = metadata_edit::history(&pool, "f1", 10, 0).await.unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].source, metadata_edit::SOURCE_SCRAPE);
+    assert_eq!(history[1].source, metadata_edit::SOURCE_EDIT);
+    assert_eq!(history[1].metadata["title"], "The Matrix");
+    assert!(history[1].locked_fields.is_empty());
+}
+
+#[tokio::test]
+async fn test_first_scrape_records_no_history() {
+    let (pool, _temp_dir) = create_test_db().await;
+    insert_media_file(&pool, "f1", "/media/f1.mkv", Some(&Value::Null)).await;
+
+    let mut details = json!({ "title": "The Matrix" });
+    let changes = metadata_edit::merge_scrape(&pool, "f1", &mut details)
+        .await
+        .unwrap();
+    assert!(changes.is_empty());
+    assert!(metadata_edit::history(&pool, "f1", 10, 0)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_set_locks_and_revert() {
+    let (pool, _temp_dir) = create_test_db().await;
+    insert_media_file(
+        &pool,
+        "f1",
+        "/media/f1.mkv",
+        Some(&json!({ "title": "The Matrix" })),
+    )
+    .await;
+
+    let state = metadata_edit::set_locks(
+        &pool,
+        "f1",
+        &["overview".to_string(), "title".to_string()],
+        &[],
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(state.locked_fields, ["title", "overview"]);
+    let state = metadata_edit::set_locks(&pool, "f1", &[], &["overview".to_string()])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(state.locked_fields, ["title"]);
+
+    metadata_edit::edit(&pool, "f1", &fields(json!({ "title": "黑客帝国" })), false)
+        .await
+        .unwrap();
+    let history = metadata_edit::history(&pool, "f1", 10, 0).await.unwrap();
+    let reverted = metadata_edit::revert(&pool, "f1", history[0].id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(reverted.metadata["title"], "The Matrix");
+    assert_eq!(reverted.locked_fields, ["title"]);
+
+    let history = metadata_edit::history(&pool, "f1", 10, 0).await.unwrap();
+    assert_eq!(history[0].source, metadata_edit::SOURCE_REVERT);
+    assert_eq!(history[0].metadata["title"], "黑客帝国");
+
+    // 历史版本属于其他文件时不可回滚
+    insert_media_file(&pool, "f2", "/media/f2.mkv", Some(&json!({}))).await;
+    assert!(metadata_edit::revert(&pool, "f2", history[0].id)
+        .await
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,510 @@
+//! 元数据源注册与多源查询测试
+
+use std::sync::Arc;
+
+use cine_backend::services::identify::{self, IdentifyCandidate, ParsedTitle};
+use cine_backend::services::metadata_provider::{
+    self, MetadataProvider, ProviderEpisode, ProviderFuture, ProviderQuery, ProviderRegistry,
+};
+use cine_backend::services::plugin::{PluginInfo, CAPABILITY_METADATA};
+use cine_backend::services::provider_guard::{self, ProviderUnavailable};
+use serde_json::{json, Value};
+
+/// 返回固定结果的测试数据源
+struct StaticProvider {
+    id: &'static str,
+    titles: Vec<(&'static str, u32)>,
+    fail: bool,
+    circuit_open: bool,
+}
+
+impl StaticProvider {
+    fn fixed(id: &'static str, titles: Vec<(&'static str, u32)>) -> Arc<dyn MetadataProvider> {
+        Arc::new(Self {
+            id,
+            titles,
+            fail: false,
+            circuit_open: false,
+        })
+    }
+
+    fn failing(id: &'static str) -> Arc<dyn MetadataProvider> {
+        Arc::new(Self {
+            id,
+            titles: Vec::new(),
+            fail: true,
+            circuit_open: false,
+        })
+    }
+
+    fn circuit_open(id: &'static str) -> Arc<dyn MetadataProvider> {
+        Arc::new(Self {
+            id,
+            titles: Vec::new(),
+            fail: false,
+            circuit_open: true,
+        })
+    }
+}
+
+impl MetadataProvider for StaticProvider {
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        self.id
+    }
+
+    fn search<'a>(
+        &'a self,
+        query: &'a ProviderQuery,
+    ) -> ProviderFuture<'a, Vec<IdentifyCandidate>> {
+        Box::pin(async move {
+            if self.fail {
+                anyhow::bail!("{} unavailable", self.id);
+            }
+            if self.circuit_open {
+                return Err(ProviderUnavailable {
+                    provider: self.id.to_string(),
+                    retry_after: std::time::Duration::from_secs(30),
+                }
+                .into());
+            }
+            Ok(self
+                .titles
+                .iter()
+                .enumerate()
+                .map(|(index, (title, year))| IdentifyCandidate {
+                    provider: self.id.to_string(),
+                    external_id: index.to_string(),
+                    media_type: query.media_type.clone(),
+                    title: title.to_string(),
+                    original_title: None,
+                    year: Some(*year),
+                    score: 0.0,
+                    overview: None,
+                    poster_url: None,
+                    backdrop_url: None,
+                    alternative_titles: Vec::new(),
+                    metadata: Value::Null,
+                })
+                .collect())
+        })
+    }
+
+    fn details<'a>(
+        &'a self,
+        external_id: &'a str,
+        media_type: &'a str,
+    ) -> ProviderFuture<'a, Value> {
+        Box::pin(async move {
+            Ok(json!({
+                "provider": self.id,
+                "external_id": external_id,
+                "media_type": media_type,
+                "poster_url": "https://img.example/poster.jpg",
+                "overview": "详情简介",
+                "aliases": ["Spirited Away"],
+            }))
+        })
+    }
+
+    fn episodes<'a>(
+        &'a self,
+        _external_id: &'a str,
+        _season: Option<u32>,
+    ) -> ProviderFuture<'a, Vec<ProviderEpisode>> {
+        Box::pin(async move { Ok(Vec::new()) })
+    }
+}
+
+fn parsed(title: &str, year: Option<u32>) -> ParsedTitle {
+    ParsedTitle {
+        title: title.to_string(),
+        year,
+        season: None,
+        episode: None,
+        episode_end: None,
+        absolute_episode: None,
+        is_special: false,
+        special_type: None,
+        confidence: 0.88,
+        parser_provider: "rules".to_string(),
+        ai_disabled_reason: None,
+        field_sources: Default::default(),
+    }
+}
+
+#[test]
+fn test_registry_select_follows_library_order() {
+    let mut registry = ProviderRegistry::new();
+    registry.register(StaticProvider::fixed("tmdb", vec![]));
+    registry.register(StaticProvider::fixed("bgm", vec![]));
+    registry.register(StaticProvider::fixed("plugin:douban", vec![]));
+
+    let all: Vec<_> = registry
+        .select(None)
+        .iter()
+        .map(|p| p.id().to_string())
+        .collect();
+    assert_eq!(all, vec!["tmdb", "bgm", "plugin:douban"]);
+
+    let enabled = vec![
+        "plugin:douban".to_string(),
+        "missing".to_string(),
+        "bgm".to_string(),
+    ];
+    let selected: Vec<_> = registry
+        .select(Some(&enabled))
+        .iter()
+        .map(|p| p.id().to_string())
+        .collect();
+    assert_eq!(selected, vec!["plugin:douban", "bgm"]);
+}
+
+#[test]
+fn test_registry_register_replaces_same_id() {
+    let mut registry = ProviderRegistry::new();
+    registry.register(StaticProvider::fixed("bgm", vec![]));
+    registry.register(StaticProvider::failing("bgm"));
+
+    assert_eq!(registry.list().len(), 1);
+    assert_eq!(registry.list()[0].kind, "builtin");
+}
+
+#[test]
+fn test_plugin_metadata_capability() {
+    let legacy: PluginInfo = serde_json::from_value(json!({
+        "name": "Subtitles",
+        "version": "1.0.0",
+        "id": "subs",
+        "supported_sources": []
+    }))
+    .unwrap();
+    assert!(legacy.capabilities.is_empty());
+    assert!(!legacy.has_capability(CAPABILITY_METADATA));
+
+    let scraper: PluginInfo = serde_json::from_value(json!({
+        "name": "Douban",
+        "version": "1.0.0",
+        "id": "douban",
+        "supported_sources": ["douban"],
+        "capabilities": ["metadata"]
+    }))
+    .unwrap();
+    assert!(scraper.has_capability(CAPABILITY_METADATA));
+}
+
+#[tokio::test]
+async fn test_query_candidates_merges_and_ranks_across_providers() {
+    let providers = vec![
+        StaticProvider::fixed(
+            "tmdb",
+            vec![("Spirited Away 2", 2030), ("Spirited Away", 2001)],
+        ),
+        StaticProvider::fixed("plugin:douban", vec![("Spirited Away", 2001)]),
+    ];
+
+    let candidates = identify::query_candidates(&providers, &parsed("Spirited Away", Some(2001)))
+        .await
+        .unwrap();
+
+    assert_eq!(candidates.len(), 3);
+    assert_eq!(candidates[0].title, "Spirited Away");
+    assert_eq!(candidates[0].year, Some(2001));
+    assert!(candidates[0].score >= candidates[2].score);
+    assert!(candidates.iter().any(|c| c.provider == "plugin:douban"));
+    assert!(candidates.iter().all(|c| c.media_type == "movie"));
+}
+
+#[tokio::test]
+async fn test_query_candidates_tolerates_single_provider_failure() {
+    let providers = vec![
+        StaticProvider::failing("tmdb"),
+        StaticProvider::fixed("bgm", vec![("千与千寻", 2001)]),
+    ];
+
+    let candidates = identify::query_candidates(&providers, &parsed("千与千寻", None))
+        .await
+        .unwrap();
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].provider, "bgm");
+
+    let all_failed = vec![StaticProvider::failing("tmdb")];
+    assert!(
+        identify::query_candidates(&all_failed, &parsed("千与千寻", None))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_search_candidates_skips_open_circuit() {
+    let providers = vec![
+        StaticProvider::circuit_open("tmdb"),
+        StaticProvider::fixed("bgm", vec![("千与千寻", 2001)]),
+    ];
+
+    let search = identify::search_candidates(&providers, &parsed("千与千寻", None))
+        .await
+        .unwrap();
+    assert_eq!(search.candidates.len(), 1);
+    assert_eq!(search.candidates[0].provider, "bgm");
+    assert_eq!(search.skipped_providers, ["tmdb"]);
+
+    // 全部数据源都熔断时返回熔断错误，由调用方等待后重试
+    let all_open = vec![
+        StaticProvider::circuit_open("tmdb"),
+        StaticProvider::failing("bgm"),
+    ];
+    let error = identify::search_candidates(&all_open, &parsed("千与千寻", None))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        provider_guard::unavailable(&error).map(|open| open.provider.as_str()),
+        Some("tmdb")
+    );
+}
+
+#[tokio::test]
+async fn test_localize_candidate_uses_details() {
+    let provider = StaticProvider::fixed("tmdb", vec![("千与千寻", 2001)]);
+    let query = ProviderQuery {
+        title: "千与千寻".to_string(),
+        year: None,
+        media_type: "movie".to_string(),
+    };
+    let mut candidate = provider.search(&query).await.unwrap().remove(0);
+
+    metadata_provider::localize_candidate(provider.as_ref(), &mut candidate).await;
+    assert_eq!(candidate.title, "千与千寻");
+    assert_eq!(candidate.overview.as_deref(), Some("详情简介"));
+    assert_eq!(candidate.alternative_titles, ["Spirited Away"]);
+}
+
+#[tokio::test]
+async fn test_default_artwork_uses_details() {
+    let provider = StaticProvider::fixed("bgm", vec![]);
+    let artwork = provider.artwork("42", "tv").await.unwrap();
+    assert_eq!(
+        artwork.poster_url.as_deref(),
+        Some("https://img.example/poster.jpg")
+    );
+    assert!(artwork.backdrop_url.is_none());
+}
+
+#[test]
+fn test_match_library_providers_prefers_deepest_folder() {
+    let folders = vec![
+        ("/media".to_string(), Some(r#"["tmdb"]"#.to_string())),
+        (
+            "/media/anime".to_string(),
+            Some(r#"["bgm","tmdb"]"#.to_string()),
+        ),
+        (
+            "/media/anime-old".to_string(),
+            Some(r#"["plugin:x"]"#.to_string()),
+        ),
+        ("/other".to_string(), None),
+    ];
+
+    assert_eq!(
+        metadata_provider::match_library_providers(&folders, "/media/anime/Show/01.mkv"),
+        Some(vec!["bgm".to_string(), "tmdb".to_string()])
+    );
+    assert_eq!(
+        metadata_provider::match_library_providers(&folders, "/media/movies/a.mkv"),
+        Some(vec!["tmdb".to_string()])
+    );
+    assert_eq!(
+        metadata_provider::match_library_providers(&folders, "/other/a.mkv"),
+        None
+    );
+    assert_eq!(
+        metadata_provider::match_library_providers(&folders, "/elsewhere/a.mkv"),
+        None
+    );
+}
+
+#[test]
+fn test_episode_payload_parsing() {
+    let tmdb = metadata_provider::episodes_from_tmdb_season(&json!({
+        "episodes": [
+            { "season_number": 2, "episode_number": 3, "name": "Third", "overview": "", "air_date": "2020-01-15", "still_path": "/s.jpg", "runtime": 42, "vote_average": 7.5 }
+        ]
+    }));
+    assert_eq!(tmdb.len(), 1);
+    assert_eq!(tmdb[0].season_number, Some(2));
+    assert_eq!(tmdb[0].episode_number, 3);
+    assert_eq!(tmdb[0].overview, None);
+    assert_eq!(
+        tmdb[0].still_url.as_deref(),
+        Some("https://image.tmdb.org/t/p/w780/s.jpg")
+    );
+    assert_eq!(tmdb[0].runtime, Some(42));
+    assert_eq!(tmdb[0].rating, Some(7.5));
+
+    let bgm = metadata_provider::episodes_from_bangumi(&json!({
+        "data": [
+            { "type": 0, "sort": 13, "ep": 1, "name": "Start", "name_cn": "开始", "airdate": "2021-04-01", "duration": "00:23:40" },
+            { "type": 1, "sort": 1, "ep": 1, "name": "SP" }
+        ]
+    }));
+    assert_eq!(bgm.len(), 1);
+    assert_eq!(bgm[0].episode_number, 1);
+    assert_eq!(bgm[0].absolute_number, Some(13));
+    assert_eq!(bgm[0].name.as_deref(), Some("开始"));
+    assert_eq!(bgm[0].runtime, Some(24));
+    assert_eq!(bgm[0].rating, None);
+}
+
+#[test]
+fn test_match_library_type_prefers_deepest_folder() {
+    let folders = vec![
+        ("/media".to_string(), Some("tv".to_string())),
+        ("/media/anime".to_string(), Some("anime".to_string())),
+        ("/media/mixed".to_string(), None),
+    ];
+
+    assert_eq!(
+        metadata_provider::match_library_type(&folders, "/media/anime/Frieren/01.mkv").as_deref(),
+        Some("anime")
+    );
+    assert_eq!(
+        metadata_provider::match_library_type(&folders, "/media/shows/a.mkv").as_deref(),
+        Some("tv")
+    );
+    assert_eq!(
+        metadata_provider::match_library_type(&folders, "/media/mixed/a.mkv"),
+        None
+    );
+}
+
+#[test]
+fn test_parse_language_list_accepts_json_and_separators() {
+    assert_eq!(
+        metadata_provider::parse_language_list(Some(r#"["zh-CN","ja"]"#)),
+        Some(vec!["zh-CN".to_string(), "ja".to_string()])
+    );
+    assert_eq!(
+        metadata_provider::parse_language_list(Some("zh-CN → zh-TW, ja,en,ja")),
+        Some(vec![
+            "zh-CN".to_string(),
+            "zh-TW".to_string(),
+            "ja".to_string(),
+            "en".to_string()
+        ])
+    );
+    assert_eq!(metadata_provider::parse_language_list(Some("  ")), None);
+    assert_eq!(metadata_provider::parse_language_list(None), None);
+
+    let folders = vec![
+        ("/media".to_string(), Some(r#"["en"]"#.to_string())),
+        ("/media/anime".to_string(), Some("ja,zh-CN".to_string())),
+    ];
+    assert_eq!(
+        metadata_provider::match_library_languages(&folders, "/media/anime/a.mkv"),
+        Some(vec!["ja".to_string(), "zh-CN".to_string()])
+    );
+    assert_eq!(
+        metadata_provider::match_library_languages(&folders, "/other/a.mkv"),
+        None
+    );
+}
+
+#[test]
+fn test_localize_tmdb_details_follows_language_chain() {
+    let payload = json!({
+        "id": 129,
+        "title": "千と千尋の神隠し",
+        "original_title": "千と千尋の神隠し",
+        "original_language": "ja",
+        "overview": "",
+        "translations": { "translations": [
+            { "iso_639_1": "zh", "iso_3166_1": "TW", "data": { "title": "神隱少女", "overview": "" } },
+            { "iso_639_1": "ja", "iso_3166_1": "JP", "data": { "title": "", "overview": "10歳の少女千尋..." } },
+            { "iso_639_1": "en", "iso_3166_1": "US", "data": { "title": "Spirited Away", "overview": "A young girl..." } },
+            { "iso_639_1": "fr", "iso_3166_1": "FR", "data": { "title": "Le Voyage de Chihiro", "overview": "" } },
+        ]},
+        "alternative_titles": { "titles": [
+            { "iso_3166_1": "CN", "title": "千与千寻" },
+            { "iso_3166_1": "US", "title": "Spirited Away" },
+        ]},
+    });
+    let languages: Vec<String> = ["zh-CN", "zh-TW", "ja", "en"]
+        .iter()
+        .map(|tag| tag.to_string())
+        .collect();
+    let mut details = json!({
+        "title": "千と千尋の神隠し",
+        "original_title": "千と千尋の神隠し",
+        "overview": "",
+    });
+    metadata_provider::localize_tmdb_details(&mut details, &payload, &languages);
+
+    // zh-CN 无翻译 → zh-TW 标题；简介在中文缺失时回退到日文
+    assert_eq!(details["title"], "神隱少女");
+    assert_eq!(details["overview"], "10歳の少女千尋...");
+    assert_eq!(details["original_language"], "ja");
+    assert_eq!(details["localized_titles"]["en-US"], "Spirited Away");
+    assert!(details["localized_titles"].get("fr-FR").is_none());
+    assert_eq!(details["aliases"], json!(["千与千寻", "Spirited Away"]));
+
+    let alternative = metadata_provider::alternative_titles(&details);
+    assert!(alternative.contains(&"Spirited Away".to_string()));
+    assert!(alternative.contains(&"千与千寻".to_string()));
+    assert!(!alternative.contains(&"神隱少女".to_string()));
+}
+
+#[test]
+fn test_localize_bangumi_details_prefers_chain_and_reads_aliases() {
+    let payload = json!({
+        "name": "ぼっち・ざ・ろっく！",
+        "name_cn": "孤独摇滚！",
+        "infobox": [
+            { "key": "中文名", "value": "孤独摇滚！" },
+            { "key": "别名", "value": [{ "v": "Bocchi the Rock!" }, { "v": "BTR" }] },
+            { "key": "放送开始", "value": "2022年10月8日" },
+        ],
+    });
+    let mut details = json!({ "title": "孤独摇滚！", "original_title": "ぼっち・ざ・ろっく！" });
+    metadata_provider::localize_bangumi_details(
+        &mut details,
+        &payload,
+        &["ja".to_string(), "zh-CN".to_string()],
+    );
+    assert_eq!(details["title"], "ぼっち・ざ・ろっく！");
+    assert_eq!(
+        details["aliases"],
+        json!(["孤独摇滚！", "Bocchi the Rock!", "BTR"])
+    );
+    assert_eq!(details["localized_titles"]["zh-CN"], "孤独摇滚！");
+}
+
+#[test]
+fn test_merge_episode_translations_replaces_placeholders() {
+    let episode = |number: u32, name: Option<&str>, overview: Option<&str>| ProviderEpisode {
+        season_number: Some(1),
+        episode_number: number,
+        name: name.map(str::to_string),
+        overview: overview.map(str::to_string),
+        ..Default::default()
+    };
+    let mut episodes = vec![
+        episode(1, Some("第 1 集"), None),
+        episode(2, Some("真正的标题"), Some("简介")),
+    ];
+    assert!(metadata_provider::episode_needs_translation(&episodes[0]));
+    assert!(!metadata_provider::episode_needs_translation(&episodes[1]));
+
+    let fallback = vec![
+        episode(1, Some("The Beginning"), Some("Overview")),
+        episode(2, Some("Episode 2"), Some("Other")),
+    ];
+    metadata_provider::merge_episode_translations(&mut episodes, &fallback);
+    assert_eq!(episodes[0].name.as_deref(), Some("The Beginning"));
+    assert_eq!(episodes[0].overview.as_deref(), Some("Overview"));
+    assert_eq!(episodes[1].name.as_deref(), Some("真正的标题"));
+    assert_eq!(episodes[1].overview.as_deref(), Some("简介"));
+}
@@ -1,14 +1,28 @@
 //! 单元测试模块
 
+mod ai_parser;
+mod anime_parser;
 mod cache;
+mod catalog;
+mod cjk;
+mod collections;
 mod dedupe;
 mod dedupe_batch;
 mod empty_dirs;
+mod episode_map;
+mod external_ids;
 mod file_ops;
 mod hasher;
 mod hasher_extended;
 mod hasher_parallel;
+mod id_hints;
+mod metadata_edit;
+mod metadata_provider;
 mod nfo;
+mod people;
+mod provider_cache;
+mod provider_guard;
+mod release_parser;
 mod renamer;
 mod scanner;
 mod scanner_batch;
@@ -16,5 +30,6 @@ mod scanner_extended;
 mod scheduler;
 mod scraper;
 mod subtitle;
+mod title_aliases;
 mod trash;
 mod watcher;
@@ -48,6 +48,8 @@ async fn test_read_save_movie_nfo() {
         fanart: None,
         tmdbid: Some("157336".to_string()),
         id: None,
+        imdbid: None,
+        uniqueid: Vec::new(),
     };
 
     // Save
@@ -69,3 +71,155 @@ fn test_escape_xml() {
     // Since escape_xml is private, we test it through public functions or assume it works
     // if generate_nfo_file handles special chars correctly.
 }
+
+#[tokio::test]
+async fn test_generate_multi_episode_nfo() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("Friends S02E12-E13.mkv");
+    fs::write(&file_path, "fake content").unwrap();
+
+    let metadata = json!({
+        "title": "Friends",
+        "tmdb_id": 1668,
+        "season_number": 2,
+        "episode_number": 12,
+        "episode_number_end": 13,
+        "episodes": [
+            { "episode_number": 12, "name": "The One After the Superbowl (1)" },
+            { "episode_number": 13, "name": "The One After the Superbowl (2)" }
+        ]
+    });
+
+    let nfo_path = nfo::generate_nfo_file(file_path.to_str().unwrap(), &metadata, "tvshow")
+        .await
+        .unwrap();
+    let content = fs::read_to_string(&nfo_path).unwrap();
+
+    assert_eq!(content.matches("<episodedetails>").count(), 2);
+    assert!(content.contains("<episode>12</episode>"));
+    assert!(content.contains("<episode>13</episode>"));
+    assert!(content.contains("<title>The One After the Superbowl (2)</title>"));
+    assert!(!content.contains("<tvshow>"));
+}
+
+#[tokio::test]
+async fn test_generate_episode_nfo_with_episode_metadata() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("Frieren S01E05.mkv");
+    fs::write(&file_path, "fake content").unwrap();
+
+    let metadata = json!({
+        "title": "Frieren",
+        "season_number": 1,
+        "episode_number": 5,
+        "episode_title": "Phantoms of the Dead",
+        "episode_overview": "A & B",
+        "episode_air_date": "2023-10-06",
+        "episode_runtime": 24,
+        "episode_rating": 8.5,
+        "episode_still_url": "https://image.tmdb.org/t/p/w780/still.jpg",
+        "provider": "tmdb",
+        "external_ids": { "tmdb": "209867", "tvdb": "424536" }
+    });
+
+    let nfo_path = nfo::generate_nfo_file(file_path.to_str().unwrap(), &metadata, "tvshow")
+        .await
+        .unwrap();
+    let content = fs::read_to_string(&nfo_path).unwrap();
+
+    assert!(content.contains("<title>Phantoms of the Dead</title>"));
+    assert!(content.contains("<plot>A &amp; B</plot>"));
+    assert!(content.contains("<aired>2023-10-06</aired>"));
+    assert!(content.contains("<runtime>24</runtime>"));
+    assert!(content.contains("<rating>8.5</rating>"));
+    assert!(content.contains("<thumb>https://image.tmdb.org/t/p/w780/still.jpg</thumb>"));
+    assert!(content.contains(r#"<uniqueid type="tmdb" default="true">209867</uniqueid>"#));
+    assert!(content.contains(r#"<uniqueid type="tvdb">424536</uniqueid>"#));
+}
+
+#[tokio::test]
+async fn test_generate_movie_nfo_with_people() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("The.Matrix.1999.mkv");
+    fs::write(&file_path, "fake content").unwrap();
+
+    let metadata = json!({
+        "title": "The Matrix",
+        "cast": [
+            { "id": "6384", "name": "Keanu Reeves", "character": "Neo", "order": 0, "profile_url": "https://image.tmdb.org/t/p/w185/keanu.jpg" },
+        ],
+        "crew": [
+            { "id": "9340", "name": "Lana Wachowski", "department": "Directing", "job": "Director" },
+            { "id": "9340", "name": "Lana Wachowski", "department": "Writing", "job": "Writer" },
+            { "id": "9340", "name": "Lana Wachowski", "department": "Writing", "job": "Screenplay" },
+        ],
+    });
+
+    let nfo_path = nfo::generate_nfo_file(file_path.to_str().unwrap(), &metadata, "movie")
+        .await
+        .unwrap();
+    let content = fs::read_to_string(&nfo_path).unwrap();
+    assert!(content.contains("<name>Keanu Reeves</name>"));
+    assert!(content.contains("<role>Neo</role>"));
+    assert!(content.contains("<thumb>https://image.tmdb.org/t/p/w185/keanu.jpg</thumb>"));
+    assert!(content.contains("<director>Lana Wachowski</director>"));
+    assert_eq!(
+        content.matches("<credits>Lana Wachowski</credits>").count(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_generate_movie_nfo_with_set() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("The.Matrix.1999.mkv");
+    fs::write(&file_path, "fake content").unwrap();
+
+    let metadata = json!({
+        "title": "The Matrix",
+        "collection": { "id": "2344", "name": "The Matrix Collection", "overview": "Neo & friends." },
+    });
+    let nfo_path = nfo::generate_nfo_file(file_path.to_str().unwrap(), &metadata, "movie")
+        .await
+        .unwrap();
+    let content = fs::read_to_string(&nfo_path).unwrap();
+    assert!(content.contains("<set>"));
+    assert!(content.contains("<name>The Matrix Collection</name>"));
+    assert!(content.contains("<overview>Neo &amp; friends.</overview>"));
+
+    let plain = json!({ "title": "Heat", "collection": null });
+    let nfo_path = nfo::generate_nfo_file(file_path.to_str().unwrap(), &plain, "movie")
+        .await
+        .unwrap();
+    assert!(!fs::read_to_string(&nfo_path).unwrap().contains("<set>"));
+}
+
+#[tokio::test]
+async fn test_generate_movie_nfo_with_unique_ids() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("The.Matrix.1999.mkv");
+    fs::write(&file_path, "fake content").unwrap();
+
+    let metadata = json!({
+        "provider": "tmdb",
+        "title": "The Matrix",
+        "year": 1999,
+        "rating": 8.2,
+        "tmdb_id": 603,
+        "external_ids": { "bgm": "1234", "imdb": "tt0133093", "tmdb": "603" }
+    });
+    let nfo_path = nfo::generate_nfo_file(file_path.to_str().unwrap(), &metadata, "movie")
+        .await
+        .unwrap();
+    let content = fs::read_to_string(&nfo_path).unwrap();
+    assert!(content.contains(r#"<uniqueid type="tmdb" default="true">603</uniqueid>"#));
+    assert!(content.contains(r#"<uniqueid type="imdb">tt0133093</uniqueid>"#));
+    assert!(content.contains(r#"<uniqueid type="bangumi">1234</uniqueid>"#));
+
+    // 读回时默认 ID 排在最前
+    let parsed = nfo::read_nfo_file(&nfo_path).await.unwrap();
+    let ids = parsed.external_ids();
+    assert_eq!(ids[0], ("tmdb".to_string(), "603".to_string()));
+    assert!(ids.contains(&("imdb".to_string(), "tt0133093".to_string())));
+    assert!(ids.contains(&("bangumi".to_string(), "1234".to_string())));
+}
@@ -0,0 +1,182 @@
+//! 演职人员测试
+
+use cine_backend::services::catalog::{self, Level};
+use cine_backend::services::people;
+#[path = "../common/mod.rs"]
+mod common;
+use common::create_test_db;
+use serde_json::json;
+
+#[test]
+fn test_apply_tmdb_movie_credits() {
+    let payload = json!({
+        "credits": {
+            "cast": [
+                { "id": 6384, "name": "Keanu Reeves", "original_name": "Keanu Reeves", "character": "Neo", "order": 0, "profile_path": "/keanu.jpg" },
+                { "id": 2975, "name": "Laurence Fishburne", "character": "Morpheus", "order": 1, "profile_path": null },
+            ],
+            "crew": [
+                { "id": 9340, "name": "Lana Wachowski", "department": "Directing", "job": "Director" },
+                { "id": 9340, "name": "Lana Wachowski", "department": "Writing", "job": "Writer" },
+                { "id": 1091, "name": "Bill Pope", "department": "Camera", "job": "Director of Photography" },
+            ],
+        }
+    });
+    let mut details = json!({ "title": "The Matrix" });
+    people::apply_tmdb_credits(&mut details, &payload);
+
+    let cast = details["cast"].as_array().unwrap();
+    assert_eq!(cast.len(), 2);
+    assert_eq!(cast[0]["id"], "6384");
+    assert_eq!(cast[0]["character"], "Neo");
+    assert_eq!(
+        cast[0]["profile_url"],
+        "https://image.tmdb.org/t/p/w185/keanu.jpg"
+    );
+    assert!(cast[1]["profile_url"].is_null());
+
+    let crew = details["crew"].as_array().unwrap();
+    assert_eq!(crew.len(), 2);
+    assert_eq!(crew[0]["job"], "Director");
+    assert_eq!(crew[1]["department"], "Writing");
+}
+
+#[test]
+fn test_apply_tmdb_aggregate_credits() {
+    let payload = json!({
+        "aggregate_credits": {
+            "cast": [
+                { "id": 17419, "name": "Bryan Cranston", "roles": [{ "character": "Walter White" }], "order": 0 },
+            ],
+            "crew": [
+                { "id": 66633, "name": "Vince Gilligan", "department": "Writing", "jobs": [{ "job": "Writer" }, { "job": "Story" }] },
+            ],
+        }
+    });
+    let mut details = json!({ "name": "Breaking Bad" });
+    people::apply_tmdb_credits(&mut details, &payload);
+
+    assert_eq!(details["cast"][0]["character"], "Walter White");
+    assert_eq!(details["crew"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn test_apply_bangumi_credits() {
+    let persons = json!([
+        { "id": 1, "name": "庵野秀明", "relation": "导演", "images": { "large": "https://lain.bgm.tv/p/1.jpg" } },
+        { "id": 2, "name": "鷺巣詩郎", "relation": "音乐" },
+    ]);
+    let characters = json!([
+        { "name": "葛城美里", "relation": "配角", "actors": [{ "id": 11, "name": "三石琴乃" }] },
+        { "name": "碇真嗣", "relation": "主角", "actors": [{ "id": 10, "name": "緒方恵美" }] },
+    ]);
+    let mut details = json!({ "title": "新世纪福音战士" });
+    people::apply_bangumi_credits(&mut details, &persons, &characters);
+
+    let cast = details["cast"].as_array().unwrap();
+    assert_eq!(cast[0]["name"], "緒方恵美");
+    assert_eq!(cast[0]["character"], "碇真嗣");
+    assert_eq!(cast[1]["order"], 1);
+
+    let crew = details["crew"].as_array().unwrap();
+    assert_eq!(crew.len(), 1);
+    assert_eq!(crew[0]["job"], "Director");
+    assert_eq!(crew[0]["profile_url"], "https://lain.bgm.tv/p/1.jpg");
+}
+
+#[tokio::test]
+async fn test_store_credits_and_titles_for_person() {
+    let (pool, _temp_dir) = create_test_db().await;
+    let keanu = json!({ "id": "6384", "name": "Keanu Reeves", "character": "Neo", "order": 0 });
+
+    catalog::link_file(
+        &pool,
+        "missing",
+        "tmdb",
+        "603",
+        "movie",
+        &json!({
+            "title": "The Matrix",
+            "year": 1999,
+            "cast": [keanu.clone()],
+            "crew": [{ "id": "9340", "name": "Lana Wachowski", "department": "Directing", "job": "Director" }],
+        }),
+    )
+    .await
+    .unwrap();
+    catalog::link_file(
+        &pool,
+        "missing",
+        "tmdb",
+        "245891",
+        "movie",
+        &json!({
+            "title": "John Wick",
+            "year": 2014,
+            "cast": [{ "id": "6384", "name": "Keanu Reeves", "character": "John Wick", "order": 0 }],
+            "crew": [],
+        }),
+    )
+    .await
+    .unwrap();
+
+    let credits = people::credits_for(&pool, Level::Movie, "tmdb:603")
+        .await
+        .unwrap();
+    assert_eq!(credits.len(), 2);
+    assert_eq!(credits[0].role, "actor");
+    assert_eq!(credits[0].character.as_deref(), Some("Neo"));
+    assert_eq!(credits[1].role, "director");
+
+    let person = people::get_person(&pool, "tmdb:6384")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(person.title_count, 2);
+
+    let titles = people::titles_for_person(&pool, "tmdb:6384").await.unwrap();
+    assert_eq!(titles.len(), 2);
+    assert_eq!(titles[0].title, "John Wick");
+    assert_eq!(titles[1].title_id, "tmdb:603");
+
+    // 重新刮削时替换演职员表，不含演职员信息的结果保留原记录
+    catalog::link_file(
+        &pool,
+        "missing",
+        "tmdb",
+        "603",
+        "movie",
+        &json!({ "title": "The Matrix", "cast": [keanu] }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        people::credits_for(&pool, Level::Movie, "tmdb:603")
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    catalog::link_file(
+        &pool,
+        "missing",
+        "tmdb",
+        "603",
+        "movie",
+        &json!({ "title": "The Matrix" }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        people::credits_for(&pool, Level::Movie, "tmdb:603")
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    let found = people::list_people(&pool, Some("keanu"), 100, 0)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+}
@@ -0,0 +1,291 @@
+//! 元数据源响应缓存测试
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use cine_backend::services::identify::IdentifyCandidate;
+use cine_backend::services::metadata_provider::{
+    MetadataProvider, ProviderEpisode, ProviderFuture, ProviderQuery,
+};
+use cine_backend::services::provider_cache::{self, CachedProvider};
+use serde_json::{json, Value};
+#[path = "../common/mod.rs"]
+mod common;
+use common::create_test_db;
+
+/// 统计请求次数的测试数据源；每次请求返回带序号的标题
+#[derive(Default)]
+struct CountingProvider {
+    calls: AtomicUsize,
+    down: AtomicBool,
+    slow: AtomicBool,
+}
+
+impl MetadataProvider for CountingProvider {
+    fn id(&self) -> &str {
+        "tmdb"
+    }
+
+    fn name(&self) -> &str {
+        "TMDb"
+    }
+
+    fn search<'a>(
+        &'a self,
+        query: &'a ProviderQuery,
+    ) -> ProviderFuture<'a, Vec<IdentifyCandidate>> {
+        Box::pin(async move {
+            if self.down.load(Ordering::SeqCst) {
+                anyhow::bail!("network unreachable");
+            }
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if self.slow.load(Ordering::SeqCst) {
+                tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+            }
+            Ok(vec![IdentifyCandidate {
+                provider: "tmdb".to_string(),
+                external_id: "1".to_string(),
+                media_type: query.media_type.clone(),
+                title: format!("{} #{call}", query.title),
+                original_title: None,
+                year: query.year,
+                score: 0.0,
+                overview: None,
+                poster_url: None,
+                backdrop_url: None,
+                alternative_titles: Vec::new(),
+                metadata: Value::Null,
+            }])
+        })
+    }
+
+    fn details<'a>(
+        &'a self,
+        external_id: &'a str,
+        _media_type: &'a str,
+    ) -> ProviderFuture<'a, Value> {
+        Box::pin(async move {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(json!({
+                "tmdb_id": external_id,
+                "poster_url": "https://img/p.jpg",
+                "external_ids": { "imdb": "tt1160419" },
+            }))
+        })
+    }
+
+    fn episodes<'a>(
+        &'a self,
+        _external_id: &'a str,
+        _season: Option<u32>,
+    ) -> ProviderFuture<'a, Vec<ProviderEpisode>> {
+        Box::pin(async move { Ok(Vec::new()) })
+    }
+}
+
+fn query() -> ProviderQuery {
+    ProviderQuery {
+        title: "Dune".to_string(),
+        year: Some(2021),
+        media_type: "movie".to_string(),
+    }
+}
+
+async fn expire_all(pool: &sqlx::SqlitePool, seconds_ago: i64) {
+    sqlx::query("UPDATE provider_cache SET expires_at = ?")
+        .bind(chrono::Utc::now() - chrono::Duration::seconds(seconds_ago))
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_cached_provider_serves_fresh_entries_without_network() {
+    let (pool, _dir) = create_test_db().await;
+    let inner = Arc::new(CountingProvider::default());
+    let cached = CachedProvider::new(inner.clone(), pool.clone(), false);
+
+    let first = cached.search(&query()).await.unwrap();
+    let second = cached.search(&query()).await.unwrap();
+    assert_eq!(first[0].title, "Dune #1");
+    assert_eq!(second[0].title, "Dune #1");
+    assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+
+    // 不同参数单独缓存
+    let other = ProviderQuery {
+        year: None,
+        ..query()
+    };
+    cached.search(&other).await.unwrap();
+    assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+
+    cached.details("42", "movie").await.unwrap();
+    let artwork = cached.artwork("42", "movie").await.unwrap();
+    assert_eq!(artwork.poster_url.as_deref(), Some("https://img/p.jpg"));
+
+    let stats = provider_cache::stats(&pool).await.unwrap();
+    let search = stats.iter().find(|s| s.endpoint == "search").unwrap();
+    assert_eq!(search.entries, 2);
+    assert_eq!(search.hits, 1);
+}
+
+#[tokio::test]
+async fn test_offline_mode_reads_only_from_cache() {
+    let (pool, _dir) = create_test_db().await;
+    let inner = Arc::new(CountingProvider::default());
+
+    let offline = CachedProvider::new(inner.clone(), pool.clone(), true);
+    let error = offline.search(&query()).await.unwrap_err();
+    assert!(error.to_string().contains("offline"));
+    assert_eq!(inner.calls.load(Ordering::SeqCst), 0);
+
+    CachedProvider::new(inner.clone(), pool.clone(), false)
+        .search(&query())
+        .await
+        .unwrap();
+    // 即使远超 stale 窗口，离线模式仍返回缓存
+    expire_all(&pool, 90 * 24 * 3600).await;
+    let result = offline.search(&query()).await.unwrap();
+    assert_eq!(result[0].title, "Dune #1");
+    assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_stale_entry_is_served_then_revalidated() {
+    let (pool, _dir) = create_test_db().await;
+    let inner = Arc::new(CountingProvider::default());
+    let cached = CachedProvider::new(inner.clone(), pool.clone(), false);
+
+    cached.search(&query()).await.unwrap();
+    expire_all(&pool, 60).await;
+
+    let stale = cached.search(&query()).await.unwrap();
+    assert_eq!(stale[0].title, "Dune #1");
+
+    let mut refreshed = None;
+    for _ in 0..50 {
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        let entries = provider_cache::list_entries(&pool, Some("tmdb"), 10)
+            .await
+            .unwrap();
+        if entries[0].expires_at > chrono::Utc::now() {
+            refreshed = Some(entries);
+            break;
+        }
+    }
+    assert!(refreshed.is_some(), "stale entry should be refreshed");
+    assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+    assert_eq!(cached.search(&query()).await.unwrap()[0].title, "Dune #2");
+}
+
+#[tokio::test]
+async fn test_concurrent_stale_reads_refresh_once() {
+    let (pool, _dir) = create_test_db().await;
+    let inner = Arc::new(CountingProvider::default());
+    let cached = CachedProvider::new(inner.clone(), pool.clone(), false);
+    let query = ProviderQuery {
+        title: "Arrival".to_string(),
+        ..query()
+    };
+
+    cached.search(&query).await.unwrap();
+    expire_all(&pool, 60).await;
+    inner.slow.store(true, Ordering::SeqCst);
+
+    for _ in 0..5 {
+        let stale = cached.search(&query).await.unwrap();
+        assert_eq!(stale[0].title, "Arrival #1");
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(400)).await;
+    assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_expired_entry_used_when_provider_unavailable() {
+    let (pool, _dir) = create_test_db().await;
+    let inner = Arc::new(CountingProvider::default());
+    let cached = CachedProvider::new(inner.clone(), pool.clone(), false);
+
+    cached.search(&query()).await.unwrap();
+    expire_all(&pool, 90 * 24 * 3600).await;
+    inner.down.store(true, Ordering::SeqCst);
+
+    let result = cached.search(&query()).await.unwrap();
+    assert_eq!(result[0].title, "Dune #1");
+
+    let fresh_query = ProviderQuery {
+        title: "Arrival".to_string(),
+        ..query()
+    };
+    assert!(cached.search(&fresh_query).await.is_err());
+}
+
+#[tokio::test]
+async fn test_purge_filters_by_provider_and_expiry() {
+    let (pool, _dir) = create_test_db().await;
+    let params = json!({ "title": "a" });
+    let ttl = chrono::Duration::days(1);
+    provider_cache::store(&pool, "tmdb", "search", &params, "[]", ttl)
+        .await
+        .unwrap();
+    provider_cache::store(&pool, "bgm", "search", &params, "[]", ttl)
+        .await
+        .unwrap();
+    provider_cache::store(&pool, "bgm", "details", &params, "{}", -ttl)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        provider_cache::purge(&pool, Some("bgm"), None, true)
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        provider_cache::list_entries(&pool, None, 10)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(
+        provider_cache::purge(&pool, None, Some("search"), false)
+            .await
+            .unwrap(),
+        2
+    );
+    assert!(provider_cache::list_entries(&pool, None, 10)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_cached_tmdb_details_without_external_ids_are_refetched() {
+    let (pool, _dir) = create_test_db().await;
+    let params = json!({ "external_id": "438631", "media_type": "movie" });
+    let ttl = chrono::Duration::days(7);
+    provider_cache::store(
+        &pool,
+        "tmdb",
+        "details",
+        &params,
+        r#"{"tmdb_id":"438631"}"#,
+        ttl,
+    )
+    .await
+    .unwrap();
+
+    // 离线时仍使用旧条目
+    let inner = Arc::new(CountingProvider::default());
+    let offline = CachedProvider::new(inner.clone(), pool.clone(), true);
+    let details = offline.details("438631", "movie").await.unwrap();
+    assert!(details.get("external_ids").is_none());
+    assert_eq!(inner.calls.load(Ordering::SeqCst), 0);
+
+    let cached = CachedProvider::new(inner.clone(), pool.clone(), false);
+    let details = cached.details("438631", "movie").await.unwrap();
+    assert_eq!(details["external_ids"]["imdb"], "tt1160419");
+    cached.details("438631", "movie").await.unwrap();
+    assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,194 @@
+//! 元数据源限速、重试与熔断测试
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::routing::get;
+use axum::Router;
+use cine_backend::services::provider_guard::{
+    self, CircuitBreaker, CircuitState, RateLimit, TokenBucket,
+};
+
+/// 启动本地 HTTP 服务：前 `failures` 次返回 `status`（Retry-After: 0），之后返回 200
+async fn spawn_server(status: StatusCode, failures: usize) -> (String, Arc<AtomicUsize>) {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    let app = Router::new().route(
+        "/",
+        get(move || {
+            let counter = counter.clone();
+            async move {
+                let mut headers = HeaderMap::new();
+                headers.insert("retry-after", HeaderValue::from_static("0"));
+                if counter.fetch_add(1, Ordering::SeqCst) < failures {
+                    (status, headers, "busy")
+                } else {
+                    (StatusCode::OK, headers, "ok")
+                }
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("http://{addr}/"), hits)
+}
+
+#[test]
+fn test_token_bucket_refills_over_time() {
+    let start = Instant::now();
+    let mut bucket = TokenBucket::new(
+        RateLimit {
+            capacity: 2.0,
+            refill_per_sec: 4.0,
+        },
+        start,
+    );
+
+    assert!(bucket.try_take(start).is_none());
+    assert!(bucket.try_take(start).is_none());
+    let wait = bucket.try_take(start).unwrap();
+    assert!(wait <= Duration::from_millis(250));
+    assert!(bucket
+        .try_take(start + Duration::from_millis(300))
+        .is_none());
+}
+
+#[test]
+fn test_circuit_breaker_opens_and_half_opens() {
+    let start = Instant::now();
+    let mut breaker = CircuitBreaker::new(2);
+
+    breaker.record_failure(start, None);
+    assert_eq!(breaker.state(start), CircuitState::Closed);
+    breaker.record_failure(start, None);
+    assert_eq!(breaker.state(start), CircuitState::Open);
+    assert!(breaker.allow(start).is_err());
+
+    // 冷却结束后只放行一个试探请求
+    let later = start + Duration::from_secs(120);
+    assert_eq!(breaker.state(later), CircuitState::HalfOpen);
+    assert!(breaker.allow(later).is_ok());
+    assert!(breaker.allow(later).is_err());
+
+    // 试探失败立即重新熔断
+    breaker.record_failure(later, None);
+    assert_eq!(breaker.state(later), CircuitState::Open);
+
+    let recovered = later + Duration::from_secs(120);
+    assert!(breaker.allow(recovered).is_ok());
+    breaker.record_success();
+    assert_eq!(breaker.state(recovered), CircuitState::Closed);
+    assert_eq!(breaker.failures(), 0);
+}
+
+#[test]
+fn test_circuit_breaker_opens_on_long_retry_after() {
+    let start = Instant::now();
+    let mut breaker = CircuitBreaker::new(5);
+
+    breaker.record_failure(start, Some(Duration::from_secs(10)));
+    assert_eq!(breaker.state(start), CircuitState::Closed);
+
+    // Retry-After 超过单次重试上限时不等连续失败次数，直接熔断
+    breaker.record_failure(start, Some(Duration::from_secs(120)));
+    assert_eq!(breaker.state(start), CircuitState::Open);
+    assert!(breaker.allow(start + Duration::from_secs(90)).is_err());
+}
+
+#[test]
+fn test_retry_after_parses_seconds_and_dates() {
+    let mut headers = reqwest::header::HeaderMap::new();
+    assert!(provider_guard::retry_after(&headers).is_none());
+
+    headers.insert("retry-after", "7".parse().unwrap());
+    assert_eq!(
+        provider_guard::retry_after(&headers),
+        Some(Duration::from_secs(7))
+    );
+
+    headers.insert(
+        "retry-after",
+        "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+    );
+    assert_eq!(provider_guard::retry_after(&headers), Some(Duration::ZERO));
+}
+
+#[tokio::test]
+async fn test_send_retries_rate_limited_responses() {
+    let (url, hits) = spawn_server(StatusCode::TOO_MANY_REQUESTS, 2).await;
+    let client = reqwest::Client::new();
+
+    let response = provider_guard::send("test-retry", client.get(&url))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(hits.load(Ordering::SeqCst), 3);
+
+    let status = provider_guard::statuses()
+        .into_iter()
+        .find(|s| s.provider == "test-retry")
+        .unwrap();
+    assert_eq!(status.circuit, CircuitState::Closed);
+    assert_eq!(status.throttled, 2);
+    assert_eq!(status.retries, 2);
+    assert_eq!(status.consecutive_failures, 0);
+    provider_guard::reset("test-retry");
+}
+
+#[tokio::test]
+async fn test_send_opens_circuit_after_repeated_failures() {
+    let (url, hits) = spawn_server(StatusCode::SERVICE_UNAVAILABLE, usize::MAX).await;
+    let client = reqwest::Client::new();
+
+    for _ in 0..5 {
+        let error = provider_guard::send("test-circuit", client.get(&url))
+            .await
+            .unwrap_err();
+        assert!(provider_guard::unavailable(&error).is_none());
+    }
+    let served = hits.load(Ordering::SeqCst);
+
+    // 熔断后不再发出请求，错误可识别为数据源不可用
+    let error = provider_guard::send("test-circuit", client.get(&url))
+        .await
+        .unwrap_err();
+    let unavailable = provider_guard::unavailable(&error).unwrap();
+    assert_eq!(unavailable.provider, "test-circuit");
+    assert!(unavailable.retry_after > Duration::ZERO);
+    assert_eq!(hits.load(Ordering::SeqCst), served);
+    assert!(provider_guard::open_circuits()
+        .iter()
+        .any(|open| open.provider == "test-circuit"));
+
+    provider_guard::reset("test-circuit");
+    assert!(provider_guard::send("test-circuit", client.get(&url))
+        .await
+        .is_err());
+    assert!(hits.load(Ordering::SeqCst) > served);
+    provider_guard::reset("test-circuit");
+}
+
+#[tokio::test]
+async fn test_send_returns_client_errors_without_opening_circuit() {
+    let (url, hits) = spawn_server(StatusCode::NOT_FOUND, usize::MAX).await;
+    let client = reqwest::Client::new();
+
+    for _ in 0..6 {
+        let error = provider_guard::send("test-not-found", client.get(&url))
+            .await
+            .unwrap_err();
+        assert!(provider_guard::unavailable(&error).is_none());
+        assert!(error.to_string().contains("404"));
+    }
+    // 404 不重试，也不计入连续失败
+    assert_eq!(hits.load(Ordering::SeqCst), 6);
+    assert!(!provider_guard::open_circuits()
+        .iter()
+        .any(|open| open.provider == "test-not-found"));
+    provider_guard::reset("test-not-found");
+}
@@ -0,0 +1,645 @@
+//! 发布名解析语料测试
+
+use cine_backend::services::release_parser;
+
+struct Case {
+    name: &'static str,
+    title: &'static str,
+    year: Option<u32>,
+    season: Option<u32>,
+    episode: Option<u32>,
+    episode_end: Option<u32>,
+    resolution: Option<&'static str>,
+    source: Option<&'static str>,
+    video_codec: Option<&'static str>,
+    hdr: Option<&'static str>,
+    audio: Option<&'static str>,
+    audio_channels: Option<&'static str>,
+    release_group: Option<&'static str>,
+    edition: Option<&'static str>,
+    repack: bool,
+    proper: bool,
+    languages: &'static [&'static str],
+}
+
+const EMPTY: Case = Case {
+    name: "",
+    title: "",
+    year: None,
+    season: None,
+    episode: None,
+    episode_end: None,
+    resolution: None,
+    source: None,
+    video_codec: None,
+    hdr: None,
+    audio: None,
+    audio_channels: None,
+    release_group: None,
+    edition: None,
+    repack: false,
+    proper: false,
+    languages: &[],
+};
+
+const CORPUS: &[Case] = &[
+    Case {
+        name: "The.Matrix.1999.1080p.BluRay.x264-SPARKS.mkv",
+        title: "The Matrix",
+        year: Some(1999),
+        resolution: Some("1080p"),
+        source: Some("BluRay"),
+        video_codec: Some("AVC"),
+        release_group: Some("SPARKS"),
+        ..EMPTY
+    },
+    Case {
+        name: "The Matrix (1999).mp4",
+        title: "The Matrix",
+        year: Some(1999),
+        ..EMPTY
+    },
+    Case {
+        name: "Dune.Part.Two.2024.2160p.UHD.BluRay.REMUX.DV.HDR.HEVC.TrueHD.7.1.Atmos-FGT.mkv",
+        title: "Dune Part Two",
+        year: Some(2024),
+        resolution: Some("2160p"),
+        source: Some("Remux"),
+        video_codec: Some("HEVC"),
+        hdr: Some("DV"),
+        audio: Some("TrueHD Atmos"),
+        audio_channels: Some("7.1"),
+        release_group: Some("FGT"),
+        ..EMPTY
+    },
+    Case {
+        name: "Oppenheimer.2023.IMAX.2160p.WEB-DL.DDP5.1.Atmos.HDR10.H.265-FLUX.mkv",
+        title: "Oppenheimer",
+        year: Some(2023),
+        resolution: Some("2160p"),
+        source: Some("WEB-DL"),
+        video_codec: Some("HEVC"),
+        hdr: Some("HDR10"),
+        audio: Some("DDP"),
+        audio_channels: Some("5.1"),
+        release_group: Some("FLUX"),
+        edition: Some("IMAX"),
+        ..EMPTY
+    },
+    Case {
+        name: "Blade.Runner.2049.2017.1080p.BluRay.x264-SPARKS.mkv",
+        title: "Blade Runner 2049",
+        year: Some(2017),
+        resolution: Some("1080p"),
+        source: Some("BluRay"),
+        video_codec: Some("AVC"),
+        release_group: Some("SPARKS"),
+        ..EMPTY
+    },
+    Case {
+        name: "2012.2009.720p.BluRay.x264.DTS-WiKi.mkv",
+        title: "2012",
+        year: Some(2009),
+        resolution: Some("720p"),
+        source: Some("BluRay"),
+        video_codec: Some("AVC"),
+        audio: Some("DTS"),
+        release_group: Some("WiKi"),
+        ..EMPTY
+    },
+    Case {
+        name: "Apocalypse.Now.1979.Final.Cut.1080p.BluRay.DTS-HD.MA.5.1.x264-DON.mkv",
+        title: "Apocalypse Now",
+        year: Some(1979),
+        resolution: Some("1080p"),
+        source: Some("BluRay"),
+        video_codec: Some("AVC"),
+        audio: Some("DTS-HD MA"),
+        audio_channels: Some("5.1"),
+        release_group: Some("DON"),
+        edition: Some("Final Cut"),
+        ..EMPTY
+    },
+    Case {
+        name: "Kingdom.of.Heaven.2005.Directors.Cut.1080p.BluRay.x264-AMIABLE.mkv",
+        title: "Kingdom of Heaven",
+        year: Some(2005),
+        resolution: Some("1080p"),
+        source: Some("BluRay"),
+        video_codec: Some("AVC"),
+        release_group: Some("AMIABLE"),
+        edition: Some("Director's Cut"),
+        ..EMPTY
+    },
+    Case {
+        name: "The.Lord.of.the.Rings.The.Fellowship.of.the.Ring.2001.EXTENDED.2160p.UHD.BluRay.x265.10bit.HDR.TrueHD.7.1.Atmos-TERMiNAL.mkv",
+        title: "The Lord of the Rings The Fellowship of the Ring",
+        year: Some(2001),
+        resolution: Some("2160p"),
+        source: Some("BluRay"),
+        video_codec: Some("HEVC"),
+        hdr: Some("HDR"),
+        audio: Some("TrueHD Atmos"),
+        audio_channels: Some("7.1"),
+        release_group: Some("TERMiNAL"),
+        edition: Some("Extended"),
+        ..EMPTY
+    },
+    Case {
+        name: "Alien.1979.REMASTERED.1080p.BluRay.x264-PSYCHD.mkv",
+        title: "Alien",
+        year: Some(1979),
+        resolution: Some("1080p"),
+        source: Some("BluRay"),
+        video_codec: Some("AVC"),
+        release_group: Some("PSYCHD"),
+        edition: Some("Remastered"),
+        ..EMPTY
+    },
+    Case {
+        name: "Justice.League.2017.UNRATED.720p.WEBRip.x264.AAC2.0-YTS.mp4",
+        title: "Justice League",
+        year: Some(2017),
+        resolution: Some("720p"),
+        source: Some("WEBRip"),
+        video_codec: Some("AVC"),
+        audio: Some("AAC"),
+        audio_channels: Some("2.0"),
+        release_group: Some("YTS"),
+        edition: Some("Unrated"),
+        ..EMPTY
+    },
+    Case {
+        name: "Inception.2010.PROPER.1080p.BluRay.x264-BLOW.mkv",
+        title: "Inception",
+        year: Some(2010),
+        resolution: Some("1080p"),
+        source: Some("BluRay"),
+        video_codec: Some("AVC"),
+        release_group: Some("BLOW"),
+        proper: true,
+        ..EMPTY
+    },
+    Case {
+        name: "Tenet.2020.REPACK.2160p.WEB-DL.DDP5.1.DV.HEVC-CMRG.mkv",
+        title: "Tenet",
+        year: Some(2020),
+        resolution: Some("2160p"),
+        source: Some("WEB-DL"),
+        video_codec: Some("HEVC"),
+        hdr: Some("DV"),
+        audio: Some("DDP"),
+        audio_channels: Some("5.1"),
+        release_group: Some("CMRG"),
+        repack: true,
+        ..EMPTY
+    },
+    Case {
+        name: "Amelie.2001.FRENCH.1080p.BluRay.x264-LOST.mkv",
+        title: "Amelie",
+        year: Some(2001),
+        resolution: Some("1080p"),
+        source: Some("BluRay"),
+        video_codec: Some("AVC"),
+        release_group: Some("LOST"),
+        languages: &["fr"],
+        ..EMPTY
+    },
+    Case {
+        name: "The English Patient (1996) 1080p BluRay.mkv",
+        title: "The English Patient",
+        year: Some(1996),
+        resolution: Some("1080p"),
+        source: Some("BluRay"),
+        ..EMPTY
+    },
+    Case {
+        name: "Parasite.2019.MULTi.2160p.UHD.BluRay.x265.HDR10+.DTS-X-GROUP.mkv",
+        title: "Parasite",
+        year: Some(2019),
+        resolution: Some("2160p"),
+        source: Some("BluRay"),
+        video_codec: Some("HEVC"),
+        hdr: Some("HDR10+"),
+        audio: Some("DTS-X"),
+        release_group: Some("GROUP"),
+        languages: &["multi"],
+        ..EMPTY
+    },
+    Case {
+        name: "Old.Movie.1950.DVDRip.XviD.MP3-NoGroup.avi",
+        title: "Old Movie",
+        year: Some(1950),
+        source: Some("DVDRip"),
+        video_codec: Some("XviD"),
+        audio: Some("MP3"),
+        release_group: Some("NoGroup"),
+        ..EMPTY
+    },
+    Case {
+        name: "Some.Film.2022.HDCAM.x264-NOGRP.mkv",
+        title: "Some Film",
+        year: Some(2022),
+        source: Some("CAM"),
+        video_codec: Some("AVC"),
+        release_group: Some("NOGRP"),
+        ..EMPTY
+    },
+    Case {
+        name: "Spider-Man.No.Way.Home.2021.1080p.WEB-DL.mkv",
+        title: "Spider-Man No Way Home",
+        year: Some(2021),
+        resolution: Some("1080p"),
+        source: Some("WEB-DL"),
+        ..EMPTY
+    },
+    Case {
+        name: "Mr. Robot (2015) S01E01 1080p.mkv",
+        title: "Mr. Robot",
+        year: Some(2015),
+        season: Some(1),
+        episode: Some(1),
+        resolution: Some("1080p"),
+        ..EMPTY
+    },
+    Case {
+        name: "Game of Thrones S01E01.mp4",
+        title: "Game of Thrones",
+        season: Some(1),
+        episode: Some(1),
+        ..EMPTY
+    },
+    Case {
+        name: "Breaking.Bad.S05E16.Felina.1080p.BluRay.x264-ROVERS.mkv",
+        title: "Breaking Bad",
+        season: Some(5),
+        episode: Some(16),
+        resolution: Some("1080p"),
+        source: Some("BluRay"),
+        video_codec: Some("AVC"),
+        release_group: Some("ROVERS"),
+        ..EMPTY
+    },
+    Case {
+        name: "The.Office.US.S03E05.1080p.BluRay.x264.mkv",
+        title: "The Office US",
+        season: Some(3),
+        episode: Some(5),
+        resolution: Some("1080p"),
+        source: Some("BluRay"),
+        video_codec: Some("AVC"),
+        ..EMPTY
+    },
+    Case {
+        name: "Friends.S02E12-E13.720p.HDTV.x264-LOL.mkv",
+        title: "Friends",
+        season: Some(2),
+        episode: Some(12),
+        episode_end: Some(13),
+        resolution: Some("720p"),
+        source: Some("HDTV"),
+        video_codec: Some("AVC"),
+        release_group: Some("LOL"),
+        ..EMPTY
+    },
+    Case {
+        name: "The.Last.of.Us.S01E03.2160p.HMAX.WEB-DL.DDP5.1.Atmos.DV.HDR.H.265-FLUX.mkv",
+        title: "The Last of Us",
+        season: Some(1),
+        episode: Some(3),
+        resolution: Some("2160p"),
+        source: Some("WEB-DL"),
+        video_codec: Some("HEVC"),
+        hdr: Some("DV"),
+        audio: Some("DDP"),
+        audio_channels: Some("5.1"),
+        release_group: Some("FLUX"),
+        ..EMPTY
+    },
+    Case {
+        name: "Severance.S02E01.REPACK.1080p.ATVP.WEB-DL.DDP5.1.H.264-NTb.mkv",
+        title: "Severance",
+        season: Some(2),
+        episode: Some(1),
+        resolution: Some("1080p"),
+        source: Some("WEB-DL"),
+        video_codec: Some("AVC"),
+        audio: Some("DDP"),
+        audio_channels: Some("5.1"),
+        release_group: Some("NTb"),
+        repack: true,
+        ..EMPTY
+    },
+    Case {
+        name: "Doctor.Who.2005.S01E01.720p.BluRay.x264-SHORTBREHD.mkv",
+        title: "Doctor Who",
+        year: Some(2005),
+        season: Some(1),
+        episode: Some(1),
+        resolution: Some("720p"),
+        source: Some("BluRay"),
+        video_codec: Some("AVC"),
+        release_group: Some("SHORTBREHD"),
+        ..EMPTY
+    },
+    Case {
+        name: "Sherlock.S04E03.1080p.iTunes.WEB-DL.AAC2.0.H264.mkv",
+        title: "Sherlock",
+        season: Some(4),
+        episode: Some(3),
+        resolution: Some("1080p"),
+        source: Some("WEB-DL"),
+        video_codec: Some("AVC"),
+        audio: Some("AAC"),
+        audio_channels: Some("2.0"),
+        ..EMPTY
+    },
+    Case {
+        name: "Planet.Earth.II.S01E01.2160p.UHD.BluRay.x265.HLG.DTS-HD.MA.5.1-DON.mkv",
+        title: "Planet Earth II",
+        season: Some(1),
+        episode: Some(1),
+        resolution: Some("2160p"),
+        source: Some("BluRay"),
+        video_codec: Some("HEVC"),
+        hdr: Some("HLG"),
+        audio: Some("DTS-HD MA"),
+        audio_channels: Some("5.1"),
+        release_group: Some("DON"),
+        ..EMPTY
+    },
+    Case {
+        name: "Band.of.Brothers.E05.1080p.BluRay.FLAC.x264.mkv",
+        title: "Band of Brothers",
+        episode: Some(5),
+        resolution: Some("1080p"),
+        source: Some("BluRay"),
+        video_codec: Some("AVC"),
+        audio: Some("FLAC"),
+        ..EMPTY
+    },
+    Case {
+        name: "Once Upon a Time 1984.mkv",
+        title: "Once Upon a Time",
+        year: Some(1984),
+        ..EMPTY
+    },
+    Case {
+        name: "Narcos.S01E01.1080p.NF.WEBRip.DD5.1.x264-NTb.mkv",
+        title: "Narcos",
+        season: Some(1),
+        episode: Some(1),
+        resolution: Some("1080p"),
+        source: Some("WEBRip"),
+        video_codec: Some("AVC"),
+        audio: Some("AC3"),
+        audio_channels: Some("5.1"),
+        release_group: Some("NTb"),
+        ..EMPTY
+    },
+    Case {
+        name: "Avatar.2009.1920x1080.BDRip.AVC.AC3.mkv",
+        title: "Avatar",
+        year: Some(2009),
+        resolution: Some("1080p"),
+        source: Some("BluRay"),
+        video_codec: Some("AVC"),
+        audio: Some("AC3"),
+        ..EMPTY
+    },
+    Case {
+        name: "Interstellar.2014.4K.HDR.mkv",
+        title: "Interstellar",
+        year: Some(2014),
+        resolution: Some("2160p"),
+        hdr: Some("HDR"),
+        ..EMPTY
+    },
+    Case {
+        name: "The.Batman.2022.1080p.AV1.Opus-Group.mkv",
+        title: "The Batman",
+        year: Some(2022),
+        resolution: Some("1080p"),
+        video_codec: Some("AV1"),
+        audio: Some("Opus"),
+        release_group: Some("Group"),
+        ..EMPTY
+    },
+    Case {
+        name: "Seven.Samurai.1954.Criterion.1080p.BluRay.x264.FLAC.1.0-DEPTH.mkv",
+        title: "Seven Samurai",
+        year: Some(1954),
+        resolution: Some("1080p"),
+        source: Some("BluRay"),
+        video_codec: Some("AVC"),
+        audio: Some("FLAC"),
+        audio_channels: Some("1.0"),
+        release_group: Some("DEPTH"),
+        edition: Some("Criterion"),
+        ..EMPTY
+    },
+    Case {
+        name: "Hero.2002.CHINESE.1080p.BluRay.x264.DTS-FGT.mkv",
+        title: "Hero",
+        year: Some(2002),
+        resolution: Some("1080p"),
+        source: Some("BluRay"),
+        video_codec: Some("AVC"),
+        audio: Some("DTS"),
+        release_group: Some("FGT"),
+        languages: &["zh"],
+        ..EMPTY
+    },
+    Case {
+        name: "流浪地球2.The.Wandering.Earth.II.2023.2160p.WEB-DL.H265.DDP5.1-国语中字.mkv",
+        title: "流浪地球2 The Wandering Earth II",
+        year: Some(2023),
+        resolution: Some("2160p"),
+        source: Some("WEB-DL"),
+        video_codec: Some("HEVC"),
+        audio: Some("DDP"),
+        audio_channels: Some("5.1"),
+        languages: &["zh"],
+        ..EMPTY
+    },
+    Case {
+        name: "三体.第07集.mp4",
+        title: "三体",
+        episode: Some(7),
+        ..EMPTY
+    },
+    Case {
+        name: "[Nekomoe kissaten] Frieren - 05v2 [1080p][CHS].mkv",
+        title: "Frieren",
+        resolution: Some("1080p"),
+        release_group: Some("Nekomoe kissaten"),
+        languages: &["zh-hans"],
+        ..EMPTY
+    },
+    Case {
+        name: "[Sakurato] Spy x Family - 12 [1080p][简繁内封].mkv",
+        title: "Spy x Family",
+        resolution: Some("1080p"),
+        release_group: Some("Sakurato"),
+        languages: &["zh-hans", "zh-hant"],
+        ..EMPTY
+    },
+    Case {
+        name: "Heat.1995.Theatrical.Cut.REMUX.1080p.BluRay.AVC.LPCM.2.0-EPSiLON.mkv",
+        title: "Heat",
+        year: Some(1995),
+        resolution: Some("1080p"),
+        source: Some("Remux"),
+        video_codec: Some("AVC"),
+        audio: Some("PCM"),
+        audio_channels: Some("2.0"),
+        release_group: Some("EPSiLON"),
+        edition: Some("Theatrical"),
+        ..EMPTY
+    },
+    Case {
+        name: "Movie.Name.2018.1080p.BluRay.VC-1.DTS-HD.MA.5.1-SiMPLE.mkv",
+        title: "Movie Name",
+        year: Some(2018),
+        resolution: Some("1080p"),
+        source: Some("BluRay"),
+        video_codec: Some("VC-1"),
+        audio: Some("DTS-HD MA"),
+        audio_channels: Some("5.1"),
+        release_group: Some("SiMPLE"),
+        ..EMPTY
+    },
+    Case {
+        name: "Yellowstone.S05E01.720p.HDTV.x264-SYNCOPY.mkv",
+        title: "Yellowstone",
+        season: Some(5),
+        episode: Some(1),
+        resolution: Some("720p"),
+        source: Some("HDTV"),
+        video_codec: Some("AVC"),
+        release_group: Some("SYNCOPY"),
+        ..EMPTY
+    },
+    Case {
+        name: "Chernobyl.S01E02.576p.DVDRip.x264.ENG-GRP.mkv",
+        title: "Chernobyl",
+        season: Some(1),
+        episode: Some(2),
+        resolution: Some("576p"),
+        source: Some("DVDRip"),
+        video_codec: Some("AVC"),
+        release_group: Some("GRP"),
+        languages: &["en"],
+        ..EMPTY
+    },
+    Case {
+        name: "Home Video.mp4",
+        title: "Home Video",
+        ..EMPTY
+    },
+    Case {
+        name: "進撃の巨人\u{3000}ENG 1080p.mkv",
+        title: "進撃の巨人",
+        resolution: Some("1080p"),
+        languages: &["en"],
+        ..EMPTY
+    },
+    Case {
+        name: "Movie\u{3000}Title ENG 1080p.mkv",
+        title: "Movie Title",
+        resolution: Some("1080p"),
+        languages: &["en"],
+        ..EMPTY
+    },
+];
+
+#[test]
+fn test_release_parser_corpus() {
+    for case in CORPUS {
+        let info = release_parser::parse(case.name);
+        let name = case.name;
+        assert_eq!(info.title, case.title, "title: {name}");
+        assert_eq!(info.year, case.year, "year: {name}");
+        assert_eq!(info.season, case.season, "season: {name}");
+        assert_eq!(info.episode, case.episode, "episode: {name}");
+        assert_eq!(info.episode_end, case.episode_end, "episode_end: {name}");
+        assert_eq!(
+            info.resolution.as_deref(),
+            case.resolution,
+            "resolution: {name}"
+        );
+        assert_eq!(info.source.as_deref(), case.source, "source: {name}");
+        assert_eq!(
+            info.video_codec.as_deref(),
+            case.video_codec,
+            "video_codec: {name}"
+        );
+        assert_eq!(info.hdr.as_deref(), case.hdr, "hdr: {name}");
+        assert_eq!(info.audio.as_deref(), case.audio, "audio: {name}");
+        assert_eq!(
+            info.audio_channels.as_deref(),
+            case.audio_channels,
+            "audio_channels: {name}"
+        );
+        assert_eq!(
+            info.release_group.as_deref(),
+            case.release_group,
+            "release_group: {name}"
+        );
+        assert_eq!(info.edition.as_deref(), case.edition, "edition: {name}");
+        assert_eq!(info.repack, case.repack, "repack: {name}");
+        assert_eq!(info.proper, case.proper, "proper: {name}");
+        assert_eq!(info.languages, case.languages, "languages: {name}");
+    }
+}
+
+#[test]
+fn test_release_parser_reports_field_confidence() {
+    let info = release_parser::parse("The.Matrix.1999.1080p.4K.BluRay.x264-SPARKS.mkv");
+    assert_eq!(info.confidence["resolution"], 0.95);
+    assert_eq!(info.confidence["year"], 0.8);
+    assert_eq!(info.confidence["title"], 0.9);
+    assert!(!info.confidence.contains_key("hdr"));
+
+    let bare = release_parser::parse("Home Video.mp4");
+    assert!(bare.confidence["title"] < 0.9);
+    assert_eq!(bare.confidence.len(), 1);
+
+    let parenthesised = release_parser::parse("The Matrix (1999).mkv");
+    assert_eq!(parenthesised.confidence["year"], 0.95);
+}
+
+#[test]
+fn test_release_info_helpers() {
+    let info = release_parser::parse("Movie.2020.2160p.BluRay.TrueHD.7.1-GRP.mkv");
+    assert_eq!(info.resolution_height(), Some(2160));
+    assert_eq!(info.channel_count(), Some(8));
+}
+
+#[test]
+fn test_quality_score_falls_back_to_release_info() {
+    use cine_backend::models::VideoInfo;
+    use cine_backend::services::quality;
+
+    let remux = release_parser::parse("Movie.2020.2160p.Remux.HDR.TrueHD.7.1-GRP.mkv");
+    let webrip = release_parser::parse("Movie.2020.720p.WEBRip.x264-GRP.mkv");
+    assert!(
+        quality::calculate_file_quality_score(None, &remux)
+            > quality::calculate_file_quality_score(None, &webrip)
+    );
+
+    // ffprobe 结果优先于发布名
+    let probed = VideoInfo {
+        width: Some(1280),
+        height: Some(720),
+        ..Default::default()
+    };
+    let probed_score = quality::calculate_file_quality_score(Some(&probed), &remux);
+    assert!(probed_score < quality::calculate_file_quality_score(None, &remux));
+
+    let repack = release_parser::parse("Movie.2020.720p.WEBRip.REPACK.x264-GRP.mkv");
+    assert_eq!(
+        quality::calculate_file_quality_score(None, &repack),
+        quality::calculate_file_quality_score(None, &webrip) + 2
+    );
+}
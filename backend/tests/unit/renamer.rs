@@ -20,6 +20,9 @@ fn test_generate_new_name_basic() {
         detected_year: None,
         detected_season: None,
         detected_episode: None,
+        detected_episode_end: None,
+        detected_absolute_episode: None,
+        release_info: None,
         parser_provider: None,
         parse_version: None,
         confidence_score: None,
@@ -29,6 +32,10 @@ fn test_generate_new_name_basic() {
         locked_match_provider: None,
         locked_match_external_id: None,
         ai_disabled_reason: None,
+        movie_id: None,
+        show_id: None,
+        season_id: None,
+        episode_id: None,
         created_at: Utc::now(),
         updated_at: Utc::now(),
         last_modified: Utc::now(),
@@ -62,6 +69,9 @@ fn test_generate_new_name_with_metadata() {
         detected_year: None,
         detected_season: None,
         detected_episode: None,
+        detected_episode_end: None,
+        detected_absolute_episode: None,
+        release_info: None,
         parser_provider: None,
         parse_version: None,
         confidence_score: None,
@@ -71,6 +81,10 @@ fn test_generate_new_name_with_metadata() {
         locked_match_provider: None,
         locked_match_external_id: None,
         ai_disabled_reason: None,
+        movie_id: None,
+        show_id: None,
+        season_id: None,
+        episode_id: None,
         created_at: Utc::now(),
         updated_at: Utc::now(),
         last_modified: Utc::now(),
@@ -108,6 +122,9 @@ fn test_generate_new_name_tv_show() {
         detected_year: None,
         detected_season: None,
         detected_episode: None,
+        detected_episode_end: None,
+        detected_absolute_episode: None,
+        release_info: None,
         parser_provider: None,
         parse_version: None,
         confidence_score: None,
@@ -117,6 +134,10 @@ fn test_generate_new_name_tv_show() {
         locked_match_provider: None,
         locked_match_external_id: None,
         ai_disabled_reason: None,
+        movie_id: None,
+        show_id: None,
+        season_id: None,
+        episode_id: None,
         created_at: Utc::now(),
         updated_at: Utc::now(),
         last_modified: Utc::now(),
@@ -152,6 +173,9 @@ fn test_sanitize_filename_indirect() {
         detected_year: None,
         detected_season: None,
         detected_episode: None,
+        detected_episode_end: None,
+        detected_absolute_episode: None,
+        release_info: None,
         parser_provider: None,
         parse_version: None,
         confidence_score: None,
@@ -161,6 +185,10 @@ fn test_sanitize_filename_indirect() {
         locked_match_provider: None,
         locked_match_external_id: None,
         ai_disabled_reason: None,
+        movie_id: None,
+        show_id: None,
+        season_id: None,
+        episode_id: None,
         created_at: Utc::now(),
         updated_at: Utc::now(),
         last_modified: Utc::now(),
@@ -173,3 +201,370 @@ fn test_sanitize_filename_indirect() {
     assert!(!name.contains(':'));
     assert!(!name.contains('/'));
 }
+
+fn make_file(id: &str, path: &std::path::Path) -> MediaFile {
+    MediaFile {
+        id: id.to_string(),
+        path: path.to_string_lossy().to_string(),
+        name: path.file_name().unwrap().to_string_lossy().to_string(),
+        size: 1000,
+        file_type: "video".to_string(),
+        hash_xxhash: None,
+        hash_md5: None,
+        tmdb_id: None,
+        quality_score: None,
+        video_info: None,
+        metadata: None,
+        detected_title: None,
+        detected_year: None,
+        detected_season: None,
+        detected_episode: None,
+        detected_episode_end: None,
+        detected_absolute_episode: None,
+        release_info: None,
+        parser_provider: None,
+        parse_version: None,
+        confidence_score: None,
+        review_state: None,
+        match_provider: None,
+        match_external_id: None,
+        locked_match_provider: None,
+        locked_match_external_id: None,
+        ai_disabled_reason: None,
+        movie_id: None,
+        show_id: None,
+        season_id: None,
+        episode_id: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        last_modified: Utc::now(),
+    }
+}
+
+#[test]
+fn test_plan_renames_suffixes_duplicate_targets() {
+    use renamer::{ConflictPolicy, PlanAction, RenameIssue};
+
+    let dir = tempfile::tempdir().unwrap();
+    let a = make_file("a", &dir.path().join("a.mkv"));
+    let b = make_file("b", &dir.path().join("b.mkv"));
+    let proposals = vec![
+        ("a".to_string(), "Show S01E01.mkv".to_string()),
+        ("b".to_string(), "show s01e01.mkv".to_string()),
+    ];
+
+    let plan = renamer::plan_renames(&[a, b], &proposals, ConflictPolicy::Suffix);
+
+    assert!(plan.executable);
+    assert_eq!(plan.items[0].new_name, "Show S01E01.mkv");
+    assert_eq!(plan.items[1].new_name, "show s01e01 (1).mkv");
+    assert_eq!(plan.items[1].action, PlanAction::Rename);
+    assert_eq!(plan.items[1].issues, vec![RenameIssue::DuplicateTarget]);
+}
+
+#[test]
+fn test_plan_renames_detects_existing_target_case_insensitively() {
+    use renamer::{ConflictPolicy, PlanAction, RenameIssue};
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("MOVIE (2024).mkv"), b"x").unwrap();
+    let file = make_file("a", &dir.path().join("movie.mkv"));
+    let proposals = vec![("a".to_string(), "Movie (2024).mkv".to_string())];
+
+    let skip = renamer::plan_renames(
+        std::slice::from_ref(&file),
+        &proposals,
+        ConflictPolicy::Skip,
+    );
+    assert_eq!(skip.items[0].action, PlanAction::Skip);
+    assert_eq!(skip.items[0].issues, vec![RenameIssue::TargetExists]);
+    assert_eq!(skip.skipped_count, 1);
+    assert!(skip.rename_items().is_empty());
+
+    let fail = renamer::plan_renames(&[file], &proposals, ConflictPolicy::Fail);
+    assert_eq!(fail.items[0].action, PlanAction::Blocked);
+    assert!(!fail.executable);
+}
+
+#[test]
+fn test_plan_renames_blocks_empty_name_under_fail_policy() {
+    use renamer::{ConflictPolicy, PlanAction};
+
+    let dir = tempfile::tempdir().unwrap();
+    let file = make_file("a", &dir.path().join("movie.mkv"));
+    let proposals = vec![("a".to_string(), "<>|".to_string())];
+
+    let skip = renamer::plan_renames(
+        std::slice::from_ref(&file),
+        &proposals,
+        ConflictPolicy::Skip,
+    );
+    assert_eq!(skip.items[0].action, PlanAction::Skip);
+    assert!(skip.executable);
+
+    let fail = renamer::plan_renames(&[file], &proposals, ConflictPolicy::Fail);
+    assert_eq!(fail.items[0].action, PlanAction::Blocked);
+    assert!(!fail.executable);
+}
+
+#[test]
+fn test_plan_renames_allows_case_only_and_chained_renames() {
+    use renamer::{ConflictPolicy, PlanAction, RenameIssue};
+
+    let dir = tempfile::tempdir().unwrap();
+    let e1 = make_file("e1", &dir.path().join("show.e01.mkv"));
+    let e2 = make_file("e2", &dir.path().join("show.e02.mkv"));
+    let e3 = make_file("e3", &dir.path().join("show.e03.mkv"));
+    for file in [&e1, &e2, &e3] {
+        std::fs::write(&file.path, b"x").unwrap();
+    }
+    // e2 先让出名称，e3 随后占用；e1 仅改变大小写
+    let proposals = vec![
+        ("e1".to_string(), "Show.E01.mkv".to_string()),
+        ("e2".to_string(), "show.e02.v2.mkv".to_string()),
+        ("e3".to_string(), "show.e02.mkv".to_string()),
+    ];
+
+    let plan = renamer::plan_renames(&[e1, e2, e3], &proposals, ConflictPolicy::Fail);
+
+    assert!(plan.executable);
+    assert_eq!(plan.items[0].issues, vec![RenameIssue::CaseOnly]);
+    assert!(plan
+        .items
+        .iter()
+        .all(|item| item.action == PlanAction::Rename));
+    assert_eq!(plan.items[2].new_name, "show.e02.mkv");
+}
+
+#[test]
+fn test_plan_renames_truncates_long_names_and_strips_invalid_chars() {
+    use renamer::{ConflictPolicy, RenameIssue, MAX_NAME_BYTES};
+
+    let dir = tempfile::tempdir().unwrap();
+    let file = make_file("a", &dir.path().join("a.mkv"));
+    let long_title = "葬送的芙莉莲".repeat(20);
+    let proposals = vec![("a".to_string(), format!("{}: 特别篇?.mkv", long_title))];
+
+    let plan = renamer::plan_renames(&[file], &proposals, ConflictPolicy::Suffix);
+    let item = &plan.items[0];
+
+    assert!(item.new_name.len() <= MAX_NAME_BYTES);
+    assert!(item.new_name.ends_with(".mkv"));
+    assert!(!item.new_name.contains(':') && !item.new_name.contains('?'));
+    assert!(item.issues.contains(&RenameIssue::InvalidChars));
+    assert!(item.issues.contains(&RenameIssue::NameTooLong));
+}
+
+#[test]
+fn test_generate_new_name_multi_episode() {
+    let template = "{title} - S{season:02d}E{episode:02d}.{ext}";
+
+    let parsed = make_file("a", std::path::Path::new("/tv/Friends.S02E12E13.mkv"));
+    assert_eq!(
+        renamer::generate_new_name(&parsed, template).as_deref(),
+        Some("Friends - S02E12-E13.mkv")
+    );
+
+    let mut stored = make_file("b", std::path::Path::new("/tv/friends-212.mkv"));
+    stored.metadata = Some(r#"{"title":"Friends","season":2,"episode":12}"#.to_string());
+    stored.detected_episode_end = Some(13);
+    assert_eq!(
+        renamer::generate_new_name(&stored, template).as_deref(),
+        Some("Friends - S02E12-E13.mkv")
+    );
+}
+
+#[test]
+fn test_generate_new_name_absolute_episode() {
+    let mut file = make_file(
+        "a",
+        std::path::Path::new("/anime/[Group] One Piece - 1071 [1080p].mkv"),
+    );
+    file.metadata = Some(
+        r#"{"title":"One Piece","season":21,"episode":179,"absolute_number":1071}"#.to_string(),
+    );
+
+    assert_eq!(
+        renamer::generate_new_name(
+            &file,
+            "{title} - {absolute:04d} - S{season:02d}E{episode:03d}.{ext}"
+        )
+        .as_deref(),
+        Some("One Piece - 1071 - S21E179.mkv")
+    );
+
+    file.metadata = None;
+    file.name = "[Group] Frieren - 05 [1080p].mkv".to_string();
+    assert_eq!(
+        renamer::generate_new_name(&file, "Frieren - {absolute}.{ext}").as_deref(),
+        Some("Frieren - 05.mkv")
+    );
+}
+
+#[test]
+fn test_generate_new_name_release_placeholders() {
+    let file = make_file(
+        "a",
+        std::path::Path::new(
+            "/movies/Blade.Runner.2049.2017.Directors.Cut.2160p.UHD.BluRay.x265.HDR.DTS-HD.MA.5.1-GROUP.mkv",
+        ),
+    );
+
+    assert_eq!(
+        renamer::generate_new_name(
+            &file,
+            "{title} ({year}) [{quality}] [{codec}] [{audio}] [{edition}]-{group}.{ext}"
+        )
+        .as_deref(),
+        Some("Blade Runner 2049 (2017) [4K HDR BluRay] [HEVC] [DTS-HD MA 5.1] [Director's Cut]-GROUP.mkv")
+    );
+
+    // 发布名中没有的字段连同方括号一起移除
+    let plain = make_file("b", std::path::Path::new("/movies/Heat.1995.mkv"));
+    assert_eq!(
+        renamer::generate_new_name(&plain, "{title} ({year}) [{codec}] [{edition}].{ext}")
+            .as_deref(),
+        Some("Heat (1995).mkv")
+    );
+}
+
+#[test]
+fn test_generate_new_name_episode_title() {
+    let template = "{title} - S{season:02d}E{episode:02d} - {episode_title}.{ext}";
+    let mut file = make_file("a", std::path::Path::new("/tv/Frieren.S01E05.mkv"));
+    file.metadata = Some(
+        r#"{"title":"Frieren","season":1,"episode":5,"episode_title":"Phantoms of the Dead"}"#
+            .to_string(),
+    );
+    assert_eq!(
+        renamer::generate_new_name(&file, template).as_deref(),
+        Some("Frieren - S01E05 - Phantoms of the Dead.mkv")
+    );
+
+    file.metadata = Some(r#"{"title":"Frieren","season":1,"episode":5}"#.to_string());
+    assert_eq!(
+        renamer::generate_new_name(&file, template).as_deref(),
+        Some("Frieren - S01E05.mkv")
+    );
+}
+
+#[test]
+fn test_generate_new_name_collection() {
+    let template = "{collection} - {title} ({year}).{ext}";
+    let mut file = make_file("a", std::path::Path::new("/movies/The.Matrix.1999.mkv"));
+    file.metadata = Some(
+        r#"{"title":"The Matrix","year":1999,"collection":{"id":"2344","name":"The Matrix Collection"}}"#
+            .to_string(),
+    );
+    assert_eq!(
+        renamer::generate_new_name(&file, template).as_deref(),
+        Some("The Matrix Collection - The Matrix (1999).mkv")
+    );
+
+    // 按系列建目录：每段单独清理，系列名中的 `/` 不会产生额外目录
+    file.metadata = Some(
+        r#"{"title":"Fate/stay night","year":2006,"collection":{"id":"1","name":"Fate/stay night Collection"}}"#
+            .to_string(),
+    );
+    assert_eq!(
+        renamer::generate_new_name(&file, "{collection}/{title} ({year}).{ext}").as_deref(),
+        Some("Fatestay night Collection/Fatestay night (2006).mkv")
+    );
+
+    // 不属于任何系列时占位符为空
+    file.metadata = Some(r#"{"title":"Heat","year":1995,"collection":null}"#.to_string());
+    assert_eq!(
+        renamer::generate_new_name(&file, "{title} ({year}) [{collection}].{ext}").as_deref(),
+        Some("Heat (1995).mkv")
+    );
+    assert_eq!(
+        renamer::generate_new_name(&file, "{collection}/{title} ({year}).{ext}").as_deref(),
+        Some("Heat (1995).mkv")
+    );
+}
+
+#[test]
+fn test_plan_renames_checks_collection_directory() {
+    use renamer::{ConflictPolicy, PlanAction, RenameIssue};
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("The Matrix Collection")).unwrap();
+    std::fs::write(
+        dir.path()
+            .join("The Matrix Collection/The Matrix (1999).mkv"),
+        b"x",
+    )
+    .unwrap();
+    let a = make_file("a", &dir.path().join("the.matrix.1999.mkv"));
+    let b = make_file("b", &dir.path().join("the.matrix.reloaded.2003.mkv"));
+    let proposals = vec![
+        (
+            "a".to_string(),
+            "The Matrix Collection/The Matrix (1999).mkv".to_string(),
+        ),
+        (
+            "b".to_string(),
+            "The Matrix Collection/The Matrix Reloaded (2003).mkv".to_string(),
+        ),
+    ];
+
+    let plan = renamer::plan_renames(&[a, b], &proposals, ConflictPolicy::Suffix);
+
+    // 目标目录中已存在的同名文件按冲突处理
+    assert_eq!(plan.items[0].issues, vec![RenameIssue::TargetExists]);
+    assert_eq!(
+        plan.items[0].new_name,
+        "The Matrix Collection/The Matrix (1999) (1).mkv"
+    );
+    assert_eq!(plan.items[1].action, PlanAction::Rename);
+    assert_eq!(
+        std::path::Path::new(&plan.items[1].new_path),
+        dir.path()
+            .join("The Matrix Collection")
+            .join("The Matrix Reloaded (2003).mkv")
+    );
+}
+
+#[test]
+fn test_plan_renames_keeps_files_already_in_collection_directory() {
+    use renamer::{ConflictPolicy, PlanAction, RenameIssue};
+
+    let dir = tempfile::tempdir().unwrap();
+    let collection = dir.path().join("The Matrix Collection");
+    std::fs::create_dir(&collection).unwrap();
+    let path = collection.join("The Matrix (1999).mkv");
+    std::fs::write(&path, b"x").unwrap();
+    let file = make_file("a", &path);
+
+    // 再次按同一模板执行不会再套一层系列目录
+    let plan = renamer::plan_renames(
+        std::slice::from_ref(&file),
+        &[(
+            "a".to_string(),
+            "The Matrix Collection/The Matrix (1999).mkv".to_string(),
+        )],
+        ConflictPolicy::Fail,
+    );
+    assert_eq!(plan.items[0].action, PlanAction::Unchanged);
+    assert_eq!(std::path::Path::new(&plan.items[0].new_path), path);
+
+    // 目录名同样检查无效字符与长度
+    let long = "长".repeat(100);
+    let plan = renamer::plan_renames(
+        &[file],
+        &[(
+            "a".to_string(),
+            format!("Fate:Zero/{long}/The Matrix (1999).mkv"),
+        )],
+        ConflictPolicy::Fail,
+    );
+    let item = &plan.items[0];
+    assert_eq!(
+        item.issues,
+        vec![RenameIssue::NameTooLong, RenameIssue::InvalidChars]
+    );
+    let sub_dirs: Vec<&str> = item.new_name.split('/').collect();
+    assert_eq!(sub_dirs[0], "FateZero");
+    assert!(sub_dirs[1].len() <= renamer::MAX_NAME_BYTES);
+    assert_eq!(item.action, PlanAction::Blocked);
+}
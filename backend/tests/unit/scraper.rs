@@ -35,3 +35,96 @@ fn test_parse_filename_complex() {
     assert_eq!(season, Some(3));
     assert_eq!(episode, Some(5));
 }
+
+#[test]
+fn test_parse_episode_range() {
+    let cases = [
+        ("Friends.S02E12-E13.mkv", Some((12, 13))),
+        ("Friends.S02E12E13.mkv", Some((12, 13))),
+        ("Friends S02E12-13 1080p.mkv", Some((12, 13))),
+        ("Show.S01E01-S01E03.mkv", Some((1, 3))),
+        ("Show.S01E01E02E03.mkv", Some((1, 3))),
+        ("某剧 第01-02集.mp4", Some((1, 2))),
+        ("Show.S01E01.mkv", None),
+        ("Show.S01E01-720p.mkv", None),
+        ("Show.S01E05-E03.mkv", None),
+    ];
+
+    for (name, expected) in cases {
+        assert_eq!(scraper::parse_episode_range(name), expected, "{name}");
+    }
+}
+
+#[test]
+fn test_parse_filename_multi_episode_title() {
+    let (title, _year, season, episode) = scraper::parse_filename("Friends.S02E12-E13.mkv");
+    assert_eq!(title, "Friends");
+    assert_eq!(season, Some(2));
+    assert_eq!(episode, Some(12));
+}
+
+#[test]
+fn test_parse_absolute_episode() {
+    let cases = [
+        ("[Group] One Piece - 1071 [1080p].mkv", Some(1071)),
+        ("[Group] Frieren - 05v2 [1080p][CHS].mkv", Some(5)),
+        ("[Group][Frieren][28][1080p].mp4", Some(28)),
+        ("Frieren - 28.mkv", Some(28)),
+        ("Frieren S01E28.mkv", None),
+        ("Some Movie - 2019 [1080p].mkv", None),
+        ("The Matrix (1999).mkv", None),
+    ];
+
+    for (name, expected) in cases {
+        assert_eq!(scraper::parse_absolute_episode(name), expected, "{name}");
+    }
+}
+
+#[test]
+fn test_parse_season_folder() {
+    let cases = [
+        ("Season 2", Some((2, ""))),
+        ("season.03", Some((3, ""))),
+        ("S02", Some((2, ""))),
+        ("Series 1", Some((1, ""))),
+        ("第二季", Some((2, ""))),
+        ("第12季", Some((12, ""))),
+        ("第二十一季", Some((21, ""))),
+        ("Specials", Some((0, ""))),
+        ("Breaking Bad Season 2", Some((2, "Breaking Bad"))),
+        ("Breaking Bad (2008)", None),
+        ("Sherlock", None),
+    ];
+
+    for (name, expected) in cases {
+        let parsed = scraper::parse_season_folder(name);
+        assert_eq!(
+            parsed.as_ref().map(|(s, rest)| (*s, rest.as_str())),
+            expected,
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn test_parse_bare_episode() {
+    let cases = [
+        ("02.mkv", Some(2)),
+        ("E05.mkv", Some(5)),
+        ("03 - Pilot.mkv", Some(3)),
+        ("第07集.mp4", Some(7)),
+        ("Pilot.mkv", None),
+        ("2019.mkv", None),
+    ];
+
+    for (name, expected) in cases {
+        assert_eq!(scraper::parse_bare_episode(name), expected, "{name}");
+    }
+}
+
+#[test]
+fn test_parse_folder_name_keeps_dots() {
+    let (title, year, _, _) = scraper::parse_folder_name("Mr. Robot (2015)");
+    assert_eq!(title, "Mr. Robot");
+    assert_eq!(year, Some(2015));
+}
@@ -0,0 +1,281 @@
+//! 标题别名测试
+
+use cine_backend::services::identify::{self, ApplySelection};
+use cine_backend::services::title_aliases::{self, AliasInput};
+#[path = "../common/mod.rs"]
+mod common;
+use common::{create_test_db, insert_media_file};
+use serde_json::json;
+
+fn alias(title: &str, path_prefix: Option<&str>, external_id: &str) -> AliasInput {
+    AliasInput {
+        title: title.to_string(),
+        path_prefix: path_prefix.map(str::to_string),
+        year: None,
+        provider: "tmdb".to_string(),
+        external_id: external_id.to_string(),
+        media_type: "tv".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_lookup_prefers_longest_matching_prefix() {
+    let (pool, _temp_dir) = create_test_db().await;
+    title_aliases::upsert(&pool, &alias("Sousou no Frieren", None, "209867"), "api")
+        .await
+        .unwrap();
+    title_aliases::upsert(
+        &pool,
+        &alias("Sousou.no.Frieren", Some("/media/anime/"), "1"),
+        "api",
+    )
+    .await
+    .unwrap();
+
+    let scoped =
+        title_aliases::lookup(&pool, "SOUSOU NO FRIEREN", None, None, "/media/anime/a.mkv")
+            .await
+            .unwrap()
+            .unwrap();
+    assert_eq!(scoped.external_id, "1");
+    assert_eq!(scoped.path_prefix, "/media/anime");
+
+    // 前缀按路径组件匹配，/media/anime2 不在 /media/anime 之下
+    let global = title_aliases::lookup(
+        &pool,
+        "sousou no frieren",
+        None,
+        None,
+        "/media/anime2/a.mkv",
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(global.external_id, "209867");
+
+    assert!(
+        title_aliases::lookup(&pool, "Frieren", None, None, "/media/anime/a.mkv")
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn test_upsert_repoints_existing_alias() {
+    let (pool, _temp_dir) = create_test_db().await;
+    let first = title_aliases::upsert(&pool, &alias("Shogun", None, "1"), "manual")
+        .await
+        .unwrap();
+    let second = title_aliases::upsert(&pool, &alias("shogun", None, "126308"), "manual")
+        .await
+        .unwrap();
+    assert_eq!(first.id, second.id);
+    assert_eq!(second.external_id, "126308");
+    assert_eq!(
+        title_aliases::list(&pool, None, 100, 0)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_update_delete_and_hits() {
+    let (pool, _temp_dir) = create_test_db().await;
+    let created = title_aliases::upsert(&pool, &alias("Kimetsu no Yaiba", None, "85937"), "api")
+        .await
+        .unwrap();
+    title_aliases::record_hit(&pool, &created.id).await.unwrap();
+
+    let updated = title_aliases::update(
+        &pool,
+        &created.id,
+        &alias("Kimetsu no Yaiba", Some("/media/anime"), "85938"),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(updated.external_id, "85938");
+    assert_eq!(updated.path_prefix, "/media/anime");
+    assert_eq!(updated.source, title_aliases::SOURCE_API);
+    assert_eq!(updated.hits, 1);
+    assert!(updated.last_used_at.is_some());
+
+    let found = title_aliases::list(&pool, Some("kimetsu"), 100, 0)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+
+    assert!(title_aliases::delete(&pool, &created.id).await.unwrap());
+    assert!(!title_aliases::delete(&pool, &created.id).await.unwrap());
+    assert!(
+        title_aliases::update(&pool, &created.id, &alias("x", None, "1"))
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn test_learn_alias_records_parsed_release_title() {
+    let (pool, _temp_dir) = create_test_db().await;
+    insert_media_file(
+        &pool,
+        "file-1",
+        "/downloads/[SubsPlease] Sousou no Frieren - 05 (1080p) [ABCD1234].mkv",
+        None,
+    )
+    .await;
+    let selection = ApplySelection {
+        file_id: "file-1".to_string(),
+        provider: "tmdb".to_string(),
+        external_id: "209867".to_string(),
+        media_type: "tv".to_string(),
+        lock_match: false,
+        download_images: false,
+        generate_nfo: false,
+    };
+    identify::learn_alias(&pool, &selection).await.unwrap();
+
+    let learned = title_aliases::lookup(
+        &pool,
+        "Sousou no Frieren",
+        None,
+        Some("tv"),
+        "/downloads/[SubsPlease] Sousou no Frieren - 06 (1080p).mkv",
+    )
+    .await
+    .unwrap()
+    .expect("sibling episode hits the learned alias");
+    assert_eq!(learned.external_id, "209867");
+    assert_eq!(learned.media_type, "tv");
+    assert_eq!(learned.source, title_aliases::SOURCE_MANUAL);
+    // 不在媒体库内时限定在文件所在目录
+    assert_eq!(learned.path_prefix, "/downloads");
+}
+
+#[tokio::test]
+async fn test_learn_alias_scopes_to_library_and_skips_conflicting_year() {
+    let (pool, _temp_dir) = create_test_db().await;
+    sqlx::query(
+        "INSERT INTO watch_folders (id, path, enabled) VALUES ('movies', '/media/movies', 1)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let selection = |file_id: &str, external_id: &str| ApplySelection {
+        file_id: file_id.to_string(),
+        provider: "tmdb".to_string(),
+        external_id: external_id.to_string(),
+        media_type: "movie".to_string(),
+        lock_match: false,
+        download_images: false,
+        generate_nfo: false,
+    };
+
+    insert_media_file(
+        &pool,
+        "dune-1984",
+        "/media/movies/Dune (1984)/Dune.1984.1080p.BluRay.mkv",
+        Some(&json!({ "title": "Dune", "year": 1984 })),
+    )
+    .await;
+    identify::learn_alias(&pool, &selection("dune-1984", "841"))
+        .await
+        .unwrap();
+    let learned = title_aliases::lookup(&pool, "Dune", Some(1984), None, "/media/movies/x.mkv")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(learned.path_prefix, "/media/movies");
+    assert_eq!(learned.year, Some(1984));
+    // 翻拍作品年份不同，不命中原作的别名
+    assert!(
+        title_aliases::lookup(&pool, "Dune", Some(2021), None, "/media/movies/x.mkv")
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        title_aliases::lookup(&pool, "Dune", None, Some("tv"), "/media/movies/x.mkv")
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // 文件名年份与所选条目冲突（多半选错了）时不学习
+    insert_media_file(
+        &pool,
+        "dune-2021",
+        "/media/movies/Dune (2021)/Dune.2021.2160p.WEB-DL.mkv",
+        Some(&json!({ "title": "Dune", "year": 1984 })),
+    )
+    .await;
+    identify::learn_alias(&pool, &selection("dune-2021", "841"))
+        .await
+        .unwrap();
+    assert_eq!(
+        title_aliases::list(&pool, None, 100, 0)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_upsert_keys_on_media_type_and_year() {
+    let (pool, _temp_dir) = create_test_db().await;
+    let movie = AliasInput {
+        media_type: "movie".to_string(),
+        year: Some(2023),
+        ..alias("Frieren", None, "1")
+    };
+    title_aliases::upsert(&pool, &alias("Frieren", None, "209867"), "api")
+        .await
+        .unwrap();
+    title_aliases::upsert(&pool, &movie, "api").await.unwrap();
+    title_aliases::upsert(&pool, &movie, "api").await.unwrap();
+    assert_eq!(
+        title_aliases::list(&pool, None, 100, 0)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+
+    let tv = title_aliases::lookup(&pool, "Frieren", None, Some("tv"), "/media/a.mkv")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(tv.external_id, "209867");
+}
+
+#[tokio::test]
+async fn test_renormalize_rewrites_stale_keys() {
+    let (pool, _temp_dir) = create_test_db().await;
+    title_aliases::upsert(&pool, &alias("進擊的巨人", None, "1429"), "api")
+        .await
+        .unwrap();
+    // 模拟旧版归一化规则写入的键
+    sqlx::query("UPDATE title_aliases SET normalized_title = '進擊的巨人'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(
+        title_aliases::lookup(&pool, "进击的巨人", None, None, "/media/a.mkv")
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    assert_eq!(title_aliases::renormalize(&pool).await.unwrap(), 1);
+    assert_eq!(title_aliases::renormalize(&pool).await.unwrap(), 0);
+    let found = title_aliases::lookup(&pool, "进击的巨人", None, None, "/media/a.mkv")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.external_id, "1429");
+}
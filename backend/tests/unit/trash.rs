@@ -66,8 +66,13 @@ async fn test_restore_from_trash() {
     .unwrap();
 
     let restore_dir = temp_dir.path().join("restored");
-    let result =
-        trash::restore_from_trash(&pool, &file_id, Some(restore_dir.to_str().unwrap())).await;
+    let result = trash::restore_from_trash_in_batch(
+        &pool,
+        &file_id,
+        Some(restore_dir.to_str().unwrap()),
+        Some("batch-1"),
+    )
+    .await;
 
     assert!(result.is_ok());
     let restored_path = result.unwrap();
@@ -75,6 +80,13 @@ async fn test_restore_from_trash() {
     // 验证文件已恢复
     assert!(!trash_file.exists());
     assert!(std::path::Path::new(&restored_path).exists());
+
+    // 恢复操作记入所属批次
+    let logs = cine_backend::services::log::get_batch_logs(&pool, "batch-1")
+        .await
+        .unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].action, "restore");
 }
 
 #[tokio::test]
@@ -16,6 +16,9 @@ export interface MediaFile {
   detected_year?: number
   detected_season?: number
   detected_episode?: number
+  detected_episode_end?: number
+  detected_absolute_episode?: number
+  release_info?: string
   parser_provider?: string
   parse_version?: string
   confidence_score?: number
@@ -25,6 +28,10 @@ export interface MediaFile {
   locked_match_provider?: string
   locked_match_external_id?: string
   ai_disabled_reason?: string
+  movie_id?: string
+  show_id?: string
+  season_id?: string
+  episode_id?: string
   created_at: string
   updated_at: string
   last_modified: string
@@ -52,15 +59,19 @@ export interface IdentifyPreview {
     year?: number
     season?: number
     episode?: number
+    episode_end?: number
+    absolute_episode?: number
     confidence: number
     parser_provider: string
     ai_disabled_reason?: string
+    field_sources?: Record<string, string>
   }
   candidates: IdentifyCandidate[]
   recommended?: IdentifyCandidate
   needs_review: boolean
   ai_used: boolean
   budget_state: string
+  skipped_providers?: string[]
 }
 
 export interface VideoInfo {
@@ -119,13 +130,48 @@ export interface ScanResponse {
 
 export interface OperationLog {
   id: string
-  action: 'rename' | 'trash' | 'restore' | 'delete' | 'move' | 'copy'
+  action: 'rename' | 'trash' | 'restore' | 'delete' | 'move' | 'copy' | 'delete_dir'
   file_id?: string
   old_path: string
   new_path?: string
+  /** JSON 编码的逆操作，为空表示不可撤销 */
+  inverse?: string
+  precondition?: string
+  undone_at?: string
+  /** 所属批次（任务 ID 或批量请求 ID） */
+  batch_id?: string
   created_at: string
 }
 
+export type BatchUndoStatus = 'ready' | 'blocked' | 'undone' | 'failed' | 'skipped'
+
+export interface BatchUndoReport {
+  batch_id: string
+  dry_run: boolean
+  total: number
+  undone: number
+  blocked: number
+  failed: number
+  skipped: number
+  items: {
+    log_id: string
+    action: OperationLog['action']
+    old_path: string
+    new_path?: string
+    status: BatchUndoStatus
+    error?: string
+  }[]
+}
+
+export type RenameConflictPolicy = 'suffix' | 'skip' | 'fail'
+
+export type RenameIssue =
+  | 'duplicate_target'
+  | 'target_exists'
+  | 'case_only'
+  | 'name_too_long'
+  | 'invalid_chars'
+
 export interface ScanHistory {
   directory: string
   total_files: number
@@ -134,6 +180,62 @@ export interface ScanHistory {
   last_scanned_at: string
 }
 
+export interface CatalogTitle {
+  id: string
+  provider: string
+  external_id: string
+  title: string
+  original_title?: string
+  localized_titles?: string
+  aliases?: string
+  year?: number
+  overview?: string
+  poster_url?: string
+  backdrop_url?: string
+  rating?: number
+  file_count: number
+  created_at: string
+  updated_at: string
+}
+
+export type Movie = CatalogTitle
+
+export interface Show extends CatalogTitle {
+  season_count: number
+  episode_count: number
+}
+
+export interface Season {
+  id: string
+  show_id: string
+  season_number: number
+  name?: string
+  episode_count: number
+  file_count: number
+}
+
+export interface Episode {
+  id: string
+  show_id: string
+  season_id: string
+  season_number: number
+  episode_number: number
+  absolute_number?: number
+  title?: string
+  overview?: string
+  air_date?: string
+  runtime?: number
+  rating?: number
+  still_url?: string
+  file_count: number
+}
+
+export interface CatalogParams {
+  search?: string
+  limit?: number
+  offset?: number
+}
+
 export interface GetFilesParams {
   page?: number
   page_size?: number
@@ -247,12 +349,21 @@ export const mediaApi = {
     file_ids: string[]
     template: string
     preview?: boolean
+    conflict_policy?: RenameConflictPolicy
   }) => api.post<{
     preview?: Array<{
       file_id: string
       old_name: string
       new_name: string
+      new_path: string
+      action: 'rename' | 'unchanged' | 'skip' | 'blocked'
+      issues: RenameIssue[]
     }>
+    conflict_policy?: RenameConflictPolicy
+    rename_count?: number
+    skipped_count?: number
+    blocked_count?: number
+    executable?: boolean
     task_id?: string
     status?: string
     message: string
@@ -414,6 +525,12 @@ export const mediaApi = {
   undoOperation: (id: string) =>
     api.post<string>(`/logs/${id}/undo`),
 
+  undoBatch: (batchId: string, dryRun = true) =>
+    api.post<BatchUndoReport | { task_id: string; status: string; message: string }>(
+      `/logs/batches/${batchId}/undo`,
+      { dry_run: dryRun }
+    ),
+
   // 扫描历史相关
   listScanHistory: () =>
     api.get<ScanHistory[]>('/history'),
@@ -424,4 +541,24 @@ export const mediaApi = {
 
   updateNfo: (fileId: string, nfo: MovieNfo) =>
     api.put(`/files/${fileId}/nfo`, nfo),
+
+  // 影视目录
+  listMovies: (params?: CatalogParams) =>
+    api.get<Movie[]>('/movies', { params }),
+
+  getMovie: (id: string) =>
+    api.get<{ movie: Movie; files: MediaFile[] }>(`/movies/${encodeURIComponent(id)}`),
+
+  listShows: (params?: CatalogParams) =>
+    api.get<Show[]>('/shows', { params }),
+
+  getShow: (id: string) =>
+    api.get<{ show: Show; seasons: Season[] }>(`/shows/${encodeURIComponent(id)}`),
+
+  getSeason: (showId: string, season: number) =>
+    api.get<{
+      season: Season
+      episodes: Array<{ episode: Episode; files: MediaFile[] }>
+      unmatched_files: MediaFile[]
+    }>(`/shows/${encodeURIComponent(showId)}/seasons/${season}`),
 }
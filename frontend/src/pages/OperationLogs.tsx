@@ -87,6 +87,9 @@ export default function OperationLogs() {
       trash: { color: "warning", text: "移入回收站" },
       restore: { color: "success", text: "还原" },
       delete: { color: "danger", text: "永久删除" },
+      move: { color: "accent", text: "移动" },
+      copy: { color: "default", text: "复制" },
+      delete_dir: { color: "warning", text: "删除空目录" },
     }
     const config = labels[action] || { color: "default" as const, text: action }
     return (
@@ -156,7 +159,7 @@ export default function OperationLogs() {
           >
             <Icon icon="mdi:eye" className="w-[13px] h-[13px] text-default-400" />
           </Button>
-          {record.action === 'rename' && (
+          {record.action !== 'delete' && !record.undone_at && (
             <Tooltip closeDelay={0}>
               <Button
                 isIconOnly
@@ -168,7 +171,7 @@ export default function OperationLogs() {
                 <ArrowRotateLeft className="w-[13px] h-[13px] text-warning/80" />
               </Button>
               <Tooltip.Content>
-                撤销重命名
+                撤销操作
               </Tooltip.Content>
             </Tooltip>
           )}
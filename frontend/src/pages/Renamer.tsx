@@ -288,10 +288,34 @@ export default function Renamer() {
                     <code className="text-[11px] text-success font-bold">{"{episode}"}</code>
                     <span className="text-[10px] text-default-400">集号</span>
                   </div>
+                  <div className="flex items-center justify-between">
+                    <code className="text-[11px] text-success font-bold">{"{absolute}"}</code>
+                    <span className="text-[10px] text-default-400">绝对集数</span>
+                  </div>
+                  <div className="flex items-center justify-between">
+                    <code className="text-[11px] text-success font-bold">{"{episode_title}"}</code>
+                    <span className="text-[10px] text-default-400">单集标题</span>
+                  </div>
                   <div className="flex items-center justify-between">
                     <code className="text-[11px] text-default-400 font-bold">{"{quality}"}</code>
                     <span className="text-[10px] text-default-400">分辨率</span>
                   </div>
+                  <div className="flex items-center justify-between">
+                    <code className="text-[11px] text-default-400 font-bold">{"{codec}"}</code>
+                    <span className="text-[10px] text-default-400">编码</span>
+                  </div>
+                  <div className="flex items-center justify-between">
+                    <code className="text-[11px] text-default-400 font-bold">{"{audio}"}</code>
+                    <span className="text-[10px] text-default-400">音轨</span>
+                  </div>
+                  <div className="flex items-center justify-between">
+                    <code className="text-[11px] text-default-400 font-bold">{"{group}"}</code>
+                    <span className="text-[10px] text-default-400">发布组</span>
+                  </div>
+                  <div className="flex items-center justify-between">
+                    <code className="text-[11px] text-default-400 font-bold">{"{edition}"}</code>
+                    <span className="text-[10px] text-default-400">版本</span>
+                  </div>
                   <div className="flex items-center justify-between">
                     <code className="text-[11px] text-default-400 font-bold">{"{ext}"}</code>
                     <span className="text-[10px] text-default-400">扩展名</span>
@@ -127,7 +127,9 @@ export default function Scraper() {
       const year = file.detected_year || readMetadataYear(metadata)
       const source = file.locked_match_provider || file.match_provider || file.parser_provider || 'rules'
       const season = file.detected_season ? `S${String(file.detected_season).padStart(2, '0')}` : undefined
-      const episode = file.detected_episode ? `E${String(file.detected_episode).padStart(2, '0')}` : undefined
+      const episode = file.detected_episode
+        ? `E${String(file.detected_episode).padStart(2, '0')}${file.detected_episode_end ? `-E${String(file.detected_episode_end).padStart(2, '0')}` : ''}`
+        : undefined
       const subtitle = [source, year ? String(year) : undefined, season, episode].filter(Boolean).join(' / ') || '等待识别'
 
       let status: GroupStatus = 'unmatched'
@@ -1061,6 +1063,11 @@ export default function Scraper() {
                       <Chip size="sm" variant="soft">
                         预算: {previewResult.budget_state}
                       </Chip>
+                      {previewResult.skipped_providers && previewResult.skipped_providers.length > 0 && (
+                        <Chip size="sm" variant="soft" color="warning">
+                          已跳过熔断数据源: {previewResult.skipped_providers.join(', ')}
+                        </Chip>
+                      )}
                       {previewResult.ai_used && (
                         <Chip size="sm" variant="soft" color="success">
                           已使用 Cloudflare AI
@@ -42,6 +42,7 @@ type BasicConfig = {
   ai_daily_budget: string
   default_dir: string
   auto_monitor: boolean
+  provider_offline_mode: boolean
 }
 
 type HealthProvider = 'tmdb' | 'bangumi' | 'cloudflare_ai'
@@ -63,6 +64,7 @@ const defaultBasicConfig: BasicConfig = {
   ai_daily_budget: '100',
   default_dir: '',
   auto_monitor: false,
+  provider_offline_mode: false,
 }
 
 export default function Settings() {
@@ -96,7 +98,8 @@ export default function Settings() {
         ai_budget_mode: settings.ai_budget_mode || 'strict_free',
         ai_daily_budget: settings.ai_daily_budget || '100',
         default_dir: settings.default_dir || '',
-        auto_monitor: settings.auto_monitor === 'true'
+        auto_monitor: settings.auto_monitor === 'true',
+        provider_offline_mode: settings.provider_offline_mode === 'true'
       })
 
       setSchedulerConfig({
@@ -125,7 +128,8 @@ export default function Settings() {
         ai_budget_mode: config.ai_budget_mode,
         ai_daily_budget: config.ai_daily_budget,
         default_dir: config.default_dir,
-        auto_monitor: String(config.auto_monitor)
+        auto_monitor: String(config.auto_monitor),
+        provider_offline_mode: String(config.provider_offline_mode)
       }
     }),
     onSuccess: () => {
@@ -211,7 +215,8 @@ export default function Settings() {
       basicConfig.ai_budget_mode !== (currentSettings.ai_budget_mode || 'strict_free') ||
       basicConfig.ai_daily_budget !== (currentSettings.ai_daily_budget || '100') ||
       basicConfig.default_dir !== (currentSettings.default_dir || '') ||
-      basicConfig.auto_monitor !== (currentSettings.auto_monitor === 'true')
+      basicConfig.auto_monitor !== (currentSettings.auto_monitor === 'true') ||
+      basicConfig.provider_offline_mode !== (currentSettings.provider_offline_mode === 'true')
     )
   }, [basicConfig, currentSettings])
 
@@ -237,7 +242,8 @@ export default function Settings() {
       ai_budget_mode: currentSettings.ai_budget_mode || 'strict_free',
       ai_daily_budget: currentSettings.ai_daily_budget || '100',
       default_dir: currentSettings.default_dir || '',
-      auto_monitor: currentSettings.auto_monitor === 'true'
+      auto_monitor: currentSettings.auto_monitor === 'true',
+      provider_offline_mode: currentSettings.provider_offline_mode === 'true'
     })
   }
 
@@ -620,6 +626,18 @@ export default function Settings() {
                     />
                   </div>
 
+                  <div className="flex items-center justify-between py-3 px-4 rounded-xl bg-default-100/30 border border-divider/10">
+                    <div className="flex flex-col gap-1">
+                      <Label className="text-[13px] font-bold text-foreground/80">离线识别模式</Label>
+                      <span className="text-[11px] text-default-400 font-medium">只使用已缓存的 TMDb / Bangumi 响应，不访问网络</span>
+                    </div>
+                    <Switch
+                      isSelected={basicConfig.provider_offline_mode}
+                      onChange={(v) => setBasicConfig({ ...basicConfig, provider_offline_mode: v })}
+                      size="md"
+                    />
+                  </div>
+
                   <div className="flex items-center justify-between pt-6 border-t border-divider/10">
                     <div className="flex items-center gap-3">
                       <Button
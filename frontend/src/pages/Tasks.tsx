@@ -48,6 +48,29 @@ const statusLabels: Record<string, string> = {
   cancelled: '已取消',
 }
 
+// 识别字段来源
+const parseFieldLabels: Record<string, string> = {
+  title: '标题',
+  year: '年份',
+  season: '季',
+  episode: '集',
+}
+
+const parseSourceLabels: Record<string, string> = {
+  file_name: '文件名',
+  season_folder: '季目录',
+  show_folder: '剧集目录',
+  nfo: 'NFO',
+  path_id: '路径 ID',
+}
+
+function describeFieldSources(sources?: Record<string, string>): string | null {
+  const parts = Object.entries(sources ?? {})
+    .filter(([, source]) => source !== 'file_name')
+    .map(([field, source]) => `${parseFieldLabels[field] ?? field}←${parseSourceLabels[source] ?? source}`)
+  return parts.length > 0 ? parts.join(' · ') : null
+}
+
 export default function Tasks() {
   const navigate = useNavigate()
   const [confirmModal, setConfirmModal] = useState<{ isOpen: boolean; taskId: string | null; action: 'cancel' | 'cleanup' }>({
@@ -686,6 +709,9 @@ function TaskDetailContent({
                     {' / '}
                     {Math.round(item.parse.confidence * 100)}%
                   </p>
+                  {describeFieldSources(item.parse.field_sources) && (
+                    <p className="mt-1 text-xs text-default-400">{describeFieldSources(item.parse.field_sources)}</p>
+                  )}
                 </div>
                 <Chip size="sm" variant="soft" color={item.needs_review ? 'warning' : 'success'}>
                   {item.needs_review ? '待确认' : '可自动应用'}
@@ -27,7 +27,7 @@ export function buildTaskSummary(task: TaskInfo): string | null {
 }
 
 export function parseTaskResult(task: TaskInfo):
-  | { type: 'identify_preview'; results: Array<{ file_id: string; file_name: string; parse: { title: string; year?: number; parser_provider: string; confidence: number }; recommended?: ParsedCandidate; needs_review: boolean; candidates: ParsedCandidate[] }> }
+  | { type: 'identify_preview'; results: Array<{ file_id: string; file_name: string; parse: { title: string; year?: number; parser_provider: string; confidence: number; field_sources?: Record<string, string> }; recommended?: ParsedCandidate; needs_review: boolean; candidates: ParsedCandidate[] }> }
   | { type: 'identify_apply'; applied: Array<{ file_id: string; metadata: Record<string, unknown> }> }
   | { type: 'raw'; text: string }
   | { type: 'empty' } {
@@ -40,7 +40,7 @@ export function parseTaskResult(task: TaskInfo):
       results?: Array<{
         file_id: string
         file_name: string
-        parse: { title: string; year?: number; parser_provider: string; confidence: number }
+        parse: { title: string; year?: number; parser_provider: string; confidence: number; field_sources?: Record<string, string> }
         recommended?: ParsedCandidate
         needs_review: boolean
         candidates?: ParsedCandidate[]
@@ -72,7 +72,9 @@ cat > app/config/config.json <<EOF
     "port": 3000,
     "database_url": "sqlite:/data/cine.db",
     "hash_cache_dir": "/data/cine/hash_cache",
-    "trash_dir": "/data/cine/trash"
+    "trash_dir": "/data/cine/trash",
+    "people_cache_dir": "/data/cine/people",
+    "collection_cache_dir": "/data/cine/collections"
 }
 EOF
 